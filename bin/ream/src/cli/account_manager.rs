@@ -1,9 +1,16 @@
+use std::{path::PathBuf, sync::Arc};
+
+use alloy_primitives::Address;
 use anyhow::ensure;
-use bip32::Mnemonic;
-use clap::Parser;
-use rand::rngs::OsRng;
+use clap::{ArgGroup, Args, Parser, Subcommand};
+use ream_account_manager::mnemonic::generate_mnemonic;
+use ream_network_spec::{cli::beacon_network_parser, networks::BeaconNetworkSpec};
 use tracing::warn;
 
+use crate::cli::constants::{
+    DEFAULT_DEPOSIT_AMOUNT, DEFAULT_NETWORK, DEFAULT_VALIDATOR_KEYS_OUTPUT_DIR,
+};
+
 const MIN_CHUNK_SIZE: u64 = 4;
 const MIN_LIFETIME: u64 = 18;
 
@@ -24,6 +31,106 @@ pub struct AccountManagerConfig {
     /// Seed phrase for key generation
     #[arg(short, long)]
     pub seed_phrase: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<AccountManagerCommand>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AccountManagerCommand {
+    /// Generate a new mnemonic and create beacon validator keystores and deposit data from it
+    #[command(name = "new_mnemonic")]
+    NewMnemonic(ValidatorKeysConfig),
+
+    /// Recover beacon validator keystores and deposit data from an existing mnemonic
+    #[command(name = "existing_mnemonic")]
+    ExistingMnemonic(ExistingMnemonicConfig),
+}
+
+#[derive(Debug, Args)]
+pub struct ValidatorKeysConfig {
+    #[arg(
+        long,
+        help = "Choose mainnet, holesky, sepolia, hoodi, dev or provide a path to a YAML config file",
+        default_value = DEFAULT_NETWORK,
+        value_parser = beacon_network_parser
+    )]
+    pub network: Arc<BeaconNetworkSpec>,
+
+    #[arg(
+        long,
+        help = "The number of validator keys to create",
+        default_value_t = 1
+    )]
+    pub num_validators: u32,
+
+    #[arg(
+        long,
+        help = "The EIP-2334 index of the first validator key to create",
+        default_value_t = 0
+    )]
+    pub validator_start_index: u32,
+
+    #[arg(long, help = "The deposit amount in Gwei", default_value_t = DEFAULT_DEPOSIT_AMOUNT)]
+    pub amount: u64,
+
+    #[arg(
+        long,
+        help = "The execution address to withdraw to. If not set, BLS withdrawal credentials are used"
+    )]
+    pub withdrawal_address: Option<Address>,
+
+    #[arg(
+        long,
+        help = "Use compounding (0x02) withdrawal credentials",
+        requires = "withdrawal_address"
+    )]
+    pub compounding: bool,
+
+    #[arg(long, help = "The directory to write keystores and deposit data to", default_value = DEFAULT_VALIDATOR_KEYS_OUTPUT_DIR)]
+    pub output_dir: PathBuf,
+
+    #[arg(
+        long,
+        group = "password_source",
+        help = "The plaintext password file used to encrypt the keystores"
+    )]
+    pub password_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        group = "password_source",
+        help = "The password used to encrypt the keystores. It's recommended to use password-file over this in order to prevent your keystore password from appearing in the shell history"
+    )]
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("mnemonic_source").required(true)))]
+pub struct ExistingMnemonicConfig {
+    #[command(flatten)]
+    pub validator_keys: ValidatorKeysConfig,
+
+    #[arg(
+        long,
+        group = "mnemonic_source",
+        help = "The mnemonic to recover the validator keys from. It's recommended to use mnemonic-file over this in order to prevent your mnemonic from appearing in the shell history"
+    )]
+    pub mnemonic: Option<String>,
+
+    #[arg(
+        long,
+        group = "mnemonic_source",
+        help = "The plaintext file containing the mnemonic to recover the validator keys from"
+    )]
+    pub mnemonic_file: Option<PathBuf>,
+
+    #[arg(
+        long,
+        help = "The BIP-39 passphrase the mnemonic was created with, if any",
+        default_value = ""
+    )]
+    pub mnemonic_passphrase: String,
 }
 
 impl Default for AccountManagerConfig {
@@ -33,6 +140,7 @@ impl Default for AccountManagerConfig {
             lifetime: 28,
             chunk_size: 5,
             seed_phrase: None,
+            command: None,
         }
     }
 }
@@ -54,17 +162,16 @@ impl AccountManagerConfig {
         Ok(())
     }
 
-    pub fn get_seed_phrase(&self) -> String {
+    pub fn get_seed_phrase(&self) -> anyhow::Result<String> {
         if let Some(phrase) = &self.seed_phrase {
-            phrase.clone()
+            Ok(phrase.clone())
         } else {
-            let mnemonic = Mnemonic::random(OsRng, Default::default());
-            let phrase = mnemonic.phrase().to_string();
+            let phrase = generate_mnemonic()?.to_string();
             warn!("⚠️  IMPORTANT: Generated new seed phrase: {phrase}");
             warn!(
                 "⚠️  Please save this seed phrase somewhere safe. You will need it to recover your keys."
             );
-            phrase
+            Ok(phrase)
         }
     }
 }
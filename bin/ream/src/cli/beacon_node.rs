@@ -1,16 +1,27 @@
-use std::{net::IpAddr, path::PathBuf, sync::Arc};
+use std::{
+    net::{IpAddr, Ipv6Addr},
+    path::PathBuf,
+    sync::Arc,
+};
 
 use clap::Parser;
 use ream_consensus_misc::checkpoint::Checkpoint;
 use ream_network_manager::config::ManagerConfig;
 use ream_network_spec::{cli::beacon_network_parser, networks::BeaconNetworkSpec};
 use ream_p2p::bootnodes::Bootnodes;
+use ream_storage::{
+    db::DEFAULT_STATE_SNAPSHOT_INTERVAL,
+    read_cache::{
+        DEFAULT_BLOCK_CACHE_SIZE, DEFAULT_COMMITTEE_CACHE_SIZE, DEFAULT_STATE_CACHE_SIZE,
+    },
+};
 use url::Url;
 
 use crate::cli::constants::{
-    DEFAULT_DISABLE_DISCOVERY, DEFAULT_DISCOVERY_PORT, DEFAULT_HTTP_ADDRESS,
-    DEFAULT_HTTP_ALLOW_ORIGIN, DEFAULT_HTTP_PORT, DEFAULT_NETWORK, DEFAULT_SOCKET_ADDRESS,
-    DEFAULT_SOCKET_PORT,
+    DEFAULT_BLOB_PRUNE_MARGIN, DEFAULT_DISABLE_DISCOVERY, DEFAULT_DISABLE_QUIC,
+    DEFAULT_DISCOVERY_PORT, DEFAULT_HTTP_ADDRESS, DEFAULT_HTTP_ALLOW_ORIGIN, DEFAULT_HTTP_PORT,
+    DEFAULT_METRICS_ADDRESS, DEFAULT_METRICS_ENABLED, DEFAULT_METRICS_PORT, DEFAULT_NETWORK,
+    DEFAULT_QUIC_PORT, DEFAULT_SOCKET_ADDRESS, DEFAULT_SOCKET_PORT,
 };
 
 #[derive(Debug, Parser)]
@@ -36,18 +47,58 @@ pub struct BeaconNodeConfig {
     #[arg(long, default_value_t = DEFAULT_HTTP_ALLOW_ORIGIN)]
     pub http_allow_origin: bool,
 
+    #[arg(long = "metrics", help = "Enable metrics", default_value_t = DEFAULT_METRICS_ENABLED)]
+    pub enable_metrics: bool,
+
+    #[arg(long, help = "Set metrics address", default_value_t = DEFAULT_METRICS_ADDRESS)]
+    pub metrics_address: IpAddr,
+
+    #[arg(long, help = "Set metrics port", default_value_t = DEFAULT_METRICS_PORT)]
+    pub metrics_port: u16,
+
     #[arg(long, help = "Set P2P socket address", default_value_t = DEFAULT_SOCKET_ADDRESS)]
     pub socket_address: IpAddr,
 
+    #[arg(
+        long,
+        help = "Set an IPv6 P2P socket address to listen on besides --socket-address, using the same ports"
+    )]
+    pub socket_address_v6: Option<Ipv6Addr>,
+
+    #[arg(
+        long,
+        help = "Advertise this IP address in the ENR instead of the one learned from peers"
+    )]
+    pub enr_address: Option<IpAddr>,
+
     #[arg(long, help = "Set P2P socket port (TCP)", default_value_t = DEFAULT_SOCKET_PORT)]
     pub socket_port: u16,
 
+    #[arg(long, help = "Set P2P QUIC port (UDP)", default_value_t = DEFAULT_QUIC_PORT)]
+    pub quic_port: u16,
+
     #[arg(long, help = "Discovery 5 listening port (UDP)", default_value_t = DEFAULT_DISCOVERY_PORT)]
     pub discovery_port: u16,
 
     #[arg(long, help = "Disable Discv5", default_value_t = DEFAULT_DISABLE_DISCOVERY)]
     pub disable_discovery: bool,
 
+    #[arg(
+        long,
+        help = "Only listen on and dial TCP, without QUIC",
+        default_value_t = DEFAULT_DISABLE_QUIC
+    )]
+    pub disable_quic: bool,
+
+    #[arg(long, help = "Map the P2P ports on the router with UPnP")]
+    pub upnp: bool,
+
+    #[arg(
+        long,
+        help = "Replace the stored node key with a new one, which changes the peer ID and ENR"
+    )]
+    pub rotate_node_key: bool,
+
     #[arg(
         long,
         help = "The directory for storing application data. If used together with --ephemeral, new child directory will be created."
@@ -77,9 +128,63 @@ pub struct BeaconNodeConfig {
     )]
     pub weak_subjectivity_checkpoint: Option<Checkpoint>,
 
+    #[arg(
+        long,
+        help = "Backfill blocks down to the Electra fork, the oldest blocks that can be decoded, instead of stopping at the blob retention boundary"
+    )]
+    pub backfill_to_genesis: bool,
+
+    #[arg(
+        long,
+        help = "Verify the proposer signature of every block downloaded by backfill sync"
+    )]
+    pub backfill_verify_signatures: bool,
+
     #[arg(long, help = "Purges the database.")]
     pub purge_db: bool,
 
+    #[arg(
+        long,
+        help = "Number of epochs to keep blobs for beyond MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS",
+        default_value_t = DEFAULT_BLOB_PRUNE_MARGIN,
+        conflicts_with = "store_all_blobs"
+    )]
+    pub blob_prune_margin: u64,
+
+    #[arg(
+        long,
+        help = "Keep all blobs instead of pruning them once they are out of the retention window"
+    )]
+    pub store_all_blobs: bool,
+
+    #[arg(
+        long,
+        help = "Number of slots between full state snapshots in the freezer. Finalized states in between are stored as diffs against the previous snapshot.",
+        default_value_t = DEFAULT_STATE_SNAPSHOT_INTERVAL
+    )]
+    pub state_snapshot_interval: u64,
+
+    #[arg(
+        long,
+        help = "Number of beacon states kept in memory",
+        default_value_t = DEFAULT_STATE_CACHE_SIZE
+    )]
+    pub state_cache_size: usize,
+
+    #[arg(
+        long,
+        help = "Number of block headers kept in memory",
+        default_value_t = DEFAULT_BLOCK_CACHE_SIZE
+    )]
+    pub block_cache_size: usize,
+
+    #[arg(
+        long,
+        help = "Number of epochs of beacon committees kept in memory",
+        default_value_t = DEFAULT_COMMITTEE_CACHE_SIZE
+    )]
+    pub committee_cache_size: usize,
+
     #[arg(
         long,
         help = "The URL of the execution endpoint. This is used to send requests to the engine api.",
@@ -102,9 +207,15 @@ impl From<BeaconNodeConfig> for ManagerConfig {
             http_port: config.http_port,
             http_allow_origin: config.http_allow_origin,
             socket_address: config.socket_address,
+            socket_address_v6: config.socket_address_v6,
+            enr_address: config.enr_address,
             socket_port: config.socket_port,
+            quic_port: config.quic_port,
             discovery_port: config.discovery_port,
             disable_discovery: config.disable_discovery,
+            disable_quic: config.disable_quic,
+            upnp: config.upnp,
+            rotate_node_key: config.rotate_node_key,
             data_dir: config.data_dir,
             ephemeral: config.ephemeral,
             bootnodes: config.bootnodes,
@@ -112,6 +223,8 @@ impl From<BeaconNodeConfig> for ManagerConfig {
             purge_db: config.purge_db,
             execution_endpoint: config.execution_endpoint,
             execution_jwt_secret: config.execution_jwt_secret,
+            backfill_to_genesis: config.backfill_to_genesis,
+            backfill_verify_signatures: config.backfill_verify_signatures,
         }
     }
 }
@@ -1,7 +1,11 @@
 use std::net::{IpAddr, Ipv4Addr};
 
 pub const DEFAULT_BEACON_API_ENDPOINT: &str = "http://localhost:5052";
+pub const DEFAULT_BLOB_PRUNE_MARGIN: u64 = 0;
+pub const DEFAULT_BUILDER_HEADER_TIMEOUT: &str = "950";
+pub const DEFAULT_DEPOSIT_AMOUNT: u64 = 32_000_000_000;
 pub const DEFAULT_DISABLE_DISCOVERY: bool = false;
+pub const DEFAULT_DISABLE_QUIC: bool = false;
 pub const DEFAULT_DISCOVERY_PORT: u16 = 9000;
 pub const DEFAULT_HTTP_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
 pub const DEFAULT_HTTP_ALLOW_ORIGIN: bool = false;
@@ -11,6 +15,8 @@ pub const DEFAULT_METRICS_ENABLED: bool = false;
 pub const DEFAULT_METRICS_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1));
 pub const DEFAULT_METRICS_PORT: u16 = 8080;
 pub const DEFAULT_NETWORK: &str = "mainnet";
+pub const DEFAULT_QUIC_PORT: u16 = 9001;
 pub const DEFAULT_REQUEST_TIMEOUT: &str = "60";
 pub const DEFAULT_SOCKET_ADDRESS: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
 pub const DEFAULT_SOCKET_PORT: u16 = 9000;
+pub const DEFAULT_VALIDATOR_KEYS_OUTPUT_DIR: &str = "validator_keys";
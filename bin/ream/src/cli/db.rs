@@ -0,0 +1,95 @@
+use std::{path::PathBuf, sync::Arc};
+
+use alloy_primitives::B256;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use ream_network_spec::{cli::beacon_network_parser, networks::BeaconNetworkSpec};
+
+use crate::cli::constants::DEFAULT_NETWORK;
+
+#[derive(Debug, Parser)]
+pub struct DbConfig {
+    /// Verbosity level
+    #[arg(short, long, default_value_t = 3)]
+    pub verbosity: u8,
+
+    #[arg(
+        long,
+        help = "Choose mainnet, holesky, sepolia, hoodi, dev or provide a path to a YAML config file",
+        default_value = DEFAULT_NETWORK,
+        value_parser = beacon_network_parser
+    )]
+    pub network: Arc<BeaconNetworkSpec>,
+
+    #[arg(long, help = "The directory of the beacon node database")]
+    pub data_dir: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub command: DbCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum DbCommand {
+    /// Print the size and entry count of every table
+    #[command(name = "stats")]
+    Stats,
+
+    /// Write a block or state to a file
+    #[command(name = "dump")]
+    Dump(DumpConfig),
+
+    /// Check the slot, state root and parent root indices against the stored blocks
+    #[command(name = "check_indices")]
+    CheckIndices,
+
+    /// Compact the database file to release unused space
+    #[command(name = "compact")]
+    Compact,
+
+    /// Delete everything after the canonical checkpoint of a finalized epoch and make it the head
+    #[command(name = "rollback")]
+    Rollback(RollbackConfig),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DumpKind {
+    Block,
+    State,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum DumpFormat {
+    Ssz,
+    Json,
+}
+
+#[derive(Debug, Args)]
+pub struct DumpConfig {
+    #[arg(value_enum, help = "Whether to dump a block or a state")]
+    pub kind: DumpKind,
+
+    #[arg(
+        long,
+        required_unless_present = "slot",
+        conflicts_with = "slot",
+        help = "The block root, or for states also the state root"
+    )]
+    pub root: Option<B256>,
+
+    #[arg(long, help = "The slot of the canonical block")]
+    pub slot: Option<u64>,
+
+    #[arg(long, value_enum, default_value_t = DumpFormat::Json, help = "The output encoding")]
+    pub format: DumpFormat,
+
+    #[arg(long, help = "The file to write to")]
+    pub output: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct RollbackConfig {
+    #[arg(
+        long,
+        help = "The epoch of the checkpoint to roll back to, must not be after the finalized epoch"
+    )]
+    pub epoch: u64,
+}
@@ -1,6 +1,7 @@
 pub mod account_manager;
 pub mod beacon_node;
 pub mod constants;
+pub mod db;
 pub mod import_keystores;
 pub mod lean_node;
 pub mod validator_node;
@@ -10,7 +11,7 @@ use clap::{Parser, Subcommand};
 use ream_node::version::FULL_VERSION;
 
 use crate::cli::{
-    account_manager::AccountManagerConfig, beacon_node::BeaconNodeConfig,
+    account_manager::AccountManagerConfig, beacon_node::BeaconNodeConfig, db::DbConfig,
     lean_node::LeanNodeConfig, validator_node::ValidatorNodeConfig,
     voluntary_exit::VoluntaryExitConfig,
 };
@@ -43,12 +44,17 @@ pub enum Commands {
     /// Perform voluntary exit for a validator
     #[command(name = "voluntary_exit")]
     VoluntaryExit(Box<VoluntaryExitConfig>),
+
+    /// Inspect and maintain the beacon node database
+    #[command(name = "db")]
+    Db(Box<DbConfig>),
 }
 
 #[cfg(test)]
 mod tests {
     use std::{
-        net::{IpAddr, Ipv4Addr},
+        net::{IpAddr, Ipv4Addr, Ipv6Addr},
+        path::PathBuf,
         time::Duration,
     };
 
@@ -56,7 +62,11 @@ mod tests {
     use url::Url;
 
     use super::*;
-    use crate::cli::constants::DEFAULT_BEACON_API_ENDPOINT;
+    use crate::cli::{
+        account_manager::AccountManagerCommand,
+        constants::DEFAULT_BEACON_API_ENDPOINT,
+        db::{DbCommand, DumpFormat, DumpKind},
+    };
 
     #[test]
     fn test_cli_lean_node_command() {
@@ -90,6 +100,10 @@ mod tests {
             "9001",
             "--discovery-port",
             "9002",
+            "--quic-port",
+            "9003",
+            "--socket-address-v6",
+            "::1",
         ]);
 
         match cli.command {
@@ -102,6 +116,9 @@ mod tests {
                 );
                 assert_eq!(config.socket_port, 9001);
                 assert_eq!(config.discovery_port, 9002);
+                assert_eq!(config.quic_port, 9003);
+                assert_eq!(config.socket_address_v6, Some(Ipv6Addr::LOCALHOST));
+                assert!(!config.disable_quic);
             }
             _ => unreachable!("This test should only validate the beacon node cli"),
         }
@@ -161,4 +178,96 @@ mod tests {
             _ => unreachable!("This test should only validate the account manager cli"),
         }
     }
+
+    #[test]
+    fn test_cli_account_manager_existing_mnemonic_requires_one_mnemonic_source() {
+        assert!(Cli::try_parse_from(["program", "account_manager", "existing_mnemonic"]).is_err());
+        assert!(
+            Cli::try_parse_from([
+                "program",
+                "account_manager",
+                "existing_mnemonic",
+                "--mnemonic",
+                "abandon",
+                "--mnemonic-file",
+                "./mnemonic.txt",
+            ])
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_cli_account_manager_existing_mnemonic_command() {
+        let cli = Cli::parse_from([
+            "program",
+            "account_manager",
+            "existing_mnemonic",
+            "--network",
+            "hoodi",
+            "--num-validators",
+            "4",
+            "--validator-start-index",
+            "2",
+            "--withdrawal-address",
+            "0x003Fb16e421E42084EBC54bcdc7F0fa344cF9316",
+            "--password",
+            "password123",
+            "--mnemonic-file",
+            "./mnemonic.txt",
+        ]);
+
+        match cli.command {
+            Commands::AccountManager(config) => match config.command {
+                Some(AccountManagerCommand::ExistingMnemonic(existing_config)) => {
+                    let validator_keys = existing_config.validator_keys;
+                    assert_eq!(validator_keys.network.network, Network::Hoodi);
+                    assert_eq!(validator_keys.num_validators, 4);
+                    assert_eq!(validator_keys.validator_start_index, 2);
+                    assert_eq!(validator_keys.amount, 32_000_000_000);
+                    assert!(!validator_keys.compounding);
+                    assert_eq!(
+                        existing_config.mnemonic_file,
+                        Some(PathBuf::from("./mnemonic.txt"))
+                    );
+                }
+                _ => unreachable!("Expected the existing_mnemonic subcommand"),
+            },
+            _ => unreachable!("This test should only validate the account manager cli"),
+        }
+    }
+
+    #[test]
+    fn test_cli_db_dump_command() {
+        let cli = Cli::parse_from([
+            "program",
+            "db",
+            "--data-dir",
+            "./data",
+            "dump",
+            "state",
+            "--slot",
+            "64",
+            "--format",
+            "ssz",
+            "--output",
+            "./state.ssz",
+        ]);
+
+        match cli.command {
+            Commands::Db(config) => {
+                assert_eq!(config.data_dir, Some(PathBuf::from("./data")));
+                match config.command {
+                    DbCommand::Dump(dump_config) => {
+                        assert_eq!(dump_config.kind, DumpKind::State);
+                        assert_eq!(dump_config.slot, Some(64));
+                        assert_eq!(dump_config.root, None);
+                        assert_eq!(dump_config.format, DumpFormat::Ssz);
+                        assert_eq!(dump_config.output, PathBuf::from("./state.ssz"));
+                    }
+                    _ => unreachable!("Expected the dump subcommand"),
+                }
+            }
+            _ => unreachable!("This test should only validate the db cli"),
+        }
+    }
 }
@@ -6,8 +6,9 @@ use ream_network_spec::{cli::beacon_network_parser, networks::BeaconNetworkSpec}
 use url::Url;
 
 use crate::cli::constants::{
-    DEFAULT_BEACON_API_ENDPOINT, DEFAULT_HTTP_ADDRESS, DEFAULT_KEY_MANAGER_HTTP_PORT,
-    DEFAULT_NETWORK, DEFAULT_REQUEST_TIMEOUT,
+    DEFAULT_BEACON_API_ENDPOINT, DEFAULT_BUILDER_HEADER_TIMEOUT, DEFAULT_HTTP_ADDRESS,
+    DEFAULT_KEY_MANAGER_HTTP_PORT, DEFAULT_METRICS_ADDRESS, DEFAULT_METRICS_ENABLED,
+    DEFAULT_METRICS_PORT, DEFAULT_NETWORK, DEFAULT_REQUEST_TIMEOUT,
 };
 
 #[derive(Debug, Parser)]
@@ -59,15 +60,33 @@ pub struct ValidatorNodeConfig {
     )]
     pub password: Option<String>,
 
+    #[arg(
+        long,
+        help = "Path to a YAML or JSON proposer config file with per-validator fee recipient, gas limit, graffiti and builder settings. The file is reloaded when it changes"
+    )]
+    pub proposer_config: Option<PathBuf>,
+
     #[arg(long, help = "Enable external block builder")]
     pub enable_builder: bool,
 
     #[arg(
         long,
-        help = "Set HTTP url of MEV relay to connect to for external block building. Will only be used if `enable_builder` is passed.",
+        help = "Set HTTP url of MEV relay to connect to for external block building. Can be passed multiple times to query several relays. Will only be used if `enable_builder` is passed.",
         requires = "enable_builder"
     )]
-    pub mev_relay_url: Option<Url>,
+    pub mev_relay_url: Vec<Url>,
+
+    #[arg(long, help = "Set the time budget in milliseconds for collecting bids from MEV relays", default_value = DEFAULT_BUILDER_HEADER_TIMEOUT, value_parser = duration_millis_parser)]
+    pub builder_header_timeout: Duration,
+
+    #[arg(long = "metrics", help = "Enable metrics", default_value_t = DEFAULT_METRICS_ENABLED)]
+    pub enable_metrics: bool,
+
+    #[arg(long, help = "Set metrics address", default_value_t = DEFAULT_METRICS_ADDRESS)]
+    pub metrics_address: IpAddr,
+
+    #[arg(long, help = "Set metrics port", default_value_t = DEFAULT_METRICS_PORT)]
+    pub metrics_port: u16,
 }
 
 pub fn duration_parser(duration_string: &str) -> Result<Duration, String> {
@@ -75,3 +94,9 @@ pub fn duration_parser(duration_string: &str) -> Result<Duration, String> {
         |err| format!("Could not parse the request timeout: {err:?}"),
     )?))
 }
+
+pub fn duration_millis_parser(duration_string: &str) -> Result<Duration, String> {
+    Ok(Duration::from_millis(duration_string.parse().map_err(
+        |err| format!("Could not parse the duration: {err:?}"),
+    )?))
+}
@@ -1,22 +1,32 @@
 use std::{
-    env,
+    env, fs,
     net::SocketAddr,
     process,
     sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
+use alloy_primitives::hex;
+use anyhow::{anyhow, bail};
+use bip39::Mnemonic;
 use clap::Parser;
 use ream::cli::{
     Cli, Commands,
-    account_manager::AccountManagerConfig,
+    account_manager::{AccountManagerCommand, AccountManagerConfig, ValidatorKeysConfig},
     beacon_node::BeaconNodeConfig,
+    db::{DbCommand, DbConfig, DumpConfig, DumpFormat, DumpKind},
     import_keystores::{load_keystore_directory, load_password_from_config, process_password},
     lean_node::LeanNodeConfig,
     validator_node::ValidatorNodeConfig,
     voluntary_exit::VoluntaryExitConfig,
 };
+use ream_account_manager::{
+    deposit_data::{create_deposit_data, save_deposit_data},
+    mnemonic::{generate_mnemonic, mnemonic_from_phrase, mnemonic_to_seed},
+    validator_keys::{ValidatorKeys, create_output_dir, unix_timestamp},
+};
 use ream_api_types_beacon::id::{ID, ValidatorID};
+use ream_chain_beacon::pruning::{BlobRetention, PruningService};
 use ream_chain_lean::{
     genesis as lean_genesis,
     lean_chain::LeanChain,
@@ -24,7 +34,8 @@ use ream_chain_lean::{
 };
 use ream_checkpoint_sync::initialize_db_from_checkpoint;
 use ream_consensus_misc::{
-    constants::beacon::set_genesis_validator_root, misc::compute_epoch_at_slot,
+    constants::beacon::{SLOTS_PER_EPOCH, set_genesis_validator_root},
+    misc::compute_epoch_at_slot,
 };
 use ream_executor::ReamExecutor;
 use ream_network_manager::service::NetworkManagerService;
@@ -44,15 +55,17 @@ use ream_rpc_lean::{config::LeanRpcServerConfig, start_lean_server};
 use ream_storage::{
     db::{ReamDB, reset_db},
     dir::setup_data_dir,
+    maintenance::compact_db,
+    read_cache::ReadCacheSizes,
     tables::Table,
 };
 use ream_validator_beacon::{
-    beacon_api_client::BeaconApiClient, validator::ValidatorService,
-    voluntary_exit::process_voluntary_exit,
+    beacon_api_client::BeaconApiClient, proposer_config::ProposerConfig,
+    validator::ValidatorService, voluntary_exit::process_voluntary_exit,
 };
 use ream_validator_lean::service::ValidatorService as LeanValidatorService;
 use tokio::sync::{RwLock, mpsc};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use tracing_subscriber::EnvFilter;
 
 pub const APP_NAME: &str = "ream";
@@ -91,6 +104,13 @@ fn main() {
         Commands::VoluntaryExit(config) => {
             executor_clone.spawn(async move { run_voluntary_exit(*config).await });
         }
+        Commands::Db(config) => {
+            if let Err(err) = run_db(*config) {
+                error!("Database command failed: {err:?}");
+                process::exit(1);
+            }
+            process::exit(0);
+        }
     }
 
     executor_clone.runtime().block_on(async {
@@ -226,6 +246,16 @@ pub async fn run_lean_node(config: LeanNodeConfig, executor: ReamExecutor) {
 pub async fn run_beacon_node(config: BeaconNodeConfig, executor: ReamExecutor) {
     info!("starting up beacon node...");
 
+    // Initialize prometheus metrics
+    if config.enable_metrics {
+        let address = SocketAddr::new(config.metrics_address, config.metrics_port);
+        prometheus_exporter::start(address).expect("Failed to start prometheus exporter");
+        info!(
+            "Metrics started on {}:{}",
+            config.metrics_address, config.metrics_port
+        );
+    }
+
     set_beacon_network_spec(config.network.clone());
 
     let ream_dir = setup_data_dir(APP_NAME, config.data_dir.clone(), config.ephemeral)
@@ -235,7 +265,14 @@ pub async fn run_beacon_node(config: BeaconNodeConfig, executor: ReamExecutor) {
         reset_db(ream_dir.clone()).expect("Unable to delete database");
     }
 
-    let ream_db = ReamDB::new(ream_dir.clone()).expect("unable to init Ream Database");
+    let ream_db = ReamDB::new(ream_dir.clone())
+        .expect("unable to init Ream Database")
+        .with_state_snapshot_interval(config.state_snapshot_interval)
+        .with_read_cache_sizes(ReadCacheSizes {
+            states: config.state_cache_size,
+            blocks: config.block_cache_size,
+            committees: config.committee_cache_size,
+        });
 
     info!("ream database initialized ");
 
@@ -249,20 +286,47 @@ pub async fn run_beacon_node(config: BeaconNodeConfig, executor: ReamExecutor) {
 
     info!("Database Initialization completed");
 
-    let oldest_root = ream_db
+    // Backfilled blocks have no state, so read the genesis validators root from the newest block
+    let highest_root = ream_db
         .slot_index_provider()
-        .get_oldest_root()
+        .get_highest_root()
         .expect("Failed to access slot index provider")
-        .expect("No oldest root found");
+        .expect("No highest root found");
     set_genesis_validator_root(
         ream_db
             .beacon_state_provider()
-            .get(oldest_root)
+            .get(highest_root)
             .expect("Failed to access beacon state provider")
             .expect("No beacon state found")
             .genesis_validators_root,
     );
 
+    // Blobs are stored before their block is imported, so recent blobs of unknown blocks are kept
+    let blob_retention_window = Duration::from_secs(
+        (beacon_network_spec().min_epochs_for_blob_sidecars_requests + config.blob_prune_margin)
+            * SLOTS_PER_EPOCH
+            * beacon_network_spec().seconds_per_slot,
+    );
+    match ream_db.repair_blob_storage(blob_retention_window) {
+        Ok(summary) => info!("Blob storage checked: {summary:?}"),
+        Err(err) => warn!("Failed to repair blob storage: {err}"),
+    }
+    info!(
+        "Earliest available blob slot: {}",
+        ream_db
+            .earliest_available_blob_slot()
+            .expect("Failed to get earliest available blob slot")
+    );
+
+    let blob_retention = match config.store_all_blobs {
+        true => BlobRetention::Archive,
+        false => BlobRetention::Epochs {
+            margin: config.blob_prune_margin,
+        },
+    };
+    let pruning_service = PruningService::new(ream_db.clone(), blob_retention)
+        .expect("Failed to create pruning service");
+
     let operation_pool = Arc::new(OperationPool::default());
 
     let server_config = RpcServerConfig::new(
@@ -282,6 +346,7 @@ pub async fn run_beacon_node(config: BeaconNodeConfig, executor: ReamExecutor) {
     .expect("Failed to create manager service");
 
     let network_state = network_manager.network_state.clone();
+    let p2p_sender = network_manager.p2p_sender.clone();
 
     let execution_engine = network_manager.beacon_chain.execution_engine.clone();
 
@@ -289,11 +354,16 @@ pub async fn run_beacon_node(config: BeaconNodeConfig, executor: ReamExecutor) {
         network_manager.start().await;
     });
 
+    let pruning_future = executor.spawn(async move {
+        pruning_service.start().await;
+    });
+
     let http_future = executor.spawn(async move {
         start_server(
             server_config,
             ream_db,
             network_state,
+            p2p_sender,
             operation_pool,
             execution_engine,
         )
@@ -307,6 +377,9 @@ pub async fn run_beacon_node(config: BeaconNodeConfig, executor: ReamExecutor) {
         _ = network_future => {
             info!("Network future completed!");
         },
+        _ = pruning_future => {
+            info!("Pruning service stopped!");
+        },
     }
 }
 
@@ -318,6 +391,16 @@ pub async fn run_beacon_node(config: BeaconNodeConfig, executor: ReamExecutor) {
 pub async fn run_validator_node(config: ValidatorNodeConfig, executor: ReamExecutor) {
     info!("starting up validator node...");
 
+    // Initialize prometheus metrics
+    if config.enable_metrics {
+        let address = SocketAddr::new(config.metrics_address, config.metrics_port);
+        prometheus_exporter::start(address).expect("Failed to start prometheus exporter");
+        info!(
+            "Metrics started on {}:{}",
+            config.metrics_address, config.metrics_port
+        );
+    }
+
     set_beacon_network_spec(config.network.clone());
 
     let password = process_password(
@@ -335,11 +418,20 @@ pub async fn run_validator_node(config: ValidatorNodeConfig, executor: ReamExecu
         })
         .collect::<Vec<_>>();
 
+    let proposer_config = ProposerConfig::new(
+        config.proposer_config,
+        config.suggested_fee_recipient,
+        config.enable_builder,
+        config.mev_relay_url,
+    )
+    .expect("Failed to load proposer config");
+
     let validator_service = ValidatorService::new(
         keystores,
-        config.suggested_fee_recipient,
+        proposer_config,
         config.beacon_api_endpoint,
         config.request_timeout,
+        config.builder_header_timeout,
         executor,
     )
     .expect("Failed to create validator service");
@@ -354,6 +446,39 @@ pub async fn run_validator_node(config: ValidatorNodeConfig, executor: ReamExecu
 pub async fn run_account_manager(mut config: AccountManagerConfig) {
     info!("starting up account manager...");
 
+    match config.command.take() {
+        Some(AccountManagerCommand::NewMnemonic(keys_config)) => {
+            let mnemonic = generate_mnemonic().expect("Failed to generate mnemonic");
+            // Printed to stderr rather than logged, so the secret doesn't end up in log files
+            eprintln!("Generated new mnemonic: {mnemonic}");
+            eprintln!(
+                "⚠️  Please save this mnemonic somewhere safe. You will need it to recover your validator keys."
+            );
+            create_validator_keys(&keys_config, &mnemonic, "")
+                .expect("Failed to create validator keys");
+            return;
+        }
+        Some(AccountManagerCommand::ExistingMnemonic(existing_config)) => {
+            // The mnemonic source group requires exactly one of them
+            let phrase = match (existing_config.mnemonic, existing_config.mnemonic_file) {
+                (Some(mnemonic), _) => mnemonic,
+                (None, Some(mnemonic_file)) => {
+                    fs::read_to_string(mnemonic_file).expect("Unable to read mnemonic file")
+                }
+                (None, None) => unreachable!("Either mnemonic or mnemonic-file is required"),
+            };
+            let mnemonic = mnemonic_from_phrase(&phrase).expect("Invalid mnemonic");
+            create_validator_keys(
+                &existing_config.validator_keys,
+                &mnemonic,
+                &existing_config.mnemonic_passphrase,
+            )
+            .expect("Failed to recover validator keys");
+            return;
+        }
+        None => {}
+    }
+
     // Validate the configuration
     config
         .validate()
@@ -364,12 +489,66 @@ pub async fn run_account_manager(mut config: AccountManagerConfig) {
         config.lifetime, config.chunk_size
     );
 
-    let seed_phrase = config.get_seed_phrase();
+    let seed_phrase = config
+        .get_seed_phrase()
+        .expect("Failed to generate seed phrase");
     ream_account_manager::generate_keys(&seed_phrase);
 
     info!("Account manager completed successfully");
 }
 
+/// Derives beacon validator keys from a mnemonic following EIP-2333/EIP-2334, and writes their
+/// EIP-2335 keystores and the deposit data to the output directory.
+fn create_validator_keys(
+    config: &ValidatorKeysConfig,
+    mnemonic: &Mnemonic,
+    mnemonic_passphrase: &str,
+) -> anyhow::Result<()> {
+    let password = process_password(load_password_from_config(
+        config.password_file.as_ref(),
+        config.password.clone(),
+    )?);
+    let seed = mnemonic_to_seed(mnemonic, mnemonic_passphrase);
+    create_output_dir(&config.output_dir)?;
+
+    let network_name = config.network.network.to_string();
+    let end_index = config
+        .validator_start_index
+        .checked_add(config.num_validators)
+        .ok_or_else(|| {
+            anyhow!(
+                "Validator start index {} plus {} validators overflows",
+                config.validator_start_index,
+                config.num_validators
+            )
+        })?;
+    let mut deposits = vec![];
+    for index in config.validator_start_index..end_index {
+        let validator_keys = ValidatorKeys::derive(&seed, index)?;
+        let keystore_path =
+            validator_keys.export_signing_keystore(&config.output_dir, password.as_bytes())?;
+        info!(
+            "Created keystore for validator {index} (0x{}) at {}",
+            hex::encode(validator_keys.signing_keystore.public_key.to_bytes()),
+            keystore_path.display()
+        );
+        deposits.push(create_deposit_data(
+            &validator_keys.signing_keystore,
+            validator_keys.withdrawal_credentials(config.withdrawal_address, config.compounding),
+            config.amount,
+            &config.network,
+            &network_name,
+        )?);
+    }
+
+    let deposit_data_path = config
+        .output_dir
+        .join(format!("deposit_data-{}.json", unix_timestamp()?));
+    save_deposit_data(&deposits, &deposit_data_path)?;
+    info!("Deposit data written to {}", deposit_data_path.display());
+    Ok(())
+}
+
 /// Runs the voluntary exit process.
 ///
 /// This function initializes the voluntary exit process by setting up the network specification,
@@ -427,6 +606,113 @@ pub async fn run_voluntary_exit(config: VoluntaryExitConfig) {
     }
 }
 
+/// Runs one of the `ream db` maintenance commands against the beacon node database.
+///
+/// The database file is locked while a node has it open, so these commands must be run while the
+/// beacon node is stopped.
+fn run_db(config: DbConfig) -> anyhow::Result<()> {
+    set_beacon_network_spec(config.network.clone());
+
+    let ream_dir = setup_data_dir(APP_NAME, config.data_dir, false)
+        .map_err(|err| anyhow!("Unable to initialize database directory: {err}"))?;
+
+    if let DbCommand::Compact = config.command {
+        match compact_db(&ream_dir)? {
+            true => info!("Database compacted"),
+            false => info!("Database is already compact"),
+        }
+        return Ok(());
+    }
+
+    let ream_db = ReamDB::new(ream_dir)?;
+    match config.command {
+        DbCommand::Stats => {
+            for table in ream_db.table_info()? {
+                info!(
+                    "{}: entries={} stored_bytes={} metadata_bytes={} fragmented_bytes={}",
+                    table.name,
+                    table.entries,
+                    table.stored_bytes,
+                    table.metadata_bytes,
+                    table.fragmented_bytes
+                );
+            }
+        }
+        DbCommand::Dump(dump_config) => dump_db_entry(&ream_db, dump_config)?,
+        DbCommand::CheckIndices => {
+            let issues = ream_db.check_indices()?;
+            for issue in &issues {
+                warn!("{issue}");
+            }
+            if !issues.is_empty() {
+                bail!("Found {} index inconsistencies", issues.len());
+            }
+            info!("All indices are consistent");
+        }
+        DbCommand::Compact => unreachable!("Compaction is handled before opening the database"),
+        DbCommand::Rollback(rollback_config) => {
+            let summary = ream_db.rollback_to_finalized_epoch(rollback_config.epoch)?;
+            info!(
+                "Rolled back to epoch {}, removed {} blocks, {} states, {} cold states and {} latest messages",
+                rollback_config.epoch,
+                summary.blocks,
+                summary.states,
+                summary.cold_states,
+                summary.latest_messages
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Writes a block or state, looked up by root or by the slot of the canonical block, as SSZ or
+/// JSON.
+fn dump_db_entry(ream_db: &ReamDB, config: DumpConfig) -> anyhow::Result<()> {
+    let block_root = match (config.root, config.slot) {
+        (Some(root), _) => root,
+        (None, Some(slot)) => ream_db
+            .slot_index_provider()
+            .get(slot)?
+            .ok_or_else(|| anyhow!("No canonical block at slot {slot}"))?,
+        (None, None) => bail!("Either --root or --slot must be provided"),
+    };
+
+    let bytes = match config.kind {
+        DumpKind::Block => {
+            let block = ream_db
+                .beacon_block_provider()
+                .get(block_root)?
+                .ok_or_else(|| anyhow!("No block found for root {block_root}"))?;
+            match config.format {
+                DumpFormat::Ssz => ssz::Encode::as_ssz_bytes(&block),
+                DumpFormat::Json => serde_json::to_vec_pretty(&block)?,
+            }
+        }
+        DumpKind::State => {
+            // A state root given with --root is mapped to the root of its block first
+            let block_root = ream_db
+                .state_root_index_provider()
+                .get(block_root)?
+                .unwrap_or(block_root);
+            let state = ream_db
+                .beacon_state_provider()
+                .get(block_root)?
+                .ok_or_else(|| anyhow!("No state found for root {block_root}"))?;
+            match config.format {
+                DumpFormat::Ssz => ssz::Encode::as_ssz_bytes(&state),
+                DumpFormat::Json => serde_json::to_vec_pretty(&state)?,
+            }
+        }
+    };
+
+    // Not written to stdout, which the logs go to
+    fs::write(&config.output, bytes)?;
+    info!("Wrote {:?} to {}", config.kind, config.output.display());
+
+    Ok(())
+}
+
 /// Calculates the current epoch from genesis time
 fn get_current_epoch(genesis_time: u64) -> u64 {
     compute_epoch_at_slot(
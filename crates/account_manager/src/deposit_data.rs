@@ -0,0 +1,131 @@
+use std::{fs, path::Path};
+
+use alloy_primitives::{B256, hex};
+use anyhow::anyhow;
+use ream_bls::traits::Signable;
+use ream_consensus_misc::{
+    constants::beacon::DOMAIN_DEPOSIT,
+    deposit_data::DepositData,
+    deposit_message::DepositMessage,
+    misc::{compute_domain, compute_signing_root},
+};
+use ream_keystore::keystore::Keystore;
+use ream_network_spec::networks::BeaconNetworkSpec;
+use serde::{Deserialize, Serialize};
+use tree_hash::TreeHash;
+
+/// The version of the deposit data format, matching the one of `staking-deposit-cli` so the
+/// output can be uploaded to the staking launchpad.
+pub const DEPOSIT_CLI_VERSION: &str = "2.7.0";
+
+/// A deposit entry as produced by `staking-deposit-cli`. Byte fields are hex encoded without
+/// the `0x` prefix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepositDataJson {
+    pub pubkey: String,
+    pub withdrawal_credentials: String,
+    pub amount: u64,
+    pub signature: String,
+    pub deposit_message_root: String,
+    pub deposit_data_root: String,
+    pub fork_version: String,
+    pub network_name: String,
+    pub deposit_cli_version: String,
+}
+
+/// Signs a [DepositMessage] with the validator signing key and returns the resulting deposit
+/// entry.
+pub fn create_deposit_data(
+    signing_keystore: &Keystore,
+    withdrawal_credentials: B256,
+    amount: u64,
+    network_spec: &BeaconNetworkSpec,
+    network_name: &str,
+) -> anyhow::Result<DepositDataJson> {
+    let deposit_message = DepositMessage {
+        public_key: signing_keystore.public_key.clone(),
+        withdrawal_credentials,
+        amount,
+    };
+    // Deposits are valid across forks, so the domain is always computed with the genesis fork
+    // version and an empty genesis validators root.
+    let domain = compute_domain(
+        DOMAIN_DEPOSIT,
+        Some(network_spec.genesis_fork_version),
+        None,
+    );
+    let signature = signing_keystore
+        .private_key
+        .sign(compute_signing_root(&deposit_message, domain).as_ref())
+        .map_err(|err| anyhow!("Failed to sign deposit message: {err}"))?;
+    let deposit_data = DepositData {
+        public_key: deposit_message.public_key.clone(),
+        withdrawal_credentials,
+        amount,
+        signature,
+    };
+
+    Ok(DepositDataJson {
+        pubkey: hex::encode(deposit_data.public_key.to_bytes()),
+        withdrawal_credentials: hex::encode(withdrawal_credentials),
+        amount,
+        signature: hex::encode(deposit_data.signature.to_slice()),
+        deposit_message_root: hex::encode(deposit_message.tree_hash_root()),
+        deposit_data_root: hex::encode(deposit_data.tree_hash_root()),
+        fork_version: hex::encode(network_spec.genesis_fork_version),
+        network_name: network_name.to_string(),
+        deposit_cli_version: DEPOSIT_CLI_VERSION.to_string(),
+    })
+}
+
+pub fn save_deposit_data<P: AsRef<Path>>(
+    deposit_data: &[DepositDataJson],
+    path: P,
+) -> anyhow::Result<()> {
+    fs::write(path, serde_json::to_string(deposit_data)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_network_spec::networks::MAINNET;
+
+    use super::*;
+    use crate::{
+        mnemonic::{mnemonic_from_phrase, mnemonic_to_seed},
+        validator_keys::ValidatorKeys,
+    };
+
+    #[test]
+    fn test_create_deposit_data() {
+        let mnemonic = mnemonic_from_phrase(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        )
+        .unwrap();
+        let validator_keys = ValidatorKeys::derive(&mnemonic_to_seed(&mnemonic, ""), 0).unwrap();
+
+        let deposit_data = create_deposit_data(
+            &validator_keys.signing_keystore,
+            validator_keys.withdrawal_credentials(None, false),
+            32_000_000_000,
+            &MAINNET,
+            "mainnet",
+        )
+        .unwrap();
+
+        assert_eq!(
+            deposit_data,
+            DepositDataJson {
+                pubkey: "b3e445d43871965d890a398f719348a1405ac72e35b92727cc570026f54471af7ea7b2040622a8fd0b5bfb2a209b5911".to_string(),
+                withdrawal_credentials: "00eca1f12f398e3ceef109f5f76d8e99f9105e800a90390f1a18895919fd4b3b".to_string(),
+                amount: 32_000_000_000,
+                signature: "91a123edabc90547f7ac0320a4ea2967940f3b1a5bef396d2c36ff2a4cdbf5c117b257983d1044b9e954303b29ce6962006c6a4a5fb68cf97adcc77c7df47cb83ce8a910f921b94e36c94e2ca13054b4d1684562f43075373fe3045ee9b0b364".to_string(),
+                deposit_message_root: "e5f649f0154082253653461a36815b23c934a01d894fdc1c6dd91785aeac1d24".to_string(),
+                deposit_data_root: "54d660cc52c015c9ceb1816c877c176b5e893a50f50ff10acfd91759448b3516".to_string(),
+                fork_version: "00000000".to_string(),
+                network_name: "mainnet".to_string(),
+                deposit_cli_version: DEPOSIT_CLI_VERSION.to_string(),
+            }
+        );
+    }
+}
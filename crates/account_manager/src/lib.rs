@@ -1,3 +1,7 @@
+pub mod deposit_data;
+pub mod mnemonic;
+pub mod validator_keys;
+
 use hashsig::signature::{
     SignatureScheme,
     generalized_xmss::instantiations_poseidon::lifetime_2_to_the_20::winternitz::SIGWinternitzLifetime20W4,
@@ -0,0 +1,85 @@
+use anyhow::anyhow;
+use bip39::{Language, Mnemonic};
+
+/// The number of words of generated mnemonics
+pub const MNEMONIC_WORD_COUNT: usize = 24;
+
+/// Generates a new random 24-word BIP-39 mnemonic.
+pub fn generate_mnemonic() -> anyhow::Result<Mnemonic> {
+    Mnemonic::generate_in(Language::English, MNEMONIC_WORD_COUNT)
+        .map_err(|err| anyhow!("Failed to generate mnemonic: {err:?}"))
+}
+
+/// Parses an existing English BIP-39 mnemonic phrase of 12, 15, 18, 21 or 24 words, validating
+/// its checksum.
+pub fn mnemonic_from_phrase(phrase: &str) -> anyhow::Result<Mnemonic> {
+    Mnemonic::parse_in(Language::English, phrase.trim())
+        .map_err(|err| anyhow!("Invalid mnemonic phrase: {err:?}"))
+}
+
+/// Returns the 64-byte BIP-39 seed of a mnemonic and an optional passphrase, used as input for
+/// EIP-2333 key derivation.
+pub fn mnemonic_to_seed(mnemonic: &Mnemonic, passphrase: &str) -> [u8; 64] {
+    mnemonic.to_seed(passphrase)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::hex;
+
+    use super::*;
+
+    // Test vectors from https://github.com/trezor/python-mnemonic/blob/master/vectors.json
+    #[test]
+    fn test_mnemonic_to_seed() {
+        for (phrase, seed) in [
+            (
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+                hex!(
+                    "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+                ),
+            ),
+            (
+                "legal winner thank year wave sausage worth useful legal winner thank year wave sausage worth useful legal will",
+                hex!(
+                    "f2b94508732bcbacbcc020faefecfc89feafa6649a5491b8c952cede496c214a0c7b3c392d168748f2d4a612bada0753b52a1c7ac53c1e93abd5c6320b9e95dd"
+                ),
+            ),
+            (
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art",
+                hex!(
+                    "bda85446c68413707090a52022edd26a1c9462295029f2e60cd7c4f2bbd3097170af7a4d73245cafa9c3cca8d561a7c3de6f5d4a10be8ed2a5e608d68f92fcc8"
+                ),
+            ),
+        ] {
+            let mnemonic = mnemonic_from_phrase(phrase).unwrap();
+            assert_eq!(mnemonic_to_seed(&mnemonic, "TREZOR"), seed);
+        }
+    }
+
+    #[test]
+    fn test_mnemonic_word_counts() {
+        for word_count in [12, 15, 18, 21, 24] {
+            let mnemonic = Mnemonic::generate_in(Language::English, word_count).unwrap();
+            let recovered = mnemonic_from_phrase(&mnemonic.to_string()).unwrap();
+            assert_eq!(recovered.word_count(), word_count);
+        }
+    }
+
+    #[test]
+    fn test_generated_mnemonic_roundtrip() {
+        let mnemonic = generate_mnemonic().unwrap();
+        assert_eq!(mnemonic.word_count(), MNEMONIC_WORD_COUNT);
+        let recovered = mnemonic_from_phrase(&mnemonic.to_string()).unwrap();
+
+        assert_eq!(
+            mnemonic_to_seed(&mnemonic, ""),
+            mnemonic_to_seed(&recovered, "")
+        );
+    }
+
+    #[test]
+    fn test_invalid_mnemonic_is_rejected() {
+        assert!(mnemonic_from_phrase("abandon abandon abandon").is_err());
+    }
+}
@@ -0,0 +1,179 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use alloy_primitives::{Address, B256};
+use anyhow::anyhow;
+use ream_consensus_misc::constants::beacon::{
+    BLS_WITHDRAWAL_PREFIX, COMPOUNDING_WITHDRAWAL_PREFIX, ETH1_ADDRESS_WITHDRAWAL_PREFIX,
+};
+use ream_keystore::{
+    key_derivation::{derive_secret_key_from_path, signing_key_path, withdrawal_key_path},
+    keystore::{EncryptedKeystore, KdfParams, Keystore},
+};
+use sha2::{Digest, Sha256};
+
+/// The signing and withdrawal keys of a validator, derived from a mnemonic seed following
+/// EIP-2334.
+pub struct ValidatorKeys {
+    pub index: u32,
+    pub signing_keystore: Keystore,
+    pub withdrawal_keystore: Keystore,
+}
+
+impl ValidatorKeys {
+    pub fn derive(seed: &[u8], index: u32) -> anyhow::Result<Self> {
+        Ok(Self {
+            index,
+            signing_keystore: derive_keystore(seed, &signing_key_path(index))?,
+            withdrawal_keystore: derive_keystore(seed, &withdrawal_key_path(index))?,
+        })
+    }
+
+    /// Returns the withdrawal credentials of the validator. If `withdrawal_address` is set, the
+    /// credentials point to that execution address, otherwise to the BLS withdrawal key.
+    pub fn withdrawal_credentials(
+        &self,
+        withdrawal_address: Option<Address>,
+        compounding: bool,
+    ) -> B256 {
+        let mut withdrawal_credentials = B256::ZERO;
+        match withdrawal_address {
+            Some(address) => {
+                let prefix = match compounding {
+                    true => COMPOUNDING_WITHDRAWAL_PREFIX,
+                    false => ETH1_ADDRESS_WITHDRAWAL_PREFIX,
+                };
+                withdrawal_credentials[..1].copy_from_slice(prefix);
+                withdrawal_credentials[12..].copy_from_slice(address.as_slice());
+            }
+            None => {
+                withdrawal_credentials.copy_from_slice(&Sha256::digest(
+                    self.withdrawal_keystore.public_key.to_bytes(),
+                ));
+                withdrawal_credentials[..1].copy_from_slice(BLS_WITHDRAWAL_PREFIX);
+            }
+        }
+        withdrawal_credentials
+    }
+
+    /// Encrypts the signing key as an EIP-2335 keystore and writes it to `output_dir`.
+    pub fn export_signing_keystore(
+        &self,
+        output_dir: &Path,
+        password: &[u8],
+    ) -> anyhow::Result<PathBuf> {
+        let path = signing_key_path(self.index);
+        let encrypted_keystore = EncryptedKeystore::encrypt(
+            &self.signing_keystore,
+            password,
+            path.clone(),
+            KdfParams::scrypt_with_random_salt(),
+        )?;
+        let file_path = output_dir.join(format!(
+            "keystore-{}-{}.json",
+            path.replace('/', "_"),
+            unix_timestamp()?
+        ));
+        encrypted_keystore.save_to_file(&file_path)?;
+        Ok(file_path)
+    }
+}
+
+fn derive_keystore(seed: &[u8], path: &str) -> anyhow::Result<Keystore> {
+    let private_key = derive_secret_key_from_path(seed, path)?;
+    let public_key = private_key
+        .public_key()
+        .map_err(|err| anyhow!("Failed to derive public key for {path}: {err}"))?;
+    Ok(Keystore {
+        public_key,
+        private_key,
+    })
+}
+
+pub fn unix_timestamp() -> anyhow::Result<u64> {
+    Ok(SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(|err| anyhow!("System time is before the unix epoch: {err:?}"))?
+        .as_secs())
+}
+
+pub fn create_output_dir(output_dir: &Path) -> anyhow::Result<()> {
+    fs::create_dir_all(output_dir).map_err(|err| {
+        anyhow!(
+            "Failed to create output directory {}: {err:?}",
+            output_dir.display()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{address, b256, hex};
+
+    use super::*;
+    use crate::mnemonic::{mnemonic_from_phrase, mnemonic_to_seed};
+
+    const MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn seed() -> [u8; 64] {
+        mnemonic_to_seed(&mnemonic_from_phrase(MNEMONIC).unwrap(), "")
+    }
+
+    // The keys at the EIP-2334 paths m/12381/3600/i/0/0 and m/12381/3600/i/0
+    #[test]
+    fn test_derive_validator_keys() {
+        let seed = seed();
+        for (index, signing_public_key, withdrawal_public_key) in [
+            (
+                0,
+                hex!(
+                    "b3e445d43871965d890a398f719348a1405ac72e35b92727cc570026f54471af7ea7b2040622a8fd0b5bfb2a209b5911"
+                ),
+                hex!(
+                    "8ebe599559cbf3abbc6a72b25d8bc13fd9b5075283fcd9ec47b2a0bf6c5148a2e9e615b181e5b2c03abe63818ef70c61"
+                ),
+            ),
+            (
+                1,
+                hex!(
+                    "aeb399bf5648b0e9980c1731824c269631a41320c3d7f730c40587e1a37a5e1c8b5755fd90080a7b3fb90d3fd419c0a7"
+                ),
+                hex!(
+                    "8b52e53cb73723a8f60fe279edfb277f48ec3ae54070326113c7277decd0f1dd9fab5267dfe40604c2614028be7860b1"
+                ),
+            ),
+        ] {
+            let validator_keys = ValidatorKeys::derive(&seed, index).unwrap();
+            assert_eq!(
+                validator_keys.signing_keystore.public_key.to_bytes(),
+                signing_public_key
+            );
+            assert_eq!(
+                validator_keys.withdrawal_keystore.public_key.to_bytes(),
+                withdrawal_public_key
+            );
+        }
+    }
+
+    #[test]
+    fn test_withdrawal_credentials() {
+        let validator_keys = ValidatorKeys::derive(&seed(), 0).unwrap();
+        let withdrawal_address = address!("0x003Fb16e421E42084EBC54bcdc7F0fa344cF9316");
+
+        assert_eq!(
+            validator_keys.withdrawal_credentials(None, false),
+            b256!("0x00eca1f12f398e3ceef109f5f76d8e99f9105e800a90390f1a18895919fd4b3b")
+        );
+        assert_eq!(
+            validator_keys.withdrawal_credentials(Some(withdrawal_address), false),
+            b256!("0x010000000000000000000000003fb16e421e42084ebc54bcdc7f0fa344cf9316")
+        );
+        assert_eq!(
+            validator_keys.withdrawal_credentials(Some(withdrawal_address), true),
+            b256!("0x020000000000000000000000003fb16e421e42084ebc54bcdc7f0fa344cf9316")
+        );
+    }
+}
@@ -1,5 +1,8 @@
 use ream_consensus_beacon::{
-    electra::{beacon_block::BeaconBlock, blinded_beacon_block::BlindedBeaconBlock},
+    electra::{
+        beacon_block::{BeaconBlock, SignedBeaconBlock},
+        blinded_beacon_block::BlindedBeaconBlock,
+    },
     execution_engine::rpc_types::get_blobs::Blob,
     polynomial_commitments::kzg_proof::KZGProof,
 };
@@ -48,3 +51,28 @@ pub struct FullBlockData {
     pub kzg_proofs: Vec<KZGProof>,
     pub blobs: Vec<Blob>,
 }
+
+/// A signed block along with the blobs of its commitments, which the beacon node publishes as
+/// sidecars.
+#[derive(Debug, Clone, Serialize, Deserialize, Encode, Decode)]
+pub struct SignedBlockContents {
+    pub signed_block: SignedBeaconBlock,
+    pub kzg_proofs: Vec<KZGProof>,
+    pub blobs: Vec<Blob>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct BlockRewards {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_index: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub total: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub attestations: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub sync_aggregate: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub proposer_slashings: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub attester_slashings: u64,
+}
@@ -12,3 +12,33 @@ pub struct BeaconCommitteeSubscription {
     pub slot: u64,
     pub is_aggregator: bool,
 }
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CommitteeData {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub index: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub slot: u64,
+    #[serde(with = "serde_utils::quoted_u64_vec")]
+    pub validators: Vec<u64>,
+}
+
+impl CommitteeData {
+    pub fn new(index: u64, slot: u64, validators: Vec<u64>) -> Self {
+        Self {
+            index,
+            slot,
+            validators,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SyncCommitteeSubscription {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub validator_index: u64,
+    #[serde(with = "serde_utils::quoted_u64_vec")]
+    pub sync_committee_indices: Vec<u64>,
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub until_epoch: u64,
+}
@@ -2,7 +2,7 @@ use ream_bls::PublicKey;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 
-#[derive(Debug, Deserialize, Serialize, Encode, Decode)]
+#[derive(Debug, Clone, Deserialize, Serialize, Encode, Decode)]
 pub struct ProposerDuty {
     #[serde(rename = "pubkey")]
     pub public_key: PublicKey,
@@ -12,7 +12,7 @@ pub struct ProposerDuty {
     pub slot: u64,
 }
 
-#[derive(Debug, Deserialize, Serialize, Encode, Decode)]
+#[derive(Debug, Clone, Deserialize, Serialize, Encode, Decode)]
 pub struct AttesterDuty {
     #[serde(rename = "pubkey")]
     pub public_key: PublicKey,
@@ -28,7 +28,7 @@ pub struct AttesterDuty {
     pub slot: u64,
 }
 
-#[derive(Debug, Deserialize, Serialize, Encode, Decode)]
+#[derive(Debug, Clone, Deserialize, Serialize, Encode, Decode)]
 pub struct SyncCommitteeDuty {
     #[serde(rename = "pubkey")]
     pub public_key: PublicKey,
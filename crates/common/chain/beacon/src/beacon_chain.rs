@@ -8,7 +8,9 @@ use ream_consensus_beacon::{
 use ream_consensus_misc::constants::beacon::genesis_validators_root;
 use ream_execution_engine::ExecutionEngine;
 use ream_fork_choice::{
-    handlers::{on_attestation, on_attester_slashing, on_block, on_tick},
+    handlers::{
+        on_attestation, on_attester_slashing, on_block, on_signature_verified_block, on_tick,
+    },
     store::Store,
 };
 use ream_network_spec::networks::beacon_network_spec;
@@ -52,6 +54,23 @@ impl BeaconChain {
         Ok(())
     }
 
+    /// Imports a block whose proposer signature was already verified by the caller, for example
+    /// as part of a batch during range sync.
+    pub async fn process_signature_verified_block(
+        &self,
+        signed_block: SignedBeaconBlock,
+    ) -> anyhow::Result<()> {
+        let mut store = self.store.lock().await;
+        on_signature_verified_block(
+            &mut store,
+            &signed_block,
+            &self.execution_engine,
+            signed_block.message.slot >= beacon_network_spec().slot_n_days_ago(17),
+        )
+        .await?;
+        Ok(())
+    }
+
     pub async fn process_attester_slashing(
         &self,
         attester_slashing: AttesterSlashing,
@@ -1 +1,2 @@
 pub mod beacon_chain;
+pub mod pruning;
@@ -0,0 +1,127 @@
+use std::time::Duration;
+
+use alloy_primitives::B256;
+use ream_consensus_misc::{checkpoint::Checkpoint, misc::compute_start_slot_at_epoch};
+use ream_metrics::{
+    BEACON_DB_EARLIEST_BLOB_SLOT, BEACON_DB_FROZEN_STATES, BEACON_DB_PRUNED_ENTRIES,
+    BEACON_DB_PRUNING_TIME, inc_int_counter_vec_by, set_int_gauge_vec, start_timer_vec, stop_timer,
+};
+use ream_network_spec::networks::beacon_network_spec;
+use ream_storage::{db::ReamDB, tables::Field};
+use tokio::time::interval;
+use tracing::{info, warn};
+
+/// How long blobs are kept after finalization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlobRetention {
+    /// Keep blobs for `MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS` plus `margin` epochs
+    Epochs { margin: u64 },
+    /// Never prune blobs
+    Archive,
+}
+
+/// Cleans up the database in the background whenever the finalized checkpoint advances.
+///
+/// Pruning runs on a blocking thread, so block and attestation processing only wait on it while
+/// its write transaction commits.
+pub struct PruningService {
+    db: ReamDB,
+    finalized_checkpoint: Checkpoint,
+    blob_retention: BlobRetention,
+}
+
+impl PruningService {
+    pub fn new(db: ReamDB, blob_retention: BlobRetention) -> anyhow::Result<Self> {
+        let finalized_checkpoint = db.finalized_checkpoint_provider().get()?;
+        Ok(Self {
+            db,
+            finalized_checkpoint,
+            blob_retention,
+        })
+    }
+
+    pub async fn start(mut self) {
+        let mut interval = interval(Duration::from_secs(beacon_network_spec().seconds_per_slot));
+        loop {
+            interval.tick().await;
+
+            let finalized_checkpoint = match self.db.finalized_checkpoint_provider().get() {
+                Ok(finalized_checkpoint) => finalized_checkpoint,
+                Err(err) => {
+                    warn!("Failed to get finalized checkpoint for pruning: {err}");
+                    continue;
+                }
+            };
+            if finalized_checkpoint.epoch <= self.finalized_checkpoint.epoch {
+                continue;
+            }
+
+            let db = self.db.clone();
+            let previous_finalized_root = self.finalized_checkpoint.root;
+            let blob_retention = self.blob_retention;
+            match tokio::task::spawn_blocking(move || {
+                prune_finalized(
+                    &db,
+                    previous_finalized_root,
+                    finalized_checkpoint,
+                    blob_retention,
+                )
+            })
+            .await
+            {
+                Ok(Ok(())) => self.finalized_checkpoint = finalized_checkpoint,
+                Ok(Err(err)) => warn!("Failed to prune the database: {err}"),
+                Err(err) => warn!("Pruning task panicked: {err}"),
+            }
+        }
+    }
+}
+
+fn prune_finalized(
+    db: &ReamDB,
+    previous_finalized_root: B256,
+    finalized_checkpoint: Checkpoint,
+    blob_retention: BlobRetention,
+) -> anyhow::Result<()> {
+    let timer = start_timer_vec(&BEACON_DB_PRUNING_TIME, &["prune"]);
+    let summary = db.prune_finalized(previous_finalized_root, finalized_checkpoint)?;
+    stop_timer(timer);
+
+    for (table, count) in [
+        ("beacon_block", summary.blocks),
+        ("beacon_state", summary.states),
+        ("checkpoint_states", summary.checkpoint_states),
+        ("latest_messages", summary.latest_messages),
+        ("block_timeliness", summary.block_timeliness),
+        ("blobs", summary.blobs),
+    ] {
+        inc_int_counter_vec_by(&BEACON_DB_PRUNED_ENTRIES, count as u64, &[table]);
+    }
+    info!(
+        "Pruned the database at finalized epoch {}: {summary:?}",
+        finalized_checkpoint.epoch
+    );
+
+    let timer = start_timer_vec(&BEACON_DB_PRUNING_TIME, &["freeze"]);
+    let frozen = db.freeze_finalized_states(finalized_checkpoint.root)?;
+    stop_timer(timer);
+    inc_int_counter_vec_by(&BEACON_DB_FROZEN_STATES, frozen as u64, &[]);
+
+    if let BlobRetention::Epochs { margin } = blob_retention {
+        let retained_epochs = beacon_network_spec().min_epochs_for_blob_sidecars_requests + margin;
+        let before_slot =
+            compute_start_slot_at_epoch(finalized_checkpoint.epoch.saturating_sub(retained_epochs));
+
+        let timer = start_timer_vec(&BEACON_DB_PRUNING_TIME, &["blobs"]);
+        let pruned = db.prune_blobs(before_slot)?;
+        stop_timer(timer);
+        inc_int_counter_vec_by(&BEACON_DB_PRUNED_ENTRIES, pruned as u64, &["blobs"]);
+    }
+    set_int_gauge_vec(
+        &BEACON_DB_EARLIEST_BLOB_SLOT,
+        db.earliest_available_blob_slot()? as i64,
+        &[],
+    );
+
+    Ok(())
+}
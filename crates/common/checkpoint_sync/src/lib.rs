@@ -5,7 +5,7 @@ use alloy_primitives::B256;
 use anyhow::{anyhow, ensure};
 use checkpoint::get_checkpoint_sync_sources;
 use ream_consensus_beacon::{
-    blob_sidecar::{BlobIdentifier, BlobSidecar},
+    blob_sidecar::BlobSidecar,
     electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState},
     execution_engine::rpc_types::get_blobs::BlobAndProofV1,
 };
@@ -149,14 +149,17 @@ async fn initialize_blobs_in_db(
     .json::<BlobSidercars>()
     .await?;
 
-    for blob_sidecar in blob_sidecar.data {
-        store.blobs_and_proofs_provider().insert(
-            BlobIdentifier::new(beacon_block_root, blob_sidecar.index),
-            BlobAndProofV1 {
-                blob: blob_sidecar.blob,
-                proof: blob_sidecar.kzg_proof,
-            },
-        )?;
-    }
+    store.blobs_and_proofs_provider().insert_block_blobs(
+        beacon_block_root,
+        blob_sidecar.data.into_iter().map(|blob_sidecar| {
+            (
+                blob_sidecar.index,
+                BlobAndProofV1 {
+                    blob: blob_sidecar.blob,
+                    proof: blob_sidecar.kzg_proof,
+                },
+            )
+        }),
+    )?;
     Ok(())
 }
@@ -84,7 +84,10 @@ use crate::{
     deposit::Deposit,
     deposit_request::DepositRequest,
     eth_1_block::Eth1Block,
-    execution_engine::{engine_trait::ExecutionApi, new_payload_request::NewPayloadRequest},
+    execution_engine::{
+        engine_trait::{ExecutionApi, ExecutionEngineError},
+        new_payload_request::NewPayloadRequest,
+    },
     helpers::xor,
     historical_summary::HistoricalSummary,
     pending_consolidation::PendingConsolidation,
@@ -2646,7 +2649,8 @@ impl BeaconState {
                         parent_beacon_block_root: self.latest_block_header.parent_root,
                         execution_requests: body.execution_requests.clone()
                     })
-                    .await?
+                    .await
+                    .map_err(ExecutionEngineError)?
             );
         }
 
@@ -2678,13 +2682,40 @@ impl BeaconState {
         signed_block: &SignedBeaconBlock,
         validate_result: bool,
         execution_engine: &Option<impl ExecutionApi>,
+    ) -> anyhow::Result<()> {
+        self.apply_block(
+            signed_block,
+            validate_result,
+            validate_result,
+            execution_engine,
+        )
+        .await
+    }
+
+    /// Runs the state transition for a block whose proposer signature was already verified, for
+    /// example as part of a batch during sync. The state root is still checked.
+    pub async fn state_transition_with_verified_signature(
+        &mut self,
+        signed_block: &SignedBeaconBlock,
+        execution_engine: &Option<impl ExecutionApi>,
+    ) -> anyhow::Result<()> {
+        self.apply_block(signed_block, false, true, execution_engine)
+            .await
+    }
+
+    async fn apply_block(
+        &mut self,
+        signed_block: &SignedBeaconBlock,
+        verify_signature: bool,
+        validate_result: bool,
+        execution_engine: &Option<impl ExecutionApi>,
     ) -> anyhow::Result<()> {
         let block = &signed_block.message;
         // Process slots (including those with no blocks) since block
         self.process_slots(block.slot)?;
 
         // Verify signature
-        if validate_result {
+        if verify_signature {
             ensure!(self.verify_block_header_signature(&signed_block.signed_header())?)
         }
         // Process block
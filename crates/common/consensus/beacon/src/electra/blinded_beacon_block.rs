@@ -1,11 +1,17 @@
 use alloy_primitives::B256;
+use anyhow::ensure;
 use ream_bls::BLSSignature;
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
 use tree_hash::TreeHash;
 use tree_hash_derive::TreeHash;
 
-use crate::electra::blinded_beacon_block_body::BlindedBeaconBlockBody;
+use crate::electra::{
+    beacon_block::{BeaconBlock, SignedBeaconBlock},
+    beacon_block_body::BeaconBlockBody,
+    blinded_beacon_block_body::BlindedBeaconBlockBody,
+    execution_payload::ExecutionPayload,
+};
 
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
 pub struct BlindedBeaconBlock {
@@ -29,3 +35,41 @@ pub struct SignedBlindedBeaconBlock {
     pub message: BlindedBeaconBlock,
     pub signature: BLSSignature,
 }
+
+impl SignedBlindedBeaconBlock {
+    /// Replaces the execution payload header with the full execution payload. The signature stays
+    /// valid, as both blocks have the same hash tree root.
+    pub fn unblind(self, execution_payload: ExecutionPayload) -> anyhow::Result<SignedBeaconBlock> {
+        let block = self.message;
+        let body = block.body;
+        ensure!(
+            execution_payload.to_execution_payload_header() == body.execution_payload_header,
+            "execution payload does not match the execution payload header of the blinded block"
+        );
+
+        Ok(SignedBeaconBlock {
+            message: BeaconBlock {
+                slot: block.slot,
+                proposer_index: block.proposer_index,
+                parent_root: block.parent_root,
+                state_root: block.state_root,
+                body: BeaconBlockBody {
+                    randao_reveal: body.randao_reveal,
+                    eth1_data: body.eth1_data,
+                    graffiti: body.graffiti,
+                    proposer_slashings: body.proposer_slashings,
+                    attester_slashings: body.attester_slashings,
+                    attestations: body.attestations,
+                    deposits: body.deposits,
+                    voluntary_exits: body.voluntary_exits,
+                    sync_aggregate: body.sync_aggregate,
+                    execution_payload,
+                    bls_to_execution_changes: body.bls_to_execution_changes,
+                    blob_kzg_commitments: body.blob_kzg_commitments,
+                    execution_requests: body.execution_requests,
+                },
+            },
+            signature: self.signature,
+        })
+    }
+}
@@ -1,8 +1,14 @@
 use alloy_primitives::B256;
 use async_trait::async_trait;
+use thiserror::Error;
 
 use super::{new_payload_request::NewPayloadRequest, rpc_types::get_blobs::BlobAndProofV1};
 
+/// A request to the execution engine failed, which says nothing about the validity of the payload.
+#[derive(Debug, Error)]
+#[error("Execution engine request failed: {0}")]
+pub struct ExecutionEngineError(pub anyhow::Error);
+
 #[async_trait]
 pub trait ExecutionApi {
     /// Return ``True`` if and only if ``new_payload_request`` is valid with respect to
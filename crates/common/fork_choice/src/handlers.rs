@@ -1,8 +1,12 @@
+use std::sync::Arc;
+
 use alloy_primitives::{B256, map::HashSet};
 use anyhow::{anyhow, ensure};
 use ream_consensus_beacon::{
-    attestation::Attestation, attester_slashing::AttesterSlashing,
-    electra::beacon_block::SignedBeaconBlock, execution_engine::engine_trait::ExecutionApi,
+    attestation::Attestation,
+    attester_slashing::AttesterSlashing,
+    electra::beacon_block::SignedBeaconBlock,
+    execution_engine::engine_trait::{ExecutionApi, ExecutionEngineError},
     predicates::is_slashable_attestation_data,
 };
 use ream_consensus_misc::{
@@ -10,19 +14,62 @@ use ream_consensus_misc::{
 };
 use ream_network_spec::networks::beacon_network_spec;
 use ream_storage::{
-    errors::StoreError,
+    read_cache::BlockSummary,
     tables::{Field, Table},
 };
+use thiserror::Error;
 use tree_hash::TreeHash;
 
 use crate::store::Store;
 
+/// The block failed consensus validation, as opposed to failing to import for a local reason like
+/// the database or the execution engine being unavailable. Callers find it with
+/// `anyhow::Error::downcast_ref`.
+#[derive(Debug, Error)]
+#[error("Invalid block: {0}")]
+pub struct InvalidBlockError(pub anyhow::Error);
+
 /// Run ``on_block`` upon receiving a new block.
 pub async fn on_block(
     store: &mut Store,
     signed_block: &SignedBeaconBlock,
     execution_engine: &Option<impl ExecutionApi>,
     verify_blob_availability: bool,
+) -> anyhow::Result<()> {
+    import_block(
+        store,
+        signed_block,
+        execution_engine,
+        verify_blob_availability,
+        false,
+    )
+    .await
+}
+
+/// Run ``on_block`` for a block whose proposer signature was already verified, e.g. in a batch
+/// during range sync.
+pub async fn on_signature_verified_block(
+    store: &mut Store,
+    signed_block: &SignedBeaconBlock,
+    execution_engine: &Option<impl ExecutionApi>,
+    verify_blob_availability: bool,
+) -> anyhow::Result<()> {
+    import_block(
+        store,
+        signed_block,
+        execution_engine,
+        verify_blob_availability,
+        true,
+    )
+    .await
+}
+
+async fn import_block(
+    store: &mut Store,
+    signed_block: &SignedBeaconBlock,
+    execution_engine: &Option<impl ExecutionApi>,
+    verify_blob_availability: bool,
+    is_signature_verified: bool,
 ) -> anyhow::Result<()> {
     let block = &signed_block.message;
 
@@ -39,25 +86,37 @@ pub async fn on_block(
 
     // Blocks cannot be in the future. If they are, their consideration must be delayed until they
     // are in the past.
-    ensure!(
-        store.get_current_slot()? >= block.slot,
-        "Block slot is ahead of current slot: block.slot = {}, store.get_current_slot() = {}",
-        block.slot,
-        store.get_current_slot()?
-    );
+    let current_slot = store.get_current_slot()?;
+    if block.slot > current_slot {
+        return Err(InvalidBlockError(anyhow!(
+            "Block slot is ahead of current slot: block.slot = {}, store.get_current_slot() = {current_slot}",
+            block.slot
+        ))
+        .into());
+    }
 
     // Check that block is later than the finalized epoch slot (optimization to reduce calls to
     // get_ancestor)
     let finalized_slot =
         compute_start_slot_at_epoch(store.db.finalized_checkpoint_provider().get()?.epoch);
-    ensure!(block.slot > finalized_slot);
+    if block.slot <= finalized_slot {
+        return Err(InvalidBlockError(anyhow!(
+            "Block slot {} is not after the finalized slot {finalized_slot}",
+            block.slot
+        ))
+        .into());
+    }
 
     // Check block is a descendant of the finalized block at the checkpoint finalized slot
     let finalized_checkpoint_block = store.get_checkpoint_block(
         block.parent_root,
         store.db.finalized_checkpoint_provider().get()?.epoch,
     )?;
-    ensure!(store.db.finalized_checkpoint_provider().get()?.root == finalized_checkpoint_block);
+    if store.db.finalized_checkpoint_provider().get()?.root != finalized_checkpoint_block {
+        return Err(
+            InvalidBlockError(anyhow!("Block doesn't descend from the finalized block")).into(),
+        );
+    }
     if verify_blob_availability {
         // Check if blob data is available
         // If not, this block MAY be queued and subsequently considered when blob data becomes
@@ -84,21 +143,34 @@ pub async fn on_block(
         .ok_or_else(|| anyhow!("beacon state not found"))?
         .clone();
     let block_root = block.tree_hash_root();
-    state
-        .state_transition(signed_block, true, execution_engine)
-        .await?;
+    let state_transition = match is_signature_verified {
+        true => {
+            state
+                .state_transition_with_verified_signature(signed_block, execution_engine)
+                .await
+        }
+        false => {
+            state
+                .state_transition(signed_block, true, execution_engine)
+                .await
+        }
+    };
+    if let Err(err) = state_transition {
+        return Err(match err.is::<ExecutionEngineError>() {
+            true => err,
+            false => InvalidBlockError(err).into(),
+        });
+    }
+
+    // Write the block, its state and the fork choice updates in a single transaction, so a
+    // failed import leaves nothing behind
+    let batch = store.db.begin_write_batch()?;
 
     // Add new block to the store
-    store
-        .db
-        .beacon_block_provider()
-        .insert(block_root, signed_block.clone())?;
+    batch.insert_beacon_block(block_root, signed_block)?;
 
     // Add new state for this block to the store
-    store
-        .db
-        .beacon_state_provider()
-        .insert(block_root, state.clone())?;
+    batch.insert_beacon_state(block_root, &state)?;
 
     // Add block timeliness to the store
     let time_into_slot = (store.db.time_provider().get()?
@@ -107,26 +179,33 @@ pub async fn on_block(
     let is_before_attesting_interval =
         time_into_slot < beacon_network_spec().seconds_per_slot / INTERVALS_PER_SLOT;
     let is_timely = store.get_current_slot()? == block.slot && is_before_attesting_interval;
-    store
-        .db
-        .block_timeliness_provider()
-        .insert(block_root, is_timely)?;
+    batch.insert_block_timeliness(block_root, is_timely)?;
 
     // Add proposer score boost if the block is timely and not conflicting with an existing block
-    let is_first_block = store.db.proposer_boost_root_provider().get()? == B256::ZERO;
+    let is_first_block = batch.get_proposer_boost_root()? == B256::ZERO;
 
     if is_timely && is_first_block {
-        store.db.proposer_boost_root_provider().insert(block_root)?;
+        batch.insert_proposer_boost_root(block_root)?;
     }
 
     // Update checkpoints in store if necessary
     store.update_checkpoints(
+        &batch,
         state.current_justified_checkpoint,
         state.finalized_checkpoint,
     )?;
 
     // Eagerly compute unrealized justification and finality.
-    store.compute_pulled_up_tip(block_root)?;
+    store.compute_pulled_up_tip(&batch, block_root)?;
+
+    batch.commit()?;
+
+    // The new block is likely to become the head, so keep it around for the next reads
+    store
+        .db
+        .read_cache
+        .put_block_summary(block_root, BlockSummary::from(block));
+    store.db.read_cache.put_state(block_root, Arc::new(state));
 
     Ok(())
 }
@@ -164,11 +243,7 @@ pub fn on_attester_slashing(
         .into_iter()
         .collect::<HashSet<_>>();
 
-    let mut equivocating = match store.db.equivocating_indices_provider().get() {
-        Ok(set) => set,
-        Err(StoreError::FieldNotInitilized) => HashSet::default(),
-        Err(err) => return Err(err.into()),
-    };
+    let mut equivocating = store.db.equivocating_indices()?;
 
     for index in attestation_1_indices.intersection(&attestation_2_indices) {
         equivocating.insert(*index);
@@ -212,10 +287,10 @@ pub fn on_attestation(
     store.store_target_checkpoint_state(attestation.data.target)?;
 
     // Get state at the `target` to fully validate attestation
-    let target_state = &store
+    let target_state = store
         .db
         .checkpoint_states_provider()
-        .get(attestation.data.target)?
+        .get_shared(attestation.data.target)?
         .ok_or_else(|| anyhow!("checkpoint_states not found"))?;
     let indexed_attestation = target_state.get_indexed_attestation(&attestation)?;
     ensure!(target_state.is_valid_indexed_attestation(&indexed_attestation)?);
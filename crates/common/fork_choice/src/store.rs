@@ -25,6 +25,7 @@ use ream_network_spec::networks::beacon_network_spec;
 use ream_operation_pool::OperationPool;
 use ream_polynomial_commitments::handlers::verify_blob_kzg_proof_batch;
 use ream_storage::{
+    batch::WriteBatch,
     db::ReamDB,
     tables::{Field, MultimapTable, Table},
 };
@@ -77,9 +78,8 @@ impl Store {
         let block = self
             .db
             .beacon_block_provider()
-            .get(root)?
-            .ok_or(anyhow!("Failed to find beacon_block_provider()"))?
-            .message;
+            .get_summary(root)?
+            .ok_or(anyhow!("Failed to find beacon_block_provider()"))?;
         if block.slot > slot {
             self.get_ancestor(block.parent_root, slot)
         } else {
@@ -228,21 +228,18 @@ impl Store {
     /// Update checkpoints in store if necessary
     pub fn update_checkpoints(
         &mut self,
+        batch: &WriteBatch,
         justified_checkpoint: Checkpoint,
         finalized_checkpoint: Checkpoint,
     ) -> anyhow::Result<()> {
         // Update justified checkpoint
-        if justified_checkpoint.epoch > self.db.justified_checkpoint_provider().get()?.epoch {
-            self.db
-                .justified_checkpoint_provider()
-                .insert(justified_checkpoint)?;
+        if justified_checkpoint.epoch > batch.get_justified_checkpoint()?.epoch {
+            batch.insert_justified_checkpoint(justified_checkpoint)?;
         }
 
         // Update finalized checkpoint
-        if finalized_checkpoint.epoch > self.db.finalized_checkpoint_provider().get()?.epoch {
-            self.db
-                .finalized_checkpoint_provider()
-                .insert(finalized_checkpoint)?;
+        if finalized_checkpoint.epoch > batch.get_finalized_checkpoint()?.epoch {
+            batch.insert_finalized_checkpoint(finalized_checkpoint)?;
             // Clean operation pool
             if let Some(beacon_state) = self
                 .db
@@ -279,33 +276,22 @@ impl Store {
     /// Update unrealized checkpoints in store if necessary
     pub fn update_unrealized_checkpoints(
         &mut self,
+        batch: &WriteBatch,
         unrealized_justified_checkpoint: Checkpoint,
         unrealized_finalized_checkpoint: Checkpoint,
     ) -> anyhow::Result<()> {
         // Update unrealized justified checkpoint
         if unrealized_justified_checkpoint.epoch
-            > self
-                .db
-                .unrealized_justified_checkpoint_provider()
-                .get()?
-                .epoch
+            > batch.get_unrealized_justified_checkpoint()?.epoch
         {
-            self.db
-                .unrealized_justified_checkpoint_provider()
-                .insert(unrealized_justified_checkpoint)?;
+            batch.insert_unrealized_justified_checkpoint(unrealized_justified_checkpoint)?;
         }
 
         // Update unrealized finalized checkpoint
         if unrealized_finalized_checkpoint.epoch
-            > self
-                .db
-                .unrealized_finalized_checkpoint_provider()
-                .get()?
-                .epoch
+            > batch.get_unrealized_finalized_checkpoint()?.epoch
         {
-            self.db
-                .unrealized_finalized_checkpoint_provider()
-                .insert(unrealized_finalized_checkpoint)?;
+            batch.insert_unrealized_finalized_checkpoint(unrealized_finalized_checkpoint)?;
         }
 
         Ok(())
@@ -371,27 +357,19 @@ impl Store {
             .filter(|&i| !state.validators[i as usize].slashed)
             .collect();
 
+        let equivocating_indices = self.db.equivocating_indices()?;
+        let root_slot = self
+            .db
+            .beacon_block_provider()
+            .get_summary(root)?
+            .ok_or_else(|| anyhow!("beacon_block not found"))?
+            .slot;
+
         let mut attestation_score: u64 = 0;
         for index in unslashed_and_active_indices {
-            if self.db.latest_messages_provider().get(index)?.is_some()
-                && !self
-                    .db
-                    .equivocating_indices_provider()
-                    .get()?
-                    .contains(&index)
-                && self.get_ancestor(
-                    self.db
-                        .latest_messages_provider()
-                        .get(index)?
-                        .ok_or_else(|| anyhow!("latest_messages not found"))?
-                        .root,
-                    self.db
-                        .beacon_block_provider()
-                        .get(root)?
-                        .ok_or_else(|| anyhow!("beacon_block not found"))?
-                        .message
-                        .slot,
-                )? == root
+            if let Some(latest_message) = self.db.latest_messages_provider().get(index)?
+                && !equivocating_indices.contains(&index)
+                && self.get_ancestor(latest_message.root, root_slot)? == root
             {
                 attestation_score += state.validators[index as usize].effective_balance;
             }
@@ -405,16 +383,7 @@ impl Store {
         // Calculate proposer score if ``proposer_boost_root`` is set
         let mut proposer_score: u64 = 0;
         // Boost is applied if ``root`` is an ancestor of ``proposer_boost_root``
-        if self.get_ancestor(
-            self.db.proposer_boost_root_provider().get()?,
-            self.db
-                .beacon_block_provider()
-                .get(root)?
-                .ok_or_else(|| anyhow!("beacon_block not found"))?
-                .message
-                .slot,
-        )? == root
-        {
+        if self.get_ancestor(self.db.proposer_boost_root_provider().get()?, root_slot)? == root {
             proposer_score = self.get_proposer_score()?;
         }
 
@@ -427,11 +396,11 @@ impl Store {
         let block = self
             .db
             .beacon_block_provider()
-            .get(block_root)?
+            .get_summary(block_root)?
             .ok_or_else(|| anyhow!("beacon_block not found"))?;
 
         let current_epoch = self.get_current_store_epoch()?;
-        let block_epoch = compute_epoch_at_slot(block.message.slot);
+        let block_epoch = compute_epoch_at_slot(block.slot);
 
         if current_epoch > block_epoch {
             // The block is from a prior epoch, the voting source will be pulled-up
@@ -545,11 +514,7 @@ impl Store {
         let beacon_block_root = attestation.data.beacon_block_root;
         let mut non_equivocating_attesting_indices = vec![];
 
-        let equivocating = self
-            .db
-            .equivocating_indices_provider()
-            .get()
-            .unwrap_or_default();
+        let equivocating = self.db.equivocating_indices()?;
 
         for &index in &attesting_indices {
             if !equivocating.contains(&index) {
@@ -597,10 +562,13 @@ impl Store {
 
         // If a new epoch, pull-up justification and finalization from previous epoch
         if current_slot > previous_slot && compute_slots_since_epoch_start(current_slot) == 0 {
+            let batch = self.db.begin_write_batch()?;
             self.update_checkpoints(
-                self.db.unrealized_justified_checkpoint_provider().get()?,
-                self.db.unrealized_finalized_checkpoint_provider().get()?,
+                &batch,
+                batch.get_unrealized_justified_checkpoint()?,
+                batch.get_unrealized_finalized_checkpoint()?,
             )?;
+            batch.commit()?;
         }
 
         Ok(())
@@ -682,7 +650,12 @@ impl Store {
     }
 
     pub fn store_target_checkpoint_state(&mut self, target: Checkpoint) -> anyhow::Result<()> {
-        if self.db.checkpoint_states_provider().get(target)?.is_some() {
+        if self
+            .db
+            .checkpoint_states_provider()
+            .get_shared(target)?
+            .is_some()
+        {
             return Ok(());
         }
 
@@ -771,28 +744,28 @@ impl Store {
         Ok(true)
     }
 
-    pub fn compute_pulled_up_tip(&mut self, block_root: B256) -> anyhow::Result<()> {
-        let mut state = self
-            .db
-            .beacon_state_provider()
-            .get(block_root)?
+    pub fn compute_pulled_up_tip(
+        &mut self,
+        batch: &WriteBatch,
+        block_root: B256,
+    ) -> anyhow::Result<()> {
+        let mut state = batch
+            .get_beacon_state(block_root)?
             .ok_or_else(|| anyhow!("beacon state not found"))?;
         // Pull up the post-state of the block to the next epoch boundary
         state.process_justification_and_finalization()?;
 
-        self.db
-            .unrealized_justifications_provider()
-            .insert(block_root, state.current_justified_checkpoint)?;
+        batch.insert_unrealized_justification(block_root, state.current_justified_checkpoint)?;
         self.update_unrealized_checkpoints(
+            batch,
             state.current_justified_checkpoint,
             state.finalized_checkpoint,
         )?;
 
         // If the block is from a prior epoch, apply the realized values
         let block_epoch = compute_epoch_at_slot(
-            self.db
-                .beacon_block_provider()
-                .get(block_root)?
+            batch
+                .get_beacon_block(block_root)?
                 .ok_or_else(|| anyhow!("beacon_block not found"))?
                 .message
                 .slot,
@@ -800,6 +773,7 @@ impl Store {
         let current_epoch = self.get_current_store_epoch()?;
         if block_epoch < current_epoch {
             self.update_checkpoints(
+                batch,
                 state.current_justified_checkpoint,
                 state.finalized_checkpoint,
             )?;
@@ -811,8 +785,8 @@ impl Store {
     pub fn is_syncing(&self) -> anyhow::Result<bool> {
         let head = self.get_head()?;
 
-        let head_slot = match self.db.beacon_block_provider().get(head) {
-            Ok(Some(block)) => block.message.slot,
+        let head_slot = match self.db.beacon_block_provider().get_summary(head) {
+            Ok(Some(block)) => block.slot,
             err => {
                 return Err(anyhow!("Failed to get head slot, error: {err:?}"));
             }
@@ -1,6 +1,8 @@
 use prometheus_exporter::prometheus::{
-    HistogramTimer, HistogramVec, IntGaugeVec, default_registry,
-    register_histogram_vec_with_registry, register_int_gauge_vec_with_registry,
+    HistogramTimer, HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec,
+    default_registry, register_histogram_vec_with_registry, register_int_counter_vec_with_registry,
+    register_int_counter_with_registry, register_int_gauge_vec_with_registry,
+    register_int_gauge_with_registry,
 };
 
 // Provisioning each metrics
@@ -28,6 +30,128 @@ lazy_static::lazy_static! {
         "The current finalized slot",
         &[]
     );
+
+    pub static ref BUILDER_RELAY_REQUEST_TIME: HistogramVec = create_histogram_vec(
+        "beacon_validator_builder_relay_request_time",
+        "Duration of requests made by the validator client to builder relays",
+        &["relay", "endpoint"]
+    );
+
+    pub static ref BUILDER_RELAY_RESPONSES: IntCounterVec = create_int_counter_vec(
+        "beacon_validator_builder_relay_responses_total",
+        "Responses received from builder relays, by outcome",
+        &["relay", "endpoint", "outcome"]
+    );
+
+    pub static ref VALIDATOR_MONITOR_ATTESTATIONS: IntCounterVec = create_int_counter_vec(
+        "beacon_validator_monitor_attestations_total",
+        "Attestation duties of the monitored validators, by outcome",
+        &["outcome"]
+    );
+
+    pub static ref VALIDATOR_MONITOR_INCLUSION_DISTANCE: IntCounter = create_int_counter(
+        "beacon_validator_monitor_attestation_inclusion_distance_total",
+        "Sum of the inclusion distances of the included attestations of the monitored validators"
+    );
+
+    pub static ref VALIDATOR_MONITOR_PROPOSALS: IntCounterVec = create_int_counter_vec(
+        "beacon_validator_monitor_proposals_total",
+        "Proposer duties of the monitored validators, by outcome",
+        &["outcome"]
+    );
+
+    pub static ref VALIDATOR_MONITOR_BLOCK_REWARDS: IntCounter = create_int_counter(
+        "beacon_validator_monitor_block_rewards_gwei_total",
+        "Consensus rewards of the blocks proposed by the monitored validators"
+    );
+
+    pub static ref VALIDATOR_MONITOR_SYNC_COMMITTEE: IntCounterVec = create_int_counter_vec(
+        "beacon_validator_monitor_sync_committee_messages_total",
+        "Sync committee messages of the monitored validators, by outcome",
+        &["outcome"]
+    );
+
+    pub static ref VALIDATOR_MONITOR_BALANCE: IntGauge = create_int_gauge(
+        "beacon_validator_monitor_balance_gwei",
+        "Total balance of the monitored validators"
+    );
+
+    pub static ref VALIDATOR_MONITOR_BALANCE_DELTA: IntGauge = create_int_gauge(
+        "beacon_validator_monitor_balance_delta_gwei",
+        "Total balance change of the monitored validators over the latest monitored epoch"
+    );
+
+    pub static ref VALIDATOR_MONITOR_VALIDATOR_ATTESTATIONS: IntCounterVec = create_int_counter_vec(
+        "beacon_validator_monitor_validator_attestations_total",
+        "Attestation duties of individually monitored validators, by outcome",
+        &["validator", "outcome"]
+    );
+
+    pub static ref VALIDATOR_MONITOR_VALIDATOR_PROPOSALS: IntCounterVec = create_int_counter_vec(
+        "beacon_validator_monitor_validator_proposals_total",
+        "Proposer duties of individually monitored validators, by outcome",
+        &["validator", "outcome"]
+    );
+
+    pub static ref VALIDATOR_MONITOR_VALIDATOR_BALANCE: IntGaugeVec = create_int_gauge_vec(
+        "beacon_validator_monitor_validator_balance_gwei",
+        "Balance of individually monitored validators",
+        &["validator"]
+    );
+
+    pub static ref PROPOSED_BLOCK_SOURCE: IntCounterVec = create_int_counter_vec(
+        "beacon_validator_proposed_block_source_total",
+        "Blocks proposed by the validator client, by the source of the execution payload",
+        &["source"]
+    );
+
+    pub static ref BEACON_DB_PRUNED_ENTRIES: IntCounterVec = create_int_counter_vec(
+        "beacon_db_pruned_entries_total",
+        "Entries removed from the beacon database after finalization, by table",
+        &["table"]
+    );
+
+    pub static ref BEACON_DB_FROZEN_STATES: IntCounterVec = create_int_counter_vec(
+        "beacon_db_frozen_states_total",
+        "Finalized beacon states moved into the freezer",
+        &[]
+    );
+
+    pub static ref BEACON_DB_EARLIEST_BLOB_SLOT: IntGaugeVec = create_int_gauge_vec(
+        "beacon_db_earliest_blob_slot",
+        "The first slot for which blobs are still stored",
+        &[]
+    );
+
+    pub static ref BEACON_DB_PRUNING_TIME: HistogramVec = create_histogram_vec(
+        "beacon_db_pruning_time",
+        "Duration of the stages of pruning the beacon database after finalization",
+        &["stage"]
+    );
+
+    pub static ref BEACON_DB_CACHE_HITS: IntCounterVec = create_int_counter_vec(
+        "beacon_db_cache_hits_total",
+        "Reads served from the in-memory beacon database caches, by cache",
+        &["cache"]
+    );
+
+    pub static ref BEACON_DB_CACHE_MISSES: IntCounterVec = create_int_counter_vec(
+        "beacon_db_cache_misses_total",
+        "Reads which missed the in-memory beacon database caches, by cache",
+        &["cache"]
+    );
+
+    pub static ref BEACON_NETWORK_PEERS: IntGaugeVec = create_int_gauge_vec(
+        "beacon_network_peers",
+        "Connected peers, by connection direction and client",
+        &["direction", "client"]
+    );
+}
+
+/// Create a new gauge metric without labels
+pub fn create_int_gauge(name: &str, help: &str) -> IntGauge {
+    let registry = default_registry();
+    register_int_gauge_with_registry!(name, help, registry).expect("failed to create int gauge")
 }
 
 /// Create a new gauge metric
@@ -42,6 +166,29 @@ pub fn set_int_gauge_vec(gauge_vec: &IntGaugeVec, value: i64, label_values: &[&s
     gauge_vec.with_label_values(label_values).set(value);
 }
 
+/// Create a new counter metric without labels
+pub fn create_int_counter(name: &str, help: &str) -> IntCounter {
+    let registry = default_registry();
+    register_int_counter_with_registry!(name, help, registry).expect("failed to create int counter")
+}
+
+/// Create a new counter metric
+pub fn create_int_counter_vec(name: &str, help: &str, label_names: &[&str]) -> IntCounterVec {
+    let registry = default_registry();
+    register_int_counter_vec_with_registry!(name, help, label_names, registry)
+        .expect("failed to create int counter vec")
+}
+
+/// Increment a counter metric
+pub fn inc_int_counter_vec(counter_vec: &IntCounterVec, label_values: &[&str]) {
+    counter_vec.with_label_values(label_values).inc();
+}
+
+/// Increment a counter metric by `value`
+pub fn inc_int_counter_vec_by(counter_vec: &IntCounterVec, value: u64, label_values: &[&str]) {
+    counter_vec.with_label_values(label_values).inc_by(value);
+}
+
 /// Create a new histogram metric
 pub fn create_histogram_vec(name: &str, help: &str, label_names: &[&str]) -> HistogramVec {
     let registry = default_registry();
@@ -1,4 +1,5 @@
 use std::{
+    fmt,
     str::FromStr,
     sync::{Arc, LazyLock, Once, OnceLock},
     time::{Duration, SystemTime, UNIX_EPOCH},
@@ -48,6 +49,19 @@ impl<'de> Deserialize<'de> for Network {
     }
 }
 
+impl fmt::Display for Network {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Network::Mainnet => write!(f, "mainnet"),
+            Network::Holesky => write!(f, "holesky"),
+            Network::Sepolia => write!(f, "sepolia"),
+            Network::Hoodi => write!(f, "hoodi"),
+            Network::Dev => write!(f, "dev"),
+            Network::Custom(name) => write!(f, "{name}"),
+        }
+    }
+}
+
 static BEACON_NETWORK_SPEC: OnceLock<Arc<BeaconNetworkSpec>> = OnceLock::new();
 
 /// MUST be called only once at the start of the application to initialize static
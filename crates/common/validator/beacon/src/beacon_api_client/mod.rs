@@ -10,15 +10,19 @@ use eventsource_client::{Client, ClientBuilder, SSE};
 use futures::{Stream, StreamExt};
 use http_client::{ClientWithBaseUrl, ContentType};
 use ream_api_types_beacon::{
-    block::{BroadcastValidation, FullBlockData, ProduceBlockData, ProduceBlockResponse},
-    committee::BeaconCommitteeSubscription,
+    block::{
+        BlockRewards, BroadcastValidation, FullBlockData, ProduceBlockData, ProduceBlockResponse,
+        SignedBlockContents,
+    },
+    committee::{BeaconCommitteeSubscription, CommitteeData},
     duties::{AttesterDuty, ProposerDuty, SyncCommitteeDuty},
     error::ValidatorError,
     id::{ID, ValidatorID},
-    request::{SyncCommitteeRequestItem, ValidatorsPostRequest},
+    request::{PrepareBeaconProposerItem, SyncCommitteeRequestItem, ValidatorsPostRequest},
     responses::{
-        BeaconResponse, DataResponse, DataVersionedResponse, DutiesResponse,
-        ETH_CONSENSUS_VERSION_HEADER, RootResponse, SyncCommitteeDutiesResponse, VERSION,
+        BeaconResponse, BeaconVersionedResponse, DataResponse, DataVersionedResponse,
+        DutiesResponse, ETH_CONSENSUS_VERSION_HEADER, RootResponse, SyncCommitteeDutiesResponse,
+        VERSION,
     },
     sync::SyncStatus,
     validator::{ValidatorData, ValidatorStatus},
@@ -28,6 +32,7 @@ use ream_consensus_beacon::{
     attestation::Attestation,
     electra::{
         beacon_block::SignedBeaconBlock,
+        beacon_state::BeaconState,
         blinded_beacon_block::{BlindedBeaconBlock, SignedBlindedBeaconBlock},
     },
     genesis::Genesis,
@@ -36,13 +41,14 @@ use ream_consensus_beacon::{
 };
 use ream_consensus_misc::{attestation_data::AttestationData, fork::Fork};
 use ream_network_spec::networks::BeaconNetworkSpec;
-use reqwest::{Url, header::HeaderMap};
+use reqwest::{StatusCode, Url, header::HeaderMap};
 use serde_json::json;
 use ssz::{Decode, Encode};
 use tracing::{error, info};
 
 use crate::{
     aggregate_and_proof::SignedAggregateAndProof,
+    builder::validator_registration::SignedValidatorRegistrationV1,
     contribution_and_proof::{SignedContributionAndProof, SyncCommitteeContribution},
 };
 
@@ -147,6 +153,120 @@ impl BeaconApiClient {
         Ok(response.json().await?)
     }
 
+    pub async fn get_state(&self, state_id: ID) -> anyhow::Result<BeaconState, ValidatorError> {
+        let response = self
+            .http_client
+            .execute(
+                self.http_client
+                    .get(format!("/eth/v2/debug/beacon/states/{state_id}"))?
+                    .build()?,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(ValidatorError::RequestFailed {
+                status_code: response.status(),
+            });
+        }
+
+        if get_header_str(response.headers(), "content-type")?.contains("application/octet-stream")
+        {
+            BeaconState::from_ssz_bytes(&response.bytes().await?)
+                .map_err(|err| ValidatorError::SszDecodeError(format!("{err:?}")))
+        } else {
+            Ok(response
+                .json::<DataVersionedResponse<BeaconState>>()
+                .await?
+                .data)
+        }
+    }
+
+    /// Returns the block at `block_id`, or `None` if there is no such block, e.g. for a skipped
+    /// slot.
+    pub async fn get_block(
+        &self,
+        block_id: ID,
+    ) -> anyhow::Result<Option<SignedBeaconBlock>, ValidatorError> {
+        let response = self
+            .http_client
+            .execute(
+                self.http_client
+                    .get(format!("/eth/v2/beacon/blocks/{block_id}"))?
+                    .build()?,
+            )
+            .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return Err(ValidatorError::RequestFailed {
+                status_code: response.status(),
+            });
+        }
+
+        if get_header_str(response.headers(), "content-type")?.contains("application/octet-stream")
+        {
+            SignedBeaconBlock::from_ssz_bytes(&response.bytes().await?)
+                .map(Some)
+                .map_err(|err| ValidatorError::SszDecodeError(format!("{err:?}")))
+        } else {
+            Ok(Some(
+                response
+                    .json::<BeaconVersionedResponse<SignedBeaconBlock>>()
+                    .await?
+                    .data,
+            ))
+        }
+    }
+
+    pub async fn get_block_rewards(
+        &self,
+        block_id: ID,
+    ) -> anyhow::Result<BeaconResponse<BlockRewards>, ValidatorError> {
+        let response = self
+            .http_client
+            .execute(
+                self.http_client
+                    .get(format!("/eth/v1/beacon/blocks/{block_id}/rewards"))?
+                    .build()?,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(ValidatorError::RequestFailed {
+                status_code: response.status(),
+            });
+        }
+
+        Ok(response.json().await?)
+    }
+
+    pub async fn get_committees(
+        &self,
+        state_id: ID,
+        epoch: u64,
+    ) -> anyhow::Result<BeaconResponse<Vec<CommitteeData>>, ValidatorError> {
+        let response = self
+            .http_client
+            .execute(
+                self.http_client
+                    .get(format!("/eth/v1/beacon/states/{state_id}/committees"))?
+                    .query(&[("epoch", epoch.to_string())])
+                    .build()?,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(ValidatorError::RequestFailed {
+                status_code: response.status(),
+            });
+        }
+
+        Ok(response.json().await?)
+    }
+
     pub async fn get_config_spec(
         &self,
     ) -> anyhow::Result<DataResponse<BeaconNetworkSpec>, ValidatorError> {
@@ -405,6 +525,54 @@ impl BeaconApiClient {
         Ok(())
     }
 
+    pub async fn prepare_beacon_proposer(
+        &self,
+        items: Vec<PrepareBeaconProposerItem>,
+    ) -> anyhow::Result<(), ValidatorError> {
+        let response = self
+            .http_client
+            .execute(
+                self.http_client
+                    .post(
+                        "/eth/v1/validator/prepare_beacon_proposer".to_string(),
+                        ContentType::Json,
+                    )?
+                    .json(&items)
+                    .build()?,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(handle_error_response(response).await);
+        }
+
+        Ok(())
+    }
+
+    pub async fn register_validators(
+        &self,
+        registrations: Vec<SignedValidatorRegistrationV1>,
+    ) -> anyhow::Result<(), ValidatorError> {
+        let response = self
+            .http_client
+            .execute(
+                self.http_client
+                    .post(
+                        "/eth/v1/validator/register_validator".to_string(),
+                        ContentType::Json,
+                    )?
+                    .json(&registrations)
+                    .build()?,
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(handle_error_response(response).await);
+        }
+
+        Ok(())
+    }
+
     pub async fn get_sync_committee_contribution(
         &self,
         slot: u64,
@@ -647,7 +815,7 @@ impl BeaconApiClient {
     pub async fn publish_block(
         &self,
         broadcast_validation: BroadcastValidation,
-        signed_beacon_block: SignedBeaconBlock,
+        signed_block_contents: SignedBlockContents,
     ) -> anyhow::Result<(), ValidatorError> {
         let response = self
             .http_client
@@ -659,7 +827,7 @@ impl BeaconApiClient {
                         serde_json::to_string(&broadcast_validation)?,
                     )])
                     .header(ETH_CONSENSUS_VERSION_HEADER, VERSION)
-                    .body(signed_beacon_block.as_ssz_bytes())
+                    .body(signed_block_contents.as_ssz_bytes())
                     .build()?,
             )
             .await?;
@@ -3,6 +3,7 @@ use ream_consensus_beacon::electra::{
     beacon_block::{BeaconBlock, SignedBeaconBlock},
     beacon_state::BeaconState,
     blinded_beacon_block::{BlindedBeaconBlock, SignedBlindedBeaconBlock},
+    blinded_beacon_block_body::BlindedBeaconBlockBody,
 };
 use ream_consensus_misc::{
     constants::beacon::DOMAIN_BEACON_PROPOSER,
@@ -10,6 +11,8 @@ use ream_consensus_misc::{
 };
 use ream_network_spec::networks::beacon_network_spec;
 
+use crate::builder::builder_bid::BuilderBid;
+
 pub fn get_block_signature(
     state: &BeaconState,
     block: &BeaconBlock,
@@ -63,3 +66,29 @@ pub fn sign_blinded_beacon_block(
         signature,
     })
 }
+
+/// Builds a blinded block from a locally produced block, replacing its execution payload with the
+/// header of a builder bid. The state root is left to the caller, as it depends on the payload.
+pub fn blinded_block_from_bid(block: &BeaconBlock, bid: &BuilderBid) -> BlindedBeaconBlock {
+    BlindedBeaconBlock {
+        slot: block.slot,
+        proposer_index: block.proposer_index,
+        parent_root: block.parent_root,
+        state_root: block.state_root,
+        body: BlindedBeaconBlockBody {
+            randao_reveal: block.body.randao_reveal.clone(),
+            eth1_data: block.body.eth1_data.clone(),
+            graffiti: block.body.graffiti,
+            proposer_slashings: block.body.proposer_slashings.clone(),
+            attester_slashings: block.body.attester_slashings.clone(),
+            attestations: block.body.attestations.clone(),
+            deposits: block.body.deposits.clone(),
+            voluntary_exits: block.body.voluntary_exits.clone(),
+            sync_aggregate: block.body.sync_aggregate.clone(),
+            execution_payload_header: bid.header.clone(),
+            bls_to_execution_changes: block.body.bls_to_execution_changes.clone(),
+            blob_kzg_commitments: bid.blob_kzg_commitments.clone(),
+            execution_requests: bid.execution_requests.clone(),
+        },
+    }
+}
@@ -1,20 +1,18 @@
 use ream_consensus_beacon::{
     electra::execution_payload::ExecutionPayload,
-    polynomial_commitments::kzg_commitment::KZGCommitment,
+    execution_engine::rpc_types::get_blobs::Blob,
+    polynomial_commitments::{kzg_commitment::KZGCommitment, kzg_proof::KZGProof},
 };
 use serde::{Deserialize, Serialize};
 use ssz_derive::{Decode, Encode};
-use ssz_types::{
-    VariableList,
-    typenum::{U96, U1024, U1048576},
-};
+use ssz_types::{VariableList, typenum::U4096};
 use tree_hash_derive::TreeHash;
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
 pub struct BlobsBundle {
-    pub blobs: VariableList<KZGCommitment, U1048576>,
-    pub commitments: VariableList<VariableList<u8, U96>, U1024>,
-    pub proofs: VariableList<VariableList<u8, U96>, U1024>,
+    pub commitments: VariableList<KZGCommitment, U4096>,
+    pub proofs: VariableList<KZGProof, U4096>,
+    pub blobs: VariableList<Blob, U4096>,
 }
 
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Encode, Decode, TreeHash)]
@@ -2,7 +2,9 @@ use std::time::Duration;
 
 use alloy_primitives::B256;
 use anyhow::{Ok, anyhow};
-use ream_api_types_beacon::responses::{ETH_CONSENSUS_VERSION_HEADER, VERSION};
+use ream_api_types_beacon::responses::{
+    DataVersionedResponse, ETH_CONSENSUS_VERSION_HEADER, VERSION,
+};
 use ream_bls::PublicKey;
 use ream_consensus_beacon::electra::blinded_beacon_block::SignedBlindedBeaconBlock;
 use reqwest::StatusCode;
@@ -35,22 +37,31 @@ impl BuilderClient {
         })
     }
 
-    /// Get an execution payload header.
+    /// Get an execution payload header. Returns `None` if the builder has no bid for the slot.
     pub async fn get_builder_header(
         &self,
         parent_hash: B256,
         public_key: &PublicKey,
         slot: u64,
-    ) -> anyhow::Result<SignedBuilderBid> {
-        Ok(self
+    ) -> anyhow::Result<Option<SignedBuilderBid>> {
+        let response = self
             .client
             .get(format!(
                 "/eth/v1/builder/header/{slot}/{parent_hash:?}/{public_key:?}"
             ))?
             .send()
-            .await?
-            .json::<SignedBuilderBid>()
-            .await?)
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => Ok(Some(
+                response
+                    .json::<DataVersionedResponse<SignedBuilderBid>>()
+                    .await?
+                    .data,
+            )),
+            StatusCode::NO_CONTENT => Ok(None),
+            status => Err(anyhow!("failed to get builder header: {status:?}")),
+        }
     }
 
     /// Submit a signed blinded block and get unblinded execution payload.
@@ -69,7 +80,13 @@ impl BuilderClient {
             .send()
             .await?;
 
-        Ok(response.json::<ExecutionPayloadAndBlobsBundle>().await?)
+        match response.status() {
+            StatusCode::OK => Ok(response
+                .json::<DataVersionedResponse<ExecutionPayloadAndBlobsBundle>>()
+                .await?
+                .data),
+            status => Err(anyhow!("failed to submit blinded block: {status:?}")),
+        }
     }
 
     /// Check if builder is healthy.
@@ -108,4 +125,25 @@ impl BuilderClient {
             status => Err(anyhow!("internal error: {status:?}")),
         }
     }
+
+    /// Registers a batch of validators' preferred fee recipients and gas limits.
+    pub async fn register_validators(
+        &self,
+        signed_registrations: &[SignedValidatorRegistrationV1],
+    ) -> anyhow::Result<()> {
+        let response = self
+            .client
+            .post("/eth/v1/builder/validators".to_string(), ContentType::Json)?
+            .json(signed_registrations)
+            .send()
+            .await?;
+
+        match response.status() {
+            StatusCode::OK => Ok(()),
+            StatusCode::BAD_REQUEST => Err(anyhow!("invalid validator registrations")),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE => Err(anyhow!("unsupported media type")),
+            StatusCode::INTERNAL_SERVER_ERROR => Err(anyhow!("builder internal error")),
+            status => Err(anyhow!("internal error: {status:?}")),
+        }
+    }
 }
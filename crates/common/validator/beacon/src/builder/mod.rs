@@ -2,6 +2,8 @@ pub mod bid;
 pub mod blobs;
 pub mod builder_bid;
 pub mod builder_client;
+pub mod multi_relay;
+pub mod registration_cache;
 pub mod validator_registration;
 pub mod verify;
 
@@ -0,0 +1,463 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use alloy_primitives::U256;
+use anyhow::{anyhow, bail, ensure};
+use futures::future::join_all;
+use ream_bls::PublicKey;
+use ream_consensus_beacon::electra::{
+    blinded_beacon_block::SignedBlindedBeaconBlock,
+    execution_payload_header::ExecutionPayloadHeader,
+};
+use ream_metrics::{
+    BUILDER_RELAY_REQUEST_TIME, BUILDER_RELAY_RESPONSES, inc_int_counter_vec, start_timer_vec,
+    stop_timer,
+};
+use tokio::time::timeout;
+use tracing::{debug, warn};
+use url::Url;
+
+use super::{
+    blobs::ExecutionPayloadAndBlobsBundle,
+    builder_bid::SignedBuilderBid,
+    builder_client::{BuilderClient, BuilderConfig},
+    validator_registration::SignedValidatorRegistrationV1,
+    verify::verify_bid_signature,
+};
+use crate::beacon_api_client::http_client::ContentType;
+
+/// The boost factor applied to builder bids when none is configured, as a percentage.
+pub const DEFAULT_BUILDER_BOOST_FACTOR: u64 = 100;
+
+const GET_HEADER_ENDPOINT: &str = "get_header";
+const REGISTER_VALIDATORS_ENDPOINT: &str = "register_validators";
+const SUBMIT_BLINDED_BLOCK_ENDPOINT: &str = "submit_blinded_block";
+
+/// The highest valid bid received for a slot, along with every relay which offered the same
+/// payload. Any of them can be asked to reveal it.
+#[derive(Debug, Clone)]
+pub struct BestBid {
+    pub bid: SignedBuilderBid,
+    pub relays: Vec<Url>,
+}
+
+/// Queries a set of builder relays concurrently and picks the most valuable bid.
+pub struct MultiRelayClient {
+    clients: Mutex<HashMap<Url, Arc<BuilderClient>>>,
+    request_timeout: Duration,
+    header_timeout: Duration,
+}
+
+impl MultiRelayClient {
+    /// `header_timeout` is the overall budget for collecting bids. Relays which did not respond
+    /// in time are ignored.
+    pub fn new(request_timeout: Duration, header_timeout: Duration) -> Self {
+        Self {
+            clients: Mutex::new(HashMap::new()),
+            request_timeout,
+            header_timeout,
+        }
+    }
+
+    fn client(&self, relay: &Url) -> anyhow::Result<Arc<BuilderClient>> {
+        let mut clients = self
+            .clients
+            .lock()
+            .map_err(|err| anyhow!("Failed to lock relay clients: {err:?}"))?;
+        if let Some(client) = clients.get(relay) {
+            return Ok(client.clone());
+        }
+
+        let client = Arc::new(BuilderClient::new(
+            BuilderConfig {
+                builder_enabled: true,
+                mev_relay_url: relay.clone(),
+            },
+            self.request_timeout,
+            ContentType::Json,
+        )?);
+        clients.insert(relay.clone(), client.clone());
+        Ok(client)
+    }
+
+    /// Requests a header from every relay and returns the highest bid which builds on
+    /// `local_header`'s parent and is consistent with it, or `None` if no relay offered one.
+    pub async fn get_best_bid(
+        &self,
+        relays: &[Url],
+        slot: u64,
+        local_header: &ExecutionPayloadHeader,
+        public_key: &PublicKey,
+    ) -> Option<BestBid> {
+        let requests = relays.iter().map(|relay| async move {
+            let relay_label = relay_label(relay);
+            let timer = start_timer_vec(
+                &BUILDER_RELAY_REQUEST_TIME,
+                &[&relay_label, GET_HEADER_ENDPOINT],
+            );
+            let result = match self.client(relay) {
+                Ok(client) => timeout(
+                    self.header_timeout,
+                    client.get_builder_header(local_header.parent_hash, public_key, slot),
+                )
+                .await
+                .unwrap_or_else(|_| Err(anyhow!("timed out"))),
+                Err(err) => Err(err),
+            };
+            stop_timer(timer);
+
+            let (outcome, bid) = match result {
+                Ok(Some(bid)) => match validate_bid(&bid, local_header) {
+                    Ok(()) => ("success", Some((relay.clone(), bid))),
+                    Err(err) => {
+                        warn!("Ignoring invalid bid from relay {relay_label}: {err:?}");
+                        ("invalid", None)
+                    }
+                },
+                Ok(None) => ("no_bid", None),
+                Err(err) => {
+                    warn!("Failed to get header from relay {relay_label}: {err:?}");
+                    ("error", None)
+                }
+            };
+            inc_int_counter_vec(
+                &BUILDER_RELAY_RESPONSES,
+                &[&relay_label, GET_HEADER_ENDPOINT, outcome],
+            );
+            bid
+        });
+
+        select_best_bid(join_all(requests).await.into_iter().flatten())
+    }
+
+    /// Submits a signed blinded block to the relays in order until one of them reveals the
+    /// execution payload.
+    pub async fn submit_blinded_block(
+        &self,
+        relays: &[Url],
+        signed_blinded_block: &SignedBlindedBeaconBlock,
+    ) -> anyhow::Result<ExecutionPayloadAndBlobsBundle> {
+        for relay in relays {
+            let relay_label = relay_label(relay);
+            let timer = start_timer_vec(
+                &BUILDER_RELAY_REQUEST_TIME,
+                &[&relay_label, SUBMIT_BLINDED_BLOCK_ENDPOINT],
+            );
+            let result = match self.client(relay) {
+                Ok(client) => client
+                    .get_blinded_blocks(signed_blinded_block.clone())
+                    .await
+                    .and_then(|payload_and_blobs| {
+                        ensure!(
+                            payload_and_blobs
+                                .execution_payload
+                                .to_execution_payload_header()
+                                == signed_blinded_block.message.body.execution_payload_header,
+                            "revealed payload does not match the signed header"
+                        );
+                        Ok(payload_and_blobs)
+                    }),
+                Err(err) => Err(err),
+            };
+            stop_timer(timer);
+
+            match result {
+                Ok(payload_and_blobs) => {
+                    inc_int_counter_vec(
+                        &BUILDER_RELAY_RESPONSES,
+                        &[&relay_label, SUBMIT_BLINDED_BLOCK_ENDPOINT, "success"],
+                    );
+                    return Ok(payload_and_blobs);
+                }
+                Err(err) => {
+                    inc_int_counter_vec(
+                        &BUILDER_RELAY_RESPONSES,
+                        &[&relay_label, SUBMIT_BLINDED_BLOCK_ENDPOINT, "error"],
+                    );
+                    warn!("Relay {relay_label} failed to reveal the payload: {err:?}");
+                }
+            }
+        }
+
+        bail!("No relay revealed the execution payload")
+    }
+
+    /// Submits validator registrations to each relay in batches of `batch_size`. Relays are
+    /// handled concurrently, and a failing relay does not stop the others.
+    pub async fn register_validators(
+        &self,
+        registrations: HashMap<Url, Vec<SignedValidatorRegistrationV1>>,
+        batch_size: usize,
+    ) -> anyhow::Result<()> {
+        let requests = registrations
+            .iter()
+            .map(|(relay, registrations)| async move {
+                let relay_label = relay_label(relay);
+                let client = self.client(relay)?;
+                for batch in registrations.chunks(batch_size) {
+                    let timer = start_timer_vec(
+                        &BUILDER_RELAY_REQUEST_TIME,
+                        &[&relay_label, REGISTER_VALIDATORS_ENDPOINT],
+                    );
+                    let result = client.register_validators(batch).await;
+                    stop_timer(timer);
+
+                    let outcome = if result.is_ok() { "success" } else { "error" };
+                    inc_int_counter_vec(
+                        &BUILDER_RELAY_RESPONSES,
+                        &[&relay_label, REGISTER_VALIDATORS_ENDPOINT, outcome],
+                    );
+                    result.map_err(|err| {
+                        anyhow!("Failed to register validators with relay {relay_label}: {err:?}")
+                    })?;
+                }
+                debug!(
+                    "Registered {} validators with relay {relay_label}",
+                    registrations.len()
+                );
+                Ok::<_, anyhow::Error>(())
+            });
+
+        let errors = join_all(requests)
+            .await
+            .into_iter()
+            .filter_map(Result::err)
+            .collect::<Vec<_>>();
+        ensure!(errors.is_empty(), "{errors:?}");
+        Ok(())
+    }
+}
+
+/// Checks that a bid is signed by the builder and is consistent with the locally built payload,
+/// which shares the same parent, randao, timestamp and withdrawals.
+pub fn validate_bid(
+    bid: &SignedBuilderBid,
+    local_header: &ExecutionPayloadHeader,
+) -> anyhow::Result<()> {
+    let header = &bid.message.header;
+    ensure!(
+        header.parent_hash == local_header.parent_hash,
+        "bid parent hash {} does not match {}",
+        header.parent_hash,
+        local_header.parent_hash
+    );
+    ensure!(
+        header.prev_randao == local_header.prev_randao,
+        "bid prev randao does not match"
+    );
+    ensure!(
+        header.timestamp == local_header.timestamp,
+        "bid timestamp does not match"
+    );
+    ensure!(
+        header.withdrawals_root == local_header.withdrawals_root,
+        "bid withdrawals root does not match"
+    );
+    ensure!(!bid.message.value.is_zero(), "bid value is zero");
+    ensure!(verify_bid_signature(bid)?, "bid signature is invalid");
+    Ok(())
+}
+
+/// Picks the bid with the highest value. Relays offering the same payload are grouped together,
+/// so the payload can still be revealed if one of them fails.
+pub fn select_best_bid(bids: impl IntoIterator<Item = (Url, SignedBuilderBid)>) -> Option<BestBid> {
+    let mut best_bid: Option<BestBid> = None;
+    for (relay, bid) in bids {
+        match &mut best_bid {
+            Some(best) if best.bid.message.header.block_hash == bid.message.header.block_hash => {
+                best.relays.push(relay);
+            }
+            Some(best) if best.bid.message.value >= bid.message.value => {}
+            _ => {
+                best_bid = Some(BestBid {
+                    bid,
+                    relays: vec![relay],
+                })
+            }
+        }
+    }
+
+    if let Some(best) = &best_bid {
+        debug!(
+            "Best builder bid is {} wei from {} relay(s)",
+            best.bid.message.value,
+            best.relays.len()
+        );
+    }
+    best_bid
+}
+
+/// Returns true if the builder bid, scaled by `boost_factor` percent, is worth more than the
+/// local payload. A boost factor of 0 always prefers the local payload.
+pub fn is_builder_bid_preferred(
+    bid_value: U256,
+    local_value: U256,
+    boost_factor: Option<u64>,
+) -> bool {
+    let boost_factor = boost_factor.unwrap_or(DEFAULT_BUILDER_BOOST_FACTOR);
+    if boost_factor == 0 {
+        return false;
+    }
+
+    bid_value.saturating_mul(U256::from(boost_factor)) / U256::from(100) > local_value
+}
+
+/// The relay host, used to label metrics without leaking credentials from the URL.
+fn relay_label(relay: &Url) -> String {
+    relay.host_str().unwrap_or_default().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use alloy_primitives::{Address, B256};
+    use ream_bls::BLSSignature;
+
+    use super::*;
+    use crate::builder::{
+        builder_bid::BuilderBid, validator_registration::ValidatorRegistrationV1,
+    };
+
+    fn bid(block_hash: u8, value: u64) -> SignedBuilderBid {
+        SignedBuilderBid {
+            message: BuilderBid {
+                header: ExecutionPayloadHeader {
+                    block_hash: B256::repeat_byte(block_hash),
+                    ..Default::default()
+                },
+                blob_kzg_commitments: Default::default(),
+                execution_requests: Default::default(),
+                value: U256::from(value),
+                public_key: PublicKey::default(),
+            },
+            signature: BLSSignature::infinity(),
+        }
+    }
+
+    fn relay(name: &str) -> Url {
+        Url::parse(&format!("https://{name}.example.org")).unwrap()
+    }
+
+    #[test]
+    fn test_select_best_bid() {
+        assert!(select_best_bid(vec![]).is_none());
+
+        let best_bid = select_best_bid(vec![
+            (relay("a"), bid(1, 10)),
+            (relay("b"), bid(2, 30)),
+            (relay("c"), bid(3, 20)),
+            (relay("d"), bid(2, 30)),
+        ])
+        .unwrap();
+        assert_eq!(best_bid.bid, bid(2, 30));
+        assert_eq!(best_bid.relays, vec![relay("b"), relay("d")]);
+    }
+
+    /// Starts a relay which accepts every registration request, returning its URL and the number
+    /// of registrations in each request it received.
+    fn start_relay() -> (Url, Arc<Mutex<Vec<usize>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = Url::parse(&format!("http://{}", listener.local_addr().unwrap())).unwrap();
+        let batches = Arc::new(Mutex::new(vec![]));
+        let received = batches.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':')
+                        && name.eq_ignore_ascii_case("content-length")
+                    {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                let registrations: Vec<serde_json::Value> = serde_json::from_slice(&body).unwrap();
+                received.lock().unwrap().push(registrations.len());
+                stream
+                    .write_all(b"HTTP/1.1 200 OK\r\ncontent-length: 0\r\nconnection: close\r\n\r\n")
+                    .unwrap();
+            }
+        });
+        (url, batches)
+    }
+
+    fn signed_registration(index: u8) -> SignedValidatorRegistrationV1 {
+        SignedValidatorRegistrationV1 {
+            message: ValidatorRegistrationV1 {
+                fee_recipient: Address::repeat_byte(index),
+                gas_limit: 30_000_000,
+                timestamp: 1,
+                public_key: PublicKey::default(),
+            },
+            signature: BLSSignature::infinity(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_validators_in_batches_per_relay() {
+        let (relay_a, batches_a) = start_relay();
+        let (relay_b, batches_b) = start_relay();
+        let client = MultiRelayClient::new(Duration::from_secs(5), Duration::from_secs(5));
+
+        client
+            .register_validators(
+                HashMap::from([
+                    (relay_a, (0..5).map(signed_registration).collect()),
+                    (relay_b, (0..2).map(signed_registration).collect()),
+                ]),
+                2,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(*batches_a.lock().unwrap(), vec![2, 2, 1]);
+        assert_eq!(*batches_b.lock().unwrap(), vec![2]);
+    }
+
+    #[test]
+    fn test_is_builder_bid_preferred() {
+        let local_value = U256::from(100);
+        assert!(is_builder_bid_preferred(U256::from(101), local_value, None));
+        assert!(!is_builder_bid_preferred(
+            U256::from(100),
+            local_value,
+            None
+        ));
+        assert!(!is_builder_bid_preferred(
+            U256::from(110),
+            local_value,
+            Some(90)
+        ));
+        assert!(is_builder_bid_preferred(
+            U256::from(90),
+            local_value,
+            Some(200)
+        ));
+        assert!(!is_builder_bid_preferred(U256::MAX, local_value, Some(0)));
+    }
+
+    #[test]
+    fn test_validate_bid_rejects_mismatched_parent() {
+        let local_header = ExecutionPayloadHeader {
+            parent_hash: B256::repeat_byte(1),
+            ..Default::default()
+        };
+        assert!(validate_bid(&bid(2, 10), &local_header).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use ream_bls::{PrivateKey, PublicKey};
+
+use super::validator_registration::{SignedValidatorRegistrationV1, ValidatorRegistrationV1};
+
+/// Signed builder registrations, keyed by validator public key.
+///
+/// A registration is only re-signed when its fee recipient or gas limit change. Keeping the
+/// original timestamp lets relays recognise a registration they have already processed, and
+/// avoids signing every key at every epoch.
+#[derive(Debug, Default)]
+pub struct RegistrationCache {
+    registrations: HashMap<PublicKey, SignedValidatorRegistrationV1>,
+}
+
+impl RegistrationCache {
+    pub fn get_or_sign(
+        &mut self,
+        registration: ValidatorRegistrationV1,
+        private_key: &PrivateKey,
+    ) -> anyhow::Result<SignedValidatorRegistrationV1> {
+        if let Some(signed_registration) = self.registrations.get(&registration.public_key)
+            && signed_registration.message.fee_recipient == registration.fee_recipient
+            && signed_registration.message.gas_limit == registration.gas_limit
+        {
+            return Ok(signed_registration.clone());
+        }
+
+        let signed_registration = registration.create_signed_registration(private_key)?;
+        self.registrations.insert(
+            signed_registration.message.public_key.clone(),
+            signed_registration.clone(),
+        );
+        Ok(signed_registration)
+    }
+
+    /// Drops the registrations of validators for which `keep` returns false.
+    pub fn retain(&mut self, keep: impl Fn(&PublicKey) -> bool) {
+        self.registrations.retain(|public_key, _| keep(public_key));
+    }
+
+    pub fn len(&self) -> usize {
+        self.registrations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.registrations.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::{Address, B256};
+    use ream_bls::traits::Verifiable;
+    use ream_consensus_misc::misc::{compute_domain, compute_signing_root};
+    use tree_hash::TreeHash;
+
+    use super::*;
+    use crate::builder::DOMAIN_APPLICATION_BUILDER;
+
+    fn registration(
+        public_key: &PublicKey,
+        fee_recipient: Address,
+        gas_limit: u64,
+        timestamp: u64,
+    ) -> ValidatorRegistrationV1 {
+        ValidatorRegistrationV1 {
+            fee_recipient,
+            gas_limit,
+            timestamp,
+            public_key: public_key.clone(),
+        }
+    }
+
+    #[test]
+    fn test_registrations_are_only_resigned_on_change() {
+        let private_key = PrivateKey {
+            inner: B256::repeat_byte(1),
+        };
+        let public_key = private_key.public_key().unwrap();
+        let mut cache = RegistrationCache::default();
+
+        let first = cache
+            .get_or_sign(
+                registration(&public_key, Address::ZERO, 30_000_000, 1),
+                &private_key,
+            )
+            .unwrap();
+        let domain = compute_domain(DOMAIN_APPLICATION_BUILDER, None, None);
+        assert!(
+            first
+                .signature
+                .verify(
+                    &public_key,
+                    compute_signing_root(first.message.tree_hash_root(), domain).as_ref()
+                )
+                .unwrap()
+        );
+
+        let unchanged = cache
+            .get_or_sign(
+                registration(&public_key, Address::ZERO, 30_000_000, 2),
+                &private_key,
+            )
+            .unwrap();
+        assert_eq!(unchanged, first);
+
+        let changed = cache
+            .get_or_sign(
+                registration(&public_key, Address::repeat_byte(1), 30_000_000, 3),
+                &private_key,
+            )
+            .unwrap();
+        assert_eq!(changed.message.timestamp, 3);
+        assert_eq!(cache.len(), 1);
+
+        cache.retain(|_| false);
+        assert!(cache.is_empty());
+    }
+}
@@ -10,6 +10,8 @@ pub mod builder;
 pub mod constants;
 pub mod contribution_and_proof;
 pub mod execution_requests;
+pub mod monitor;
+pub mod proposer_config;
 pub mod randao;
 pub mod state;
 pub mod sync_committee;
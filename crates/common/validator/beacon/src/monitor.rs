@@ -0,0 +1,611 @@
+use std::collections::{BTreeMap, HashMap};
+
+use alloy_primitives::B256;
+use futures::future::join_all;
+use ream_api_types_beacon::{
+    duties::{AttesterDuty, ProposerDuty, SyncCommitteeDuty},
+    id::{ID, ValidatorID},
+};
+use ream_consensus_beacon::{attestation::Attestation, electra::beacon_block::BeaconBlock};
+use ream_consensus_misc::{
+    constants::beacon::SLOTS_PER_EPOCH,
+    misc::{compute_start_slot_at_epoch, get_committee_indices},
+};
+use ream_metrics::{
+    VALIDATOR_MONITOR_ATTESTATIONS, VALIDATOR_MONITOR_BALANCE, VALIDATOR_MONITOR_BALANCE_DELTA,
+    VALIDATOR_MONITOR_BLOCK_REWARDS, VALIDATOR_MONITOR_INCLUSION_DISTANCE,
+    VALIDATOR_MONITOR_PROPOSALS, VALIDATOR_MONITOR_SYNC_COMMITTEE,
+    VALIDATOR_MONITOR_VALIDATOR_ATTESTATIONS, VALIDATOR_MONITOR_VALIDATOR_BALANCE,
+    VALIDATOR_MONITOR_VALIDATOR_PROPOSALS, inc_int_counter_vec_by, set_int_gauge_vec,
+};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+use tree_hash::TreeHash;
+
+use crate::beacon_api_client::BeaconApiClient;
+
+/// The number of validators with their own metrics, so the label cardinality stays bounded
+pub const MAX_INDIVIDUALLY_MONITORED_VALIDATORS: usize = 64;
+
+/// The outcome of the duties of a single validator over one epoch.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ValidatorEpochSummary {
+    pub attestations_expected: u64,
+    pub attestations_included: u64,
+    pub correct_head: u64,
+    pub correct_target: u64,
+    pub correct_source: u64,
+    pub inclusion_distance: Option<u64>,
+    pub proposals_expected: u64,
+    pub proposals_included: u64,
+    pub block_rewards: u64,
+    pub sync_messages_expected: u64,
+    pub sync_messages_included: u64,
+    pub balance: Option<u64>,
+    pub balance_delta: Option<i64>,
+}
+
+/// Tracks whether the duties of the managed validators made it on chain.
+///
+/// Epoch `N` is checked at the start of epoch `N + 2`, once attestations for it can no longer be
+/// included. The blocks of the epochs involved are fetched from the beacon node and kept until
+/// they are no longer needed.
+#[derive(Debug, Default)]
+pub struct ValidatorMonitor {
+    blocks: BTreeMap<u64, Option<(B256, BeaconBlock)>>,
+    attester_duties: BTreeMap<u64, Vec<AttesterDuty>>,
+    proposer_duties: BTreeMap<u64, Vec<ProposerDuty>>,
+    sync_committee_duties: BTreeMap<u64, Vec<SyncCommitteeDuty>>,
+    balances: HashMap<u64, u64>,
+}
+
+impl ValidatorMonitor {
+    pub fn record_attester_duties(&mut self, epoch: u64, duties: &[AttesterDuty]) {
+        self.attester_duties.insert(epoch, duties.to_vec());
+    }
+
+    pub fn record_proposer_duties(&mut self, epoch: u64, duties: &[ProposerDuty]) {
+        self.proposer_duties.insert(epoch, duties.to_vec());
+    }
+
+    pub fn record_sync_committee_duties(&mut self, epoch: u64, duties: &[SyncCommitteeDuty]) {
+        self.sync_committee_duties.insert(epoch, duties.to_vec());
+    }
+
+    /// Records the canonical block at `slot`, or `None` if the slot was skipped.
+    pub fn insert_block(&mut self, slot: u64, block: Option<BeaconBlock>) {
+        self.blocks
+            .insert(slot, block.map(|block| (block.tree_hash_root(), block)));
+    }
+
+    /// Checks the duties of `epoch`, then publishes the results as metrics and logs a summary.
+    ///
+    /// The monitor is only locked to read and update its records, never across requests to the
+    /// beacon node, so the duties of the following epochs can be recorded in the meantime.
+    pub async fn process_epoch(
+        monitor: &Mutex<Self>,
+        beacon_api_client: &BeaconApiClient,
+        epoch: u64,
+    ) -> anyhow::Result<()> {
+        let start_slot = compute_start_slot_at_epoch(epoch);
+        let (validator_indices, missing_slots) = {
+            let mut monitor = monitor.lock().await;
+            let validator_indices = monitor.validator_indices(epoch);
+            if validator_indices.is_empty() {
+                monitor.prune(epoch);
+                return Ok(());
+            }
+            let missing_slots = (start_slot.saturating_sub(SLOTS_PER_EPOCH)
+                ..start_slot + 2 * SLOTS_PER_EPOCH)
+                .filter(|slot| !monitor.blocks.contains_key(slot))
+                .collect::<Vec<_>>();
+            (validator_indices, missing_slots)
+        };
+
+        let blocks = join_all(missing_slots.iter().map(|slot| async move {
+            (*slot, beacon_api_client.get_block(ID::Slot(*slot)).await)
+        }))
+        .await;
+        let committee_lengths = beacon_api_client
+            .get_committees(ID::Slot(start_slot), epoch)
+            .await?
+            .data
+            .into_iter()
+            .map(|committee| {
+                (
+                    (committee.slot, committee.index),
+                    committee.validators.len(),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        let (mut summaries, included_proposals) = {
+            let mut monitor = monitor.lock().await;
+            for (slot, block) in blocks {
+                // The slot is left out of the summary and fetched again for the next epoch
+                match block {
+                    Ok(block) => monitor.insert_block(slot, block.map(|block| block.message)),
+                    Err(err) => warn!("Failed to get block at slot {slot}: {err:?}"),
+                }
+            }
+            let included_proposals = monitor
+                .proposer_duties
+                .get(&epoch)
+                .into_iter()
+                .flatten()
+                .filter(|duty| monitor.is_proposal_included(duty))
+                .map(|duty| (duty.slot, duty.validator_index))
+                .collect::<Vec<_>>();
+            (
+                monitor.summarize_epoch(epoch, &committee_lengths),
+                included_proposals,
+            )
+        };
+
+        for (slot, validator_index) in included_proposals {
+            match beacon_api_client.get_block_rewards(ID::Slot(slot)).await {
+                Ok(rewards) => {
+                    if let Some(summary) = summaries.get_mut(&validator_index) {
+                        summary.block_rewards += rewards.data.total;
+                    }
+                }
+                Err(err) => warn!("Failed to get block rewards for slot {slot}: {err:?}"),
+            }
+        }
+
+        // The balances after the epoch transition out of `epoch`
+        let validators = beacon_api_client
+            .get_state_validator_list(
+                ID::Slot(compute_start_slot_at_epoch(epoch + 1)),
+                Some(
+                    validator_indices
+                        .iter()
+                        .map(|index| ValidatorID::Index(*index))
+                        .collect(),
+                ),
+                None,
+            )
+            .await;
+
+        {
+            let mut monitor = monitor.lock().await;
+            match validators {
+                Ok(validators) => {
+                    for validator in validators.data {
+                        let Some(summary) = summaries.get_mut(&validator.index) else {
+                            continue;
+                        };
+                        summary.balance = Some(validator.balance);
+                        summary.balance_delta = monitor
+                            .balances
+                            .insert(validator.index, validator.balance)
+                            .map(|previous| validator.balance as i64 - previous as i64);
+                    }
+                }
+                Err(err) => warn!("Failed to get validator balances: {err:?}"),
+            }
+            monitor.prune(epoch);
+        }
+
+        record_metrics(&summaries);
+        log_summary(epoch, &summaries);
+        Ok(())
+    }
+
+    fn validator_indices(&self, epoch: u64) -> Vec<u64> {
+        let mut validator_indices = self
+            .attester_duties
+            .get(&epoch)
+            .into_iter()
+            .flatten()
+            .map(|duty| duty.validator_index)
+            .chain(
+                self.proposer_duties
+                    .get(&epoch)
+                    .into_iter()
+                    .flatten()
+                    .map(|duty| duty.validator_index),
+            )
+            .chain(
+                self.sync_committee_duties
+                    .get(&epoch)
+                    .into_iter()
+                    .flatten()
+                    .map(|duty| duty.validator_index),
+            )
+            .collect::<Vec<_>>();
+        validator_indices.sort_unstable();
+        validator_indices.dedup();
+        validator_indices
+    }
+
+    /// Summarizes the duties of `epoch` from the recorded blocks. `committee_lengths` holds the
+    /// length of every committee of the epoch, keyed by slot and committee index.
+    pub fn summarize_epoch(
+        &self,
+        epoch: u64,
+        committee_lengths: &HashMap<(u64, u64), usize>,
+    ) -> BTreeMap<u64, ValidatorEpochSummary> {
+        let mut summaries = self
+            .validator_indices(epoch)
+            .into_iter()
+            .map(|index| (index, ValidatorEpochSummary::default()))
+            .collect::<BTreeMap<_, _>>();
+        let start_slot = compute_start_slot_at_epoch(epoch);
+        let target_root = self.block_root_at(start_slot);
+
+        for duty in self.attester_duties.get(&epoch).into_iter().flatten() {
+            let summary = summaries.entry(duty.validator_index).or_default();
+            summary.attestations_expected += 1;
+
+            let inclusion = self
+                .blocks
+                .range(duty.slot + 1..start_slot + 2 * SLOTS_PER_EPOCH)
+                .filter_map(|(slot, block)| block.as_ref().map(|(_, block)| (slot, block)))
+                .find_map(|(slot, block)| {
+                    block
+                        .body
+                        .attestations
+                        .iter()
+                        .find(|attestation| {
+                            attestation.data.slot == duty.slot
+                                && includes_attester(attestation, duty, committee_lengths)
+                        })
+                        .map(|attestation| (slot - duty.slot, attestation))
+                });
+            let Some((inclusion_distance, attestation)) = inclusion else {
+                continue;
+            };
+
+            summary.attestations_included += 1;
+            summary.inclusion_distance = Some(inclusion_distance);
+            // Attestations with a wrong source can not be included in a block
+            summary.correct_source += 1;
+            if Some(attestation.data.target.root) == target_root
+                && attestation.data.target.epoch == epoch
+            {
+                summary.correct_target += 1;
+            }
+            if Some(attestation.data.beacon_block_root) == self.block_root_at(duty.slot) {
+                summary.correct_head += 1;
+            }
+        }
+
+        for duty in self.proposer_duties.get(&epoch).into_iter().flatten() {
+            let summary = summaries.entry(duty.validator_index).or_default();
+            summary.proposals_expected += 1;
+            if self.is_proposal_included(duty) {
+                summary.proposals_included += 1;
+            }
+        }
+
+        // The sync aggregate of a block covers the messages of the previous slot
+        for duty in self.sync_committee_duties.get(&epoch).into_iter().flatten() {
+            let summary = summaries.entry(duty.validator_index).or_default();
+            for (_, block) in self
+                .blocks
+                .range(start_slot + 1..=start_slot + SLOTS_PER_EPOCH)
+                .filter_map(|(_, block)| block.as_ref())
+            {
+                let sync_committee_bits = &block.body.sync_aggregate.sync_committee_bits;
+                for index in &duty.validator_sync_committee_indices {
+                    summary.sync_messages_expected += 1;
+                    if sync_committee_bits.get(*index as usize).unwrap_or(false) {
+                        summary.sync_messages_included += 1;
+                    }
+                }
+            }
+        }
+
+        summaries
+    }
+
+    fn is_proposal_included(&self, duty: &ProposerDuty) -> bool {
+        matches!(
+            self.blocks.get(&duty.slot),
+            Some(Some((_, block))) if block.proposer_index == duty.validator_index
+        )
+    }
+
+    /// The root of the latest recorded block at or before `slot`.
+    fn block_root_at(&self, slot: u64) -> Option<B256> {
+        self.blocks
+            .range(..=slot)
+            .rev()
+            .find_map(|(_, block)| block.as_ref().map(|(root, _)| *root))
+    }
+
+    /// Drops the blocks and duties which are not needed to check epochs after `epoch`.
+    fn prune(&mut self, epoch: u64) {
+        let start_slot = compute_start_slot_at_epoch(epoch);
+        self.blocks = self.blocks.split_off(&start_slot);
+        self.attester_duties = self.attester_duties.split_off(&(epoch + 1));
+        self.proposer_duties = self.proposer_duties.split_off(&(epoch + 1));
+        self.sync_committee_duties = self.sync_committee_duties.split_off(&(epoch + 1));
+    }
+}
+
+/// Returns true if the aggregation bits of `attestation` include the validator of `duty`.
+fn includes_attester(
+    attestation: &Attestation,
+    duty: &AttesterDuty,
+    committee_lengths: &HashMap<(u64, u64), usize>,
+) -> bool {
+    if !attestation
+        .committee_bits
+        .get(duty.committee_index as usize)
+        .unwrap_or(false)
+    {
+        return false;
+    }
+
+    let mut offset = 0;
+    for committee_index in get_committee_indices(&attestation.committee_bits) {
+        if committee_index == duty.committee_index {
+            break;
+        }
+        let Some(length) = committee_lengths.get(&(duty.slot, committee_index)) else {
+            return false;
+        };
+        offset += length;
+    }
+
+    attestation
+        .aggregation_bits
+        .get(offset + duty.validator_committee_index as usize)
+        .unwrap_or(false)
+}
+
+/// Publishes the totals of `summaries` as metrics, along with metrics labelled by validator for
+/// the first `MAX_INDIVIDUALLY_MONITORED_VALIDATORS` validators.
+fn record_metrics(summaries: &BTreeMap<u64, ValidatorEpochSummary>) {
+    for summary in summaries.values() {
+        for (outcome, count) in [
+            ("included", summary.attestations_included),
+            (
+                "missed",
+                summary.attestations_expected - summary.attestations_included,
+            ),
+            ("correct_head", summary.correct_head),
+            ("correct_target", summary.correct_target),
+            ("correct_source", summary.correct_source),
+        ] {
+            inc_int_counter_vec_by(&VALIDATOR_MONITOR_ATTESTATIONS, count, &[outcome]);
+        }
+        if let Some(inclusion_distance) = summary.inclusion_distance {
+            VALIDATOR_MONITOR_INCLUSION_DISTANCE.inc_by(inclusion_distance);
+        }
+
+        inc_int_counter_vec_by(
+            &VALIDATOR_MONITOR_PROPOSALS,
+            summary.proposals_included,
+            &["proposed"],
+        );
+        inc_int_counter_vec_by(
+            &VALIDATOR_MONITOR_PROPOSALS,
+            summary.proposals_expected - summary.proposals_included,
+            &["missed"],
+        );
+        VALIDATOR_MONITOR_BLOCK_REWARDS.inc_by(summary.block_rewards);
+
+        inc_int_counter_vec_by(
+            &VALIDATOR_MONITOR_SYNC_COMMITTEE,
+            summary.sync_messages_included,
+            &["included"],
+        );
+        inc_int_counter_vec_by(
+            &VALIDATOR_MONITOR_SYNC_COMMITTEE,
+            summary.sync_messages_expected - summary.sync_messages_included,
+            &["missed"],
+        );
+    }
+
+    for (validator_index, summary) in summaries.iter().take(MAX_INDIVIDUALLY_MONITORED_VALIDATORS) {
+        let validator = validator_index.to_string();
+        let validator = validator.as_str();
+        inc_int_counter_vec_by(
+            &VALIDATOR_MONITOR_VALIDATOR_ATTESTATIONS,
+            summary.attestations_included,
+            &[validator, "included"],
+        );
+        inc_int_counter_vec_by(
+            &VALIDATOR_MONITOR_VALIDATOR_ATTESTATIONS,
+            summary.attestations_expected - summary.attestations_included,
+            &[validator, "missed"],
+        );
+        inc_int_counter_vec_by(
+            &VALIDATOR_MONITOR_VALIDATOR_PROPOSALS,
+            summary.proposals_included,
+            &[validator, "proposed"],
+        );
+        inc_int_counter_vec_by(
+            &VALIDATOR_MONITOR_VALIDATOR_PROPOSALS,
+            summary.proposals_expected - summary.proposals_included,
+            &[validator, "missed"],
+        );
+        if let Some(balance) = summary.balance {
+            set_int_gauge_vec(
+                &VALIDATOR_MONITOR_VALIDATOR_BALANCE,
+                balance as i64,
+                &[validator],
+            );
+        }
+    }
+
+    if summaries.values().any(|summary| summary.balance.is_some()) {
+        let balance = summaries
+            .values()
+            .filter_map(|summary| summary.balance)
+            .sum::<u64>();
+        VALIDATOR_MONITOR_BALANCE.set(balance as i64);
+    }
+    if summaries
+        .values()
+        .any(|summary| summary.balance_delta.is_some())
+    {
+        let balance_delta = summaries
+            .values()
+            .filter_map(|summary| summary.balance_delta)
+            .sum::<i64>();
+        VALIDATOR_MONITOR_BALANCE_DELTA.set(balance_delta);
+    }
+}
+
+fn log_summary(epoch: u64, summaries: &BTreeMap<u64, ValidatorEpochSummary>) {
+    let mut total = ValidatorEpochSummary::default();
+    let mut inclusion_distances = vec![];
+    let mut balance_delta = 0;
+    for (validator_index, summary) in summaries {
+        if summary.attestations_included < summary.attestations_expected {
+            warn!("Validator {validator_index} missed an attestation in epoch {epoch}");
+        }
+        if summary.proposals_included < summary.proposals_expected {
+            warn!("Validator {validator_index} missed a block proposal in epoch {epoch}");
+        }
+        total.attestations_expected += summary.attestations_expected;
+        total.attestations_included += summary.attestations_included;
+        total.correct_head += summary.correct_head;
+        total.correct_target += summary.correct_target;
+        total.correct_source += summary.correct_source;
+        total.proposals_expected += summary.proposals_expected;
+        total.proposals_included += summary.proposals_included;
+        total.block_rewards += summary.block_rewards;
+        total.sync_messages_expected += summary.sync_messages_expected;
+        total.sync_messages_included += summary.sync_messages_included;
+        inclusion_distances.extend(summary.inclusion_distance);
+        balance_delta += summary.balance_delta.unwrap_or_default();
+    }
+
+    let average_inclusion_distance = match inclusion_distances.is_empty() {
+        true => 0.0,
+        false => inclusion_distances.iter().sum::<u64>() as f64 / inclusion_distances.len() as f64,
+    };
+    info!(
+        "Epoch {epoch} summary: attestations {}/{} (head {}, target {}, source {}, average inclusion distance {average_inclusion_distance:.2}), proposals {}/{} ({} gwei rewards), sync committee messages {}/{}, balance change {balance_delta} gwei",
+        total.attestations_included,
+        total.attestations_expected,
+        total.correct_head,
+        total.correct_target,
+        total.correct_source,
+        total.proposals_included,
+        total.proposals_expected,
+        total.block_rewards,
+        total.sync_messages_included,
+        total.sync_messages_expected,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_bls::{BLSSignature, PublicKey};
+    use ream_consensus_misc::{attestation_data::AttestationData, checkpoint::Checkpoint};
+    use ssz_types::{BitList, BitVector};
+
+    use super::*;
+
+    const EPOCH: u64 = 2;
+
+    fn attester_duty(
+        slot: u64,
+        committee_index: u64,
+        validator_committee_index: u64,
+    ) -> AttesterDuty {
+        AttesterDuty {
+            public_key: PublicKey::default(),
+            validator_index: 7,
+            committee_index,
+            committees_at_slot: 2,
+            validator_committee_index,
+            slot,
+        }
+    }
+
+    fn block(slot: u64, parent_root: B256, attestations: Vec<Attestation>) -> BeaconBlock {
+        let mut block = BeaconBlock {
+            slot,
+            proposer_index: 7,
+            parent_root,
+            ..Default::default()
+        };
+        block.body.attestations = attestations.into();
+        block
+    }
+
+    fn attestation(slot: u64, beacon_block_root: B256, target_root: B256) -> Attestation {
+        let mut committee_bits = BitVector::new();
+        committee_bits.set(0, true).unwrap();
+        committee_bits.set(1, true).unwrap();
+        // Committee 0 has 3 members and committee 1 has 4, the validator is the second member of
+        // committee 1
+        let mut aggregation_bits = BitList::with_capacity(7).unwrap();
+        aggregation_bits.set(4, true).unwrap();
+
+        Attestation {
+            aggregation_bits,
+            data: AttestationData {
+                slot,
+                index: 0,
+                beacon_block_root,
+                source: Checkpoint::default(),
+                target: Checkpoint {
+                    epoch: EPOCH,
+                    root: target_root,
+                },
+            },
+            signature: BLSSignature::infinity(),
+            committee_bits,
+        }
+    }
+
+    #[test]
+    fn test_summarize_epoch() {
+        let start_slot = compute_start_slot_at_epoch(EPOCH);
+        let mut monitor = ValidatorMonitor::default();
+        let committee_lengths = HashMap::from([((start_slot, 0), 3), ((start_slot, 1), 4)]);
+
+        let first_block = block(start_slot, B256::ZERO, vec![]);
+        let first_root = first_block.tree_hash_root();
+        monitor.insert_block(start_slot, Some(first_block));
+        monitor.insert_block(start_slot + 1, None);
+        monitor.insert_block(
+            start_slot + 2,
+            Some(block(
+                start_slot + 2,
+                first_root,
+                vec![attestation(start_slot, first_root, first_root)],
+            )),
+        );
+
+        monitor.record_attester_duties(EPOCH, &[attester_duty(start_slot, 1, 1)]);
+        monitor.record_proposer_duties(
+            EPOCH,
+            &[
+                ProposerDuty {
+                    public_key: PublicKey::default(),
+                    validator_index: 7,
+                    slot: start_slot,
+                },
+                ProposerDuty {
+                    public_key: PublicKey::default(),
+                    validator_index: 7,
+                    slot: start_slot + 1,
+                },
+            ],
+        );
+
+        let summary = monitor.summarize_epoch(EPOCH, &committee_lengths)[&7].clone();
+        assert_eq!(summary.attestations_expected, 1);
+        assert_eq!(summary.attestations_included, 1);
+        assert_eq!(summary.inclusion_distance, Some(2));
+        assert_eq!(summary.correct_head, 1);
+        assert_eq!(summary.correct_target, 1);
+        assert_eq!(summary.correct_source, 1);
+        assert_eq!(summary.proposals_expected, 2);
+        assert_eq!(summary.proposals_included, 1);
+
+        // A different validator of the same committee was not included
+        monitor.record_attester_duties(EPOCH, &[attester_duty(start_slot, 1, 0)]);
+        let summary = monitor.summarize_epoch(EPOCH, &committee_lengths)[&7].clone();
+        assert_eq!(summary.attestations_included, 0);
+    }
+}
@@ -0,0 +1,305 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use alloy_primitives::{Address, B256};
+use anyhow::{anyhow, ensure};
+use ream_bls::PublicKey;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use url::Url;
+
+use crate::{
+    builder::validator_registration::ValidatorRegistrationV1, constants::DEFAULT_GAS_LIMIT,
+};
+
+/// Builder settings of a proposer config entry.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuilderOptions {
+    #[serde(default)]
+    pub enabled: bool,
+    pub boost_factor: Option<u64>,
+    #[serde(default)]
+    pub relays: Vec<Url>,
+}
+
+/// Settings of a proposer config entry. Unset fields fall back to the `default_config` of the
+/// file, and then to the validator node flags.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposerOptions {
+    pub fee_recipient: Option<Address>,
+    pub gas_limit: Option<u64>,
+    pub graffiti: Option<String>,
+    pub builder: Option<BuilderOptions>,
+}
+
+/// A proposer config file, keyed by validator public key:
+///
+/// ```yaml
+/// proposer_config:
+///   "0xa057816155ad77931185101128655c0191bd0214c201ca48ed887f6c4c6adf334070efcd75140eada5ac83a92506dd7a":
+///     fee_recipient: "0x50155530FCE8a85ec7055A5F8b2bE214B3DaeFd3"
+///     graffiti: "ream"
+///     builder:
+///       enabled: true
+///       boost_factor: 90
+///       relays: ["https://relay.example.org"]
+/// default_config:
+///   fee_recipient: "0x6e35733c5af9B61374A128e6F85f553aF09ff89A"
+///   gas_limit: 36000000
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposerConfigFile {
+    #[serde(default)]
+    pub proposer_config: HashMap<PublicKey, ProposerOptions>,
+    #[serde(default)]
+    pub default_config: ProposerOptions,
+}
+
+impl ProposerConfigFile {
+    pub fn load_from_file<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .map_err(|err| anyhow!("Failed to read proposer config {}: {err:?}", path.display()))?;
+        let config: Self = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => serde_json::from_str(&contents)?,
+            _ => serde_yaml::from_str(&contents)?,
+        };
+        config.validate()?;
+        Ok(config)
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        for options in self.proposer_config.values().chain([&self.default_config]) {
+            if let Some(graffiti) = &options.graffiti {
+                ensure!(
+                    graffiti.len() <= 32,
+                    "Graffiti must be at most 32 bytes: {graffiti}"
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The resolved proposer settings of a single validator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProposerSettings {
+    pub fee_recipient: Address,
+    pub gas_limit: u64,
+    pub graffiti: Option<B256>,
+    pub builder_enabled: bool,
+    pub builder_boost_factor: Option<u64>,
+    pub relays: Vec<Url>,
+}
+
+impl ProposerSettings {
+    pub fn validator_registration(
+        &self,
+        public_key: PublicKey,
+        timestamp: u64,
+    ) -> ValidatorRegistrationV1 {
+        ValidatorRegistrationV1 {
+            fee_recipient: self.fee_recipient,
+            gas_limit: self.gas_limit,
+            timestamp,
+            public_key,
+        }
+    }
+}
+
+/// Per-validator proposer configuration, optionally backed by a proposer config file that is
+/// reloaded when it changes on disk.
+#[derive(Debug, Clone)]
+pub struct ProposerConfig {
+    path: Option<PathBuf>,
+    last_modified: Option<SystemTime>,
+    file: ProposerConfigFile,
+    default_settings: ProposerSettings,
+}
+
+impl ProposerConfig {
+    pub fn new(
+        path: Option<PathBuf>,
+        suggested_fee_recipient: Address,
+        builder_enabled: bool,
+        mev_relay_urls: Vec<Url>,
+    ) -> anyhow::Result<Self> {
+        let (file, last_modified) = match &path {
+            Some(path) => (
+                ProposerConfigFile::load_from_file(path)?,
+                last_modified(path),
+            ),
+            None => (ProposerConfigFile::default(), None),
+        };
+
+        Ok(Self {
+            path,
+            last_modified,
+            file,
+            default_settings: ProposerSettings {
+                fee_recipient: suggested_fee_recipient,
+                gas_limit: DEFAULT_GAS_LIMIT,
+                graffiti: None,
+                builder_enabled,
+                builder_boost_factor: None,
+                relays: mev_relay_urls,
+            },
+        })
+    }
+
+    /// Reloads the proposer config file if it was modified since it was last loaded. An invalid
+    /// file is ignored and the previous configuration is kept.
+    pub fn reload_if_changed(&mut self) -> bool {
+        let Some(path) = &self.path else {
+            return false;
+        };
+        let modified = last_modified(path);
+        if modified == self.last_modified {
+            return false;
+        }
+
+        match ProposerConfigFile::load_from_file(path) {
+            Ok(file) => {
+                info!("Reloaded proposer config from {}", path.display());
+                self.file = file;
+                self.last_modified = modified;
+                true
+            }
+            Err(err) => {
+                warn!(
+                    "Failed to reload proposer config from {}, keeping the previous one: {err:?}",
+                    path.display()
+                );
+                self.last_modified = modified;
+                false
+            }
+        }
+    }
+
+    pub fn settings(&self, public_key: &PublicKey) -> ProposerSettings {
+        let default_options = &self.file.default_config;
+        let options = self
+            .file
+            .proposer_config
+            .get(public_key)
+            .unwrap_or(default_options);
+        let builder = options
+            .builder
+            .as_ref()
+            .or(default_options.builder.as_ref());
+
+        ProposerSettings {
+            fee_recipient: options
+                .fee_recipient
+                .or(default_options.fee_recipient)
+                .unwrap_or(self.default_settings.fee_recipient),
+            gas_limit: options
+                .gas_limit
+                .or(default_options.gas_limit)
+                .unwrap_or(self.default_settings.gas_limit),
+            graffiti: options
+                .graffiti
+                .as_ref()
+                .or(default_options.graffiti.as_ref())
+                .map(|graffiti| graffiti_to_bytes(graffiti.as_str())),
+            builder_enabled: builder
+                .map(|builder| builder.enabled)
+                .unwrap_or(self.default_settings.builder_enabled),
+            builder_boost_factor: builder.and_then(|builder| builder.boost_factor),
+            relays: match builder {
+                Some(builder) if !builder.relays.is_empty() => builder.relays.clone(),
+                _ => self.default_settings.relays.clone(),
+            },
+        }
+    }
+}
+
+fn last_modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+fn graffiti_to_bytes(graffiti: &str) -> B256 {
+    let mut bytes = B256::ZERO;
+    let length = graffiti.len().min(32);
+    bytes[..length].copy_from_slice(&graffiti.as_bytes()[..length]);
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use alloy_primitives::address;
+
+    use super::*;
+
+    const PUBLIC_KEY: &str = "0xa057816155ad77931185101128655c0191bd0214c201ca48ed887f6c4c6adf334070efcd75140eada5ac83a92506dd7a";
+
+    fn proposer_config(file: &str) -> ProposerConfig {
+        ProposerConfig {
+            path: None,
+            last_modified: None,
+            file: serde_yaml::from_str(file).unwrap(),
+            default_settings: ProposerSettings {
+                fee_recipient: Address::ZERO,
+                gas_limit: DEFAULT_GAS_LIMIT,
+                graffiti: None,
+                builder_enabled: false,
+                builder_boost_factor: None,
+                relays: vec![],
+            },
+        }
+    }
+
+    #[test]
+    fn test_settings_resolution() {
+        let config = proposer_config(&format!(
+            r#"
+proposer_config:
+  "{PUBLIC_KEY}":
+    fee_recipient: "0x50155530FCE8a85ec7055A5F8b2bE214B3DaeFd3"
+    graffiti: "ream"
+    builder:
+      enabled: true
+      boost_factor: 90
+      relays: ["https://relay.example.org"]
+default_config:
+  fee_recipient: "0x6e35733c5af9B61374A128e6F85f553aF09ff89A"
+  gas_limit: 30000000
+"#
+        ));
+
+        let settings = config.settings(&PublicKey::from_str(PUBLIC_KEY).unwrap());
+        assert_eq!(
+            settings.fee_recipient,
+            address!("0x50155530FCE8a85ec7055A5F8b2bE214B3DaeFd3")
+        );
+        assert_eq!(settings.gas_limit, 30000000);
+        assert_eq!(&settings.graffiti.unwrap()[..4], b"ream");
+        assert!(settings.builder_enabled);
+        assert_eq!(settings.builder_boost_factor, Some(90));
+        assert_eq!(settings.relays.len(), 1);
+
+        let default_settings = config.settings(&PublicKey::default());
+        assert_eq!(
+            default_settings.fee_recipient,
+            address!("0x6e35733c5af9B61374A128e6F85f553aF09ff89A")
+        );
+        assert_eq!(default_settings.graffiti, None);
+        assert!(!default_settings.builder_enabled);
+    }
+
+    #[test]
+    fn test_settings_fall_back_to_flags() {
+        let config = proposer_config("{}");
+        let settings = config.settings(&PublicKey::default());
+
+        assert_eq!(settings, config.default_settings);
+    }
+}
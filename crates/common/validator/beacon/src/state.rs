@@ -4,6 +4,7 @@ use ream_consensus_beacon::{
     electra::{
         beacon_block::{BeaconBlock, SignedBeaconBlock},
         beacon_state::BeaconState,
+        blinded_beacon_block::BlindedBeaconBlock,
     },
     execution_engine::engine_trait::ExecutionApi,
 };
@@ -11,6 +12,7 @@ use ream_consensus_misc::{
     constants::beacon::DOMAIN_RANDAO,
     misc::{compute_epoch_at_slot, compute_signing_root},
 };
+use ream_execution_engine::ExecutionEngine;
 use tree_hash::TreeHash;
 
 pub fn get_epoch_signature(
@@ -41,3 +43,33 @@ pub async fn compute_new_state_root<T: ExecutionApi>(
         .await?;
     Ok(temp_state.tree_hash_root())
 }
+
+/// Computes the post state root of a blinded block which was built from `block`, a locally
+/// produced block with the same consensus contents. The local execution payload stands in for the
+/// builder payload during the state transition; the parts of the state derived from the payload
+/// are then replaced with the ones committed to by the blinded block.
+pub async fn compute_new_blinded_state_root(
+    state: &BeaconState,
+    block: &BeaconBlock,
+    blinded_block: &BlindedBeaconBlock,
+) -> anyhow::Result<B256> {
+    let mut block = block.clone();
+    block.body.blob_kzg_commitments = blinded_block.body.blob_kzg_commitments.clone();
+    block.body.execution_requests = blinded_block.body.execution_requests.clone();
+
+    let mut temp_state = state.clone();
+    temp_state
+        .state_transition(
+            &SignedBeaconBlock {
+                message: block,
+                signature: BLSSignature::infinity(),
+            },
+            false,
+            &None::<ExecutionEngine>,
+        )
+        .await?;
+    temp_state.latest_block_header.body_root = blinded_block.body.tree_hash_root();
+    temp_state.latest_execution_payload_header =
+        blinded_block.body.execution_payload_header.clone();
+    Ok(temp_state.tree_hash_root())
+}
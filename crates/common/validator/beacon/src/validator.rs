@@ -1,23 +1,27 @@
 use std::{
-    collections::{HashMap, hash_map::Entry},
+    collections::{HashMap, HashSet, hash_map::Entry},
     mem::take,
     sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
     vec,
 };
 
-use alloy_primitives::Address;
-use anyhow::{anyhow, bail};
+use alloy_primitives::{B256, U256};
+use anyhow::{anyhow, bail, ensure};
 use futures::future::try_join_all;
 use ream_api_types_beacon::{
-    block::{BroadcastValidation, ProduceBlockData},
+    block::{BroadcastValidation, FullBlockData, ProduceBlockData, SignedBlockContents},
     duties::{AttesterDuty, ProposerDuty, SyncCommitteeDuty},
     id::{ID, ValidatorID},
-    request::SyncCommitteeRequestItem,
+    request::{PrepareBeaconProposerItem, SyncCommitteeRequestItem},
 };
 use ream_bls::{BLSSignature, PublicKey, traits::Signable};
 use ream_consensus_beacon::{
-    electra::beacon_state::BeaconState, single_attestation::SingleAttestation,
+    electra::{
+        beacon_block::BeaconBlock, beacon_state::BeaconState,
+        blinded_beacon_block::BlindedBeaconBlock,
+    },
+    single_attestation::SingleAttestation,
 };
 use ream_consensus_misc::{
     attestation_data::AttestationData,
@@ -28,9 +32,13 @@ use ream_consensus_misc::{
 };
 use ream_executor::ReamExecutor;
 use ream_keystore::keystore::Keystore;
+use ream_metrics::{PROPOSED_BLOCK_SOURCE, inc_int_counter_vec};
 use ream_network_spec::networks::beacon_network_spec;
 use reqwest::Url;
-use tokio::time::{Instant, MissedTickBehavior, interval_at, sleep};
+use tokio::{
+    sync::Mutex,
+    time::{Instant, MissedTickBehavior, interval_at, sleep},
+};
 use tracing::{error, info, warn};
 use tree_hash::TreeHash;
 
@@ -38,12 +46,20 @@ use crate::{
     aggregate_and_proof::{AggregateAndProof, SignedAggregateAndProof, sign_aggregate_and_proof},
     attestation::{get_selection_proof, sign_attestation_data},
     beacon_api_client::BeaconApiClient,
-    block::{sign_beacon_block, sign_blinded_beacon_block},
-    constants::SYNC_COMMITTEE_SUBNET_COUNT,
+    block::{blinded_block_from_bid, sign_beacon_block, sign_blinded_beacon_block},
+    builder::{
+        builder_bid::BuilderBid,
+        multi_relay::{BestBid, MultiRelayClient, is_builder_bid_preferred},
+        registration_cache::RegistrationCache,
+    },
+    constants::{SYNC_COMMITTEE_SUBNET_COUNT, VALIDATOR_REGISTRATION_BATCH_SIZE},
     contribution_and_proof::{
         ContributionAndProof, SignedContributionAndProof, get_contribution_and_proof_signature,
     },
+    monitor::ValidatorMonitor,
+    proposer_config::ProposerConfig,
     randao::sign_randao_reveal,
+    state::compute_new_blinded_state_root,
     sync_committee::{get_sync_committee_selection_proof, is_sync_committee_aggregator},
     voluntary_exit::sign_voluntary_exit,
 };
@@ -72,7 +88,10 @@ pub struct SyncTaskInfo {
 pub struct ValidatorService {
     pub beacon_api_client: Arc<BeaconApiClient>,
     pub validators: Vec<Arc<Keystore>>,
-    pub suggested_fee_recipient: Arc<Address>,
+    pub proposer_config: ProposerConfig,
+    pub relay_client: MultiRelayClient,
+    pub registration_cache: RegistrationCache,
+    pub monitor: Arc<Mutex<ValidatorMonitor>>,
     pub executor: ReamExecutor,
     pub active_validator_count: usize,
     pub public_key_to_index: HashMap<PublicKey, u64>,
@@ -82,14 +101,17 @@ pub struct ValidatorService {
     pub sync_committee_duties: Vec<SyncCommitteeDuty>,
     pub sync_aggregator_infos: Vec<SyncTaskInfo>,
     pub sync_normal_infos: Vec<SyncTaskInfo>,
+    /// The head state fetched ahead of a proposal which may use a builder payload
+    pub proposal_state: Option<BeaconState>,
 }
 
 impl ValidatorService {
     pub fn new(
         keystores: Vec<Keystore>,
-        suggested_fee_recipient: Address,
+        proposer_config: ProposerConfig,
         beacon_api_endpoint: Url,
         request_timeout: Duration,
+        builder_header_timeout: Duration,
         executor: ReamExecutor,
     ) -> anyhow::Result<Self> {
         let validators = keystores.into_iter().map(Arc::new).collect::<Vec<_>>();
@@ -100,7 +122,10 @@ impl ValidatorService {
                 request_timeout,
             )?),
             validators,
-            suggested_fee_recipient: Arc::new(suggested_fee_recipient),
+            proposer_config,
+            relay_client: MultiRelayClient::new(request_timeout, builder_header_timeout),
+            registration_cache: RegistrationCache::default(),
+            monitor: Arc::new(Mutex::new(ValidatorMonitor::default())),
             executor,
             active_validator_count: 0,
             public_key_to_index: HashMap::new(),
@@ -110,6 +135,7 @@ impl ValidatorService {
             sync_committee_duties: Vec::new(),
             sync_aggregator_infos: Vec::new(),
             sync_normal_infos: Vec::new(),
+            proposal_state: None,
         })
     }
 
@@ -134,6 +160,13 @@ impl ValidatorService {
         };
         interval.set_missed_tick_behavior(MissedTickBehavior::Burst);
 
+        // Register with the builders right away, so proposals before the next epoch can already
+        // use them.
+        self.fetch_validator_indicies().await;
+        if let Err(err) = self.register_validators().await {
+            warn!("Failed to register validators with the builder network: {err:?}");
+        }
+
         loop {
             tokio::select! {
                 _ = interval.tick() => {
@@ -217,11 +250,30 @@ impl ValidatorService {
     }
 
     // Runs on the start of every epoch prior to the per-slot code.
+    // - Reloads the proposer config if it changed
     // - Fetches validator indicies
+    // - Sends the proposer preparations and builder registrations to the beacon node
     // - Fetches proposer and committee duties for the epoch
+    // - Checks in the background whether the duties of two epochs ago landed on chain
     pub async fn on_epoch(&mut self, epoch: u64) {
         info!("Current Epoch: {epoch}");
 
+        if let Some(monitored_epoch) = epoch.checked_sub(2) {
+            let monitor = self.monitor.clone();
+            let beacon_api_client = self.beacon_api_client.clone();
+            self.executor.spawn(async move {
+                if let Err(err) =
+                    ValidatorMonitor::process_epoch(&monitor, &beacon_api_client, monitored_epoch)
+                        .await
+                {
+                    warn!(
+                        "Failed to monitor validator duties for epoch {monitored_epoch}: {err:?}"
+                    );
+                }
+            });
+        }
+
+        self.proposer_config.reload_if_changed();
         self.fetch_validator_indicies().await;
         let validator_indices: Vec<u64> = self.public_key_to_index.values().cloned().collect();
 
@@ -230,7 +282,18 @@ impl ValidatorService {
             return;
         }
 
+        if let Err(err) = self.prepare_beacon_proposers().await {
+            warn!("Failed to prepare beacon proposers: {err:?}");
+        }
+        if let Err(err) = self.register_validators().await {
+            warn!("Failed to register validators with the builder network: {err:?}");
+        }
+
         if let Some(proposer_duties) = self.fetch_proposer_duties(epoch, &validator_indices).await {
+            self.monitor
+                .lock()
+                .await
+                .record_proposer_duties(epoch, &proposer_duties);
             self.proposer_duties = proposer_duties;
         }
     }
@@ -254,10 +317,18 @@ impl ValidatorService {
         );
 
         if let Some(attester_duties) = attester_duties {
+            self.monitor
+                .lock()
+                .await
+                .record_attester_duties(epoch + 1, &attester_duties);
             self.attester_duties = attester_duties;
         }
 
         if let Some(sync_duties) = sync_duties {
+            self.monitor
+                .lock()
+                .await
+                .record_sync_committee_duties(epoch + 1, &sync_duties);
             self.sync_committee_duties = sync_duties;
         }
 
@@ -266,6 +337,10 @@ impl ValidatorService {
             .fetch_proposer_duties(epoch + 1, &validator_indices)
             .await
         {
+            self.monitor
+                .lock()
+                .await
+                .record_proposer_duties(epoch + 1, &proposer_duties);
             self.proposer_duties = proposer_duties;
         }
     }
@@ -286,6 +361,33 @@ impl ValidatorService {
         if let Err(sync_error) = self.process_aggregator_sync_infos(slot - 1).await {
             warn!("Could not process the aggregator sync infos: {sync_error:?}");
         }
+        self.prefetch_proposal_state(slot + 1).await;
+    }
+
+    /// Fetches the head state if one of our validators proposes at `slot` with the relays, as the
+    /// state root of a blinded block is computed from it. Fetching it when proposing would delay
+    /// the block.
+    pub async fn prefetch_proposal_state(&mut self, slot: u64) {
+        self.proposal_state = None;
+        let uses_relays = self
+            .proposer_duties
+            .iter()
+            .filter(|duty| duty.slot == slot)
+            .filter_map(|duty| self.validator_index_to_keystore.get(&duty.validator_index))
+            .any(|keystore| {
+                let settings = self.proposer_config.settings(&keystore.public_key);
+                settings.builder_enabled && !settings.relays.is_empty()
+            });
+        if !uses_relays {
+            return;
+        }
+
+        match self.beacon_api_client.get_state(ID::Head).await {
+            Ok(state) => self.proposal_state = Some(state),
+            Err(err) => {
+                warn!("Failed to fetch the head state for the proposal at slot {slot}: {err:?}")
+            }
+        }
     }
 
     pub async fn fetch_validator_indicies(&mut self) {
@@ -333,6 +435,79 @@ impl ValidatorService {
         }
     }
 
+    /// Sends the fee recipient of every known validator to the beacon node, so it can prepare
+    /// execution payloads for upcoming proposals.
+    pub async fn prepare_beacon_proposers(&self) -> anyhow::Result<()> {
+        let items = self
+            .validator_index_to_keystore
+            .iter()
+            .map(|(validator_index, keystore)| PrepareBeaconProposerItem {
+                validator_index: *validator_index,
+                fee_recipient: self
+                    .proposer_config
+                    .settings(&keystore.public_key)
+                    .fee_recipient,
+            })
+            .collect::<Vec<_>>();
+
+        if items.is_empty() {
+            return Ok(());
+        }
+
+        Ok(self
+            .beacon_api_client
+            .prepare_beacon_proposer(items)
+            .await?)
+    }
+
+    /// Signs and submits builder validator registrations for all validators which have the
+    /// builder enabled in the proposer config. Registrations are sent to the beacon node, and to
+    /// each configured relay in batches. Signatures are cached and only renewed when the fee
+    /// recipient or gas limit of a validator changes.
+    pub async fn register_validators(&mut self) -> anyhow::Result<()> {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        let mut registrations = vec![];
+        let mut relay_registrations: HashMap<Url, Vec<_>> = HashMap::new();
+        for keystore in self.validator_index_to_keystore.values() {
+            let settings = self.proposer_config.settings(&keystore.public_key);
+            if !settings.builder_enabled {
+                continue;
+            }
+
+            let signed_registration = self.registration_cache.get_or_sign(
+                settings.validator_registration(keystore.public_key.clone(), timestamp),
+                &keystore.private_key,
+            )?;
+            for relay in settings.relays {
+                relay_registrations
+                    .entry(relay)
+                    .or_default()
+                    .push(signed_registration.clone());
+            }
+            registrations.push(signed_registration);
+        }
+
+        let public_keys = self
+            .validator_index_to_keystore
+            .values()
+            .map(|keystore| &keystore.public_key)
+            .collect::<HashSet<_>>();
+        self.registration_cache
+            .retain(|public_key| public_keys.contains(public_key));
+
+        if registrations.is_empty() {
+            return Ok(());
+        }
+
+        let (beacon_node_result, relay_result) = tokio::join!(
+            self.beacon_api_client.register_validators(registrations),
+            self.relay_client
+                .register_validators(relay_registrations, VALIDATOR_REGISTRATION_BATCH_SIZE),
+        );
+        beacon_node_result?;
+        relay_result
+    }
+
     pub async fn fetch_proposer_duties(
         &self,
         epoch: u64,
@@ -389,6 +564,11 @@ impl ValidatorService {
         }
     }
 
+    /// Proposes a block for `slot`. If the builder is enabled and relays are configured, the
+    /// relays are asked for bids on top of a locally built payload, and the most valuable of the
+    /// two is proposed. The local payload is used whenever no valid bid beats it or the blinded
+    /// block can not be built. Once the blinded block is signed there is no fallback, as signing
+    /// the local block for the same slot would be slashable.
     pub async fn propose_block(&self, slot: u64, validator_index: u64) -> anyhow::Result<()> {
         let keystore = self
             .validator_index_to_keystore
@@ -396,20 +576,26 @@ impl ValidatorService {
             .cloned()
             .ok_or_else(|| anyhow!("keystore not found for validator: {validator_index}"))?;
         let randao_reveal = sign_randao_reveal(slot, &keystore.private_key)?;
+        let settings = self.proposer_config.settings(&keystore.public_key);
+        let use_relays = settings.builder_enabled && !settings.relays.is_empty();
+        // A boost factor of 0 tells the beacon node to always use the local payload
+        let builder_boost_factor = match settings.builder_enabled && !use_relays {
+            true => settings.builder_boost_factor,
+            false => Some(0),
+        };
         let block_response = self
             .beacon_api_client
-            .produce_block(slot, randao_reveal, None, None, None)
+            .produce_block(
+                slot,
+                randao_reveal,
+                settings.graffiti,
+                None,
+                builder_boost_factor,
+            )
             .await?;
 
-        match block_response.data {
-            ProduceBlockData::Full(full_block) => {
-                let signed_beacon_block =
-                    sign_beacon_block(slot, full_block.block, &keystore.private_key)?;
-
-                self.beacon_api_client
-                    .publish_block(BroadcastValidation::Gossip, signed_beacon_block)
-                    .await?;
-            }
+        let full_block = match block_response.data {
+            ProduceBlockData::Full(full_block) => full_block,
             ProduceBlockData::Blinded(blinded_block) => {
                 let signed_blinded_block =
                     sign_blinded_beacon_block(slot, blinded_block, &keystore.private_key)?;
@@ -417,9 +603,143 @@ impl ValidatorService {
                 self.beacon_api_client
                     .publish_blinded_block(BroadcastValidation::Gossip, signed_blinded_block)
                     .await?;
+                inc_int_counter_vec(&PROPOSED_BLOCK_SOURCE, &["beacon_node_builder"]);
+                return Ok(());
             }
         };
 
+        if use_relays
+            && let Some(best_bid) = self
+                .relay_client
+                .get_best_bid(
+                    &settings.relays,
+                    slot,
+                    &full_block
+                        .block
+                        .body
+                        .execution_payload
+                        .to_execution_payload_header(),
+                    &keystore.public_key,
+                )
+                .await
+        {
+            let local_value = U256::from(block_response.execution_payload_value);
+            if is_builder_bid_preferred(
+                best_bid.bid.message.value,
+                local_value,
+                settings.builder_boost_factor,
+            ) {
+                match self
+                    .build_blinded_block(&full_block.block, &best_bid.bid.message)
+                    .await
+                {
+                    Ok(blinded_block) => {
+                        return self
+                            .publish_builder_block(slot, blinded_block, &best_bid, &keystore)
+                            .await;
+                    }
+                    Err(err) => warn!(
+                        "Failed to build a blinded block for slot {slot}, falling back to the local payload: {err:?}"
+                    ),
+                }
+            } else {
+                info!(
+                    "Local payload value {local_value} beats the best builder bid of {} for slot {slot}",
+                    best_bid.bid.message.value
+                );
+            }
+        }
+
+        self.publish_local_block(slot, full_block, &keystore).await
+    }
+
+    async fn publish_local_block(
+        &self,
+        slot: u64,
+        full_block: FullBlockData,
+        keystore: &Keystore,
+    ) -> anyhow::Result<()> {
+        let signed_block = sign_beacon_block(slot, full_block.block, &keystore.private_key)?;
+        self.beacon_api_client
+            .publish_block(
+                BroadcastValidation::Gossip,
+                SignedBlockContents {
+                    signed_block,
+                    kzg_proofs: full_block.kzg_proofs,
+                    blobs: full_block.blobs,
+                },
+            )
+            .await?;
+        inc_int_counter_vec(&PROPOSED_BLOCK_SOURCE, &["local"]);
+
+        Ok(())
+    }
+
+    /// Builds a blinded block carrying the payload header of `bid`, on top of the consensus
+    /// contents of a locally produced block. The post state root is computed from the head state
+    /// fetched ahead of the proposal, which must be the parent of `block`.
+    async fn build_blinded_block(
+        &self,
+        block: &BeaconBlock,
+        bid: &BuilderBid,
+    ) -> anyhow::Result<BlindedBeaconBlock> {
+        let state = self
+            .proposal_state
+            .as_ref()
+            .ok_or_else(|| anyhow!("The head state wasn't fetched ahead of the proposal"))?;
+        let mut parent_header = state.latest_block_header.clone();
+        if parent_header.state_root == B256::ZERO {
+            parent_header.state_root = state.tree_hash_root();
+        }
+        ensure!(
+            parent_header.tree_hash_root() == block.parent_root,
+            "Head state does not match the parent of the block"
+        );
+
+        let mut blinded_block = blinded_block_from_bid(block, bid);
+        blinded_block.state_root =
+            compute_new_blinded_state_root(state, block, &blinded_block).await?;
+        Ok(blinded_block)
+    }
+
+    /// Signs the blinded block and asks the relays which offered its payload to reveal it, then
+    /// publishes the block with the blobs of the builder. If no relay reveals the payload the
+    /// slot is missed, since proposing the local block as well would be a slashable offence.
+    async fn publish_builder_block(
+        &self,
+        slot: u64,
+        blinded_block: BlindedBeaconBlock,
+        best_bid: &BestBid,
+        keystore: &Keystore,
+    ) -> anyhow::Result<()> {
+        let signed_blinded_block =
+            sign_blinded_beacon_block(slot, blinded_block, &keystore.private_key)?;
+
+        let payload_and_blobs = self
+            .relay_client
+            .submit_blinded_block(&best_bid.relays, &signed_blinded_block)
+            .await
+            .map_err(|err| anyhow!("Relays did not reveal the payload for slot {slot}: {err:?}"))?;
+        ensure!(
+            payload_and_blobs.blobs_bundle.commitments
+                == signed_blinded_block.message.body.blob_kzg_commitments,
+            "The revealed blobs do not match the commitments of the block for slot {slot}"
+        );
+        let blobs_bundle = payload_and_blobs.blobs_bundle;
+        let signed_block = signed_blinded_block.unblind(payload_and_blobs.execution_payload)?;
+
+        self.beacon_api_client
+            .publish_block(
+                BroadcastValidation::Gossip,
+                SignedBlockContents {
+                    signed_block,
+                    kzg_proofs: blobs_bundle.proofs.to_vec(),
+                    blobs: blobs_bundle.blobs.to_vec(),
+                },
+            )
+            .await?;
+        inc_int_counter_vec(&PROPOSED_BLOCK_SOURCE, &["relay"]);
+
         Ok(())
     }
 
@@ -1,8 +1,9 @@
 use anyhow::anyhow;
 use blst::{
-    BLST_ERROR,
+    BLST_ERROR, blst_scalar,
     min_pk::{AggregateSignature as BlstAggregateSignature, Signature as BlstSignature},
 };
+use rand::Rng;
 use ssz_types::FixedVector;
 
 use crate::{
@@ -10,9 +11,15 @@ use crate::{
     errors::BLSError,
     public_key::PublicKey,
     signature::BLSSignature,
-    traits::{Aggregatable, SupranationalAggregatable, SupranationalVerifiable, Verifiable},
+    traits::{
+        Aggregatable, BatchVerifiable, SupranationalAggregatable, SupranationalBatchVerifiable,
+        SupranationalVerifiable, Verifiable,
+    },
 };
 
+/// Number of random bits each signature set is scaled by in batch verification
+const BATCH_RANDOM_BITS: usize = 64;
+
 impl BLSSignature {
     pub fn to_blst_signature(&self) -> Result<BlstSignature, BLSError> {
         BlstSignature::from_bytes(&self.inner).map_err(|err| BLSError::BlstError(err.into()))
@@ -63,6 +70,52 @@ impl Verifiable for BLSSignature {
     }
 }
 
+impl BatchVerifiable for BLSSignature {
+    type Error = BLSError;
+
+    fn verify_batch(
+        signature_sets: &[(&BLSSignature, &PublicKey, &[u8])],
+    ) -> Result<bool, BLSError> {
+        if signature_sets.is_empty() {
+            return Ok(true);
+        }
+        let signatures = signature_sets
+            .iter()
+            .map(|(signature, _, _)| signature.to_blst_signature())
+            .collect::<Result<Vec<_>, _>>()?;
+        let public_keys = signature_sets
+            .iter()
+            .map(|(_, public_key, _)| public_key.to_blst_public_key())
+            .collect::<Result<Vec<_>, _>>()?;
+        let messages = signature_sets
+            .iter()
+            .map(|(_, _, message)| *message)
+            .collect::<Vec<_>>();
+
+        // Each set is scaled by a random non-zero factor, so invalid signatures can't cancel out
+        let mut rng = rand::thread_rng();
+        let randoms = signature_sets
+            .iter()
+            .map(|_| {
+                let mut scalar = blst_scalar::default();
+                scalar.b[..8].copy_from_slice(&rng.gen_range(1..=u64::MAX).to_le_bytes());
+                scalar
+            })
+            .collect::<Vec<_>>();
+
+        Ok(BlstSignature::verify_multiple_aggregate_signatures(
+            &messages,
+            DST,
+            &public_keys.iter().collect::<Vec<_>>(),
+            false,
+            &signatures.iter().collect::<Vec<_>>(),
+            true,
+            &randoms,
+            BATCH_RANDOM_BITS,
+        ) == BLST_ERROR::BLST_SUCCESS)
+    }
+}
+
 impl Aggregatable<BLSSignature> for BLSSignature {
     type Error = anyhow::Error;
 
@@ -83,3 +136,60 @@ impl Aggregatable<BLSSignature> for BLSSignature {
 impl SupranationalAggregatable<BLSSignature> for BLSSignature {}
 
 impl SupranationalVerifiable for BLSSignature {}
+
+impl SupranationalBatchVerifiable for BLSSignature {}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+
+    use crate::{
+        BLSSignature, PrivateKey, PublicKey,
+        traits::{BatchVerifiable, Signable},
+    };
+
+    fn signature_sets(count: u8) -> Vec<(BLSSignature, PublicKey, Vec<u8>)> {
+        (1..=count)
+            .map(|index| {
+                let private_key = PrivateKey {
+                    inner: B256::with_last_byte(index),
+                };
+                let message = vec![index; 32];
+                (
+                    private_key.sign(&message).expect("Failed to sign message"),
+                    private_key
+                        .public_key()
+                        .expect("Failed to derive public key"),
+                    message,
+                )
+            })
+            .collect()
+    }
+
+    fn verify_batch(signature_sets: &[(BLSSignature, PublicKey, Vec<u8>)]) -> bool {
+        let signature_sets = signature_sets
+            .iter()
+            .map(|(signature, public_key, message)| (signature, public_key, message.as_slice()))
+            .collect::<Vec<_>>();
+        BLSSignature::verify_batch(&signature_sets).expect("Failed to verify batch")
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        assert!(verify_batch(&[]));
+        assert!(verify_batch(&signature_sets(4)));
+    }
+
+    #[test]
+    fn test_verify_batch_with_one_invalid_signature() {
+        // A valid signature by another key
+        let mut sets = signature_sets(4);
+        sets[2].0 = sets[0].0.clone();
+        assert!(!verify_batch(&sets));
+
+        // A signature over a different message
+        let mut sets = signature_sets(4);
+        sets[3].2 = vec![0; 32];
+        assert!(!verify_batch(&sets));
+    }
+}
@@ -89,3 +89,30 @@ pub trait ZkcryptoVerifiable: Verifiable<Error = BLSError> {}
 
 /// Marker trait for supranational/blst BLS signature verification implementation
 pub trait SupranationalVerifiable: Verifiable<Error = BLSError> {}
+
+/// Trait for verifying a batch of BLS signatures, each by its own public key over its own message.
+///
+/// Verifying the batch at once is cheaper than verifying each signature on its own, but doesn't
+/// tell which signature is invalid.
+pub trait BatchVerifiable {
+    type Error;
+
+    /// Verifies every signature of `signature_sets` against its public key and message.
+    ///
+    /// # Arguments
+    /// * `signature_sets` - The signatures with the public key and message they are verified
+    ///   against
+    ///
+    /// # Returns
+    /// * `Result<bool, BLSError>` - Ok(true) if every signature is valid, Ok(false) if any
+    ///   verification fails, or Err if there are issues with signature or public key bytes
+    fn verify_batch(
+        signature_sets: &[(&BLSSignature, &PublicKey, &[u8])],
+    ) -> Result<bool, Self::Error>;
+}
+
+/// Marker trait for zkcrypto/bls12_381 BLS batch verification implementation
+pub trait ZkcryptoBatchVerifiable: BatchVerifiable<Error = BLSError> {}
+
+/// Marker trait for supranational/blst BLS batch verification implementation
+pub trait SupranationalBatchVerifiable: BatchVerifiable<Error = BLSError> {}
@@ -1,18 +1,28 @@
 use bls12_381::{
-    G2Projective, Scalar,
+    G1Projective, G2Projective, Scalar,
     hash_to_curve::{ExpandMsgXmd, HashToCurve},
 };
 use group::Curve;
 use ssz_types::FixedVector;
 
 use crate::{
-    PrivateKey,
+    PrivateKey, PublicKey,
     constants::DST,
     errors::BLSError,
     signature::BLSSignature,
     traits::{Signable, ZkcryptoSignable},
 };
 
+impl PrivateKey {
+    /// Derives the public key corresponding to this private key.
+    pub fn public_key(&self) -> Result<PublicKey, BLSError> {
+        let scalar = Scalar::from_bytes(self.inner.as_ref())
+            .into_option()
+            .ok_or(BLSError::InvalidPrivateKey)?;
+        Ok(PublicKey::from(G1Projective::generator() * scalar))
+    }
+}
+
 impl Signable for PrivateKey {
     type Error = BLSError;
 
@@ -8,7 +8,10 @@ use crate::{
     BLSSignature, PublicKey,
     constants::DST,
     errors::BLSError,
-    traits::{Aggregatable, Verifiable, ZkcryptoAggregatable, ZkcryptoVerifiable},
+    traits::{
+        Aggregatable, BatchVerifiable, Verifiable, ZkcryptoAggregatable, ZkcryptoBatchVerifiable,
+        ZkcryptoVerifiable,
+    },
 };
 
 impl TryFrom<&BLSSignature> for G2Affine {
@@ -72,6 +75,23 @@ impl Verifiable for BLSSignature {
     }
 }
 
+impl BatchVerifiable for BLSSignature {
+    type Error = BLSError;
+
+    /// Batching relies on random scalars, which zkVMs can't provide, so each signature is
+    /// verified on its own.
+    fn verify_batch(
+        signature_sets: &[(&BLSSignature, &PublicKey, &[u8])],
+    ) -> Result<bool, BLSError> {
+        for (signature, public_key, message) in signature_sets {
+            if !signature.verify(public_key, message)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
 impl Aggregatable<BLSSignature> for BLSSignature {
     type Error = BLSError;
 
@@ -90,3 +110,60 @@ impl Aggregatable<BLSSignature> for BLSSignature {
 impl ZkcryptoAggregatable<BLSSignature> for BLSSignature {}
 
 impl ZkcryptoVerifiable for BLSSignature {}
+
+impl ZkcryptoBatchVerifiable for BLSSignature {}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+
+    use crate::{
+        BLSSignature, PrivateKey, PublicKey,
+        traits::{BatchVerifiable, Signable},
+    };
+
+    fn signature_sets(count: u8) -> Vec<(BLSSignature, PublicKey, Vec<u8>)> {
+        (1..=count)
+            .map(|index| {
+                let private_key = PrivateKey {
+                    inner: B256::with_last_byte(index),
+                };
+                let message = vec![index; 32];
+                (
+                    private_key.sign(&message).expect("Failed to sign message"),
+                    private_key
+                        .public_key()
+                        .expect("Failed to derive public key"),
+                    message,
+                )
+            })
+            .collect()
+    }
+
+    fn verify_batch(signature_sets: &[(BLSSignature, PublicKey, Vec<u8>)]) -> bool {
+        let signature_sets = signature_sets
+            .iter()
+            .map(|(signature, public_key, message)| (signature, public_key, message.as_slice()))
+            .collect::<Vec<_>>();
+        BLSSignature::verify_batch(&signature_sets).expect("Failed to verify batch")
+    }
+
+    #[test]
+    fn test_verify_batch() {
+        assert!(verify_batch(&[]));
+        assert!(verify_batch(&signature_sets(4)));
+    }
+
+    #[test]
+    fn test_verify_batch_with_one_invalid_signature() {
+        // A valid signature by another key
+        let mut sets = signature_sets(4);
+        sets[2].0 = sets[0].0.clone();
+        assert!(!verify_batch(&sets));
+
+        // A signature over a different message
+        let mut sets = signature_sets(4);
+        sets[3].2 = vec![0; 32];
+        assert!(!verify_batch(&sets));
+    }
+}
@@ -0,0 +1,182 @@
+use alloy_primitives::{B256, U512, hex};
+use anyhow::{anyhow, ensure};
+use ream_bls::PrivateKey;
+use sha2::{Digest, Sha256};
+
+use crate::hmac::hmac_sha_256;
+
+/// The order of the BLS12-381 curve.
+const CURVE_ORDER: [u8; 32] =
+    hex!("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001");
+const KEYGEN_SALT: &[u8] = b"BLS-SIG-KEYGEN-SALT-";
+const LAMPORT_CHUNK_COUNT: usize = 255;
+const LAMPORT_CHUNK_SIZE: usize = 32;
+
+/// EIP-2334 purpose for BLS12-381 keys.
+pub const PURPOSE: u32 = 12381;
+/// EIP-2334 coin type for Ethereum consensus layer keys.
+pub const COIN_TYPE: u32 = 3600;
+
+/// Returns the EIP-2334 withdrawal key path for the validator at `index`.
+pub fn withdrawal_key_path(index: u32) -> String {
+    format!("m/{PURPOSE}/{COIN_TYPE}/{index}/0")
+}
+
+/// Returns the EIP-2334 signing key path for the validator at `index`.
+pub fn signing_key_path(index: u32) -> String {
+    format!("m/{PURPOSE}/{COIN_TYPE}/{index}/0/0")
+}
+
+// Based on https://www.rfc-editor.org/rfc/rfc5869
+fn hkdf_extract(salt: &[u8], input_key_material: &[u8]) -> B256 {
+    hmac_sha_256(salt, input_key_material)
+}
+
+fn hkdf_expand(pseudo_random_key: &B256, info: &[u8], length: usize) -> Vec<u8> {
+    let mut output_key_material = Vec::with_capacity(length);
+    let mut previous_block: Vec<u8> = vec![];
+    let mut counter = 1u8;
+    while output_key_material.len() < length {
+        previous_block = hmac_sha_256(
+            pseudo_random_key.as_slice(),
+            &[previous_block.as_slice(), info, &[counter]].concat(),
+        )
+        .to_vec();
+        output_key_material.extend_from_slice(&previous_block);
+        counter = counter.wrapping_add(1);
+    }
+    output_key_material.truncate(length);
+    output_key_material
+}
+
+fn hkdf_mod_r(input_key_material: &[u8]) -> PrivateKey {
+    let curve_order = U512::from_be_slice(&CURVE_ORDER);
+    let mut salt = KEYGEN_SALT.to_vec();
+    loop {
+        salt = Sha256::digest(&salt).to_vec();
+        let pseudo_random_key = hkdf_extract(&salt, &[input_key_material, &[0]].concat());
+        let output_key_material = hkdf_expand(&pseudo_random_key, &48u16.to_be_bytes(), 48);
+        let secret_key = U512::from_be_slice(&output_key_material) % curve_order;
+        if !secret_key.is_zero() {
+            return PrivateKey {
+                inner: B256::from_slice(&secret_key.to_be_bytes::<64>()[32..]),
+            };
+        }
+    }
+}
+
+fn input_key_material_to_lamport_secret_key(input_key_material: &[u8], salt: &[u8]) -> Vec<u8> {
+    let pseudo_random_key = hkdf_extract(salt, input_key_material);
+    hkdf_expand(
+        &pseudo_random_key,
+        &[],
+        LAMPORT_CHUNK_COUNT * LAMPORT_CHUNK_SIZE,
+    )
+}
+
+fn parent_secret_key_to_lamport_public_key(parent_secret_key: &PrivateKey, index: u32) -> B256 {
+    let salt = index.to_be_bytes();
+    let input_key_material = parent_secret_key.inner.as_slice();
+    let not_input_key_material = input_key_material
+        .iter()
+        .map(|byte| !byte)
+        .collect::<Vec<_>>();
+
+    let mut hasher = Sha256::new();
+    for lamport_secret_key in [
+        input_key_material_to_lamport_secret_key(input_key_material, &salt),
+        input_key_material_to_lamport_secret_key(&not_input_key_material, &salt),
+    ] {
+        for chunk in lamport_secret_key.chunks(LAMPORT_CHUNK_SIZE) {
+            hasher.update(Sha256::digest(chunk));
+        }
+    }
+    B256::from_slice(&hasher.finalize())
+}
+
+/// Derives the master secret key from a seed, as defined in EIP-2333.
+pub fn derive_master_secret_key(seed: &[u8]) -> anyhow::Result<PrivateKey> {
+    ensure!(seed.len() >= 32, "Seed must be at least 32 bytes long");
+    Ok(hkdf_mod_r(seed))
+}
+
+/// Derives the child secret key at `index` from a parent secret key, as defined in EIP-2333.
+pub fn derive_child_secret_key(parent_secret_key: &PrivateKey, index: u32) -> PrivateKey {
+    hkdf_mod_r(parent_secret_key_to_lamport_public_key(parent_secret_key, index).as_slice())
+}
+
+/// Derives the secret key for an EIP-2334 path such as `m/12381/3600/0/0/0` from a seed.
+pub fn derive_secret_key_from_path(seed: &[u8], path: &str) -> anyhow::Result<PrivateKey> {
+    let mut nodes = path.split('/');
+    ensure!(
+        nodes.next() == Some("m"),
+        "Key derivation path must start with 'm': {path}"
+    );
+    nodes.try_fold(derive_master_secret_key(seed)?, |secret_key, node| {
+        let index = node
+            .parse::<u32>()
+            .map_err(|err| anyhow!("Invalid path node '{node}' in {path}: {err:?}"))?;
+        Ok(derive_child_secret_key(&secret_key, index))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use alloy_primitives::U256;
+
+    use super::*;
+
+    fn assert_secret_key_eq(secret_key: &PrivateKey, expected: &str) {
+        assert_eq!(
+            U256::from_be_slice(secret_key.inner.as_slice()),
+            U256::from_str(expected).unwrap()
+        );
+    }
+
+    // Test vectors from https://eips.ethereum.org/EIPS/eip-2333#test-cases
+    #[test]
+    fn test_derive_master_and_child_secret_key() {
+        let seed = hex!(
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+        let master_secret_key = derive_master_secret_key(&seed).unwrap();
+        assert_secret_key_eq(
+            &master_secret_key,
+            "6083874454709270928345386274498605044986640685124978867557563392430687146096",
+        );
+
+        let child_secret_key = derive_child_secret_key(&master_secret_key, 0);
+        assert_secret_key_eq(
+            &child_secret_key,
+            "20397789859736650942317412262472558107875392172444076792671091975210932703118",
+        );
+    }
+
+    #[test]
+    fn test_derive_secret_key_from_path() {
+        let seed = hex!(
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+        let master_secret_key = derive_master_secret_key(&seed).unwrap();
+        let expected = derive_child_secret_key(
+            &derive_child_secret_key(
+                &derive_child_secret_key(&master_secret_key, PURPOSE),
+                COIN_TYPE,
+            ),
+            7,
+        );
+        assert_eq!(
+            derive_secret_key_from_path(&seed, &format!("m/{PURPOSE}/{COIN_TYPE}/7")).unwrap(),
+            expected
+        );
+        assert!(derive_secret_key_from_path(&seed, "12381/3600").is_err());
+        assert!(derive_secret_key_from_path(&seed, "m/12381/abc").is_err());
+    }
+
+    #[test]
+    fn test_short_seed_is_rejected() {
+        assert!(derive_master_secret_key(&[0u8; 31]).is_err());
+    }
+}
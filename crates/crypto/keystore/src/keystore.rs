@@ -1,6 +1,6 @@
 use std::{fs, path::Path};
 
-use alloy_primitives::B256;
+use alloy_primitives::{B256, hex};
 use anyhow::{Result, anyhow, ensure};
 use ream_bls::{PrivateKey, PublicKey};
 use serde::{Deserialize, Serialize};
@@ -25,6 +25,45 @@ pub struct Keystore {
 }
 
 impl EncryptedKeystore {
+    /// Encrypts a keystore as defined in EIP-2335, using the given key derivation function.
+    pub fn encrypt(
+        keystore: &Keystore,
+        password: &[u8],
+        path: String,
+        kdf_params: KdfParams,
+    ) -> anyhow::Result<Self> {
+        let derived_key = kdf_params.derive_key(password)?;
+        let key_param: [u8; 16] = derived_key[0..16]
+            .try_into()
+            .map_err(|err| anyhow!("Failed to convert derived key into 16 byte array: {err:?}"))?;
+        let iv = rand::random::<[u8; 16]>();
+        let mut cipher_message = keystore.private_key.inner.to_vec();
+        aes128_ctr(&mut cipher_message, key_param, &iv);
+        let checksum = Sha256::digest([&derived_key[16..32], &cipher_message].concat());
+
+        Ok(Self {
+            crypto: Crypto {
+                kdf: FunctionBlock {
+                    params: kdf_params,
+                    message: vec![],
+                },
+                checksum: FunctionBlock {
+                    params: ChecksumParams::Sha256 {},
+                    message: checksum.to_vec(),
+                },
+                cipher: FunctionBlock {
+                    params: CipherParams::Aes128Ctr { iv: iv.to_vec() },
+                    message: cipher_message,
+                },
+            },
+            description: String::new(),
+            public_key: keystore.public_key.clone(),
+            path,
+            uuid: generate_uuid_v4(),
+            version: 4,
+        })
+    }
+
     pub fn load_from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         Ok(serde_json::from_str(fs::read_to_string(path)?.as_str())?)
     }
@@ -35,21 +74,7 @@ impl EncryptedKeystore {
     }
 
     pub fn validate_password(&self, password: &[u8]) -> anyhow::Result<bool> {
-        let derived_key = match &self.crypto.kdf.params {
-            KdfParams::Pbkdf2 {
-                c,
-                dklen,
-                prf: _,
-                salt,
-            } => pbkdf2(password, salt, *c, *dklen)?,
-            KdfParams::Scrypt {
-                n,
-                p,
-                r,
-                dklen,
-                salt,
-            } => scrypt(password, salt, *n, *p, *r, *dklen)?,
-        };
+        let derived_key = self.crypto.kdf.params.derive_key(password)?;
         let derived_key_slice = &derived_key[16..32];
         let pre_image = [derived_key_slice, &self.crypto.cipher.message].concat();
         let checksum = Sha256::digest(&pre_image);
@@ -58,21 +83,7 @@ impl EncryptedKeystore {
     }
 
     pub fn decrypt(&self, password: &[u8]) -> anyhow::Result<Keystore> {
-        let derived_key = match &self.crypto.kdf.params {
-            KdfParams::Pbkdf2 {
-                c,
-                dklen,
-                prf: _,
-                salt,
-            } => pbkdf2(password, salt, *c, *dklen)?,
-            KdfParams::Scrypt {
-                n,
-                p,
-                r,
-                dklen,
-                salt,
-            } => scrypt(password, salt, *n, *p, *r, *dklen)?,
-        };
+        let derived_key = self.crypto.kdf.params.derive_key(password)?;
         let derived_key_slice = &derived_key[16..32];
         let pre_image = [derived_key_slice, &self.crypto.cipher.message].concat();
         let checksum = Sha256::digest(&pre_image);
@@ -137,6 +148,47 @@ pub enum KdfParams {
     },
 }
 
+impl KdfParams {
+    /// Scrypt parameters recommended by EIP-2335, with a random salt.
+    pub fn scrypt_with_random_salt() -> Self {
+        Self::Scrypt {
+            dklen: 32,
+            n: 262144,
+            p: 1,
+            r: 8,
+            salt: rand::random::<[u8; 32]>().to_vec(),
+        }
+    }
+
+    /// PBKDF2 parameters recommended by EIP-2335, with a random salt.
+    pub fn pbkdf2_with_random_salt() -> Self {
+        Self::Pbkdf2 {
+            c: 262144,
+            dklen: 32,
+            prf: Prf::HmacSha256,
+            salt: rand::random::<[u8; 32]>().to_vec(),
+        }
+    }
+
+    pub fn derive_key(&self, password: &[u8]) -> anyhow::Result<Vec<u8>> {
+        match self {
+            KdfParams::Pbkdf2 {
+                c,
+                dklen,
+                prf: _,
+                salt,
+            } => pbkdf2(password, salt, *c, *dklen),
+            KdfParams::Scrypt {
+                n,
+                p,
+                r,
+                dklen,
+                salt,
+            } => scrypt(password, salt, *n, *p, *r, *dklen),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 #[serde(rename_all = "kebab-case")]
 pub enum Prf {
@@ -159,6 +211,22 @@ pub enum ChecksumParams {
     Sha256 {},
 }
 
+fn generate_uuid_v4() -> String {
+    let mut bytes = rand::random::<[u8; 16]>();
+    // Set the version (4) and variant (RFC 4122) bits
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let encoded = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &encoded[0..8],
+        &encoded[8..12],
+        &encoded[12..16],
+        &encoded[16..20],
+        &encoded[20..32]
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use alloy_primitives::hex;
@@ -322,4 +390,35 @@ mod tests {
             private_key
         );
     }
+
+    #[test]
+    fn encrypt_and_decrypt_roundtrip() {
+        let keystore =
+            EncryptedKeystore::load_from_file("./assets/Pbkdf2TestKeystore.json").unwrap();
+        let password = hex!("7465737470617373776f7264f09f9491");
+        let decrypted = keystore.decrypt(&password).unwrap();
+
+        let kdf_params = KdfParams::Pbkdf2 {
+            c: 2,
+            dklen: 32,
+            prf: Prf::HmacSha256,
+            salt: vec![0x42; 32],
+        };
+        let encrypted = EncryptedKeystore::encrypt(
+            &decrypted,
+            b"another password",
+            "m/12381/3600/0/0/0".to_string(),
+            kdf_params,
+        )
+        .unwrap();
+
+        assert_eq!(encrypted.version, 4);
+        assert_eq!(encrypted.public_key, keystore.public_key);
+        assert!(encrypted.validate_password(b"another password").unwrap());
+        assert!(!encrypted.validate_password(&password).unwrap());
+        assert_eq!(
+            encrypted.decrypt(b"another password").unwrap().private_key,
+            decrypted.private_key
+        );
+    }
 }
@@ -1,6 +1,7 @@
 pub mod decrypt;
 pub mod hex_serde;
 pub mod hmac;
+pub mod key_derivation;
 pub mod keystore;
 pub mod pbkdf2;
 pub mod salsa;
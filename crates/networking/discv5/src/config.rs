@@ -1,4 +1,4 @@
-use std::net::{IpAddr, Ipv4Addr};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 
 use discv5::{ConfigBuilder, Enr, ListenConfig};
 
@@ -8,9 +8,15 @@ pub struct DiscoveryConfig {
     pub discv5_config: discv5::Config,
     pub bootnodes: Vec<Enr>,
     pub socket_address: IpAddr,
+    /// An IPv6 address to listen on besides `socket_address`, with the same ports
+    pub socket_address_v6: Option<Ipv6Addr>,
+    /// The IP advertised in the ENR, instead of the one learned from peers
+    pub enr_address: Option<IpAddr>,
     pub socket_port: u16,
+    pub quic_port: u16,
     pub discovery_port: u16,
     pub disable_discovery: bool,
+    pub disable_quic: bool,
     pub attestation_subnets: AttestationSubnets,
     pub sync_committee_subnets: SyncCommitteeSubnets,
 }
@@ -30,6 +36,7 @@ impl Default for DiscoveryConfig {
 
         let socket_address = Ipv4Addr::UNSPECIFIED;
         let socket_port = 9000;
+        let quic_port = 9001;
         let discovery_port = 9000;
         let listen_config = ListenConfig::from_ip(socket_address.into(), discovery_port);
 
@@ -39,9 +46,13 @@ impl Default for DiscoveryConfig {
             discv5_config,
             bootnodes: Vec::new(),
             socket_address: socket_address.into(),
+            socket_address_v6: None,
+            enr_address: None,
             socket_port,
+            quic_port,
             discovery_port,
             disable_discovery: false,
+            disable_quic: false,
             attestation_subnets,
             sync_committee_subnets,
         }
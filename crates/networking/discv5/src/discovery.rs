@@ -1,12 +1,13 @@
 use std::{
     collections::HashMap,
     future::Future,
+    net::{IpAddr, SocketAddr},
     pin::Pin,
     task::{Context, Poll},
     time::Instant,
 };
 
-use anyhow::anyhow;
+use anyhow::{anyhow, bail, ensure};
 use discv5::{
     Discv5, Enr, Event,
     enr::{CombinedKey, NodeId, k256::ecdsa::SigningKey},
@@ -16,6 +17,7 @@ use libp2p::{
     Multiaddr, PeerId,
     core::{Endpoint, transport::PortUse},
     identity::Keypair,
+    multiaddr::Protocol,
     swarm::{
         ConnectionDenied, ConnectionId, FromSwarm, NetworkBehaviour, THandler, THandlerInEvent,
         THandlerOutEvent, ToSwarm, dummy::ConnectionHandler,
@@ -27,10 +29,11 @@ use tracing::{error, info, trace, warn};
 
 use crate::{
     config::DiscoveryConfig,
+    enr_ext::{EnrExt, QUIC_ENR_KEY, QUIC6_ENR_KEY},
     eth2::{ENR_ETH2_KEY, EnrForkId},
     subnet::{
-        ATTESTATION_BITFIELD_ENR_KEY, SYNC_COMMITTEE_BITFIELD_ENR_KEY,
-        attestation_subnet_predicate, sync_committee_subnet_predicate,
+        ATTESTATION_BITFIELD_ENR_KEY, AttestationSubnets, SYNC_COMMITTEE_BITFIELD_ENR_KEY,
+        SyncCommitteeSubnets, attestation_subnet_predicate, sync_committee_subnet_predicate,
     },
 };
 
@@ -73,16 +76,57 @@ pub struct Discovery {
 }
 
 impl Discovery {
-    pub async fn new(local_key: Keypair, config: &DiscoveryConfig) -> anyhow::Result<Self> {
+    /// The ENR is given a higher sequence number than `previous_enr_seq`, the one of the ENR the
+    /// node advertised when it last ran, so peers replace the ENR they know of.
+    pub async fn new(
+        local_key: Keypair,
+        config: &DiscoveryConfig,
+        previous_enr_seq: u64,
+    ) -> anyhow::Result<Self> {
         let enr_local =
             convert_to_enr(local_key).map_err(|err| anyhow!("Failed to convert key: {err:?}"))?;
 
+        // A pinned address is advertised as is. Otherwise discv5 learns the external IP from the
+        // addresses peers see us on, so an unspecified listen address is left out of the ENR.
+        let enr_address = config.enr_address.unwrap_or(config.socket_address);
         let mut enr_builder = Enr::builder();
-        enr_builder.ip(config.socket_address);
-        enr_builder.tcp4(config.socket_port);
-        enr_builder.udp4(config.discovery_port);
+        match enr_address {
+            IpAddr::V4(ip) => {
+                if !ip.is_unspecified() {
+                    enr_builder.ip4(ip);
+                }
+                enr_builder.tcp4(config.socket_port);
+                enr_builder.udp4(config.discovery_port);
+                if !config.disable_quic {
+                    enr_builder.add_value(QUIC_ENR_KEY, &config.quic_port);
+                }
+            }
+            IpAddr::V6(ip6) => {
+                if !ip6.is_unspecified() {
+                    enr_builder.ip6(ip6);
+                }
+                enr_builder.tcp6(config.socket_port);
+                enr_builder.udp6(config.discovery_port);
+                if !config.disable_quic {
+                    enr_builder.add_value(QUIC6_ENR_KEY, &config.quic_port);
+                }
+            }
+        }
+        if let Some(ip6) = config.socket_address_v6
+            && enr_address.is_ipv4()
+        {
+            if !ip6.is_unspecified() {
+                enr_builder.ip6(ip6);
+            }
+            enr_builder.tcp6(config.socket_port);
+            enr_builder.udp6(config.discovery_port);
+            if !config.disable_quic {
+                enr_builder.add_value(QUIC6_ENR_KEY, &config.quic_port);
+            }
+        }
 
         let enr = enr_builder
+            .seq(previous_enr_seq + 1)
             .add_value(ENR_ETH2_KEY, &EnrForkId::electra(genesis_validators_root()))
             .add_value(ATTESTATION_BITFIELD_ENR_KEY, &config.attestation_subnets)
             .add_value(
@@ -161,7 +205,7 @@ impl Discovery {
                                 .and_then(Result::ok)
                                 .map(|id| id.fork_digest == fork_digest)
                                 .unwrap_or(false)
-                                && (enr.tcp4().is_some() || enr.tcp6().is_some())
+                                && !enr.multiaddrs(true).is_empty()
                         })
                     }
                     QueryType::AttestationSubnetPeers(subnet_ids) => {
@@ -259,6 +303,70 @@ impl Discovery {
     pub fn local_enr(&self) -> Enr {
         self.discv5.local_enr()
     }
+
+    /// Adds a node to the routing table, e.g. one known from a previous run.
+    pub fn add_enr(&mut self, enr: Enr) {
+        if let Err(err) = self.discv5.add_enr(enr) {
+            trace!("Failed to add ENR to the routing table: {err:?}");
+        }
+    }
+
+    /// The nodes in the routing table.
+    pub fn table_entries(&self) -> Vec<Enr> {
+        self.discv5.table_entries_enr()
+    }
+
+    /// Advertises the subnets the node is subscribed to, returning the updated ENR.
+    pub fn update_subnets(
+        &mut self,
+        attestation_subnets: &AttestationSubnets,
+        sync_committee_subnets: &SyncCommitteeSubnets,
+    ) -> anyhow::Result<Enr> {
+        self.discv5
+            .enr_insert(ATTESTATION_BITFIELD_ENR_KEY, attestation_subnets)
+            .map_err(|err| anyhow!("Failed to update attestation subnets in ENR: {err:?}"))?;
+        self.discv5
+            .enr_insert(SYNC_COMMITTEE_BITFIELD_ENR_KEY, sync_committee_subnets)
+            .map_err(|err| anyhow!("Failed to update sync committee subnets in ENR: {err:?}"))?;
+        Ok(self.local_enr())
+    }
+
+    /// Advertises an address a NAT maps to us, returning the updated ENR.
+    pub fn update_external_address(&mut self, address: &Multiaddr) -> anyhow::Result<Enr> {
+        let mut protocols = address.iter();
+        let ip = match protocols.next() {
+            Some(Protocol::Ip4(ip)) => IpAddr::V4(ip),
+            Some(Protocol::Ip6(ip6)) => IpAddr::V6(ip6),
+            _ => bail!("Unsupported external address {address}"),
+        };
+        match (protocols.next(), protocols.next()) {
+            (Some(Protocol::Tcp(port)), _) => ensure!(
+                self.discv5
+                    .update_local_enr_socket(SocketAddr::new(ip, port), true),
+                "Failed to update the TCP socket in ENR to {address}"
+            ),
+            // The IP comes with the TCP address, mapped by the same gateway
+            (Some(Protocol::Udp(port)), Some(Protocol::QuicV1)) => {
+                let quic_key = match ip {
+                    IpAddr::V4(_) => QUIC_ENR_KEY,
+                    IpAddr::V6(_) => QUIC6_ENR_KEY,
+                };
+                self.discv5
+                    .enr_insert(quic_key, &port)
+                    .map_err(|err| anyhow!("Failed to update QUIC port in ENR: {err:?}"))?;
+            }
+            _ => bail!("Unsupported external address {address}"),
+        }
+        Ok(self.local_enr())
+    }
+
+    /// Advertises the fork the node is on, returning the updated ENR.
+    pub fn update_fork_id(&mut self, enr_fork_id: &EnrForkId) -> anyhow::Result<Enr> {
+        self.discv5
+            .enr_insert(ENR_ETH2_KEY, enr_fork_id)
+            .map_err(|err| anyhow!("Failed to update fork ID in ENR: {err:?}"))?;
+        Ok(self.local_enr())
+    }
 }
 
 impl NetworkBehaviour for Discovery {
@@ -336,21 +444,28 @@ impl NetworkBehaviour for Discovery {
                     }
                 }
             }
-            EventStream::Present(receiver) => match receiver.try_recv() {
-                Ok(event) => {
-                    if let Event::SocketUpdated(_) = event {
-                        return Poll::Ready(ToSwarm::GenerateEvent(
-                            DiscoveryOutEvent::UpdatedEnr {
-                                enr: self.local_enr(),
-                            },
-                        ));
+            EventStream::Present(receiver) => {
+                while let Poll::Ready(event) = receiver.poll_recv(cx) {
+                    match event {
+                        // discv5 updates the ENR once enough peers agree on the address they see
+                        // us on in their PONGs
+                        Some(Event::SocketUpdated(socket_address)) => {
+                            info!("Updated local ENR address to {socket_address}");
+                            return Poll::Ready(ToSwarm::GenerateEvent(
+                                DiscoveryOutEvent::UpdatedEnr {
+                                    enr: self.local_enr(),
+                                },
+                            ));
+                        }
+                        Some(_) => {}
+                        None => {
+                            warn!("Discovery event stream ended");
+                            self.event_stream = EventStream::Inactive;
+                            break;
+                        }
                     }
                 }
-                Err(err) => {
-                    warn!("No discovery event found: {err:?}");
-                    self.event_stream = EventStream::Inactive;
-                }
-            },
+            }
         };
 
         Poll::Pending
@@ -395,7 +510,7 @@ mod tests {
         config.attestation_subnets.disable_attestation_subnet(1)?; // Set subnet 1
         config.disable_discovery = true;
 
-        let discovery = Discovery::new(key, &config).await.unwrap();
+        let discovery = Discovery::new(key, &config, 0).await.unwrap();
         // Check ENR reflects config.subnets
         let enr_subnets = discovery
             .discv5
@@ -408,6 +523,35 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_enr_addresses() -> anyhow::Result<()> {
+        let _ = GENESIS_VALIDATORS_ROOT.set(B256::ZERO);
+        initialize_test_network_spec();
+        let config = DiscoveryConfig {
+            disable_discovery: true,
+            ..DiscoveryConfig::default()
+        };
+        let mut discovery = Discovery::new(Keypair::generate_secp256k1(), &config, 0).await?;
+        // The unspecified listen address is left for peers to fill in
+        assert_eq!(discovery.local_enr().ip4(), None);
+        assert_eq!(discovery.local_enr().quic4(), Some(config.quic_port));
+
+        let enr = discovery.update_external_address(&"/ip4/1.2.3.4/tcp/9100".parse()?)?;
+        assert_eq!(enr.ip4(), Some(Ipv4Addr::new(1, 2, 3, 4)));
+        assert_eq!(enr.tcp4(), Some(9100));
+        let enr = discovery.update_external_address(&"/ip4/1.2.3.4/udp/9101/quic-v1".parse()?)?;
+        assert_eq!(enr.quic4(), Some(9101));
+
+        let config = DiscoveryConfig {
+            enr_address: Some(Ipv4Addr::new(5, 6, 7, 8).into()),
+            disable_discovery: true,
+            ..DiscoveryConfig::default()
+        };
+        let discovery = Discovery::new(Keypair::generate_secp256k1(), &config, 0).await?;
+        assert_eq!(discovery.local_enr().ip4(), Some(Ipv4Addr::new(5, 6, 7, 8)));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_attestation_subnet_predicate() -> anyhow::Result<()> {
         let key = Keypair::generate_secp256k1();
@@ -416,7 +560,7 @@ mod tests {
         config.attestation_subnets.disable_attestation_subnet(1)?;
         config.disable_discovery = true;
 
-        let discovery = Discovery::new(key, &config).await.unwrap();
+        let discovery = Discovery::new(key, &config, 0).await.unwrap();
 
         // Predicate for subnet 0 should match
         let predicate = attestation_subnet_predicate(vec![0]);
@@ -444,7 +588,7 @@ mod tests {
 
         config.attestation_subnets.enable_attestation_subnet(0)?; // Local node on subnet 0
         config.disable_discovery = false;
-        let mut discovery = Discovery::new(key, &config).await.unwrap();
+        let mut discovery = Discovery::new(key, &config, 0).await.unwrap();
 
         // Simulate a peer with another Discovery instance
         let peer_key = Keypair::generate_secp256k1();
@@ -463,7 +607,7 @@ mod tests {
         peer_config.socket_port = 9001; // Different port
         peer_config.disable_discovery = true;
 
-        let peer_discovery = Discovery::new(peer_key, &peer_config).await.unwrap();
+        let peer_discovery = Discovery::new(peer_key, &peer_config, 0).await.unwrap();
 
         // Add peer to discv5
         discovery
@@ -0,0 +1,109 @@
+//! The QUIC fields of an ENR and the libp2p addresses an ENR advertises.
+//!
+//! https://github.com/ethereum/consensus-specs/blob/master/specs/phase0/p2p-interface.md#enr-structure
+
+use discv5::Enr;
+use libp2p::{Multiaddr, multiaddr::Protocol};
+
+pub const QUIC_ENR_KEY: &str = "quic";
+pub const QUIC6_ENR_KEY: &str = "quic6";
+
+pub trait EnrExt {
+    /// The IPv4 QUIC port of the node.
+    fn quic4(&self) -> Option<u16>;
+
+    /// The IPv6 QUIC port of the node.
+    fn quic6(&self) -> Option<u16>;
+
+    /// The addresses to dial the node on, in order of preference. QUIC addresses come first if
+    /// `quic_support` is set, as QUIC connections are set up in fewer round trips than TCP ones.
+    fn multiaddrs(&self, quic_support: bool) -> Vec<Multiaddr>;
+}
+
+impl EnrExt for Enr {
+    fn quic4(&self) -> Option<u16> {
+        self.get_decodable(QUIC_ENR_KEY).and_then(Result::ok)
+    }
+
+    fn quic6(&self) -> Option<u16> {
+        self.get_decodable(QUIC6_ENR_KEY).and_then(Result::ok)
+    }
+
+    fn multiaddrs(&self, quic_support: bool) -> Vec<Multiaddr> {
+        let mut quic_multiaddrs = vec![];
+        let mut tcp_multiaddrs = vec![];
+        if let Some(ip) = self.ip4() {
+            if let Some(quic) = self.quic4() {
+                let mut multiaddr: Multiaddr = ip.into();
+                multiaddr.push(Protocol::Udp(quic));
+                multiaddr.push(Protocol::QuicV1);
+                quic_multiaddrs.push(multiaddr);
+            }
+            if let Some(tcp) = self.tcp4() {
+                let mut multiaddr: Multiaddr = ip.into();
+                multiaddr.push(Protocol::Tcp(tcp));
+                tcp_multiaddrs.push(multiaddr);
+            }
+        }
+        if let Some(ip6) = self.ip6() {
+            if let Some(quic6) = self.quic6() {
+                let mut multiaddr: Multiaddr = ip6.into();
+                multiaddr.push(Protocol::Udp(quic6));
+                multiaddr.push(Protocol::QuicV1);
+                quic_multiaddrs.push(multiaddr);
+            }
+            if let Some(tcp6) = self.tcp6() {
+                let mut multiaddr: Multiaddr = ip6.into();
+                multiaddr.push(Protocol::Tcp(tcp6));
+                tcp_multiaddrs.push(multiaddr);
+            }
+        }
+
+        if !quic_support {
+            return tcp_multiaddrs;
+        }
+        quic_multiaddrs.extend(tcp_multiaddrs);
+        quic_multiaddrs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    use discv5::{Enr, enr::CombinedKey};
+
+    use super::{EnrExt, QUIC_ENR_KEY, QUIC6_ENR_KEY};
+
+    #[test]
+    fn test_multiaddrs_prefer_quic() {
+        let key = CombinedKey::generate_secp256k1();
+        let enr = Enr::builder()
+            .ip4(Ipv4Addr::new(1, 2, 3, 4))
+            .tcp4(9000)
+            .ip6(Ipv6Addr::LOCALHOST)
+            .tcp6(9000)
+            .add_value(QUIC_ENR_KEY, &9001u16)
+            .add_value(QUIC6_ENR_KEY, &9001u16)
+            .build(&key)
+            .unwrap();
+
+        assert_eq!(enr.quic4(), Some(9001));
+        assert_eq!(
+            enr.multiaddrs(true),
+            vec![
+                "/ip4/1.2.3.4/udp/9001/quic-v1".parse().unwrap(),
+                "/ip6/::1/udp/9001/quic-v1".parse().unwrap(),
+                "/ip4/1.2.3.4/tcp/9000".parse().unwrap(),
+                "/ip6/::1/tcp/9000".parse().unwrap(),
+            ]
+        );
+        assert_eq!(
+            enr.multiaddrs(false),
+            vec![
+                "/ip4/1.2.3.4/tcp/9000".parse().unwrap(),
+                "/ip6/::1/tcp/9000".parse().unwrap(),
+            ]
+        );
+    }
+}
@@ -1,4 +1,5 @@
 pub mod config;
 pub mod discovery;
+pub mod enr_ext;
 pub mod eth2;
 pub mod subnet;
@@ -1,4 +1,7 @@
-use std::{net::IpAddr, path::PathBuf};
+use std::{
+    net::{IpAddr, Ipv6Addr},
+    path::PathBuf,
+};
 
 use ream_p2p::bootnodes::Bootnodes;
 use url::Url;
@@ -8,9 +11,15 @@ pub struct ManagerConfig {
     pub http_port: u16,
     pub http_allow_origin: bool,
     pub socket_address: IpAddr,
+    pub socket_address_v6: Option<Ipv6Addr>,
+    pub enr_address: Option<IpAddr>,
     pub socket_port: u16,
+    pub quic_port: u16,
     pub discovery_port: u16,
     pub disable_discovery: bool,
+    pub disable_quic: bool,
+    pub upnp: bool,
+    pub rotate_node_key: bool,
     pub data_dir: Option<PathBuf>,
     pub ephemeral: bool,
     pub bootnodes: Bootnodes,
@@ -18,4 +27,6 @@ pub struct ManagerConfig {
     pub purge_db: bool,
     pub execution_endpoint: Option<Url>,
     pub execution_jwt_secret: Option<PathBuf>,
+    pub backfill_to_genesis: bool,
+    pub backfill_verify_signatures: bool,
 }
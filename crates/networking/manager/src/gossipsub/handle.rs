@@ -5,36 +5,37 @@ use ream_consensus_beacon::{
 };
 use ream_consensus_misc::constants::beacon::genesis_validators_root;
 use ream_network_spec::networks::beacon_network_spec;
-use ream_p2p::{
-    channel::GossipMessage,
-    gossipsub::beacon::{
-        configurations::GossipsubConfig,
-        message::GossipsubMessage,
-        topics::{GossipTopic, GossipTopicKind},
-    },
+use ream_p2p::gossipsub::beacon::{
+    configurations::GossipsubConfig,
+    message::GossipsubMessage,
+    topics::{GossipTopic, GossipTopicKind},
 };
 use ream_storage::{cache::CachedDB, tables::Table};
+use ream_syncer::lookup::LookupSync;
 use ream_validator_beacon::blob_sidecars::compute_subnet_for_blob_sidecar;
-use ssz::Encode;
 use tracing::{error, info, trace, warn};
 use tree_hash::TreeHash;
 
-use crate::{
-    gossipsub::validate::{
-        attester_slashing::validate_attester_slashing,
-        beacon_attestation::validate_beacon_attestation,
-        beacon_block::validate_gossip_beacon_block, blob_sidecar::validate_blob_sidecar,
-        bls_to_execution_change::validate_bls_to_execution_change,
-        proposer_slashing::validate_proposer_slashing, result::ValidationResult,
-        sync_committee::validate_sync_committee, voluntary_exit::validate_voluntary_exit,
-    },
-    p2p_sender::P2PSender,
+use crate::gossipsub::validate::{
+    aggregate_and_proof::validate_aggregate_and_proof,
+    attester_slashing::validate_attester_slashing,
+    beacon_attestation::{prevalidate_beacon_attestation, validate_beacon_attestation},
+    beacon_block::{prevalidate_gossip_beacon_block, validate_gossip_beacon_block},
+    blob_sidecar::validate_blob_sidecar,
+    bls_to_execution_change::validate_bls_to_execution_change,
+    proposer_slashing::validate_proposer_slashing,
+    result::ValidationResult,
+    sync_committee::validate_sync_committee,
+    sync_committee_contribution::validate_sync_committee_contribution,
+    voluntary_exit::validate_voluntary_exit,
 };
 
+/// The topics subscribed to for the lifetime of the node. Attestation and sync committee subnets
+/// are subscribed to by the network as validators need them.
 pub fn init_gossipsub_config_with_topics() -> GossipsubConfig {
     let mut gossipsub_config = GossipsubConfig::default();
 
-    gossipsub_config.set_topics(vec![
+    let mut topics = vec![
         GossipTopic {
             fork: beacon_network_spec().fork_digest(genesis_validators_root()),
             kind: GossipTopicKind::BeaconBlock,
@@ -55,14 +56,6 @@ pub fn init_gossipsub_config_with_topics() -> GossipsubConfig {
             fork: beacon_network_spec().fork_digest(genesis_validators_root()),
             kind: GossipTopicKind::AttesterSlashing,
         },
-        GossipTopic {
-            fork: beacon_network_spec().fork_digest(genesis_validators_root()),
-            kind: GossipTopicKind::BeaconAttestation(0),
-        },
-        GossipTopic {
-            fork: beacon_network_spec().fork_digest(genesis_validators_root()),
-            kind: GossipTopicKind::SyncCommittee(0),
-        },
         GossipTopic {
             fork: beacon_network_spec().fork_digest(genesis_validators_root()),
             kind: GossipTopicKind::SyncCommitteeContributionAndProof,
@@ -79,189 +72,278 @@ pub fn init_gossipsub_config_with_topics() -> GossipsubConfig {
             fork: beacon_network_spec().fork_digest(genesis_validators_root()),
             kind: GossipTopicKind::LightClientOptimisticUpdate,
         },
-        GossipTopic {
-            fork: beacon_network_spec().fork_digest(genesis_validators_root()),
-            kind: GossipTopicKind::BlobSidecar(0),
-        },
         GossipTopic {
             fork: beacon_network_spec().fork_digest(genesis_validators_root()),
             kind: GossipTopicKind::VoluntaryExit,
         },
-    ]);
+    ];
+    // Blob sidecars are needed from every subnet to make blocks available
+    topics.extend(
+        (0..beacon_network_spec().blob_sidecar_subnet_count_electra).map(|subnet_id| GossipTopic {
+            fork: beacon_network_spec().fork_digest(genesis_validators_root()),
+            kind: GossipTopicKind::BlobSidecar(subnet_id),
+        }),
+    );
+    gossipsub_config.set_topics(topics);
 
     gossipsub_config
 }
 
-/// Dispatches a gossipsub message to its appropriate handler.
+/// Dispatches a gossipsub message to its appropriate handler, returning the validation result
+/// which decides whether gossipsub forwards the message and how it scores the sender.
+///
+/// Messages which are held back for a block lookup, or have no validation yet, are ignored.
 pub async fn handle_gossipsub_message(
     message: Message,
     beacon_chain: &BeaconChain,
     cached_db: &CachedDB,
-    p2p_sender: &P2PSender,
-) {
-    match GossipsubMessage::decode(&message.topic, &message.data) {
-        Ok(gossip_message) => match gossip_message {
-            GossipsubMessage::BeaconBlock(signed_block) => {
-                info!(
-                    "Beacon block received over gossipsub: slot: {}, root: {}",
-                    signed_block.message.slot,
-                    signed_block.message.block_root()
-                );
+    lookup_sync: &mut LookupSync,
+) -> ValidationResult {
+    let gossip_message = match GossipsubMessage::decode(&message.topic, &message.data) {
+        Ok(gossip_message) => gossip_message,
+        Err(err) => {
+            trace!("Failed to decode gossip message: {err:?}");
+            return ValidationResult::Reject(format!("Failed to decode gossip message: {err:?}"));
+        }
+    };
+
+    match gossip_message {
+        GossipsubMessage::BeaconBlock(signed_block) => {
+            info!(
+                "Beacon block received over gossipsub: slot: {}, root: {}",
+                signed_block.message.slot,
+                signed_block.message.block_root()
+            );
 
-                let validation_result = match validate_gossip_beacon_block(
-                    beacon_chain,
-                    cached_db,
-                    &signed_block,
-                )
-                .await
-                {
+            match prevalidate_gossip_beacon_block(beacon_chain, cached_db, &signed_block).await {
+                Ok(ValidationResult::Accept) => {}
+                Ok(validation_result) => {
+                    warn!("Dropping gossipsub beacon block: {validation_result:?}");
+                    return validation_result;
+                }
+                Err(err) => {
+                    warn!("Failed to validate gossipsub beacon block: {err}");
+                    return ValidationResult::Ignore(err.to_string());
+                }
+            }
+
+            // Hold the block back until its parent is looked up
+            let parent_root = signed_block.message.parent_root;
+            if !lookup_sync.is_block_imported(parent_root) {
+                lookup_sync
+                    .search_block(parent_root, signed_block.message.slot, message)
+                    .await;
+                return ValidationResult::Ignore("Parent block is unknown".to_string());
+            }
+
+            let validation_result =
+                match validate_gossip_beacon_block(beacon_chain, cached_db, &signed_block).await {
                     Ok(result) => result,
                     Err(err) => {
                         warn!("Failed to validate gossipsub beacon block: {err}");
-                        return;
+                        return ValidationResult::Ignore(err.to_string());
                     }
                 };
 
-                match validation_result {
-                    ValidationResult::Accept => {
-                        let signed_block_bytes = signed_block.as_ssz_bytes();
-                        if let Err(err) = beacon_chain.process_block(*signed_block).await {
-                            error!("Failed to process gossipsub beacon block: {err}");
-                        }
-                        p2p_sender.send_gossip(GossipMessage {
-                            topic: GossipTopic::from_topic_hash(&message.topic)
-                                .expect("invalid topic hash"),
-                            data: signed_block_bytes,
-                        });
-                    }
-                    ValidationResult::Ignore(reason) => {
-                        warn!("Ignoring gossipsub beacon block: {reason}");
-                    }
-                    ValidationResult::Reject(reason) => {
-                        warn!("Rejecting gossipsub beacon block: {reason}");
+            match &validation_result {
+                ValidationResult::Accept => {
+                    if let Err(err) = beacon_chain.process_block(*signed_block).await {
+                        error!("Failed to process gossipsub beacon block: {err}");
                     }
                 }
+                ValidationResult::Ignore(reason) => {
+                    warn!("Ignoring gossipsub beacon block: {reason}");
+                }
+                ValidationResult::Reject(reason) => {
+                    warn!("Rejecting gossipsub beacon block: {reason}");
+                }
             }
-            GossipsubMessage::BeaconAttestation((single_attestation, subnet_id)) => {
-                trace!(
-                    "Beacon Attestation received over gossipsub: root: {}",
-                    single_attestation.tree_hash_root()
-                );
+            validation_result
+        }
+        GossipsubMessage::BeaconAttestation((single_attestation, subnet_id)) => {
+            trace!(
+                "Beacon Attestation received over gossipsub: root: {}",
+                single_attestation.tree_hash_root()
+            );
 
-                match validate_beacon_attestation(
-                    &single_attestation,
-                    beacon_chain,
-                    subnet_id,
-                    cached_db,
-                )
-                .await
-                {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: single_attestation.as_ssz_bytes(),
-                            });
-                        }
+            match prevalidate_beacon_attestation(
+                &single_attestation,
+                beacon_chain,
+                subnet_id,
+                cached_db,
+            )
+            .await
+            {
+                Ok(ValidationResult::Accept) => {}
+                Ok(validation_result) => {
+                    trace!("Dropping attestation: {validation_result:?}");
+                    return validation_result;
+                }
+                Err(err) => {
+                    trace!("Could not validate attestation: {err}");
+                    return ValidationResult::Ignore(err.to_string());
+                }
+            }
+
+            // Hold the attestation back until the block it votes for is looked up
+            let beacon_block_root = single_attestation.data.beacon_block_root;
+            if !lookup_sync.is_block_imported(beacon_block_root) {
+                lookup_sync
+                    .search_block(beacon_block_root, single_attestation.data.slot, message)
+                    .await;
+                return ValidationResult::Ignore("Attested block is unknown".to_string());
+            }
+
+            match validate_beacon_attestation(
+                &single_attestation,
+                beacon_chain,
+                subnet_id,
+                cached_db,
+            )
+            .await
+            {
+                Ok(validation_result) => {
+                    match &validation_result {
+                        ValidationResult::Accept => {}
                         ValidationResult::Reject(reason) => {
                             info!("Attestation rejected: {reason}");
                         }
                         ValidationResult::Ignore(reason) => {
                             info!("Attestation ignored: {reason}");
                         }
-                    },
-                    Err(err) => {
-                        trace!("Could not validate attestation: {err}");
                     }
+                    validation_result
+                }
+                Err(err) => {
+                    trace!("Could not validate attestation: {err}");
+                    ValidationResult::Ignore(err.to_string())
                 }
             }
-            GossipsubMessage::BlsToExecutionChange(signed_bls_to_execution_change) => {
-                info!(
-                    "BLS to Execution Change received over gossipsub: root: {}",
-                    signed_bls_to_execution_change.tree_hash_root()
-                );
+        }
+        GossipsubMessage::BlsToExecutionChange(signed_bls_to_execution_change) => {
+            info!(
+                "BLS to Execution Change received over gossipsub: root: {}",
+                signed_bls_to_execution_change.tree_hash_root()
+            );
 
-                match validate_bls_to_execution_change(
-                    &signed_bls_to_execution_change,
-                    beacon_chain,
-                    cached_db,
-                )
-                .await
-                {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: signed_bls_to_execution_change.as_ssz_bytes(),
-                            });
-                        }
+            match validate_bls_to_execution_change(
+                &signed_bls_to_execution_change,
+                beacon_chain,
+                cached_db,
+            )
+            .await
+            {
+                Ok(validation_result) => {
+                    match &validation_result {
+                        ValidationResult::Accept => {}
                         ValidationResult::Reject(reason) => {
                             info!("BLS to Execution Change rejected: {reason}");
                         }
                         ValidationResult::Ignore(reason) => {
                             info!("BLS to Execution Change ignored: {reason}");
                         }
-                    },
-                    Err(err) => {
-                        error!("Could not validate BLS to Execution Change: {err}");
                     }
+                    validation_result
+                }
+                Err(err) => {
+                    error!("Could not validate BLS to Execution Change: {err}");
+                    ValidationResult::Ignore(err.to_string())
                 }
             }
-            GossipsubMessage::AggregateAndProof(aggregate_and_proof) => {
-                info!(
-                    "Aggregate And Proof received over gossipsub: root: {}",
-                    aggregate_and_proof.tree_hash_root()
-                );
-            }
-            GossipsubMessage::SyncCommittee((sync_committee, subnet_id)) => {
-                info!(
-                    "Sync Committee received over gossipsub: root: {}",
-                    sync_committee.tree_hash_root()
-                );
+        }
+        GossipsubMessage::AggregateAndProof(aggregate_and_proof) => {
+            info!(
+                "Aggregate And Proof received over gossipsub: root: {}",
+                aggregate_and_proof.tree_hash_root()
+            );
 
-                match validate_sync_committee(&sync_committee, beacon_chain, subnet_id, cached_db)
-                    .await
-                {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: sync_committee.as_ssz_bytes(),
-                            });
+            match validate_aggregate_and_proof(&aggregate_and_proof, beacon_chain, cached_db).await
+            {
+                Ok(validation_result) => {
+                    match &validation_result {
+                        ValidationResult::Accept => {}
+                        ValidationResult::Reject(reason) => {
+                            info!("Aggregate and proof rejected: {reason}");
                         }
+                        ValidationResult::Ignore(reason) => {
+                            info!("Aggregate and proof ignored: {reason}");
+                        }
+                    }
+                    validation_result
+                }
+                Err(err) => {
+                    error!("Could not validate aggregate and proof: {err}");
+                    ValidationResult::Ignore(err.to_string())
+                }
+            }
+        }
+        GossipsubMessage::SyncCommittee((sync_committee, subnet_id)) => {
+            info!(
+                "Sync Committee received over gossipsub: root: {}",
+                sync_committee.tree_hash_root()
+            );
+
+            match validate_sync_committee(&sync_committee, beacon_chain, subnet_id, cached_db).await
+            {
+                Ok(validation_result) => {
+                    match &validation_result {
+                        ValidationResult::Accept => {}
                         ValidationResult::Reject(reason) => {
                             info!("Sync committee message rejected: {reason}");
                         }
                         ValidationResult::Ignore(reason) => {
                             info!("Sync committee message ignored: {reason}");
                         }
-                    },
-                    Err(err) => {
-                        error!("Could not validate sync committee message: {err}");
                     }
+                    validation_result
+                }
+                Err(err) => {
+                    error!("Could not validate sync committee message: {err}");
+                    ValidationResult::Ignore(err.to_string())
                 }
             }
-            GossipsubMessage::SyncCommitteeContributionAndProof(
-                _sync_committee_contribution_and_proof,
-            ) => {}
-            GossipsubMessage::AttesterSlashing(attester_slashing) => {
-                info!(
-                    "Attester Slashing received over gossipsub: root: {}",
-                    attester_slashing.tree_hash_root()
-                );
+        }
+        GossipsubMessage::SyncCommitteeContributionAndProof(contribution_and_proof) => {
+            info!(
+                "Sync Committee Contribution And Proof received over gossipsub: root: {}",
+                contribution_and_proof.tree_hash_root()
+            );
 
-                match validate_attester_slashing(&attester_slashing, beacon_chain, cached_db).await
-                {
-                    Ok(validation_result) => match validation_result {
+            match validate_sync_committee_contribution(
+                &contribution_and_proof,
+                beacon_chain,
+                cached_db,
+            )
+            .await
+            {
+                Ok(validation_result) => {
+                    match &validation_result {
+                        ValidationResult::Accept => {}
+                        ValidationResult::Reject(reason) => {
+                            info!("Sync committee contribution rejected: {reason}");
+                        }
+                        ValidationResult::Ignore(reason) => {
+                            info!("Sync committee contribution ignored: {reason}");
+                        }
+                    }
+                    validation_result
+                }
+                Err(err) => {
+                    error!("Could not validate sync committee contribution: {err}");
+                    ValidationResult::Ignore(err.to_string())
+                }
+            }
+        }
+        GossipsubMessage::AttesterSlashing(attester_slashing) => {
+            info!(
+                "Attester Slashing received over gossipsub: root: {}",
+                attester_slashing.tree_hash_root()
+            );
+
+            match validate_attester_slashing(&attester_slashing, beacon_chain, cached_db).await {
+                Ok(validation_result) => {
+                    match &validation_result {
                         ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: attester_slashing.as_ssz_bytes(),
-                            });
                             if let Err(err) = beacon_chain
                                 .process_attester_slashing(*attester_slashing)
                                 .await
@@ -275,56 +357,56 @@ pub async fn handle_gossipsub_message(
                         ValidationResult::Ignore(reason) => {
                             info!("Attester slashing ignored: {reason}");
                         }
-                    },
-                    Err(err) => {
-                        error!("Could not validate attester slashing: {err}");
                     }
+                    validation_result
+                }
+                Err(err) => {
+                    error!("Could not validate attester slashing: {err}");
+                    ValidationResult::Ignore(err.to_string())
                 }
             }
-            GossipsubMessage::ProposerSlashing(proposer_slashing) => {
-                info!(
-                    "Proposer Slashing received over gossipsub: root: {}",
-                    proposer_slashing.tree_hash_root()
-                );
+        }
+        GossipsubMessage::ProposerSlashing(proposer_slashing) => {
+            info!(
+                "Proposer Slashing received over gossipsub: root: {}",
+                proposer_slashing.tree_hash_root()
+            );
 
-                match validate_proposer_slashing(&proposer_slashing, beacon_chain, cached_db).await
-                {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: proposer_slashing.as_ssz_bytes(),
-                            });
-                        }
+            match validate_proposer_slashing(&proposer_slashing, beacon_chain, cached_db).await {
+                Ok(validation_result) => {
+                    match &validation_result {
+                        ValidationResult::Accept => {}
                         ValidationResult::Reject(reason) => {
                             info!("Proposer slashing rejected: {reason}");
                         }
                         ValidationResult::Ignore(reason) => {
                             info!("Proposer slashing ignored: {reason}");
                         }
-                    },
-                    Err(err) => {
-                        error!("Could not validate proposer slashing: {err}");
                     }
+                    validation_result
+                }
+                Err(err) => {
+                    error!("Could not validate proposer slashing: {err}");
+                    ValidationResult::Ignore(err.to_string())
                 }
             }
-            GossipsubMessage::BlobSidecar(blob_sidecar) => {
-                info!(
-                    "Blob Sidecar received over gossipsub: root: {}",
-                    blob_sidecar.tree_hash_root()
-                );
-                match validate_blob_sidecar(
-                    beacon_chain,
-                    &blob_sidecar,
-                    compute_subnet_for_blob_sidecar(blob_sidecar.index),
-                    cached_db,
-                )
-                .await
-                {
-                    Ok(validation_result) => match validation_result {
+        }
+        GossipsubMessage::BlobSidecar(blob_sidecar) => {
+            info!(
+                "Blob Sidecar received over gossipsub: root: {}",
+                blob_sidecar.tree_hash_root()
+            );
+            match validate_blob_sidecar(
+                beacon_chain,
+                &blob_sidecar,
+                compute_subnet_for_blob_sidecar(blob_sidecar.index),
+                cached_db,
+            )
+            .await
+            {
+                Ok(validation_result) => {
+                    match &validation_result {
                         ValidationResult::Accept => {
-                            let blob_sidecar_bytes = blob_sidecar.as_ssz_bytes();
                             if let Err(err) = beacon_chain
                                 .store
                                 .lock()
@@ -344,12 +426,6 @@ pub async fn handle_gossipsub_message(
                             {
                                 error!("Failed to insert blob_sidecar: {err}");
                             }
-
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: blob_sidecar_bytes,
-                            });
                         }
                         ValidationResult::Reject(reason) => {
                             info!("Blob_sidecar rejected: {reason}");
@@ -357,54 +433,57 @@ pub async fn handle_gossipsub_message(
                         ValidationResult::Ignore(reason) => {
                             info!("Blob_sidecar ignored: {reason}");
                         }
-                    },
-                    Err(err) => {
-                        error!("Could not validate blob_sidecar: {err}");
                     }
+                    validation_result
+                }
+                Err(err) => {
+                    error!("Could not validate blob_sidecar: {err}");
+                    ValidationResult::Ignore(err.to_string())
                 }
             }
-            GossipsubMessage::LightClientFinalityUpdate(light_client_finality_update) => {
-                info!(
-                    "Light Client Finality Update received over gossipsub: root: {}",
-                    light_client_finality_update.tree_hash_root()
-                );
-            }
-            GossipsubMessage::LightClientOptimisticUpdate(light_client_optimistic_update) => {
-                info!(
-                    "Light Client Optimistic Update received over gossipsub: root: {}",
-                    light_client_optimistic_update.tree_hash_root()
-                );
-            }
-            GossipsubMessage::VoluntaryExit(voluntary_exit) => {
-                info!(
-                    "Voluntary Exit received over gossipsub: root: {}",
-                    voluntary_exit.tree_hash_root()
-                );
+        }
+        GossipsubMessage::LightClientFinalityUpdate(light_client_finality_update) => {
+            info!(
+                "Light Client Finality Update received over gossipsub: root: {}",
+                light_client_finality_update.tree_hash_root()
+            );
+            ValidationResult::Ignore(
+                "Light client finality update validation is not supported".to_string(),
+            )
+        }
+        GossipsubMessage::LightClientOptimisticUpdate(light_client_optimistic_update) => {
+            info!(
+                "Light Client Optimistic Update received over gossipsub: root: {}",
+                light_client_optimistic_update.tree_hash_root()
+            );
+            ValidationResult::Ignore(
+                "Light client optimistic update validation is not supported".to_string(),
+            )
+        }
+        GossipsubMessage::VoluntaryExit(voluntary_exit) => {
+            info!(
+                "Voluntary Exit received over gossipsub: root: {}",
+                voluntary_exit.tree_hash_root()
+            );
 
-                match validate_voluntary_exit(&voluntary_exit, beacon_chain, cached_db).await {
-                    Ok(validation_result) => match validation_result {
-                        ValidationResult::Accept => {
-                            p2p_sender.send_gossip(GossipMessage {
-                                topic: GossipTopic::from_topic_hash(&message.topic)
-                                    .expect("invalid topic hash"),
-                                data: voluntary_exit.as_ssz_bytes(),
-                            });
-                        }
+            match validate_voluntary_exit(&voluntary_exit, beacon_chain, cached_db).await {
+                Ok(validation_result) => {
+                    match &validation_result {
+                        ValidationResult::Accept => {}
                         ValidationResult::Reject(reason) => {
                             info!("voluntary_exit rejected: {reason}");
                         }
                         ValidationResult::Ignore(reason) => {
                             info!("voluntary_exit ignored: {reason}");
                         }
-                    },
-                    Err(err) => {
-                        error!("Could not validate voluntary_exit: {err}");
                     }
+                    validation_result
+                }
+                Err(err) => {
+                    error!("Could not validate voluntary_exit: {err}");
+                    ValidationResult::Ignore(err.to_string())
                 }
             }
-        },
-        Err(err) => {
-            trace!("Failed to decode gossip message: {err:?}");
         }
-    };
+    }
 }
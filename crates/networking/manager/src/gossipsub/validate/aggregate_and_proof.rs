@@ -0,0 +1,248 @@
+use anyhow::anyhow;
+use ream_bls::traits::Verifiable;
+use ream_chain_beacon::beacon_chain::BeaconChain;
+use ream_consensus_misc::{
+    constants::beacon::{ATTESTATION_PROPAGATION_SLOT_RANGE, DOMAIN_AGGREGATE_AND_PROOF},
+    misc::{compute_epoch_at_slot, compute_signing_root, get_committee_indices},
+};
+use ream_storage::{
+    cache::CachedDB,
+    tables::{Field, Table},
+};
+use ream_validator_beacon::{
+    aggregate_and_proof::SignedAggregateAndProof, attestation::is_aggregator,
+    constants::DOMAIN_SELECTION_PROOF,
+};
+use tree_hash::TreeHash;
+
+use super::result::ValidationResult;
+
+pub async fn validate_aggregate_and_proof(
+    signed_aggregate_and_proof: &SignedAggregateAndProof,
+    beacon_chain: &BeaconChain,
+    cached_db: &CachedDB,
+) -> anyhow::Result<ValidationResult> {
+    let aggregate_and_proof = &signed_aggregate_and_proof.message;
+    let aggregate = &aggregate_and_proof.aggregate;
+    let aggregator_key = (
+        aggregate_and_proof.aggregator_index,
+        aggregate.data.target.epoch,
+    );
+
+    // [IGNORE] The aggregate is the first valid aggregate received for the aggregator with index
+    // aggregate_and_proof.aggregator_index for the epoch aggregate.data.target.epoch.
+    if cached_db
+        .seen_aggregators
+        .read()
+        .await
+        .contains(&aggregator_key)
+    {
+        return Ok(ValidationResult::Ignore(
+            "Aggregate already received for the aggregator and epoch".to_string(),
+        ));
+    }
+
+    let mut store = beacon_chain.store.lock().await;
+
+    // [IGNORE] aggregate.data.slot is within the last ATTESTATION_PROPAGATION_SLOT_RANGE slots
+    // (with a MAXIMUM_GOSSIP_CLOCK_DISPARITY allowance)
+    let current_slot = store.get_current_slot()?;
+    if aggregate.data.slot > current_slot {
+        return Ok(ValidationResult::Ignore(
+            "Aggregate is from a future slot".to_string(),
+        ));
+    }
+    if aggregate.data.slot + ATTESTATION_PROPAGATION_SLOT_RANGE < current_slot {
+        return Ok(ValidationResult::Ignore(
+            "Aggregate is from a slot too far in the past".to_string(),
+        ));
+    }
+
+    // [REJECT] The aggregate attestation's epoch matches its target
+    if aggregate.data.target.epoch != compute_epoch_at_slot(aggregate.data.slot) {
+        return Ok(ValidationResult::Reject(
+            "The aggregate's epoch doesn't match its target".to_string(),
+        ));
+    }
+
+    // [REJECT] len(committee_indices) == 1, where committee_indices =
+    // get_committee_indices(aggregate).
+    let committee_indices = get_committee_indices(&aggregate.committee_bits);
+    let [index] = committee_indices[..] else {
+        return Ok(ValidationResult::Reject(
+            "Aggregate must have exactly one committee index".to_string(),
+        ));
+    };
+
+    // [IGNORE] A valid aggregate attestation defined by hash_tree_root(aggregate.data) whose
+    // aggregation_bits is a non-strict superset has not already been seen.
+    let aggregate_key = (aggregate.data.tree_hash_root(), index);
+    if cached_db
+        .seen_aggregates
+        .read()
+        .await
+        .peek(&aggregate_key)
+        .is_some_and(|seen_bits| {
+            seen_bits
+                .iter()
+                .any(|bits| aggregate.aggregation_bits.is_subset(bits))
+        })
+    {
+        return Ok(ValidationResult::Ignore(
+            "An aggregate with a superset of the aggregation bits was already seen".to_string(),
+        ));
+    }
+
+    // [IGNORE] The block being voted for (aggregate.data.beacon_block_root) has been seen (via
+    // gossip or non-gossip sources) (a client MAY queue aggregates for processing once block is
+    // retrieved).
+    if store
+        .db
+        .beacon_block_provider()
+        .get(aggregate.data.beacon_block_root)?
+        .is_none()
+    {
+        return Ok(ValidationResult::Ignore(
+            "The block being voted for has not been seen".to_string(),
+        ));
+    }
+
+    // [REJECT] The aggregate attestation's target block is an ancestor of the block named in the
+    // LMD vote
+    if store.get_checkpoint_block(
+        aggregate.data.beacon_block_root,
+        aggregate.data.target.epoch,
+    )? != aggregate.data.target.root
+    {
+        return Ok(ValidationResult::Reject(
+            "The target block is not an ancestor of the LMD vote block".to_string(),
+        ));
+    }
+
+    // [IGNORE] The current finalized_checkpoint is an ancestor of the block defined by
+    // aggregate.data.beacon_block_root
+    let finalized_checkpoint = store.db.finalized_checkpoint_provider().get()?;
+    if store.get_checkpoint_block(aggregate.data.beacon_block_root, finalized_checkpoint.epoch)?
+        != finalized_checkpoint.root
+    {
+        return Ok(ValidationResult::Ignore(
+            "Finalized checkpoint is not an ancestor of the block defined by aggregate.data.beacon_block_root".to_string(),
+        ));
+    }
+
+    // The committees are those of the target checkpoint, the head may be on another fork
+    store.store_target_checkpoint_state(aggregate.data.target)?;
+    let state = store
+        .db
+        .checkpoint_states_provider()
+        .get_shared(aggregate.data.target)?
+        .ok_or_else(|| {
+            anyhow!(
+                "No checkpoint state found for target: {:?}",
+                aggregate.data.target
+            )
+        })?;
+
+    // [REJECT] The committee index is within the expected range
+    if index >= state.get_committee_count_per_slot(aggregate.data.target.epoch) {
+        return Ok(ValidationResult::Reject(
+            "The committee index is not within the expected range".to_string(),
+        ));
+    }
+
+    // [REJECT] The number of aggregation bits matches the committee size
+    let committee = store
+        .db
+        .read_cache
+        .get_beacon_committee(&state, aggregate.data.slot, index)?;
+    if aggregate.aggregation_bits.len() != committee.len() {
+        return Ok(ValidationResult::Reject(
+            "The number of aggregation bits doesn't match the committee size".to_string(),
+        ));
+    }
+
+    // [REJECT] The aggregate attestation has participants
+    if aggregate.aggregation_bits.is_zero() {
+        return Ok(ValidationResult::Reject(
+            "The aggregate has no participants".to_string(),
+        ));
+    }
+
+    // [REJECT] aggregate_and_proof.selection_proof selects the validator as an aggregator for the
+    // slot
+    if !is_aggregator(
+        &state,
+        aggregate.data.slot,
+        index,
+        aggregate_and_proof.selection_proof.clone(),
+    )? {
+        return Ok(ValidationResult::Reject(
+            "The validator is not selected as an aggregator".to_string(),
+        ));
+    }
+
+    // [REJECT] The aggregator's validator index is within the committee
+    if !committee.contains(&aggregate_and_proof.aggregator_index) {
+        return Ok(ValidationResult::Reject(
+            "The aggregator is not a member of the committee".to_string(),
+        ));
+    }
+
+    // The signatures are verified without holding the store lock
+    drop(store);
+
+    let aggregator = state
+        .validators
+        .get(aggregate_and_proof.aggregator_index as usize)
+        .ok_or_else(|| anyhow!("Could not get validator"))?;
+    let epoch = compute_epoch_at_slot(aggregate.data.slot);
+
+    // [REJECT] The aggregate_and_proof.selection_proof is a valid signature of the
+    // aggregate.data.slot by the validator with index aggregate_and_proof.aggregator_index.
+    let signing_root = compute_signing_root(
+        aggregate.data.slot,
+        state.get_domain(DOMAIN_SELECTION_PROOF, Some(epoch)),
+    );
+    if !aggregate_and_proof
+        .selection_proof
+        .verify(&aggregator.public_key, signing_root.as_slice())?
+    {
+        return Ok(ValidationResult::Reject(
+            "Invalid selection proof".to_string(),
+        ));
+    }
+
+    // [REJECT] The aggregator signature, signed_aggregate_and_proof.signature, is valid.
+    let signing_root = compute_signing_root(
+        aggregate_and_proof,
+        state.get_domain(DOMAIN_AGGREGATE_AND_PROOF, Some(epoch)),
+    );
+    if !signed_aggregate_and_proof
+        .signature
+        .verify(&aggregator.public_key, signing_root.as_slice())?
+    {
+        return Ok(ValidationResult::Reject(
+            "Invalid aggregator signature".to_string(),
+        ));
+    }
+
+    // [REJECT] The signature of aggregate is valid.
+    if !state.is_valid_indexed_attestation(&state.get_indexed_attestation(aggregate)?)? {
+        return Ok(ValidationResult::Reject(
+            "Invalid aggregate signature".to_string(),
+        ));
+    }
+
+    cached_db
+        .seen_aggregators
+        .write()
+        .await
+        .put(aggregator_key, ());
+    cached_db
+        .seen_aggregates
+        .write()
+        .await
+        .get_or_insert_mut(aggregate_key, Vec::new)
+        .push(aggregate.aggregation_bits.clone());
+    Ok(ValidationResult::Accept)
+}
@@ -1,9 +1,7 @@
 use anyhow::anyhow;
 use ream_bls::traits::Verifiable;
 use ream_chain_beacon::beacon_chain::BeaconChain;
-use ream_consensus_beacon::{
-    electra::beacon_state::BeaconState, single_attestation::SingleAttestation,
-};
+use ream_consensus_beacon::single_attestation::SingleAttestation;
 use ream_consensus_misc::{
     constants::beacon::DOMAIN_BEACON_ATTESTER,
     misc::{compute_epoch_at_slot, compute_signing_root},
@@ -16,19 +14,81 @@ use ream_validator_beacon::attestation::compute_subnet_for_attestation;
 
 use super::result::ValidationResult;
 
+/// The checks which don't need the attested block, run before the block is looked up so
+/// attestations which would be dropped anyway don't start lookups.
+pub async fn prevalidate_beacon_attestation(
+    attestation: &SingleAttestation,
+    beacon_chain: &BeaconChain,
+    attestation_subnet_id: u64,
+    cached_db: &CachedDB,
+) -> anyhow::Result<ValidationResult> {
+    let current_slot = beacon_chain.store.lock().await.get_current_slot()?;
+
+    // [IGNORE] attestation.data.slot is equal to or earlier than the current_slot (with a
+    // MAXIMUM_GOSSIP_CLOCK_DISPARITY allowance)
+    if attestation.data.slot > current_slot {
+        return Ok(ValidationResult::Ignore(
+            "Attestation is from a future slot".to_string(),
+        ));
+    }
+
+    // [IGNORE] the epoch of attestation.data.slot is either the current or previous epoch (with a
+    // MAXIMUM_GOSSIP_CLOCK_DISPARITY allowance)
+    let attestation_epoch = compute_epoch_at_slot(attestation.data.slot);
+    if attestation_epoch + 1 < compute_epoch_at_slot(current_slot) {
+        return Ok(ValidationResult::Ignore(
+            "Attestation is from a epoch too far in the past".to_string(),
+        ));
+    }
+
+    // [REJECT] The attestation's epoch matches its target
+    if attestation.data.target.epoch != attestation_epoch {
+        return Ok(ValidationResult::Reject(
+            "The attestation's epoch doesn't match its target".to_string(),
+        ));
+    }
+
+    // [IGNORE] There has been no other valid attestation seen on an attestation subnet that has an
+    // identical attestation.data.target.epoch and participating validator index.
+    let attestation_key = AtestationKey {
+        attestation_subnet_id,
+        target_epoch: attestation.data.target.epoch,
+        participating_validator_index: attestation.attester_index,
+    };
+    if cached_db
+        .seen_attestations
+        .read()
+        .await
+        .contains(&attestation_key)
+    {
+        return Ok(ValidationResult::Ignore(
+            "There has been no other valid attestation seen".to_string(),
+        ));
+    }
+
+    Ok(ValidationResult::Accept)
+}
+
 pub async fn validate_beacon_attestation(
     attestation: &SingleAttestation,
     beacon_chain: &BeaconChain,
     attestation_subnet_id: u64,
     cached_db: &CachedDB,
 ) -> anyhow::Result<ValidationResult> {
+    let result =
+        prevalidate_beacon_attestation(attestation, beacon_chain, attestation_subnet_id, cached_db)
+            .await?;
+    if !matches!(result, ValidationResult::Accept) {
+        return Ok(result);
+    }
+
     let store = beacon_chain.store.lock().await;
 
     let head_root = store.get_head()?;
-    let state: BeaconState = store
+    let state = store
         .db
         .beacon_state_provider()
-        .get(head_root)?
+        .get_shared(head_root)?
         .ok_or_else(|| anyhow!("No beacon state found for head root: {head_root}"))?;
 
     let index = attestation.committee_index;
@@ -50,41 +110,6 @@ pub async fn validate_beacon_attestation(
         ));
     }
 
-    let block = store
-        .db
-        .beacon_block_provider()
-        .get(head_root)?
-        .ok_or_else(|| anyhow!("Could not get block for head root: {head_root}"))?;
-
-    let current_slot = block.message.slot;
-
-    // [IGNORE] attestation.data.slot is equal to or earlier than the current_slot (with a
-    // MAXIMUM_GOSSIP_CLOCK_DISPARITY allowance)
-    if attestation.data.slot > current_slot {
-        return Ok(ValidationResult::Ignore(
-            "Attestation is from a future slot".to_string(),
-        ));
-    }
-
-    // [IGNORE] the epoch of attestation.data.slot is either the current or previous epoch (with a
-    // MAXIMUM_GOSSIP_CLOCK_DISPARITY allowance)
-    let attestation_epoch = compute_epoch_at_slot(attestation.data.slot);
-    let current_epoch = state.get_current_epoch();
-    let previous_epoch = state.get_previous_epoch();
-
-    if attestation_epoch != current_epoch && attestation_epoch != previous_epoch {
-        return Ok(ValidationResult::Ignore(
-            "Attestation is from a epoch too far in the past".to_string(),
-        ));
-    }
-
-    // [REJECT] The attestation's epoch matches its target
-    if attestation.data.target.epoch != attestation_epoch {
-        return Ok(ValidationResult::Reject(
-            "The attestation's epoch doesn't match its target".to_string(),
-        ));
-    }
-
     // [REJECT] attestation.data.index == 0
     if index == 0 {
         return Ok(ValidationResult::Reject(
@@ -93,8 +118,10 @@ pub async fn validate_beacon_attestation(
     }
 
     // [REJECT] The attester is a member of the committee
-    if !state
-        .get_beacon_committee(attestation.data.slot, index)?
+    if !store
+        .db
+        .read_cache
+        .get_beacon_committee(&state, attestation.data.slot, index)?
         .contains(&(attestation.attester_index))
     {
         return Ok(ValidationResult::Reject(
@@ -102,23 +129,11 @@ pub async fn validate_beacon_attestation(
         ));
     }
 
-    // [IGNORE] There has been no other valid attestation seen on an attestation subnet that has an
-    // identical attestation.data.target.epoch and participating validator index.
     let attestation_key = AtestationKey {
         attestation_subnet_id,
         target_epoch: attestation.data.target.epoch,
         participating_validator_index: attestation.attester_index,
     };
-    if cached_db
-        .seen_attestations
-        .read()
-        .await
-        .contains(&attestation_key)
-    {
-        return Ok(ValidationResult::Ignore(
-            "There has been no other valid attestation seen".to_string(),
-        ));
-    }
 
     // [REJECT] The signature of attestation is valid.
     let validator = state
@@ -15,11 +15,66 @@ use ream_storage::{
 
 use super::result::ValidationResult;
 
+/// The checks which don't need the parent of the block, run before the parent is looked up so
+/// blocks which would be dropped anyway don't start lookups.
+pub async fn prevalidate_gossip_beacon_block(
+    beacon_chain: &BeaconChain,
+    cached_db: &CachedDB,
+    block: &SignedBeaconBlock,
+) -> anyhow::Result<ValidationResult> {
+    // [IGNORE] The block is the first block with valid signature received for the proposer for the
+    // slot.
+    if cached_db
+        .seen_block_proposers
+        .read()
+        .await
+        .contains(&(block.message.proposer_index, block.message.slot))
+    {
+        return Ok(ValidationResult::Ignore(
+            "Block already received for the proposer and slot".to_string(),
+        ));
+    }
+
+    let store = beacon_chain.store.lock().await;
+
+    // [IGNORE] The block is not from a future slot.
+    if block.message.slot > store.get_current_slot()? {
+        return Ok(ValidationResult::Ignore(
+            "Block is from a future slot".to_string(),
+        ));
+    }
+
+    // [IGNORE] The block is from a slot greater than the latest finalized slot.
+    if block.message.slot
+        <= compute_start_slot_at_epoch(store.db.finalized_checkpoint_provider().get()?.epoch)
+    {
+        return Ok(ValidationResult::Ignore(
+            "Block is from a slot greater than the latest finalized slot".to_string(),
+        ));
+    }
+
+    Ok(ValidationResult::Accept)
+}
+
 pub async fn validate_gossip_beacon_block(
     beacon_chain: &BeaconChain,
     cached_db: &CachedDB,
     block: &SignedBeaconBlock,
 ) -> anyhow::Result<ValidationResult> {
+    // [IGNORE] The block is the first block with valid signature received for the proposer for the
+    // slot.
+    let proposer_key = (block.message.proposer_index, block.message.slot);
+    if cached_db
+        .seen_block_proposers
+        .read()
+        .await
+        .contains(&proposer_key)
+    {
+        return Ok(ValidationResult::Ignore(
+            "Block already received for the proposer and slot".to_string(),
+        ));
+    }
+
     let latest_state = beacon_chain.store.lock().await.db.get_latest_state()?;
 
     // Validate incoming block
@@ -46,7 +101,7 @@ pub async fn validate_gossip_beacon_block(
         let Some(parent_state) = store
             .db
             .beacon_state_provider()
-            .get(block.message.parent_root)?
+            .get_shared(block.message.parent_root)?
         else {
             return Err(anyhow!("failed to get parent state"));
         };
@@ -66,20 +121,11 @@ pub async fn validate_gossip_beacon_block(
         }
     };
 
-    let Some(validator) = latest_state
-        .validators
-        .get(block.message.proposer_index as usize)
-    else {
-        return Ok(ValidationResult::Reject("Validator not found".to_string()));
-    };
-
-    cached_db.seen_proposer_signature.write().await.put(
-        AddressSlotIdentifier {
-            address: validator.public_key.clone(),
-            slot: block.message.slot,
-        },
-        block.signature.clone(),
-    );
+    cached_db
+        .seen_block_proposers
+        .write()
+        .await
+        .put(proposer_key, ());
 
     for signed_bls_execution_change in block.message.body.bls_to_execution_changes.iter() {
         let validator =
@@ -126,22 +172,6 @@ pub async fn validate_beacon_block(
         return Ok(ValidationResult::Reject("Validator not found".to_string()));
     };
 
-    // [IGNORE] The block is the first block with valid signature received for the proposer for the
-    // slot.
-    if cached_db
-        .seen_proposer_signature
-        .read()
-        .await
-        .contains(&AddressSlotIdentifier {
-            address: validator.public_key.clone(),
-            slot: block.message.slot,
-        })
-    {
-        return Ok(ValidationResult::Ignore(
-            "Signature already received".to_string(),
-        ));
-    }
-
     // [REJECT] The proposer signature, signed_beacon_block.signature, is valid with respect to the
     // proposer_index pubkey.
     match state.verify_block_header_signature(&block.signed_header()) {
@@ -1,6 +1,6 @@
 use anyhow::anyhow;
 use ream_chain_beacon::beacon_chain::BeaconChain;
-use ream_consensus_beacon::{blob_sidecar::BlobSidecar, electra::beacon_state::BeaconState};
+use ream_consensus_beacon::blob_sidecar::BlobSidecar;
 use ream_consensus_misc::{
     constants::beacon::MAX_BLOBS_PER_BLOCK_ELECTRA, misc::compute_start_slot_at_epoch,
 };
@@ -53,10 +53,10 @@ pub async fn validate_blob_sidecar(
     }
 
     let head_root = store.get_head()?;
-    let state: BeaconState = store
+    let state = store
         .db
         .beacon_state_provider()
-        .get(head_root)?
+        .get_shared(head_root)?
         .ok_or_else(|| anyhow!("No beacon state found for head root: {head_root}"))?;
 
     // [REJECT] The proposer signature of blob_sidecar.signed_block_header, is valid with respect to
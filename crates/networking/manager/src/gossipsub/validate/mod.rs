@@ -1,3 +1,4 @@
+pub mod aggregate_and_proof;
 pub mod attester_slashing;
 pub mod beacon_attestation;
 pub mod beacon_block;
@@ -6,4 +7,5 @@ pub mod bls_to_execution_change;
 pub mod proposer_slashing;
 pub mod result;
 pub mod sync_committee;
+pub mod sync_committee_contribution;
 pub mod voluntary_exit;
@@ -1,6 +1,19 @@
+use libp2p::gossipsub::MessageAcceptance;
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum ValidationResult {
     Accept,
     Ignore(String),
     Reject(String),
 }
+
+impl ValidationResult {
+    /// The acceptance reported to gossipsub, which decides whether the message is forwarded.
+    pub fn acceptance(&self) -> MessageAcceptance {
+        match self {
+            ValidationResult::Accept => MessageAcceptance::Accept,
+            ValidationResult::Ignore(_) => MessageAcceptance::Ignore,
+            ValidationResult::Reject(_) => MessageAcceptance::Reject,
+        }
+    }
+}
@@ -1,15 +1,11 @@
 use anyhow::anyhow;
 use ream_bls::traits::Verifiable;
 use ream_chain_beacon::beacon_chain::BeaconChain;
-use ream_consensus_beacon::electra::beacon_state::BeaconState;
 use ream_consensus_misc::{
     constants::beacon::DOMAIN_SYNC_COMMITTEE,
     misc::{compute_epoch_at_slot, compute_signing_root},
 };
-use ream_storage::{
-    cache::{CachedDB, SyncCommitteeKey},
-    tables::Table,
-};
+use ream_storage::cache::{CachedDB, SyncCommitteeKey};
 use ream_validator_beacon::sync_committee::{
     SyncCommitteeMessage, compute_subnets_for_sync_committee,
 };
@@ -25,10 +21,10 @@ pub async fn validate_sync_committee(
     let store = beacon_chain.store.lock().await;
 
     let head_root = store.get_head()?;
-    let state: BeaconState = store
+    let state = store
         .db
         .beacon_state_provider()
-        .get(head_root)?
+        .get_shared(head_root)?
         .ok_or_else(|| anyhow!("No beacon state found for head root: {head_root}"))?;
 
     // [IGNORE] The message's slot is for the current slot (with a MAXIMUM_GOSSIP_CLOCK_DISPARITY
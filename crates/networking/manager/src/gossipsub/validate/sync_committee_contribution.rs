@@ -0,0 +1,173 @@
+use anyhow::anyhow;
+use ream_bls::traits::Verifiable;
+use ream_chain_beacon::beacon_chain::BeaconChain;
+use ream_consensus_misc::{
+    constants::beacon::{DOMAIN_SYNC_COMMITTEE, SYNC_COMMITTEE_SIZE},
+    misc::{compute_epoch_at_slot, compute_signing_root},
+};
+use ream_storage::cache::{CachedDB, SyncContributionKey};
+use ream_validator_beacon::{
+    constants::{
+        DOMAIN_CONTRIBUTION_AND_PROOF, DOMAIN_SYNC_COMMITTEE_SELECTION_PROOF,
+        SYNC_COMMITTEE_SUBNET_COUNT,
+    },
+    contribution_and_proof::SignedContributionAndProof,
+    sync_committee::{
+        SyncAggregatorSelectionData, compute_sync_committee_period, is_sync_committee_aggregator,
+    },
+};
+
+use super::result::ValidationResult;
+
+pub async fn validate_sync_committee_contribution(
+    signed_contribution_and_proof: &SignedContributionAndProof,
+    beacon_chain: &BeaconChain,
+    cached_db: &CachedDB,
+) -> anyhow::Result<ValidationResult> {
+    let contribution_and_proof = &signed_contribution_and_proof.message;
+    let contribution = &contribution_and_proof.contribution;
+
+    // [REJECT] The subcommittee index is in the allowed range
+    if contribution.subcommittee_index >= SYNC_COMMITTEE_SUBNET_COUNT {
+        return Ok(ValidationResult::Reject(
+            "The subcommittee index is out of range".into(),
+        ));
+    }
+
+    // [IGNORE] The sync committee contribution is the first valid contribution received for the
+    // aggregator with index contribution_and_proof.aggregator_index for the slot contribution.slot
+    // and subcommittee index contribution.subcommittee_index.
+    let key = SyncContributionKey {
+        aggregator_index: contribution_and_proof.aggregator_index,
+        slot: contribution.slot,
+        subcommittee_index: contribution.subcommittee_index,
+    };
+    if cached_db
+        .seen_sync_contributions
+        .read()
+        .await
+        .contains(&key)
+    {
+        return Ok(ValidationResult::Ignore(
+            "Contribution already received for the aggregator, slot and subcommittee".into(),
+        ));
+    }
+
+    // [REJECT] The contribution has participants
+    if contribution.aggregation_bits.is_zero() {
+        return Ok(ValidationResult::Reject(
+            "The contribution has no participants".into(),
+        ));
+    }
+
+    // [REJECT] contribution_and_proof.selection_proof selects the validator as an aggregator for
+    // the slot
+    if !is_sync_committee_aggregator(&contribution_and_proof.selection_proof) {
+        return Ok(ValidationResult::Reject(
+            "The validator is not selected as an aggregator".into(),
+        ));
+    }
+
+    let store = beacon_chain.store.lock().await;
+
+    // [IGNORE] The contribution's slot is for the current slot (with a
+    // MAXIMUM_GOSSIP_CLOCK_DISPARITY allowance)
+    if contribution.slot != store.get_current_slot()? {
+        return Ok(ValidationResult::Ignore(
+            "Contribution is not from current slot".into(),
+        ));
+    }
+
+    let head_root = store.get_head()?;
+    let state = store
+        .db
+        .beacon_state_provider()
+        .get_shared(head_root)?
+        .ok_or_else(|| anyhow!("No beacon state found for head root: {head_root}"))?;
+    // The signatures are verified without holding the store lock
+    drop(store);
+
+    // get_sync_subcommittee_pubkeys
+    let next_slot_epoch = compute_epoch_at_slot(state.slot + 1);
+    let sync_committee = if compute_sync_committee_period(state.get_current_epoch())
+        == compute_sync_committee_period(next_slot_epoch)
+    {
+        &state.current_sync_committee
+    } else {
+        &state.next_sync_committee
+    };
+    let sync_subcommittee_size = (SYNC_COMMITTEE_SIZE / SYNC_COMMITTEE_SUBNET_COUNT) as usize;
+    let start = contribution.subcommittee_index as usize * sync_subcommittee_size;
+    let subcommittee_public_keys =
+        &sync_committee.public_keys[start..start + sync_subcommittee_size];
+
+    // [REJECT] The aggregator's validator index is in the declared subcommittee of the current
+    // sync committee
+    let aggregator = state
+        .validators
+        .get(contribution_and_proof.aggregator_index as usize)
+        .ok_or_else(|| anyhow!("Validator not found"))?;
+    if !subcommittee_public_keys.contains(&aggregator.public_key) {
+        return Ok(ValidationResult::Reject(
+            "The aggregator is not in the subcommittee".into(),
+        ));
+    }
+
+    let epoch = compute_epoch_at_slot(contribution.slot);
+
+    // [REJECT] The contribution_and_proof.selection_proof is a valid signature of the
+    // SyncAggregatorSelectionData derived from the contribution by the validator with index
+    // contribution_and_proof.aggregator_index.
+    let signing_root = compute_signing_root(
+        SyncAggregatorSelectionData {
+            slot: contribution.slot,
+            subcommittee_index: contribution.subcommittee_index,
+        },
+        state.get_domain(DOMAIN_SYNC_COMMITTEE_SELECTION_PROOF, Some(epoch)),
+    );
+    if !contribution_and_proof
+        .selection_proof
+        .verify(&aggregator.public_key, signing_root.as_slice())?
+    {
+        return Ok(ValidationResult::Reject("Invalid selection proof".into()));
+    }
+
+    // [REJECT] The aggregator signature, signed_contribution_and_proof.signature, is valid.
+    let signing_root = compute_signing_root(
+        contribution_and_proof,
+        state.get_domain(DOMAIN_CONTRIBUTION_AND_PROOF, Some(epoch)),
+    );
+    if !signed_contribution_and_proof
+        .signature
+        .verify(&aggregator.public_key, signing_root.as_slice())?
+    {
+        return Ok(ValidationResult::Reject(
+            "Invalid aggregator signature".into(),
+        ));
+    }
+
+    // [REJECT] The aggregate signature is valid for the message beacon_block_root and aggregate
+    // pubkey derived from the participation info in aggregation_bits for the subcommittee
+    // specified by the contribution.subcommittee_index.
+    let participant_public_keys = subcommittee_public_keys
+        .iter()
+        .zip(contribution.aggregation_bits.iter())
+        .filter_map(|(public_key, participated)| participated.then_some(public_key))
+        .collect::<Vec<_>>();
+    let signing_root = compute_signing_root(
+        contribution.beacon_block_root,
+        state.get_domain(DOMAIN_SYNC_COMMITTEE, Some(epoch)),
+    );
+    if !contribution
+        .signature
+        .fast_aggregate_verify(participant_public_keys, signing_root.as_slice())?
+    {
+        return Ok(ValidationResult::Reject(
+            "Invalid contribution signature".into(),
+        ));
+    }
+
+    cached_db.seen_sync_contributions.write().await.put(key, ());
+
+    Ok(ValidationResult::Accept)
+}
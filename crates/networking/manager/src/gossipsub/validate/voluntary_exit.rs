@@ -1,9 +1,7 @@
 use anyhow::anyhow;
 use ream_chain_beacon::beacon_chain::BeaconChain;
-use ream_consensus_beacon::{
-    electra::beacon_state::BeaconState, voluntary_exit::SignedVoluntaryExit,
-};
-use ream_storage::{cache::CachedDB, tables::Table};
+use ream_consensus_beacon::voluntary_exit::SignedVoluntaryExit;
+use ream_storage::cache::CachedDB;
 
 use super::result::ValidationResult;
 
@@ -15,10 +13,10 @@ pub async fn validate_voluntary_exit(
     let store = beacon_chain.store.lock().await;
 
     let head_root = store.get_head()?;
-    let state: BeaconState = store
+    let state = store
         .db
         .beacon_state_provider()
-        .get(head_root)?
+        .get_shared(head_root)?
         .ok_or_else(|| anyhow!("No beacon state found for head root: {head_root}"))?;
 
     // [IGNORE] The voluntary exit is the first valid voluntary exit received for the validator with
@@ -1,5 +1,4 @@
 pub mod config;
 pub mod gossipsub;
-pub mod p2p_sender;
 pub mod req_resp;
 pub mod service;
@@ -2,7 +2,9 @@ use std::sync::Arc;
 
 use libp2p::{PeerId, swarm::ConnectionId};
 use ream_consensus_beacon::blob_sidecar::BlobIdentifier;
+use ream_network_spec::networks::beacon_network_spec;
 use ream_p2p::{
+    channel::P2PSender,
     network_state::NetworkState,
     req_resp::messages::{
         RequestMessage, ResponseMessage,
@@ -11,9 +13,17 @@ use ream_p2p::{
     },
 };
 use ream_storage::{db::ReamDB, tables::Table};
+use ream_syncer::verification::blob_retention_slot;
 use tracing::{info, trace, warn};
 
-use crate::p2p_sender::P2PSender;
+/// The current slot by the time stored in the database.
+fn current_slot(ream_db: &ReamDB) -> anyhow::Result<u64> {
+    Ok(ream_db
+        .time_provider()
+        .get()?
+        .saturating_sub(ream_db.genesis_time_provider().get()?)
+        / beacon_network_spec().seconds_per_slot)
+}
 
 pub async fn handle_req_resp_message(
     peer_id: PeerId,
@@ -48,6 +58,20 @@ pub async fn handle_req_resp_message(
             count,
             ..
         }) => {
+            // Blocks before the oldest one we have are outside of what we serve
+            if let Ok(Some(oldest_slot)) = ream_db.slot_index_provider().get_oldest_slot()
+                && start_slot < oldest_slot
+            {
+                trace!("Blocks from slot {start_slot} are before our oldest slot {oldest_slot}");
+                p2p_sender.send_resource_unavailable_response(
+                    peer_id,
+                    connection_id,
+                    stream_id,
+                    &format!("Blocks are only available from slot {oldest_slot}"),
+                );
+                return;
+            }
+
             for slot in start_slot..start_slot + count {
                 let Ok(Some(block_root)) = ream_db.slot_index_provider().get(slot) else {
                     trace!("No block root found for slot {slot}");
@@ -104,7 +128,46 @@ pub async fn handle_req_resp_message(
             p2p_sender.send_end_of_stream_response(peer_id, connection_id, stream_id);
         }
         RequestMessage::BlobSidecarsByRange(BlobSidecarsByRangeV1Request { start_slot, count }) => {
-            for slot in start_slot..start_slot + count {
+            // Blobs before the earliest available blob slot have been pruned
+            let earliest_blob_slot = match ream_db.earliest_available_blob_slot() {
+                Ok(earliest_blob_slot) => earliest_blob_slot,
+                Err(err) => {
+                    p2p_sender.send_error_response(
+                        peer_id,
+                        connection_id,
+                        stream_id,
+                        &format!("Failed to get earliest available blob slot: {err}"),
+                    );
+                    return;
+                }
+            };
+
+            // Peers may not ask for blobs older than the retention window, and we can't serve blobs
+            // within it which we haven't backfilled yet
+            let min_request_slot = match current_slot(ream_db) {
+                Ok(current_slot) => start_slot.max(blob_retention_slot(current_slot)),
+                Err(err) => {
+                    p2p_sender.send_error_response(
+                        peer_id,
+                        connection_id,
+                        stream_id,
+                        &format!("Failed to get the current slot: {err}"),
+                    );
+                    return;
+                }
+            };
+            if earliest_blob_slot > min_request_slot {
+                trace!("Blobs from slot {min_request_slot} are before our earliest blob slot");
+                p2p_sender.send_resource_unavailable_response(
+                    peer_id,
+                    connection_id,
+                    stream_id,
+                    &format!("Blobs are only available from slot {earliest_blob_slot}"),
+                );
+                return;
+            }
+
+            for slot in min_request_slot..start_slot + count {
                 let Ok(Some(block_root)) = ream_db.slot_index_provider().get(slot) else {
                     trace!("No block root found for slot {slot}");
                     p2p_sender.send_error_response(
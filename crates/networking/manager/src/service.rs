@@ -1,4 +1,5 @@
 use std::{
+    net::{IpAddr, SocketAddrV4, SocketAddrV6},
     path::PathBuf,
     sync::Arc,
     time::{Duration, SystemTime, UNIX_EPOCH},
@@ -14,28 +15,34 @@ use ream_executor::ReamExecutor;
 use ream_network_spec::networks::beacon_network_spec;
 use ream_operation_pool::OperationPool;
 use ream_p2p::{
+    channel::P2PSender,
     config::NetworkConfig,
     network::beacon::{Network, ReamNetworkEvent},
     network_state::NetworkState,
 };
 use ream_storage::{cache::CachedDB, db::ReamDB};
-use ream_syncer::block_range::BlockRangeSyncer;
+use ream_syncer::{
+    backfill::{BackfillConfig, BackfillSyncer},
+    block_range::BlockRangeSyncer,
+    lookup::LookupSync,
+};
 use tokio::{sync::mpsc, time::interval};
-use tracing::{error, info};
+use tracing::{error, info, trace};
 
 use crate::{
     config::ManagerConfig,
     gossipsub::handle::{handle_gossipsub_message, init_gossipsub_config_with_topics},
-    p2p_sender::P2PSender,
     req_resp::handle_req_resp_message,
 };
 
 pub struct NetworkManagerService {
     pub beacon_chain: Arc<BeaconChain>,
     manager_receiver: mpsc::UnboundedReceiver<ReamNetworkEvent>,
-    p2p_sender: P2PSender,
+    pub p2p_sender: P2PSender,
     pub network_state: Arc<NetworkState>,
     pub block_range_syncer: BlockRangeSyncer,
+    pub backfill_syncer: BackfillSyncer,
+    pub lookup_sync: LookupSync,
     pub ream_db: ReamDB,
     pub cached_db: CachedDB,
 }
@@ -61,11 +68,22 @@ impl NetworkManagerService {
         ream_dir: PathBuf,
         operation_pool: Arc<OperationPool>,
     ) -> anyhow::Result<Self> {
-        let discv5_config = discv5::ConfigBuilder::new(discv5::ListenConfig::from_ip(
-            config.socket_address,
-            config.discovery_port,
-        ))
-        .build();
+        // Discovery listens on both IP versions when an extra IPv6 address is configured
+        let listen_config = match (config.socket_address, config.socket_address_v6) {
+            (IpAddr::V4(ip), Some(ip6)) => discv5::ListenConfig::from_two_sockets(
+                Some(SocketAddrV4::new(ip, config.discovery_port)),
+                Some(SocketAddrV6::new(ip6, config.discovery_port, 0, 0)),
+            ),
+            (socket_address, _) => {
+                discv5::ListenConfig::from_ip(socket_address, config.discovery_port)
+            }
+        };
+        let mut discv5_config = discv5::ConfigBuilder::new(listen_config);
+        // A pinned ENR address isn't replaced by the address peers see us on
+        if config.enr_address.is_some() {
+            discv5_config.disable_enr_update();
+        }
+        let discv5_config = discv5_config.build();
 
         let bootnodes = config
             .bootnodes
@@ -74,9 +92,13 @@ impl NetworkManagerService {
             discv5_config,
             bootnodes,
             socket_address: config.socket_address,
+            socket_address_v6: config.socket_address_v6,
+            enr_address: config.enr_address,
             socket_port: config.socket_port,
+            quic_port: config.quic_port,
             discovery_port: config.discovery_port,
             disable_discovery: config.disable_discovery,
+            disable_quic: config.disable_quic,
             attestation_subnets: AttestationSubnets::new(),
             sync_committee_subnets: SyncCommitteeSubnets::new(),
         };
@@ -87,10 +109,15 @@ impl NetworkManagerService {
             discv5_config,
             gossipsub_config,
             data_dir: ream_dir,
+            upnp: config.upnp,
+            rotate_node_key: config.rotate_node_key,
+            genesis_time: ream_db.genesis_time_provider().get()?,
+            disable_outbound_rate_limit: false,
         };
 
         let (manager_sender, manager_receiver) = mpsc::unbounded_channel();
         let (p2p_sender, p2p_receiver) = mpsc::unbounded_channel();
+        let p2p_sender = P2PSender(p2p_sender);
 
         let execution_engine = if let (Some(execution_endpoint), Some(jwt_path)) =
             (config.execution_endpoint, config.execution_jwt_secret)
@@ -121,14 +148,35 @@ impl NetworkManagerService {
             executor.clone(),
         );
 
+        let backfill_syncer = BackfillSyncer::new(
+            beacon_chain.clone(),
+            p2p_sender.clone(),
+            network_state.clone(),
+            executor.clone(),
+            BackfillConfig {
+                to_genesis: config.backfill_to_genesis,
+                verify_signatures: config.backfill_verify_signatures,
+            },
+        );
+
+        let lookup_sync = LookupSync::new(
+            beacon_chain.clone(),
+            ream_db.clone(),
+            p2p_sender.clone(),
+            network_state.clone(),
+            executor.clone(),
+        );
+
         let cached_db = CachedDB::new();
 
         Ok(Self {
             beacon_chain,
             manager_receiver,
-            p2p_sender: P2PSender(p2p_sender),
+            p2p_sender,
             network_state,
             block_range_syncer,
+            backfill_syncer,
+            lookup_sync,
             ream_db,
             cached_db,
         })
@@ -147,18 +195,27 @@ impl NetworkManagerService {
             cached_db,
             network_state,
             block_range_syncer,
+            backfill_syncer,
+            mut lookup_sync,
             ..
         } = self;
 
         let mut interval = interval(Duration::from_secs(beacon_network_spec().seconds_per_slot));
-        let mut syncer_handle = block_range_syncer.start();
+        let executor = block_range_syncer.executor.clone();
+        // The handle is taken once the syncer finishes, a finished task can't be polled again
+        let mut syncer_handle = Some(block_range_syncer.start());
+        let mut restart_syncer = false;
+        // Backfill starts once forward sync has caught up, so it doesn't take peers away from it
+        let mut backfill_syncer = Some(backfill_syncer);
         loop {
             tokio::select! {
-                result = &mut syncer_handle => {
+                result = async { syncer_handle.as_mut().expect("Block range syncer is running").await }, if syncer_handle.is_some() => {
+                    syncer_handle = None;
                     let joined_result = match result {
                         Ok(joined_result) => joined_result,
                         Err(err) => {
                             error!("Block range syncer failed to join task: {err}");
+                            restart_syncer = true;
                             continue;
                         }
                     };
@@ -167,6 +224,7 @@ impl NetworkManagerService {
                         Ok(result) => result,
                         Err(err) => {
                             error!("Block range syncer thread failed: {err}");
+                            restart_syncer = true;
                             continue;
                         }
                     };
@@ -175,12 +233,16 @@ impl NetworkManagerService {
                         Ok(syncer) => syncer,
                         Err(err) => {
                             error!("Block range syncer failed to start: {err}");
+                            restart_syncer = true;
                             continue;
                         }
                     };
 
                     if !block_range_syncer.is_synced_to_finalized_slot().await {
-                        syncer_handle = block_range_syncer.start();
+                        syncer_handle = Some(block_range_syncer.start());
+                    } else if let Some(backfill_syncer) = backfill_syncer.take() {
+                        info!("Forward sync reached the finalized slot, starting backfill sync");
+                        backfill_syncer.start();
                     }
                 }
                 _ = interval.tick() => {
@@ -192,18 +254,41 @@ impl NetworkManagerService {
                     if let Err(err) =  beacon_chain.process_tick(time).await {
                         error!("Failed to process gossipsub tick: {err}");
                     }
+
+                    // A failed syncer is replaced a slot later, so a persistent error doesn't spin
+                    if restart_syncer {
+                        restart_syncer = false;
+                        syncer_handle = Some(
+                            BlockRangeSyncer::new(
+                                beacon_chain.clone(),
+                                p2p_sender.clone(),
+                                network_state.clone(),
+                                executor.clone(),
+                            )
+                            .start(),
+                        );
+                    }
+                }
+                Some(lookup_result) = lookup_sync.recv() => {
+                    // Process the gossip messages which waited for the looked up blocks. They were
+                    // already ignored by gossipsub, so the validation results aren't reported.
+                    for message in lookup_sync.on_lookup_result(lookup_result).await {
+                        handle_gossipsub_message(message, &beacon_chain, &cached_db, &mut lookup_sync).await;
+                    }
                 }
                 Some(event) = manager_receiver.recv() => {
                     match event {
                         // Handles Gossipsub messages from other peers.
-                        ReamNetworkEvent::GossipsubMessage { message } =>
-                            handle_gossipsub_message(message, &beacon_chain, &cached_db, &p2p_sender).await,
+                        ReamNetworkEvent::GossipsubMessage { message, message_id, propagation_source } => {
+                            let validation_result = handle_gossipsub_message(message, &beacon_chain, &cached_db, &mut lookup_sync).await;
+                            p2p_sender.report_validation_result(message_id, propagation_source, validation_result.acceptance());
+                        }
                         // Handles Req/Resp messages from other peers.
                         ReamNetworkEvent::RequestMessage { peer_id, stream_id, connection_id, message } =>
                             handle_req_resp_message(peer_id, stream_id, connection_id, message, &p2p_sender, &ream_db, network_state.clone()).await,
-                        // Log and skip unrecognized requests.
-                        unhandled_request => {
-                            info!("Unhandled request: {unhandled_request:?}");
+                        // Peer lifecycle, validation and request outcomes are only traced here
+                        unhandled_event => {
+                            trace!("Unhandled network event: {unhandled_event:?}");
                         }
                     }
                 }
@@ -1,14 +1,23 @@
 use alloy_primitives::B256;
-use libp2p::{PeerId, swarm::ConnectionId};
+use anyhow::anyhow;
+use libp2p::{
+    PeerId,
+    gossipsub::{MessageAcceptance, MessageId},
+    swarm::ConnectionId,
+};
 use ream_consensus_beacon::blob_sidecar::BlobIdentifier;
+use ream_discv5::eth2::EnrForkId;
 use tokio::sync::mpsc;
+use tracing::warn;
 
 use crate::{
     gossipsub::beacon::topics::GossipTopic,
     req_resp::{
+        error::ReqRespError,
         handler::RespMessage,
         messages::{ResponseMessage, status::Status},
     },
+    scoring::PeerAction,
 };
 
 pub enum P2PCallbackResponse {
@@ -18,10 +27,24 @@ pub enum P2PCallbackResponse {
     EndOfStream,
 }
 
-pub enum P2PMessage {
+/// The commands the beacon `Network` accepts from the rest of the node.
+pub enum NetworkCommand {
+    Publish(GossipMessage),
+    Subscribe(GossipTopic),
+    Unsubscribe(GossipTopic),
     Request(P2PRequest),
     Response(P2PResponse),
-    Gossip(GossipMessage),
+    GossipValidation(GossipValidationResult),
+    ReportPeer {
+        peer_id: PeerId,
+        action: PeerAction,
+        reason: String,
+    },
+    BanPeer {
+        peer_id: PeerId,
+        reason: String,
+    },
+    UpdateEnr(EnrUpdate),
 }
 
 pub enum P2PRequest {
@@ -45,6 +68,12 @@ pub enum P2PRequest {
         blob_identifiers: Vec<BlobIdentifier>,
         callback: mpsc::Sender<anyhow::Result<P2PCallbackResponse>>,
     },
+    BlobRange {
+        peer_id: PeerId,
+        start: u64,
+        count: u64,
+        callback: mpsc::Sender<anyhow::Result<P2PCallbackResponse>>,
+    },
 }
 
 pub struct P2PResponse {
@@ -59,3 +88,172 @@ pub struct GossipMessage {
     pub topic: GossipTopic,
     pub data: Vec<u8>,
 }
+
+/// The outcome of validating a gossip message, which decides whether gossipsub forwards it and
+/// how it scores the peer that sent it.
+pub struct GossipValidationResult {
+    pub message_id: MessageId,
+    pub propagation_source: PeerId,
+    pub acceptance: MessageAcceptance,
+}
+
+/// The ENR fields other components can change. The subnet fields follow the subnet service, so they
+/// aren't set directly.
+pub enum EnrUpdate {
+    /// Sets the `eth2` field, e.g. when a fork activates
+    ForkId(EnrForkId),
+}
+
+/// A handle sending commands to the beacon `Network`, shared by every component driving the
+/// swarm.
+#[derive(Clone)]
+pub struct P2PSender(pub mpsc::UnboundedSender<NetworkCommand>);
+
+impl P2PSender {
+    fn send(&self, command: NetworkCommand, description: &str) {
+        if let Err(err) = self.0.send(command) {
+            warn!("Failed to send {description}: {err}");
+        }
+    }
+
+    pub fn publish(&self, message: GossipMessage) {
+        self.send(NetworkCommand::Publish(message), "gossip message");
+    }
+
+    pub fn subscribe(&self, topic: GossipTopic) {
+        self.send(NetworkCommand::Subscribe(topic), "topic subscription");
+    }
+
+    pub fn unsubscribe(&self, topic: GossipTopic) {
+        self.send(NetworkCommand::Unsubscribe(topic), "topic unsubscription");
+    }
+
+    /// Sends a request to a peer. Fails if the network is gone, as the callback would never be
+    /// answered then.
+    pub fn send_request(&self, request: P2PRequest) -> anyhow::Result<()> {
+        self.0
+            .send(NetworkCommand::Request(request))
+            .map_err(|err| anyhow!("Failed to send P2P request: {err}"))
+    }
+
+    pub fn send_response(
+        &self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        stream_id: u64,
+        message: ResponseMessage,
+    ) {
+        self.send(
+            NetworkCommand::Response(P2PResponse {
+                peer_id,
+                connection_id,
+                stream_id,
+                message: Box::new(RespMessage::Response(Box::new(message))),
+            }),
+            "P2P response",
+        );
+    }
+
+    pub fn send_end_of_stream_response(
+        &self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        stream_id: u64,
+    ) {
+        self.send(
+            NetworkCommand::Response(P2PResponse {
+                peer_id,
+                connection_id,
+                stream_id,
+                message: Box::new(RespMessage::EndOfStream),
+            }),
+            "end of stream response",
+        );
+    }
+
+    pub fn send_error_response(
+        &self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        stream_id: u64,
+        error: &str,
+    ) {
+        self.send(
+            NetworkCommand::Response(P2PResponse {
+                peer_id,
+                connection_id,
+                stream_id,
+                message: Box::new(RespMessage::Error(ReqRespError::Anyhow(anyhow!(
+                    error.to_string()
+                )))),
+            }),
+            "error response",
+        );
+    }
+
+    /// Answers a request for data we don't have or no longer keep, which peers don't penalize us
+    /// for.
+    pub fn send_resource_unavailable_response(
+        &self,
+        peer_id: PeerId,
+        connection_id: ConnectionId,
+        stream_id: u64,
+        reason: &str,
+    ) {
+        self.send(
+            NetworkCommand::Response(P2PResponse {
+                peer_id,
+                connection_id,
+                stream_id,
+                message: Box::new(RespMessage::Error(ReqRespError::ResourceUnavailable(
+                    reason.to_string(),
+                ))),
+            }),
+            "resource unavailable response",
+        );
+    }
+
+    /// Reports the result of validating a gossip message, so gossipsub forwards accepted messages
+    /// and penalizes the peers which sent rejected ones.
+    pub fn report_validation_result(
+        &self,
+        message_id: MessageId,
+        propagation_source: PeerId,
+        acceptance: MessageAcceptance,
+    ) {
+        self.send(
+            NetworkCommand::GossipValidation(GossipValidationResult {
+                message_id,
+                propagation_source,
+                acceptance,
+            }),
+            "gossip validation result",
+        );
+    }
+
+    /// Penalizes a peer, which is banned once its score drops below the ban threshold.
+    pub fn report_peer(&self, peer_id: PeerId, action: PeerAction, reason: &str) {
+        self.send(
+            NetworkCommand::ReportPeer {
+                peer_id,
+                action,
+                reason: reason.to_string(),
+            },
+            "peer report",
+        );
+    }
+
+    pub fn ban_peer(&self, peer_id: PeerId, reason: &str) {
+        self.send(
+            NetworkCommand::BanPeer {
+                peer_id,
+                reason: reason.to_string(),
+            },
+            "peer ban",
+        );
+    }
+
+    pub fn update_enr(&self, update: EnrUpdate) {
+        self.send(NetworkCommand::UpdateEnr(update), "ENR update");
+    }
+}
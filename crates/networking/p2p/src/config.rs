@@ -10,4 +10,17 @@ pub struct NetworkConfig {
     pub gossipsub_config: GossipsubConfig,
 
     pub data_dir: PathBuf,
+
+    /// Whether to map the P2P ports on the router with UPnP
+    pub upnp: bool,
+
+    /// Whether to replace the node key in `data_dir` with a new one, changing the peer ID
+    pub rotate_node_key: bool,
+
+    /// Used to derive the current slot, which decides the subnets to subscribe to
+    pub genesis_time: u64,
+
+    /// Whether to send requests over the quota of peers instead of delaying them. Only set by
+    /// simulations testing the rate limiter of the remote peer.
+    pub disable_outbound_rate_limit: bool,
 }
@@ -5,7 +5,14 @@ use alloy_primitives::{aliases::B32, fixed_bytes};
 /// The maximum allowed size of uncompressed payload in gossipsub messages and RPC chunks
 pub const MAX_PAYLOAD_SIZE: u64 = 10485760;
 pub const MESSAGE_DOMAIN_VALID_SNAPPY: B32 = fixed_bytes!("0x01000000");
-pub const MESSAGE_DOMAIN_INVALID_SNAPPY: B32 = fixed_bytes!("0x00000000");
 
+pub const PEER_SCORE_UPDATE_INTERVAL: Duration = Duration::from_secs(10);
 pub const PING_INTERVAL_DURATION: Duration = Duration::from_secs(300);
 pub const TARGET_PEER_COUNT: usize = 50;
+pub const KNOWN_PEERS_SAVE_INTERVAL: Duration = Duration::from_secs(300);
+/// Peers are searched for on subnets with fewer peers than this
+pub const MIN_PEERS_PER_SUBNET: usize = 3;
+/// The number of gossip message-ids remembered after gossipsub's duplicate cache forgets them
+pub const SEEN_MESSAGE_IDS_CAPACITY: usize = 65536;
+/// The number of recent gossip messages remembered as failing snappy decompression
+pub const INVALID_SNAPPY_MESSAGES_CAPACITY: usize = 1024;
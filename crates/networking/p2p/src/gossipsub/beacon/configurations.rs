@@ -1,22 +1,26 @@
 use std::time::Duration;
 
-use libp2p::gossipsub::{Config, ConfigBuilder, MessageId, ValidationMode};
+use libp2p::gossipsub::{Config, ConfigBuilder, MessageId, TopicHash, ValidationMode};
 use ream_consensus_misc::constants::beacon::SLOTS_PER_EPOCH;
 use ream_network_spec::networks::beacon_network_spec;
 use sha2::{Digest, Sha256};
 
 use super::topics::GossipTopic;
-use crate::{constants::MESSAGE_DOMAIN_VALID_SNAPPY, utils::max_message_size};
+use crate::{gossipsub::snappy::InvalidSnappyMessages, utils::max_message_size};
 
 #[derive(Debug, Clone)]
 pub struct GossipsubConfig {
     pub config: Config,
     pub topics: Vec<GossipTopic>,
+    /// Shared with the `SnappyTransform`, which records the messages it failed to decompress
+    pub invalid_snappy_messages: InvalidSnappyMessages,
 }
 
 impl Default for GossipsubConfig {
     // https://ethereum.github.io/consensus-specs/specs/phase0/p2p-interface/#the-gossip-domain-gossipsub
     fn default() -> Self {
+        let invalid_snappy_messages = InvalidSnappyMessages::default();
+        let message_id_snappy_messages = invalid_snappy_messages.clone();
         let config = ConfigBuilder::default()
             .max_transmit_size(max_message_size() as usize)
             .heartbeat_interval(Duration::from_millis(700))
@@ -36,17 +40,13 @@ impl Default for GossipsubConfig {
             .allow_self_origin(true)
             .flood_publish(false)
             .idontwant_message_size_threshold(1000)
+            // Gossipsub computes the message-id after `SnappyTransform` decompressed the message,
+            // messages which fail to decompress are passed on with their raw data
             .message_id_fn(move |message| {
-                MessageId::from(
-                    &Sha256::digest({
-                        let topic_bytes = message.topic.as_str().as_bytes();
-                        let mut digest = vec![];
-                        digest.extend_from_slice(MESSAGE_DOMAIN_VALID_SNAPPY.as_slice());
-                        digest.extend_from_slice(&topic_bytes.len().to_le_bytes());
-                        digest.extend_from_slice(topic_bytes);
-                        digest.extend_from_slice(&message.data);
-                        digest
-                    })[..20],
+                compute_message_id(
+                    &message.topic,
+                    &message.data,
+                    !message_id_snappy_messages.contains(&message.data),
                 )
             })
             .build()
@@ -55,12 +55,63 @@ impl Default for GossipsubConfig {
         Self {
             config,
             topics: vec![],
+            invalid_snappy_messages,
         }
     }
 }
 
+/// The message-id of a gossip message: messages with a valid snappy compression are identified by
+/// their decompressed data, the others by their raw data.
+///
+/// https://ethereum.github.io/consensus-specs/specs/altair/p2p-interface/#topics-and-messages
+pub fn compute_message_id(topic: &TopicHash, data: &[u8], valid_snappy: bool) -> MessageId {
+    let message_domain = if valid_snappy {
+        beacon_network_spec().message_domain_valid_snappy
+    } else {
+        beacon_network_spec().message_domain_invalid_snappy
+    };
+    let topic_bytes = topic.as_str().as_bytes();
+
+    let mut hasher = Sha256::new();
+    hasher.update(message_domain.as_slice());
+    hasher.update((topic_bytes.len() as u64).to_le_bytes());
+    hasher.update(topic_bytes);
+    hasher.update(data);
+    MessageId::from(&hasher.finalize()[..20])
+}
+
 impl GossipsubConfig {
     pub fn set_topics(&mut self, topics: Vec<GossipTopic>) {
         self.topics = topics;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use libp2p::gossipsub::{MessageId, TopicHash};
+    use ream_network_spec::networks::initialize_test_network_spec;
+    use sha2::{Digest, Sha256};
+
+    use super::compute_message_id;
+
+    #[test]
+    fn test_compute_message_id() {
+        initialize_test_network_spec();
+        let topic = TopicHash::from_raw("/eth2/00000000/beacon_block/ssz_snappy");
+        let data = [1, 2, 3];
+
+        let mut preimage = vec![1, 0, 0, 0];
+        preimage.extend_from_slice(&(topic.as_str().len() as u64).to_le_bytes());
+        preimage.extend_from_slice(topic.as_str().as_bytes());
+        preimage.extend_from_slice(&data);
+        assert_eq!(
+            compute_message_id(&topic, &data, true),
+            MessageId::from(&Sha256::digest(&preimage)[..20])
+        );
+
+        assert_ne!(
+            compute_message_id(&topic, &data, true),
+            compute_message_id(&topic, &data, false)
+        );
+    }
+}
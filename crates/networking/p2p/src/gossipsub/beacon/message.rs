@@ -11,8 +11,8 @@ use ream_light_client::{
 };
 use ream_network_spec::networks::beacon_network_spec;
 use ream_validator_beacon::{
-    aggregate_and_proof::AggregateAndProof, contribution_and_proof::SignedContributionAndProof,
-    sync_committee::SyncCommitteeMessage,
+    aggregate_and_proof::SignedAggregateAndProof,
+    contribution_and_proof::SignedContributionAndProof, sync_committee::SyncCommitteeMessage,
 };
 use ssz::Decode;
 
@@ -24,7 +24,7 @@ pub enum GossipsubMessage {
     BeaconBlock(Box<SignedBeaconBlock>),
     AttesterSlashing(Box<AttesterSlashing>),
     ProposerSlashing(Box<ProposerSlashing>),
-    AggregateAndProof(Box<AggregateAndProof>),
+    AggregateAndProof(Box<SignedAggregateAndProof>),
     BlobSidecar(Box<BlobSidecar>),
     BeaconAttestation((Box<SingleAttestation>, u64)),
     SyncCommittee((Box<SyncCommitteeMessage>, u64)),
@@ -59,7 +59,7 @@ impl GossipsubMessage {
                 )))
             }
             GossipTopicKind::AggregateAndProof => Ok(Self::AggregateAndProof(Box::new(
-                AggregateAndProof::from_ssz_bytes(data)?,
+                SignedAggregateAndProof::from_ssz_bytes(data)?,
             ))),
             GossipTopicKind::BeaconAttestation(subnet_id) => Ok(Self::BeaconAttestation((
                 Box::new(SingleAttestation::from_ssz_bytes(data)?),
@@ -89,3 +89,77 @@ impl GossipsubMessage {
         }
     }
 }
+
+/// The max uncompressed size of a message on `topic`: the SSZ size of the topic's type if it is
+/// fixed, otherwise `MAX_PAYLOAD_SIZE`. Used to drop oversized messages before decompressing them.
+pub fn max_message_size_for_topic(topic: &TopicHash) -> usize {
+    let Ok(gossip_topic) = GossipTopic::from_topic_hash(topic) else {
+        return beacon_network_spec().max_payload_size as usize;
+    };
+
+    match gossip_topic.kind {
+        GossipTopicKind::BeaconBlock => max_ssz_len::<SignedBeaconBlock>(),
+        GossipTopicKind::SyncCommittee(_) => max_ssz_len::<SyncCommitteeMessage>(),
+        GossipTopicKind::SyncCommitteeContributionAndProof => {
+            max_ssz_len::<SignedContributionAndProof>()
+        }
+        GossipTopicKind::AggregateAndProof => max_ssz_len::<SignedAggregateAndProof>(),
+        GossipTopicKind::BeaconAttestation(_) => max_ssz_len::<SingleAttestation>(),
+        GossipTopicKind::BlsToExecutionChange => max_ssz_len::<SignedBLSToExecutionChange>(),
+        GossipTopicKind::AttesterSlashing => max_ssz_len::<AttesterSlashing>(),
+        GossipTopicKind::ProposerSlashing => max_ssz_len::<ProposerSlashing>(),
+        GossipTopicKind::BlobSidecar(_) => max_ssz_len::<BlobSidecar>(),
+        GossipTopicKind::LightClientFinalityUpdate => max_ssz_len::<LightClientFinalityUpdate>(),
+        GossipTopicKind::LightClientOptimisticUpdate => {
+            max_ssz_len::<LightClientOptimisticUpdate>()
+        }
+        GossipTopicKind::VoluntaryExit => max_ssz_len::<SignedVoluntaryExit>(),
+    }
+}
+
+fn max_ssz_len<T: Decode>() -> usize {
+    let max_payload_size = beacon_network_spec().max_payload_size as usize;
+    if T::is_ssz_fixed_len() {
+        T::ssz_fixed_len().min(max_payload_size)
+    } else {
+        max_payload_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::aliases::B32;
+    use libp2p::gossipsub::TopicHash;
+    use ream_consensus_beacon::single_attestation::SingleAttestation;
+    use ream_network_spec::networks::{beacon_network_spec, initialize_test_network_spec};
+    use ssz::Decode;
+
+    use super::max_message_size_for_topic;
+    use crate::gossipsub::beacon::topics::{GossipTopic, GossipTopicKind};
+
+    #[test]
+    fn test_max_message_size_for_topic() {
+        initialize_test_network_spec();
+        let fork = B32::ZERO;
+
+        let attestation_topic: TopicHash = GossipTopic {
+            fork,
+            kind: GossipTopicKind::BeaconAttestation(1),
+        }
+        .into();
+        assert_eq!(
+            max_message_size_for_topic(&attestation_topic),
+            SingleAttestation::ssz_fixed_len()
+        );
+
+        let block_topic: TopicHash = GossipTopic {
+            fork,
+            kind: GossipTopicKind::BeaconBlock,
+        }
+        .into();
+        assert_eq!(
+            max_message_size_for_topic(&block_topic),
+            beacon_network_spec().max_payload_size as usize
+        );
+    }
+}
@@ -0,0 +1,139 @@
+use std::{collections::HashMap, time::Duration};
+
+use libp2p::gossipsub::{PeerScoreParams, PeerScoreThresholds, TopicHash, TopicScoreParams};
+use ream_consensus_misc::constants::beacon::SLOTS_PER_EPOCH;
+use ream_network_spec::networks::beacon_network_spec;
+use ream_validator_beacon::constants::{ATTESTATION_SUBNET_COUNT, SYNC_COMMITTEE_SUBNET_COUNT};
+
+use super::topics::{GossipTopic, GossipTopicKind};
+
+/// Below this score we stop gossiping to a peer
+pub const GOSSIP_THRESHOLD: f64 = -4000.0;
+/// Below this score we don't publish to a peer
+pub const PUBLISH_THRESHOLD: f64 = -8000.0;
+/// Below this score all messages from a peer are ignored
+pub const GRAYLIST_THRESHOLD: f64 = -16000.0;
+
+/// Number of invalid messages on a topic which graylist a peer
+const INVALID_MESSAGES_BEFORE_GRAYLIST: f64 = 10.0;
+
+/// Counters below this value are reset to zero
+const DECAY_TO_ZERO: f64 = 0.01;
+
+/// Gossipsub peer-score parameters for the subscribed `topics`.
+///
+/// Peers are rewarded for time in the mesh and for delivering messages first, and penalized for
+/// invalid messages. Mesh delivery rates vary too much between topics to penalize peers for them.
+pub fn peer_score_params(topics: &[GossipTopic]) -> PeerScoreParams {
+    let decay_interval = slot_duration();
+    PeerScoreParams {
+        topics: topics
+            .iter()
+            .map(|topic| (TopicHash::from(*topic), topic_score_params(&topic.kind)))
+            .collect::<HashMap<_, _>>(),
+        topic_score_cap: 50.0,
+        // The application score is kept outside of gossipsub
+        app_specific_weight: 0.0,
+        ip_colocation_factor_weight: -50.0,
+        ip_colocation_factor_threshold: 8.0,
+        behaviour_penalty_weight: -15.0,
+        behaviour_penalty_threshold: 6.0,
+        behaviour_penalty_decay: score_decay(10 * epoch_duration(), decay_interval),
+        decay_interval,
+        decay_to_zero: DECAY_TO_ZERO,
+        retain_score: 100 * epoch_duration(),
+        ..Default::default()
+    }
+}
+
+pub fn peer_score_thresholds() -> PeerScoreThresholds {
+    PeerScoreThresholds {
+        gossip_threshold: GOSSIP_THRESHOLD,
+        publish_threshold: PUBLISH_THRESHOLD,
+        graylist_threshold: GRAYLIST_THRESHOLD,
+        accept_px_threshold: 100.0,
+        opportunistic_graft_threshold: 5.0,
+    }
+}
+
+/// Gossipsub score parameters of a topic, also used for topics subscribed to after startup.
+pub fn topic_score_params(kind: &GossipTopicKind) -> TopicScoreParams {
+    let decay_interval = slot_duration();
+    // Topics sharing the load, like subnets, share the weight
+    let topic_weight = match kind {
+        GossipTopicKind::BeaconBlock => 0.5,
+        GossipTopicKind::AggregateAndProof => 0.5,
+        GossipTopicKind::BeaconAttestation(_) => 1.0 / ATTESTATION_SUBNET_COUNT as f64,
+        GossipTopicKind::SyncCommittee(_) => 0.4 / SYNC_COMMITTEE_SUBNET_COUNT as f64,
+        GossipTopicKind::SyncCommitteeContributionAndProof => 0.4,
+        GossipTopicKind::BlobSidecar(_) => {
+            0.5 / beacon_network_spec().blob_sidecar_subnet_count_electra as f64
+        }
+        GossipTopicKind::VoluntaryExit
+        | GossipTopicKind::ProposerSlashing
+        | GossipTopicKind::AttesterSlashing
+        | GossipTopicKind::BlsToExecutionChange
+        | GossipTopicKind::LightClientFinalityUpdate
+        | GossipTopicKind::LightClientOptimisticUpdate => 0.05,
+    };
+
+    TopicScoreParams {
+        topic_weight,
+        time_in_mesh_weight: 0.03,
+        time_in_mesh_quantum: decay_interval,
+        time_in_mesh_cap: 300.0,
+        first_message_deliveries_weight: 1.0,
+        first_message_deliveries_decay: score_decay(20 * epoch_duration(), decay_interval),
+        first_message_deliveries_cap: 100.0,
+        mesh_message_deliveries_weight: 0.0,
+        mesh_failure_penalty_weight: 0.0,
+        // The penalty grows with the square of the count, so this many invalid messages graylist
+        // the peer regardless of the topic weight
+        invalid_message_deliveries_weight: GRAYLIST_THRESHOLD
+            / (topic_weight * INVALID_MESSAGES_BEFORE_GRAYLIST.powi(2)),
+        invalid_message_deliveries_decay: score_decay(50 * epoch_duration(), decay_interval),
+        ..Default::default()
+    }
+}
+
+/// The decay factor which brings a counter to `decay_to_zero` after `decay_time`.
+fn score_decay(decay_time: Duration, decay_interval: Duration) -> f64 {
+    let ticks = decay_time.as_secs_f64() / decay_interval.as_secs_f64();
+    DECAY_TO_ZERO.powf(1.0 / ticks)
+}
+
+fn slot_duration() -> Duration {
+    Duration::from_secs(beacon_network_spec().seconds_per_slot)
+}
+
+fn epoch_duration() -> Duration {
+    slot_duration() * SLOTS_PER_EPOCH as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::aliases::B32;
+    use ream_network_spec::networks::initialize_test_network_spec;
+
+    use super::{peer_score_params, peer_score_thresholds};
+    use crate::gossipsub::beacon::topics::{GossipTopic, GossipTopicKind};
+
+    #[test]
+    fn test_peer_score_params_are_valid() {
+        initialize_test_network_spec();
+
+        let topics = [
+            GossipTopicKind::BeaconBlock,
+            GossipTopicKind::BeaconAttestation(3),
+            GossipTopicKind::BlobSidecar(0),
+            GossipTopicKind::VoluntaryExit,
+        ]
+        .map(|kind| GossipTopic {
+            fork: B32::ZERO,
+            kind,
+        });
+
+        assert!(peer_score_params(&topics).validate().is_ok());
+        assert!(peer_score_thresholds().validate().is_ok());
+    }
+}
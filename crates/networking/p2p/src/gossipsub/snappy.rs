@@ -1,34 +1,107 @@
+use std::{num::NonZeroUsize, sync::Arc};
+
 use libp2p::gossipsub::{DataTransform, Message, RawMessage, TopicHash};
+use lru::LruCache;
+use parking_lot::Mutex;
+use sha2::{Digest, Sha256};
 use snap::raw::{Decoder, Encoder, decompress_len};
 
+use crate::constants::INVALID_SNAPPY_MESSAGES_CAPACITY;
+
+/// Recent messages which failed to decompress and were passed on with their raw data, so their
+/// message-id can be computed over the raw data.
+#[derive(Debug, Clone)]
+pub struct InvalidSnappyMessages(Arc<Mutex<LruCache<[u8; 32], ()>>>);
+
+impl Default for InvalidSnappyMessages {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(LruCache::new(
+            NonZeroUsize::new(INVALID_SNAPPY_MESSAGES_CAPACITY)
+                .expect("Invalid snappy messages capacity is zero"),
+        ))))
+    }
+}
+
+impl InvalidSnappyMessages {
+    fn insert(&self, data: &[u8]) {
+        self.0.lock().put(Sha256::digest(data).into(), ());
+    }
+
+    /// Returns true if `data` is the raw data of a message which failed to decompress.
+    pub fn contains(&self, data: &[u8]) -> bool {
+        let invalid_snappy_messages = self.0.lock();
+        !invalid_snappy_messages.is_empty()
+            && invalid_snappy_messages.contains(&Sha256::digest(data).into())
+    }
+}
+
 pub struct SnappyTransform {
     max_size_per_message: usize,
+    /// The max decompressed size of messages per topic, checked before decompressing
+    max_size_per_topic: Option<fn(&TopicHash) -> usize>,
+    /// Where messages which fail to decompress are recorded before being passed on with their
+    /// raw data, instead of being dropped
+    invalid_snappy_messages: Option<InvalidSnappyMessages>,
 }
 
 impl SnappyTransform {
     pub fn new(max_size_per_message: usize) -> Self {
         SnappyTransform {
             max_size_per_message,
+            max_size_per_topic: None,
+            invalid_snappy_messages: None,
         }
     }
-}
 
-impl DataTransform for SnappyTransform {
-    fn inbound_transform(&self, raw_message: RawMessage) -> Result<Message, std::io::Error> {
-        let len = decompress_len(&raw_message.data)?;
+    pub fn with_max_size_per_topic(mut self, max_size_per_topic: fn(&TopicHash) -> usize) -> Self {
+        self.max_size_per_topic = Some(max_size_per_topic);
+        self
+    }
 
-        if len > self.max_size_per_message {
-            return Err(std::io::Error::new(
-                std::io::ErrorKind::InvalidData,
-                format!(
-                    "Message size ({}) exceeds max gossip size per message ({})",
-                    len, self.max_size_per_message
-                ),
-            ));
+    pub fn with_invalid_snappy_messages(
+        mut self,
+        invalid_snappy_messages: InvalidSnappyMessages,
+    ) -> Self {
+        self.invalid_snappy_messages = Some(invalid_snappy_messages);
+        self
+    }
+
+    fn max_size(&self, topic: &TopicHash) -> usize {
+        match self.max_size_per_topic {
+            Some(max_size_per_topic) => max_size_per_topic(topic).min(self.max_size_per_message),
+            None => self.max_size_per_message,
         }
+    }
+}
 
-        let mut decoder = Decoder::new();
-        let data = decoder.decompress_vec(&raw_message.data)?;
+impl DataTransform for SnappyTransform {
+    fn inbound_transform(&self, raw_message: RawMessage) -> Result<Message, std::io::Error> {
+        // The decompressed length is read from the snappy header, so oversized messages are
+        // dropped before anything is allocated for them
+        let data = match decompress_len(&raw_message.data) {
+            Ok(len) => {
+                let max_size = self.max_size(&raw_message.topic);
+                if len > max_size {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!(
+                            "Message size ({len}) exceeds max gossip size for the topic ({max_size})"
+                        ),
+                    ));
+                }
+                Decoder::new().decompress_vec(&raw_message.data)
+            }
+            Err(err) => Err(err),
+        };
+        let data = match (data, &self.invalid_snappy_messages) {
+            (Ok(data), _) => data,
+            // The raw data fails validation, which rejects the message under its own message-id
+            (Err(_), Some(invalid_snappy_messages)) => {
+                invalid_snappy_messages.insert(&raw_message.data);
+                raw_message.data
+            }
+            (Err(err), None) => return Err(err.into()),
+        };
 
         Ok(Message {
             source: raw_message.source,
@@ -60,3 +133,53 @@ impl DataTransform for SnappyTransform {
         Ok(raw_message)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use libp2p::gossipsub::{DataTransform, RawMessage, TopicHash};
+
+    use super::{InvalidSnappyMessages, SnappyTransform};
+
+    fn raw_message(data: Vec<u8>) -> RawMessage {
+        RawMessage {
+            source: None,
+            data,
+            sequence_number: None,
+            topic: TopicHash::from_raw("/eth2/00000000/voluntary_exit/ssz_snappy"),
+            signature: None,
+            key: None,
+            validated: false,
+        }
+    }
+
+    #[test]
+    fn test_invalid_snappy_is_passed_on_raw() {
+        let invalid_snappy_messages = InvalidSnappyMessages::default();
+        let transform = SnappyTransform::new(1024)
+            .with_invalid_snappy_messages(invalid_snappy_messages.clone());
+
+        let data = vec![1, 2, 3];
+        let compressed = transform
+            .outbound_transform(&TopicHash::from_raw("topic"), data.clone())
+            .unwrap();
+        let message = transform
+            .inbound_transform(raw_message(compressed))
+            .unwrap();
+        assert_eq!(message.data, data);
+        assert!(!invalid_snappy_messages.contains(&message.data));
+
+        let invalid = vec![0xff; 8];
+        let message = transform
+            .inbound_transform(raw_message(invalid.clone()))
+            .unwrap();
+        assert_eq!(message.data, invalid);
+        assert!(invalid_snappy_messages.contains(&message.data));
+
+        // Without the record, messages which fail to decompress are dropped
+        assert!(
+            SnappyTransform::new(1024)
+                .inbound_transform(raw_message(invalid))
+                .is_err()
+        );
+    }
+}
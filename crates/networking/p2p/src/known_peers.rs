@@ -0,0 +1,104 @@
+//! Peers remembered across restarts, stored in the data directory. At startup the good peers we
+//! were connected to are dialed right away and the discv5 routing table is restored, so the node
+//! doesn't start cold from the bootnodes.
+
+use std::{fs, path::Path};
+
+use anyhow::anyhow;
+use discv5::Enr;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+pub const KNOWN_PEERS_FILE_NAME: &str = "known_peers.yaml";
+
+/// The most ENRs of each kind stored
+const MAX_KNOWN_PEERS: usize = 256;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct KnownPeersFile {
+    good_peers: Vec<String>,
+    routing_table: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct KnownPeers {
+    /// Peers we were connected to without a bad score
+    pub good_peers: Vec<Enr>,
+    /// The nodes in the discv5 routing table
+    pub routing_table: Vec<Enr>,
+}
+
+impl KnownPeers {
+    pub fn load(data_dir: &Path) -> anyhow::Result<Self> {
+        let path = data_dir.join(KNOWN_PEERS_FILE_NAME);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let file: KnownPeersFile = serde_yaml::from_str(&fs::read_to_string(path)?)
+            .map_err(|err| anyhow!("Failed to decode known peers: {err:?}"))?;
+        Ok(Self {
+            good_peers: decode_enrs(file.good_peers),
+            routing_table: decode_enrs(file.routing_table),
+        })
+    }
+
+    pub fn save(&self, data_dir: &Path) -> anyhow::Result<()> {
+        let file = KnownPeersFile {
+            good_peers: encode_enrs(&self.good_peers),
+            routing_table: encode_enrs(&self.routing_table),
+        };
+        let yaml = serde_yaml::to_string(&file)
+            .map_err(|err| anyhow!("Failed to encode known peers: {err:?}"))?;
+        fs::write(data_dir.join(KNOWN_PEERS_FILE_NAME), yaml)
+            .map_err(|err| anyhow!("Failed to write known peers to disk: {err:?}"))
+    }
+}
+
+fn encode_enrs(enrs: &[Enr]) -> Vec<String> {
+    enrs.iter()
+        .take(MAX_KNOWN_PEERS)
+        .map(Enr::to_base64)
+        .collect()
+}
+
+fn decode_enrs(enrs: Vec<String>) -> Vec<Enr> {
+    enrs.into_iter()
+        .take(MAX_KNOWN_PEERS)
+        .filter_map(|enr| match enr.parse() {
+            Ok(enr) => Some(enr),
+            Err(err) => {
+                warn!("Skipping invalid known peer {enr}: {err}");
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use discv5::{Enr, enr::CombinedKey};
+    use tempdir::TempDir;
+
+    use super::KnownPeers;
+
+    #[test]
+    fn test_known_peers_are_persisted() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new("test_known_peers_are_persisted")?;
+        assert!(KnownPeers::load(tmp_dir.path())?.good_peers.is_empty());
+
+        let enr = Enr::builder()
+            .tcp4(9000)
+            .build(&CombinedKey::generate_secp256k1())?;
+        KnownPeers {
+            good_peers: vec![enr.clone()],
+            routing_table: vec![],
+        }
+        .save(tmp_dir.path())?;
+
+        let known_peers = KnownPeers::load(tmp_dir.path())?;
+        assert_eq!(known_peers.good_peers, vec![enr]);
+        assert!(known_peers.routing_table.is_empty());
+        Ok(())
+    }
+}
@@ -3,8 +3,12 @@ pub mod channel;
 pub mod config;
 pub mod constants;
 pub mod gossipsub;
+pub mod known_peers;
 pub mod network;
 pub mod network_state;
 pub mod peer;
+pub mod peer_manager;
 pub mod req_resp;
+pub mod scoring;
+pub mod subnet_service;
 pub mod utils;
@@ -1,11 +1,13 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{HashMap, HashSet, VecDeque},
     fmt::Debug,
+    net::IpAddr,
     num::{NonZeroU8, NonZeroUsize},
     sync::Arc,
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
+use alloy_primitives::B256;
 use anyhow::anyhow;
 use delay_map::{HashMapDelay, HashSetDelay};
 use discv5::Enr;
@@ -14,31 +16,59 @@ use libp2p::{
     connection_limits::{self, ConnectionLimits},
     core::ConnectedPoint,
     futures::StreamExt,
-    gossipsub::{Event as GossipsubEvent, IdentTopic as Topic, Message, MessageAuthenticity},
+    gossipsub::{
+        Event as GossipsubEvent, IdentTopic as Topic, Message, MessageAcceptance,
+        MessageAuthenticity, MessageId, TopicHash,
+    },
     identify,
     multiaddr::Protocol,
-    swarm::{self, ConnectionId, NetworkBehaviour, SwarmEvent},
+    swarm::{
+        self, ConnectionId, NetworkBehaviour, SwarmEvent, behaviour::toggle::Toggle,
+        dial_opts::DialOpts,
+    },
+    upnp,
 };
-use libp2p_identity::{Keypair, PublicKey, secp256k1};
+use libp2p_identity::{Keypair, PublicKey};
+use lru::LruCache;
 use parking_lot::{Mutex, RwLock};
 use ream_consensus_misc::constants::beacon::genesis_validators_root;
-use ream_discv5::discovery::{Discovery, DiscoveryOutEvent, QueryType};
+use ream_discv5::{
+    discovery::{Discovery, DiscoveryOutEvent, QueryType},
+    enr_ext::EnrExt,
+    subnet::{AttestationSubnets, SyncCommitteeSubnets},
+};
 use ream_executor::ReamExecutor;
 use ream_network_spec::networks::beacon_network_spec;
 use tokio::{
     sync::mpsc::{self, UnboundedReceiver, UnboundedSender},
-    time::interval,
+    time::{interval, interval_at},
 };
 use tracing::{error, info, trace, warn};
 
 use crate::{
-    channel::{P2PCallbackResponse, P2PMessage, P2PRequest, P2PResponse},
+    channel::{
+        EnrUpdate, GossipValidationResult, NetworkCommand, P2PCallbackResponse, P2PRequest,
+        P2PResponse,
+    },
     config::NetworkConfig,
-    constants::{PING_INTERVAL_DURATION, TARGET_PEER_COUNT},
-    gossipsub::{GossipsubBehaviour, beacon::topics::GossipTopic, snappy::SnappyTransform},
+    constants::{
+        KNOWN_PEERS_SAVE_INTERVAL, MIN_PEERS_PER_SUBNET, PEER_SCORE_UPDATE_INTERVAL,
+        PING_INTERVAL_DURATION, SEEN_MESSAGE_IDS_CAPACITY, TARGET_PEER_COUNT,
+    },
+    gossipsub::{
+        GossipsubBehaviour,
+        beacon::{
+            message::max_message_size_for_topic,
+            scoring::{peer_score_params, peer_score_thresholds, topic_score_params},
+            topics::{GossipTopic, GossipTopicKind},
+        },
+        snappy::{InvalidSnappyMessages, SnappyTransform},
+    },
+    known_peers::KnownPeers,
     network::misc::{Executor, build_transport, peer_id_from_enr},
     network_state::NetworkState,
     peer::{CachedPeer, ConnectionState, Direction},
+    peer_manager::{PeerManager, record_peer_metrics},
     req_resp::{
         ReqResp, ReqRespMessage,
         configurations::REQUEST_TIMEOUT,
@@ -46,13 +76,15 @@ use crate::{
         messages::{
             RequestMessage, ResponseMessage,
             beacon_blocks::{BeaconBlocksByRangeV2Request, BeaconBlocksByRootV2Request},
-            blob_sidecars::BlobSidecarsByRootV1Request,
+            blob_sidecars::{BlobSidecarsByRangeV1Request, BlobSidecarsByRootV1Request},
             meta_data::GetMetaDataV2,
             ping::Ping,
             status::Status,
         },
     },
-    utils::read_meta_data_from_disk,
+    scoring::{BAN_DURATION, BannedPeers, PeerAction, ScoreState},
+    subnet_service::{RequiredSubnets, SubnetService},
+    utils::{load_node_key, read_enr_from_disk, read_meta_data_from_disk, write_enr_to_disk},
 };
 
 #[derive(NetworkBehaviour)]
@@ -69,15 +101,25 @@ pub(crate) struct ReamBehaviour {
     pub gossipsub: GossipsubBehaviour,
 
     pub connection_registry: connection_limits::Behaviour,
+
+    /// Maps our ports on the router, if enabled
+    pub upnp: Toggle<upnp::tokio::Behaviour>,
 }
 
-// TODO: these are stub events which needs to be replaced
+/// The events the beacon `Network` sends to the rest of the node.
 #[derive(Debug)]
 pub enum ReamNetworkEvent {
-    PeerConnectedIncoming(PeerId),
-    PeerConnectedOutgoing(PeerId),
+    PeerConnected {
+        peer_id: PeerId,
+        direction: Direction,
+    },
     PeerDisconnected(PeerId),
-    DisconnectPeer(PeerId),
+    /// A peer on our chain sent its status, making it usable for syncing
+    PeerStatus {
+        peer_id: PeerId,
+        status: Status,
+    },
+    PeerBanned(PeerId),
     RequestMessage {
         peer_id: PeerId,
         stream_id: u64,
@@ -86,6 +128,19 @@ pub enum ReamNetworkEvent {
     },
     GossipsubMessage {
         message: Message,
+        message_id: MessageId,
+        propagation_source: PeerId,
+    },
+    /// A gossip message was validated, and forwarded by gossipsub if accepted
+    GossipValidated {
+        message_id: MessageId,
+        propagation_source: PeerId,
+        acceptance: MessageAcceptance,
+    },
+    /// A request to or from a peer failed
+    RequestFailed {
+        peer_id: PeerId,
+        error: ReqRespMessageError,
     },
 }
 
@@ -97,6 +152,23 @@ pub struct Network {
     request_id: u64,
     network_state: Arc<NetworkState>,
     peers_to_ping: HashSetDelay<PeerId>,
+    genesis_time: u64,
+    /// The subnets in the local ENR and metadata, `None` until first advertised
+    advertised_subnets: Option<(AttestationSubnets, SyncCommitteeSubnets)>,
+    peer_manager: PeerManager,
+    /// The subnets needed at the current slot, kept to prune peers which don't serve them
+    required_subnets: RequiredSubnets,
+    /// Events raised while handling another event or command, sent after it is handled
+    pending_events: VecDeque<ReamNetworkEvent>,
+    /// Whether we listen on and dial QUIC addresses besides TCP ones
+    quic_support: bool,
+    /// Whether the ENR address was set by the user, so it is not replaced by UPnP mappings
+    enr_address_pinned: bool,
+    /// The ids of gossip messages already handed to the node. Gossipsub forgets message-ids after
+    /// its duplicate cache time, so replayed messages are ignored here before they are decoded
+    seen_message_ids: LruCache<MessageId, ()>,
+    /// Messages which failed to decompress, they reach us with their raw data to be rejected
+    invalid_snappy_messages: InvalidSnappyMessages,
 }
 
 impl Network {
@@ -114,24 +186,56 @@ impl Network {
         config: &NetworkConfig,
         status: Status,
     ) -> anyhow::Result<Self> {
-        let local_key = secp256k1::Keypair::generate();
+        let local_key = load_node_key(&config.data_dir, config.rotate_node_key)?;
+        let previous_enr_seq = read_enr_from_disk(&config.data_dir)
+            .unwrap_or_else(|err| {
+                error!("Failed to read ENR from disk: {err:?}");
+                None
+            })
+            .map_or(0, |enr| enr.seq());
 
-        let mut discovery =
-            Discovery::new(Keypair::from(local_key.clone()), &config.discv5_config).await?;
+        let mut discovery = Discovery::new(
+            Keypair::from(local_key.clone()),
+            &config.discv5_config,
+            previous_enr_seq,
+        )
+        .await?;
+        let known_peers = KnownPeers::load(&config.data_dir).unwrap_or_else(|err| {
+            error!("Failed to read known peers from disk: {err:?}");
+            KnownPeers::default()
+        });
+        for enr in known_peers.routing_table {
+            discovery.add_enr(enr);
+        }
         discovery.discover_peers(QueryType::Peers, 16);
 
-        let req_resp = ReqResp::new();
+        let req_resp = if config.disable_outbound_rate_limit {
+            ReqResp::new().without_outbound_rate_limit()
+        } else {
+            ReqResp::new()
+        };
 
         let gossipsub = {
             let snappy_transform =
-                SnappyTransform::new(config.gossipsub_config.config.max_transmit_size());
-            GossipsubBehaviour::new_with_transform(
+                SnappyTransform::new(config.gossipsub_config.config.max_transmit_size())
+                    .with_max_size_per_topic(max_message_size_for_topic)
+                    .with_invalid_snappy_messages(
+                        config.gossipsub_config.invalid_snappy_messages.clone(),
+                    );
+            let mut gossipsub = GossipsubBehaviour::new_with_transform(
                 MessageAuthenticity::Anonymous,
                 config.gossipsub_config.config.clone(),
                 None,
                 snappy_transform,
             )
-            .map_err(|err| anyhow!("Failed to create gossipsub behaviour: {err:?}"))?
+            .map_err(|err| anyhow!("Failed to create gossipsub behaviour: {err:?}"))?;
+            gossipsub
+                .with_peer_score(
+                    peer_score_params(&config.gossipsub_config.topics),
+                    peer_score_thresholds(),
+                )
+                .map_err(|err| anyhow!("Failed to set gossipsub peer score: {err}"))?;
+            gossipsub
         };
 
         let connection_limits = {
@@ -156,6 +260,9 @@ impl Network {
         };
 
         let local_enr = discovery.local_enr();
+        if let Err(err) = write_enr_to_disk(&config.data_dir, &local_enr) {
+            warn!("{err}");
+        }
         let behaviour = {
             ReamBehaviour {
                 discovery,
@@ -163,10 +270,12 @@ impl Network {
                 gossipsub,
                 identify,
                 connection_registry: connection_limits,
+                upnp: Toggle::from(config.upnp.then(upnp::tokio::Behaviour::default)),
             }
         };
 
-        let transport = build_transport(Keypair::from(local_key.clone()))
+        let quic_support = !config.discv5_config.disable_quic;
+        let transport = build_transport(Keypair::from(local_key.clone()), quic_support)
             .map_err(|err| anyhow!("Failed to build transport: {err:?}"))?;
 
         let swarm = {
@@ -187,6 +296,7 @@ impl Network {
                 .build()
         };
 
+        let node_id = B256::from(local_enr.node_id().raw());
         let network_state = Arc::new(NetworkState {
             local_enr: RwLock::new(local_enr),
             peer_table: RwLock::new(HashMap::new()),
@@ -197,6 +307,14 @@ impl Network {
                 }),
             ),
             status: RwLock::new(status),
+            backfill_progress: RwLock::new(None),
+            banned_peers: RwLock::new(BannedPeers::load(config.data_dir.clone()).unwrap_or_else(
+                |err| {
+                    error!("Failed to read banned peers from disk: {err:?}");
+                    BannedPeers::default()
+                },
+            )),
+            subnet_service: RwLock::new(SubnetService::new(node_id)),
             data_dir: config.data_dir.clone(),
         });
 
@@ -208,34 +326,72 @@ impl Network {
             request_id: 0,
             network_state,
             peers_to_ping: HashSetDelay::new(PING_INTERVAL_DURATION),
+            genesis_time: config.genesis_time,
+            advertised_subnets: None,
+            peer_manager: PeerManager::new(TARGET_PEER_COUNT),
+            required_subnets: RequiredSubnets::default(),
+            pending_events: VecDeque::new(),
+            quic_support,
+            enr_address_pinned: config.discv5_config.enr_address.is_some(),
+            seen_message_ids: LruCache::new(
+                NonZeroUsize::new(SEEN_MESSAGE_IDS_CAPACITY).expect("Invalid cache size"),
+            ),
+            invalid_snappy_messages: config.gossipsub_config.invalid_snappy_messages.clone(),
         };
 
-        network.start_network_worker(config).await?;
+        network
+            .start_network_worker(config, known_peers.good_peers)
+            .await?;
 
         Ok(network)
     }
 
-    async fn start_network_worker(&mut self, config: &NetworkConfig) -> anyhow::Result<()> {
+    async fn start_network_worker(
+        &mut self,
+        config: &NetworkConfig,
+        known_good_peers: Vec<Enr>,
+    ) -> anyhow::Result<()> {
         info!("Libp2p starting .... ");
 
-        let mut multi_addr: Multiaddr = config.discv5_config.socket_address.into();
-        multi_addr.push(Protocol::Tcp(config.discv5_config.socket_port));
-
-        match self.swarm.listen_on(multi_addr.clone()) {
-            Ok(listener_id) => {
-                info!(
-                    "Listening on {:?} with peer_id {:?} {listener_id:?}",
-                    multi_addr, self.peer_id
-                );
+        let discv5_config = &config.discv5_config;
+        let mut socket_addresses = vec![discv5_config.socket_address];
+        socket_addresses.extend(discv5_config.socket_address_v6.map(IpAddr::V6));
+        let mut multi_addrs = vec![];
+        for socket_address in socket_addresses {
+            let mut tcp_multi_addr: Multiaddr = socket_address.into();
+            tcp_multi_addr.push(Protocol::Tcp(discv5_config.socket_port));
+            multi_addrs.push(tcp_multi_addr);
+            if self.quic_support {
+                let mut quic_multi_addr: Multiaddr = socket_address.into();
+                quic_multi_addr.push(Protocol::Udp(discv5_config.quic_port));
+                quic_multi_addr.push(Protocol::QuicV1);
+                multi_addrs.push(quic_multi_addr);
             }
-            Err(err) => {
-                error!("Failed to start libp2p peer listen on {multi_addr:?}, error: {err:?}",);
+        }
+
+        for multi_addr in multi_addrs {
+            match self.swarm.listen_on(multi_addr.clone()) {
+                Ok(listener_id) => {
+                    info!(
+                        "Listening on {:?} with peer_id {:?} {listener_id:?}",
+                        multi_addr, self.peer_id
+                    );
+                }
+                Err(err) => {
+                    error!("Failed to start libp2p peer listen on {multi_addr:?}, error: {err:?}",);
+                }
             }
         }
 
+        // The peers from the previous run are dialed along with the bootnodes
         let mut bootnodes = HashMap::new();
-        for bootnode in config.discv5_config.bootnodes.clone() {
-            bootnodes.insert(bootnode, None);
+        for bootnode in config
+            .discv5_config
+            .bootnodes
+            .iter()
+            .chain(&known_good_peers)
+        {
+            bootnodes.insert(bootnode.clone(), None);
         }
         self.handle_discovered_peers(bootnodes);
 
@@ -279,19 +435,29 @@ impl Network {
     /// Starts monitoring for network events. The network worker awaits for different types
     /// of network events:
     /// - A swarm event
-    /// - A p2p message
+    /// - A command from the rest of the node
     /// - A peer pinging
     /// - An interval tick to perform p2p maintenance e.g. peer pinging, peer clean up and peer
     ///   discovery
+    /// - An interval tick to update peer scores, disconnecting and banning misbehaving peers
+    /// - A slot tick to update the attestation and sync committee subnet subscriptions
     ///
     /// The network worker will then route each event to the appropriate handler. The handlers are
     /// defined in `NetworkManagerService`.
     pub async fn start(
         mut self,
         manager_sender: UnboundedSender<ReamNetworkEvent>,
-        mut p2p_receiver: UnboundedReceiver<P2PMessage>,
+        mut p2p_receiver: UnboundedReceiver<NetworkCommand>,
     ) {
         let mut status_interval = interval(Duration::from_secs(30));
+        let mut peer_score_interval = interval(PEER_SCORE_UPDATE_INTERVAL);
+        let mut subnet_interval =
+            interval(Duration::from_secs(beacon_network_spec().seconds_per_slot));
+        // Skips the first tick, as there is nothing new to save at startup
+        let mut known_peers_interval = interval_at(
+            tokio::time::Instant::now() + KNOWN_PEERS_SAVE_INTERVAL,
+            KNOWN_PEERS_SAVE_INTERVAL,
+        );
         loop {
             tokio::select! {
                 Some(event) = self.swarm.next() => {
@@ -299,9 +465,9 @@ impl Network {
                         warn!("Failed to send event: {err:?}");
                     }
                 }
-                Some(event) = p2p_receiver.recv() => {
-                    match event {
-                        P2PMessage::Request(request) => match request {
+                Some(command) = p2p_receiver.recv() => {
+                    match command {
+                        NetworkCommand::Request(request) => match request {
                             P2PRequest::BlockRange { peer_id, start, count, callback } => {
                                 if let Some(request_id) = self.send_request(peer_id, RequestMessage::BeaconBlocksByRange(BeaconBlocksByRangeV2Request::new(start, count))) {
                                     self.callbacks.insert(request_id, callback);
@@ -323,18 +489,51 @@ impl Network {
                                     warn!("Failed to send error response: {err:?}");
                                 }
                             }
+                            P2PRequest::BlobRange { peer_id, start, count, callback } => {
+                                if let Some(request_id) = self.send_request(peer_id, RequestMessage::BlobSidecarsByRange(BlobSidecarsByRangeV1Request::new(start, count))) {
+                                    self.callbacks.insert(request_id, callback);
+                                } else if let Err(err) = callback.send(Ok(P2PCallbackResponse::Disconnected)).await {
+                                    warn!("Failed to send error response: {err:?}");
+                                }
+                            }
                             P2PRequest::Status { peer_id, status } => {
                                 self.send_request(peer_id, RequestMessage::Status(status));
                             }
                         },
-                        P2PMessage::Response(P2PResponse {peer_id, connection_id, stream_id, message}) => {
+                        NetworkCommand::Response(P2PResponse {peer_id, connection_id, stream_id, message}) => {
                             self.swarm.behaviour_mut().req_resp.send_response(peer_id, connection_id, stream_id, *message)
                         },
-                        P2PMessage::Gossip(message) => {
+                        NetworkCommand::Publish(message) => {
                             if let Err(err) = self.swarm.behaviour_mut().gossipsub.publish(message.topic, message.data) {
                                 warn!("Failed to publish gossip message: {err}");
                             }
                         }
+                        NetworkCommand::Subscribe(topic) => {
+                            if self.subscribe_to_topic(topic) {
+                                info!("Subscribed to topic: {topic}");
+                            } else {
+                                error!("Failed to subscribe to topic: {topic}");
+                            }
+                        }
+                        NetworkCommand::Unsubscribe(topic) => {
+                            if self.unsubscribe_from_topic(topic) {
+                                info!("Unsubscribed from topic: {topic}");
+                            }
+                        }
+                        NetworkCommand::GossipValidation(GossipValidationResult { message_id, propagation_source, acceptance }) => {
+                            // Rejected messages are penalized by the gossipsub score, which is
+                            // folded into the peer score
+                            self.swarm.behaviour_mut().gossipsub.report_message_validation_result(&message_id, &propagation_source, acceptance.clone());
+                            self.pending_events.push_back(ReamNetworkEvent::GossipValidated { message_id, propagation_source, acceptance });
+                        }
+                        NetworkCommand::ReportPeer { peer_id, action, reason } => {
+                            self.report_peer(peer_id, action, &reason);
+                        }
+                        NetworkCommand::BanPeer { peer_id, reason } => {
+                            warn!("Banning peer {peer_id}: {reason}");
+                            self.ban_peer(peer_id);
+                        }
+                        NetworkCommand::UpdateEnr(update) => self.update_enr(update),
                     }
                 }
                 Some(Ok(peer_id)) = self.peers_to_ping.next() => {
@@ -353,6 +552,15 @@ impl Network {
                         warn!("Failed to send timeout response: {err:?}");
                     }
                 }
+                _ = peer_score_interval.tick() => {
+                    self.update_peer_scores();
+                }
+                _ = subnet_interval.tick() => {
+                    self.update_subnets();
+                }
+                _ = known_peers_interval.tick() => {
+                    self.save_known_peers();
+                }
                 _ = status_interval.tick() => {
                     let now = Instant::now();
                     let mut peer_table = self.network_state.peer_table.write();
@@ -375,22 +583,273 @@ impl Network {
                         }
                     }
 
-                    let peer_count = peer_table.len();
+                    drop(peer_table);
                     let peers_to_ping_count = self.peers_to_ping.len();
                     let seq_number = self.network_state.meta_data.read().seq_number;
 
                     info!("Peer statuses: {counts:?}, Peers with Status {status_is_some_count}, Peers with MetaData {meta_data_some_count}, Peers to ping: {peers_to_ping_count}, MetaData seq_number: {seq_number}");
 
-                    if peer_count < TARGET_PEER_COUNT {
-                        info!("Peer count is below target: {peer_count}, discovering more peers");
-                        self.swarm
-                            .behaviour_mut()
-                            .discovery
-                            .discover_peers(QueryType::Peers, 16);
+                    self.manage_peers();
+                }
+            }
+
+            while let Some(event) = self.pending_events.pop_front() {
+                if let Err(err) = manager_sender.send(event) {
+                    warn!("Failed to send event: {err:?}");
+                }
+            }
+        }
+    }
+
+    /// Disconnects peers down to the target once above the max peer count, or discovers new ones
+    /// when below the target.
+    fn manage_peers(&mut self) {
+        let connected_peers = self.network_state.connected_peers();
+        record_peer_metrics(&connected_peers);
+
+        for peer_id in self
+            .peer_manager
+            .peers_to_prune(&connected_peers, &self.required_subnets)
+        {
+            trace!("Pruning peer {peer_id} above the target peer count");
+            let _ = self.swarm.disconnect_peer_id(peer_id);
+        }
+
+        let peers_to_discover = self.peer_manager.peers_to_discover(&connected_peers);
+        if peers_to_discover > 0 {
+            info!(
+                "Peer count is below target: {}, discovering more peers",
+                connected_peers.len()
+            );
+            self.swarm
+                .behaviour_mut()
+                .discovery
+                .discover_peers(QueryType::Peers, peers_to_discover);
+        }
+    }
+
+    /// Penalizes a peer, banning it right away if its score drops below the ban threshold.
+    fn report_peer(&mut self, peer_id: PeerId, action: PeerAction, reason: &str) {
+        self.network_state.report_peer(peer_id, action, reason);
+        if self
+            .cached_peer(&peer_id)
+            .is_some_and(|peer| peer.score.state() == ScoreState::Banned)
+        {
+            self.ban_peer(peer_id);
+        }
+    }
+
+    fn ban_peer(&mut self, peer_id: PeerId) {
+        warn!("Banning peer {peer_id} for {BAN_DURATION:?}");
+        self.network_state
+            .banned_peers
+            .write()
+            .ban(peer_id, BAN_DURATION);
+        self.network_state.peer_table.write().remove(&peer_id);
+        self.peers_to_ping.remove(&peer_id);
+        let _ = self.swarm.disconnect_peer_id(peer_id);
+        self.pending_events
+            .push_back(ReamNetworkEvent::PeerBanned(peer_id));
+    }
+
+    fn update_enr(&mut self, update: EnrUpdate) {
+        let discovery = &mut self.swarm.behaviour_mut().discovery;
+        let result = match update {
+            EnrUpdate::ForkId(enr_fork_id) => discovery.update_fork_id(&enr_fork_id),
+        };
+        match result {
+            Ok(enr) => self.network_state.set_local_enr(enr),
+            Err(err) => error!("Failed to update the local ENR: {err:?}"),
+        }
+    }
+
+    /// Folds the gossipsub scores into the peer scores and decays them, then disconnects or bans
+    /// the peers whose score dropped below the thresholds.
+    fn update_peer_scores(&mut self) {
+        let mut peers_to_disconnect = vec![];
+        let mut peers_to_ban = vec![];
+        for (peer_id, cached_peer) in self.network_state.peer_table.write().iter_mut() {
+            if let Some(gossipsub_score) = self.swarm.behaviour().gossipsub.peer_score(peer_id) {
+                cached_peer.score.update_gossipsub_score(gossipsub_score);
+            }
+            cached_peer.score.decay();
+
+            match cached_peer.score.state() {
+                ScoreState::Healthy => {}
+                ScoreState::Disconnected => {
+                    if cached_peer.state == ConnectionState::Connected {
+                        peers_to_disconnect.push(*peer_id);
                     }
                 }
+                ScoreState::Banned => peers_to_ban.push(*peer_id),
             }
         }
+
+        for peer_id in peers_to_disconnect {
+            info!("Disconnecting peer {peer_id} with a low score");
+            let _ = self.swarm.disconnect_peer_id(peer_id);
+        }
+        for peer_id in peers_to_ban {
+            self.ban_peer(peer_id);
+        }
+        for peer_id in self.network_state.banned_peers.write().remove_expired() {
+            info!("Ban of peer {peer_id} expired");
+        }
+    }
+
+    fn current_slot(&self) -> u64 {
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("correct time")
+            .as_secs();
+        time.saturating_sub(self.genesis_time) / beacon_network_spec().seconds_per_slot
+    }
+
+    /// Subscribes to the subnets needed at the current slot and unsubscribes from the rest, then
+    /// advertises the long-lived subnets and searches for peers on subnets with too few of them.
+    fn update_subnets(&mut self) {
+        let required_subnets = self
+            .network_state
+            .subnet_service
+            .write()
+            .required_subnets(self.current_slot());
+
+        let fork = beacon_network_spec().fork_digest(genesis_validators_root());
+        let required_topics = required_subnets
+            .attestation_subnets()
+            .into_iter()
+            .map(GossipTopicKind::BeaconAttestation)
+            .chain(
+                required_subnets
+                    .sync_committee_subnets
+                    .iter()
+                    .copied()
+                    .map(GossipTopicKind::SyncCommittee),
+            )
+            .map(|kind| GossipTopic { fork, kind })
+            .collect::<HashSet<_>>();
+        let subscribed_topics = self
+            .subscribed_topics
+            .lock()
+            .iter()
+            .filter(|topic| {
+                matches!(
+                    topic.kind,
+                    GossipTopicKind::BeaconAttestation(_) | GossipTopicKind::SyncCommittee(_)
+                )
+            })
+            .copied()
+            .collect::<HashSet<_>>();
+
+        for topic in required_topics.difference(&subscribed_topics) {
+            if self.subscribe_to_topic(*topic) {
+                info!("Subscribed to subnet topic: {topic}");
+            } else {
+                error!("Failed to subscribe to subnet topic: {topic}");
+            }
+        }
+        for topic in subscribed_topics.difference(&required_topics) {
+            if self.unsubscribe_from_topic(*topic) {
+                info!("Unsubscribed from subnet topic: {topic}");
+            }
+        }
+
+        self.advertise_subnets(&required_subnets);
+        self.discover_subnet_peers(&required_topics);
+        self.required_subnets = required_subnets;
+    }
+
+    /// Updates the subnets in the local ENR and metadata, bumping the metadata sequence number if
+    /// they changed.
+    fn advertise_subnets(&mut self, required_subnets: &RequiredSubnets) {
+        let mut attestation_subnets = AttestationSubnets::new();
+        for subnet_id in &required_subnets.long_lived_attestation_subnets {
+            attestation_subnets
+                .enable_attestation_subnet(*subnet_id as u8)
+                .expect("Subnet ID is less than the attestation subnet count");
+        }
+        let mut sync_committee_subnets = SyncCommitteeSubnets::new();
+        for subnet_id in &required_subnets.sync_committee_subnets {
+            let result = u8::try_from(*subnet_id)
+                .map_err(anyhow::Error::from)
+                .and_then(|subnet_id| {
+                    sync_committee_subnets.enable_sync_committee_subnet(subnet_id)
+                });
+            if let Err(err) = result {
+                warn!("Not advertising sync committee subnet {subnet_id}: {err}");
+            }
+        }
+
+        let subnets = (attestation_subnets, sync_committee_subnets);
+        if self.advertised_subnets.as_ref() == Some(&subnets) {
+            return;
+        }
+
+        match self
+            .swarm
+            .behaviour_mut()
+            .discovery
+            .update_subnets(&subnets.0, &subnets.1)
+        {
+            Ok(enr) => self.network_state.set_local_enr(enr),
+            Err(err) => warn!("Failed to advertise subnets in ENR: {err:?}"),
+        }
+
+        let meta_data_changed = {
+            let mut meta_data = self.network_state.meta_data.write();
+            let changed = meta_data.attnets != subnets.0.0 || meta_data.syncnets != subnets.1.0;
+            if changed {
+                meta_data.seq_number += 1;
+                meta_data.attnets = subnets.0.0.clone();
+                meta_data.syncnets = subnets.1.0.clone();
+            }
+            changed
+        };
+        if meta_data_changed && let Err(err) = self.network_state.write_meta_data_to_disk() {
+            warn!("Failed to write meta data to disk: {err:?}");
+        }
+
+        self.advertised_subnets = Some(subnets);
+    }
+
+    fn discover_subnet_peers(&mut self, required_topics: &HashSet<GossipTopic>) {
+        let mut peer_counts = HashMap::<TopicHash, usize>::new();
+        for (_, topics) in self.swarm.behaviour().gossipsub.all_peers() {
+            for topic in topics {
+                *peer_counts.entry(topic.clone()).or_default() += 1;
+            }
+        }
+
+        let mut attestation_subnets = vec![];
+        let mut sync_committee_subnets = vec![];
+        for topic in required_topics {
+            if peer_counts
+                .get(&TopicHash::from(*topic))
+                .is_some_and(|count| *count >= MIN_PEERS_PER_SUBNET)
+            {
+                continue;
+            }
+            match topic.kind {
+                GossipTopicKind::BeaconAttestation(subnet_id) => {
+                    attestation_subnets.push(subnet_id as u8)
+                }
+                GossipTopicKind::SyncCommittee(subnet_id) => {
+                    sync_committee_subnets.push(subnet_id as u8)
+                }
+                _ => {}
+            }
+        }
+
+        // Only one query runs at a time, attestation subnets are searched first
+        let discovery = &mut self.swarm.behaviour_mut().discovery;
+        if !attestation_subnets.is_empty() {
+            discovery.discover_peers(QueryType::AttestationSubnetPeers(attestation_subnets), 16);
+        } else if !sync_committee_subnets.is_empty() {
+            discovery.discover_peers(
+                QueryType::SyncCommitteeSubnetPeers(sync_committee_subnets),
+                16,
+            );
+        }
     }
 
     fn send_request(&mut self, peer_id: PeerId, message: RequestMessage) -> Option<u64> {
@@ -430,7 +889,43 @@ impl Network {
             SwarmEvent::ConnectionEstablished {
                 peer_id, endpoint, ..
             } => {
+                if self.network_state.is_banned(&peer_id) {
+                    trace!("Refusing connection from banned peer {peer_id}");
+                    let _ = self.swarm.disconnect_peer_id(peer_id);
+                    return None;
+                }
+
+                // Peers disconnected for a low score may reconnect once it has decayed
+                if self
+                    .cached_peer(&peer_id)
+                    .is_some_and(|peer| peer.score.state() != ScoreState::Healthy)
+                {
+                    trace!("Refusing connection from peer {peer_id} with a low score");
+                    let _ = self.swarm.disconnect_peer_id(peer_id);
+                    return None;
+                }
+
                 if let ConnectedPoint::Listener { send_back_addr, .. } = &endpoint {
+                    let active_peers = self
+                        .network_state
+                        .peer_table
+                        .read()
+                        .values()
+                        .filter(|peer| {
+                            peer.peer_id != peer_id
+                                && matches!(
+                                    peer.state,
+                                    ConnectionState::Connected | ConnectionState::Connecting
+                                )
+                        })
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    if !self.peer_manager.accepts_inbound_peer(&active_peers) {
+                        trace!("Refusing inbound peer {peer_id}, no inbound peer slots left");
+                        let _ = self.swarm.disconnect_peer_id(peer_id);
+                        return None;
+                    }
+
                     self.network_state.upsert_peer(
                         peer_id,
                         Some(send_back_addr.clone()),
@@ -438,6 +933,10 @@ impl Network {
                         Direction::Inbound,
                         None,
                     );
+                    Some(ReamNetworkEvent::PeerConnected {
+                        peer_id,
+                        direction: Direction::Inbound,
+                    })
                 } else {
                     // send status request to the peer
                     let status_message =
@@ -447,9 +946,11 @@ impl Network {
                         self.network_state.meta_data.read().seq_number,
                     ));
                     self.send_request(peer_id, ping_message);
+                    Some(ReamNetworkEvent::PeerConnected {
+                        peer_id,
+                        direction: Direction::Outbound,
+                    })
                 }
-
-                None
             }
             SwarmEvent::ConnectionClosed {
                 peer_id,
@@ -467,6 +968,16 @@ impl Network {
                 }
             }
             SwarmEvent::Behaviour(behaviour_event) => match behaviour_event {
+                ReamBehaviourEvent::Identify(identify::Event::Received {
+                    peer_id, info, ..
+                }) => {
+                    if let Some(cached_peer) =
+                        self.network_state.peer_table.write().get_mut(&peer_id)
+                    {
+                        cached_peer.agent_version = Some(info.agent_version);
+                    }
+                    None
+                }
                 ReamBehaviourEvent::Identify(_) => None,
                 ReamBehaviourEvent::Discovery(discovery_event) => match discovery_event {
                     DiscoveryOutEvent::DiscoveredPeers { peers } => {
@@ -474,7 +985,7 @@ impl Network {
                         None
                     }
                     DiscoveryOutEvent::UpdatedEnr { enr } => {
-                        *self.network_state.local_enr.write() = enr;
+                        self.network_state.set_local_enr(enr);
                         None
                     }
                 },
@@ -482,6 +993,10 @@ impl Network {
                     self.handle_request_response_event(message).await
                 }
                 ReamBehaviourEvent::Gossipsub(event) => self.handle_gossipsub_event(event),
+                ReamBehaviourEvent::Upnp(event) => {
+                    self.handle_upnp_event(event);
+                    None
+                }
                 ream_behavior_event => {
                     info!("Unhandled behaviour event: {ream_behavior_event:?}");
                     None
@@ -494,48 +1009,90 @@ impl Network {
         }
     }
 
+    /// Stores the good peers we are connected to and the discv5 routing table, to start from them
+    /// after a restart.
+    fn save_known_peers(&self) {
+        let good_peers = self
+            .network_state
+            .connected_peers()
+            .into_iter()
+            .filter(|peer| peer.score.score() >= 0.0)
+            .filter_map(|peer| peer.enr)
+            .collect::<Vec<_>>();
+        let routing_table = self.swarm.behaviour().discovery.table_entries();
+        if good_peers.is_empty() && routing_table.is_empty() {
+            return;
+        }
+
+        let known_peers = KnownPeers {
+            good_peers,
+            routing_table,
+        };
+        if let Err(err) = known_peers.save(&self.network_state.data_dir) {
+            warn!("Failed to save known peers: {err:?}");
+        }
+    }
+
+    fn handle_upnp_event(&mut self, event: upnp::Event) {
+        match event {
+            upnp::Event::NewExternalAddr(address) => {
+                info!("UPnP mapped external address {address}");
+                if self.enr_address_pinned {
+                    return;
+                }
+                match self
+                    .swarm
+                    .behaviour_mut()
+                    .discovery
+                    .update_external_address(&address)
+                {
+                    Ok(enr) => self.network_state.set_local_enr(enr),
+                    Err(err) => warn!("Failed to advertise UPnP address {address}: {err:?}"),
+                }
+            }
+            upnp::Event::ExpiredExternalAddr(address) => {
+                info!("UPnP mapping of external address {address} expired");
+            }
+            upnp::Event::GatewayNotFound => info!("No UPnP gateway found, ports are not mapped"),
+            upnp::Event::NonRoutableGateway => {
+                warn!("UPnP gateway is not exposed to the public network, ports are not mapped")
+            }
+        }
+    }
+
     fn handle_discovered_peers(&mut self, peers: HashMap<Enr, Option<Instant>>) {
         trace!("Discovered peers: {peers:?}");
         for (enr, _) in peers {
-            let mut multiaddrs: Vec<Multiaddr> = Vec::new();
-            if let Some(ip) = enr.ip4()
-                && let Some(tcp) = enr.tcp4()
-            {
-                let mut multiaddr: Multiaddr = ip.into();
-                multiaddr.push(Protocol::Tcp(tcp));
-                multiaddrs.push(multiaddr);
-            }
-            if let Some(ip6) = enr.ip6()
-                && let Some(tcp6) = enr.tcp6()
-            {
-                let mut multiaddr: Multiaddr = ip6.into();
-                multiaddr.push(Protocol::Tcp(tcp6));
-                multiaddrs.push(multiaddr);
+            let Some(peer_id) = peer_id_from_enr(&enr) else {
+                trace!("Not dialing peer without a secp256k1 key: {enr:?}");
+                continue;
+            };
+            if self.network_state.is_banned(&peer_id) {
+                trace!("Not dialing banned peer {peer_id}");
+                continue;
             }
 
-            let mut successfully_dialed = false;
-            for multiaddr in multiaddrs {
-                if let Err(err) = self.swarm.dial(multiaddr) {
-                    warn!("Failed to dial peer: {err:?}");
-                } else {
-                    successfully_dialed = true;
-                }
+            // The addresses are tried one at a time, so the preferred transport is used if it works
+            let multiaddrs = enr.multiaddrs(self.quic_support);
+            if multiaddrs.is_empty() {
+                trace!("No multiaddr to dial for peer: {enr:?}");
+                continue;
             }
-
-            if !successfully_dialed {
-                trace!("Failed to dial any multiaddr for peer: {:?}", enr);
+            if let Err(err) = self
+                .swarm
+                .dial(DialOpts::peer_id(peer_id).addresses(multiaddrs).build())
+            {
+                warn!("Failed to dial peer {peer_id}: {err:?}");
                 continue;
             }
 
-            if let Some(peer_id) = peer_id_from_enr(&enr) {
-                self.network_state.upsert_peer(
-                    peer_id,
-                    None,
-                    ConnectionState::Connecting,
-                    Direction::Outbound,
-                    Some(enr.clone()),
-                );
-            }
+            self.network_state.upsert_peer(
+                peer_id,
+                None,
+                ConnectionState::Connecting,
+                Direction::Outbound,
+                Some(enr.clone()),
+            );
         }
     }
 
@@ -561,13 +1118,26 @@ impl Network {
         let message = match message {
             Ok(message) => message,
             Err(err) => {
+                let (ReqRespMessageError::Inbound {
+                    err: req_resp_err, ..
+                }
+                | ReqRespMessageError::Outbound {
+                    err: req_resp_err, ..
+                }) = &err;
+                if let Some(action) = req_resp_err.peer_action() {
+                    self.report_peer(peer_id, action, &format!("Req/resp error: {req_resp_err}"));
+                }
+
                 if let ReqRespMessageError::Outbound { request_id, .. } = &err
                     && let Some(callback) = self.callbacks.get(request_id)
                     && let Err(err) = callback.send(Err(anyhow!("{err:?}"))).await
                 {
                     warn!("Failed to send error response: {err:?}");
                 }
-                return None;
+                return Some(ReamNetworkEvent::RequestFailed {
+                    peer_id,
+                    error: err,
+                });
             }
         };
 
@@ -730,12 +1300,25 @@ impl Network {
         if self.network_state.peer_table.read().get(&peer_id).is_some() {
             // We only want to have peers on the same network as us
             let fork_digest = beacon_network_spec().fork_digest(genesis_validators_root());
+            let local_status = self.network_state.status.read().clone();
             if status.fork_digest != fork_digest {
                 warn!(
-                    "Peer {peer_id} is not on the same network as us, removing from peer table, fork_digest: {}, our fork_digest: {fork_digest}",
+                    "Peer {peer_id} is not on the same network as us, fork_digest: {}, our fork_digest: {fork_digest}",
                     status.fork_digest,
                 );
-                self.network_state.peer_table.write().remove(&peer_id);
+                self.report_peer(peer_id, PeerAction::Fatal, "Status on another fork");
+            } else if status.finalized_epoch == local_status.finalized_epoch
+                && status.finalized_root != local_status.finalized_root
+            {
+                warn!(
+                    "Peer {peer_id} finalized another chain, finalized_root: {}, our finalized_root: {}",
+                    status.finalized_root, local_status.finalized_root,
+                );
+                self.report_peer(
+                    peer_id,
+                    PeerAction::Fatal,
+                    "Status with another finalized root",
+                );
             } else {
                 self.network_state
                     .peer_table
@@ -743,9 +1326,11 @@ impl Network {
                     .entry(peer_id)
                     .and_modify(|cached_peer| {
                         cached_peer.state = ConnectionState::Connected;
-                        cached_peer.status = Some(status);
+                        cached_peer.status = Some(status.clone());
                     });
                 self.peers_to_ping.insert(peer_id);
+                self.pending_events
+                    .push_back(ReamNetworkEvent::PeerStatus { peer_id, status });
             }
         }
     }
@@ -753,10 +1338,42 @@ impl Network {
     fn handle_gossipsub_event(&mut self, event: GossipsubEvent) -> Option<ReamNetworkEvent> {
         match event {
             GossipsubEvent::Message {
-                propagation_source: _,
-                message_id: _,
+                propagation_source,
+                message_id,
                 message,
-            } => Some(ReamNetworkEvent::GossipsubMessage { message }),
+            } => {
+                if self.seen_message_ids.put(message_id.clone(), ()).is_some() {
+                    trace!("Ignoring already seen gossip message: {message_id}");
+                    self.swarm
+                        .behaviour_mut()
+                        .gossipsub
+                        .report_message_validation_result(
+                            &message_id,
+                            &propagation_source,
+                            MessageAcceptance::Ignore,
+                        );
+                    return None;
+                }
+
+                if self.invalid_snappy_messages.contains(&message.data) {
+                    trace!("Rejecting gossip message which failed to decompress: {message_id}");
+                    self.swarm
+                        .behaviour_mut()
+                        .gossipsub
+                        .report_message_validation_result(
+                            &message_id,
+                            &propagation_source,
+                            MessageAcceptance::Reject,
+                        );
+                    return None;
+                }
+
+                Some(ReamNetworkEvent::GossipsubMessage {
+                    message,
+                    message_id,
+                    propagation_source,
+                })
+            }
             GossipsubEvent::Subscribed { peer_id, topic } => {
                 trace!("Peer {peer_id} subscribed to topic: {topic:?}");
                 None
@@ -772,16 +1389,19 @@ impl Network {
     fn subscribe_to_topic(&mut self, topic: GossipTopic) -> bool {
         self.subscribed_topics.lock().insert(topic);
 
+        let score_params = topic_score_params(&topic.kind);
         let topic: Topic = topic.into();
 
-        self.swarm
-            .behaviour_mut()
-            .gossipsub
-            .subscribe(&topic)
-            .is_ok()
+        let gossipsub = &mut self.swarm.behaviour_mut().gossipsub;
+        if gossipsub.subscribe(&topic).is_err() {
+            return false;
+        }
+        if let Err(err) = gossipsub.set_topic_params(topic, score_params) {
+            warn!("Failed to set topic score params: {err}");
+        }
+        true
     }
 
-    #[allow(dead_code)]
     fn unsubscribe_from_topic(&mut self, topic: GossipTopic) -> bool {
         self.subscribed_topics.lock().remove(&topic);
 
@@ -834,9 +1454,14 @@ mod tests {
                 discv5_config,
                 bootnodes,
                 socket_address,
+                socket_address_v6: None,
+                enr_address: None,
                 socket_port,
+                // TCP and UDP ports don't clash, and discovery uses a different one
+                quic_port: socket_port,
                 discovery_port,
                 disable_discovery,
+                disable_quic: false,
                 attestation_subnets: AttestationSubnets::new(),
                 sync_committee_subnets: SyncCommitteeSubnets::new(),
             },
@@ -845,6 +1470,11 @@ mod tests {
                 ..Default::default()
             },
             data_dir: std::env::temp_dir().join("ream_network_test"),
+            upnp: false,
+            // Each test node needs its own peer ID, while they share the data directory
+            rotate_node_key: true,
+            genesis_time: 0,
+            disable_outbound_rate_limit: false,
         };
 
         Network::init(
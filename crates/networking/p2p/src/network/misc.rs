@@ -2,6 +2,7 @@ use std::{io, pin::Pin, time::Duration};
 
 use discv5::Enr;
 use enr::CombinedPublicKey;
+use futures::future::Either;
 use libp2p::{
     Transport,
     core::{
@@ -11,6 +12,7 @@ use libp2p::{
     },
     dns::Transport as DnsTransport,
     noise::Config as NoiseConfig,
+    quic::{Config as QuicConfig, tokio::Transport as QuicTransport},
     tcp::{Config as TcpConfig, tokio::Transport as TcpTransport},
     yamux,
 };
@@ -27,7 +29,11 @@ impl libp2p::swarm::Executor for Executor {
     }
 }
 
-pub fn build_transport(local_private_key: Keypair) -> io::Result<Boxed<(PeerId, StreamMuxerBox)>> {
+/// Builds a TCP transport, combined with a QUIC transport if `quic_support` is set.
+pub fn build_transport(
+    local_private_key: Keypair,
+    quic_support: bool,
+) -> io::Result<Boxed<(PeerId, StreamMuxerBox)>> {
     // mplex config
     let mut mplex_config = MplexConfig::new();
     mplex_config.set_max_buffer_size(256);
@@ -40,7 +46,17 @@ pub fn build_transport(local_private_key: Keypair) -> io::Result<Boxed<(PeerId,
         .authenticate(NoiseConfig::new(&local_private_key).expect("Noise disabled"))
         .multiplex(SelectUpgrade::new(yamux_config, mplex_config))
         .timeout(Duration::from_secs(10));
-    let transport = tcp.boxed();
+    let transport = if quic_support {
+        let quic = QuicTransport::new(QuicConfig::new(&local_private_key));
+        tcp.or_transport(quic)
+            .map(|output, _| match output {
+                Either::Left((peer_id, muxer)) => (peer_id, StreamMuxerBox::new(muxer)),
+                Either::Right((peer_id, muxer)) => (peer_id, StreamMuxerBox::new(muxer)),
+            })
+            .boxed()
+    } else {
+        tcp.boxed()
+    };
 
     let transport = DnsTransport::system(transport)?.boxed();
 
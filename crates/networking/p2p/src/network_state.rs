@@ -5,18 +5,36 @@ use discv5::Enr;
 use libp2p::{Multiaddr, PeerId};
 use parking_lot::RwLock;
 use ssz::Encode;
+use tracing::{debug, warn};
 
 use crate::{
     peer::{CachedPeer, ConnectionState, Direction},
     req_resp::messages::{meta_data::GetMetaDataV2, status::Status},
-    utils::META_DATA_FILE_NAME,
+    scoring::{BannedPeers, PeerAction},
+    subnet_service::SubnetService,
+    utils::{META_DATA_FILE_NAME, write_enr_to_disk},
 };
 
+/// How far backfill sync has walked back from the block the node started from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackfillProgress {
+    /// The slot of the oldest block in the database
+    pub oldest_slot: u64,
+    /// The slot backfill sync stops at
+    pub target_slot: u64,
+    pub is_complete: bool,
+}
+
 pub struct NetworkState {
     pub local_enr: RwLock<Enr>,
     pub peer_table: RwLock<HashMap<PeerId, CachedPeer>>,
     pub meta_data: RwLock<GetMetaDataV2>,
     pub status: RwLock<Status>,
+    /// `None` until backfill sync has started
+    pub backfill_progress: RwLock<Option<BackfillProgress>>,
+    pub banned_peers: RwLock<BannedPeers>,
+    /// The subnets requested by validators, subscribed to by the network every slot
+    pub subnet_service: RwLock<SubnetService>,
     pub data_dir: PathBuf,
 }
 
@@ -54,6 +72,32 @@ impl NetworkState {
             });
     }
 
+    /// Penalizes a peer for misbehaving. The network disconnects or bans the peer once its score
+    /// drops below the thresholds.
+    pub fn report_peer(&self, peer_id: PeerId, action: PeerAction, reason: &str) {
+        if let Some(cached_peer) = self.peer_table.write().get_mut(&peer_id) {
+            cached_peer.score.apply_action(action);
+            debug!(
+                ?peer_id,
+                ?action,
+                score = cached_peer.score.score(),
+                "Reported peer: {reason}"
+            );
+        }
+    }
+
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.banned_peers.read().is_banned(peer_id)
+    }
+
+    /// Replaces the local ENR, storing it so the next run advertises a newer one.
+    pub fn set_local_enr(&self, enr: Enr) {
+        if let Err(err) = write_enr_to_disk(&self.data_dir, &enr) {
+            warn!("{err}");
+        }
+        *self.local_enr.write() = enr;
+    }
+
     pub fn write_meta_data_to_disk(&self) -> anyhow::Result<()> {
         let meta_data_path = self.data_dir.join(META_DATA_FILE_NAME);
         fs::write(meta_data_path, self.meta_data.read().as_ssz_bytes())
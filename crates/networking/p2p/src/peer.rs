@@ -4,7 +4,10 @@ use discv5::Enr;
 use libp2p::{Multiaddr, PeerId};
 use serde::Serialize;
 
-use crate::req_resp::messages::{meta_data::GetMetaDataV2, status::Status};
+use crate::{
+    req_resp::messages::{meta_data::GetMetaDataV2, status::Status},
+    scoring::PeerScore,
+};
 
 #[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
 #[serde(rename_all = "lowercase")]
@@ -23,6 +26,51 @@ pub enum Direction {
     Unknown,
 }
 
+/// The client a peer runs, parsed from the agent version it sends in identify.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Client {
+    Grandine,
+    Lighthouse,
+    Lodestar,
+    Nimbus,
+    Prysm,
+    Ream,
+    Teku,
+    Unknown,
+}
+
+impl Client {
+    pub fn from_agent_version(agent_version: &str) -> Self {
+        let agent_version = agent_version.to_lowercase();
+        [
+            Client::Grandine,
+            Client::Lighthouse,
+            Client::Lodestar,
+            Client::Nimbus,
+            Client::Prysm,
+            Client::Ream,
+            Client::Teku,
+        ]
+        .into_iter()
+        .find(|client| agent_version.starts_with(client.as_str()))
+        .unwrap_or(Client::Unknown)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Client::Grandine => "grandine",
+            Client::Lighthouse => "lighthouse",
+            Client::Lodestar => "lodestar",
+            Client::Nimbus => "nimbus",
+            Client::Prysm => "prysm",
+            Client::Ream => "ream",
+            Client::Teku => "teku",
+            Client::Unknown => "unknown",
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct CachedPeer {
     /// libp2p peer ID
@@ -46,6 +94,12 @@ pub struct CachedPeer {
     pub status: Option<Status>,
 
     pub meta_data: Option<GetMetaDataV2>,
+
+    /// Agent version the peer sent in identify, if received
+    pub agent_version: Option<String>,
+
+    /// Reputation of the peer, used to disconnect and ban misbehaving peers
+    pub score: PeerScore,
 }
 
 impl CachedPeer {
@@ -65,9 +119,17 @@ impl CachedPeer {
             enr,
             status: None,
             meta_data: None,
+            agent_version: None,
+            score: PeerScore::default(),
         }
     }
 
+    pub fn client(&self) -> Client {
+        self.agent_version
+            .as_deref()
+            .map_or(Client::Unknown, Client::from_agent_version)
+    }
+
     /// Update the last seen timestamp
     pub fn update_last_seen(&mut self) {
         self.last_seen = Instant::now();
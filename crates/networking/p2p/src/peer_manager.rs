@@ -0,0 +1,261 @@
+//! Keeps the number of connected peers around a target, preferring peers which serve the subnets
+//! we need and keeping a share of the slots for outbound peers.
+
+use std::collections::HashMap;
+
+use libp2p::PeerId;
+use ream_metrics::{BEACON_NETWORK_PEERS, set_int_gauge_vec};
+
+use crate::{
+    constants::MIN_PEERS_PER_SUBNET,
+    peer::{CachedPeer, Direction},
+    subnet_service::RequiredSubnets,
+};
+
+/// Share of the target peer count tolerated on top of it before pruning
+const PEER_EXCESS_FACTOR: f64 = 0.1;
+
+/// Share of the target peer count kept for outbound peers, which we chose ourselves and are
+/// harder to eclipse us with
+const MIN_OUTBOUND_PEERS_FACTOR: f64 = 0.2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Subnet {
+    Attestation(u64),
+    SyncCommittee(u64),
+}
+
+pub struct PeerManager {
+    target_peers: usize,
+}
+
+impl PeerManager {
+    pub fn new(target_peers: usize) -> Self {
+        Self { target_peers }
+    }
+
+    pub fn target_peers(&self) -> usize {
+        self.target_peers
+    }
+
+    /// The most peers we stay connected to, peers above it are pruned right away.
+    pub fn max_peers(&self) -> usize {
+        self.target_peers + (self.target_peers as f64 * PEER_EXCESS_FACTOR).ceil() as usize
+    }
+
+    fn min_outbound_peers(&self) -> usize {
+        (self.target_peers as f64 * MIN_OUTBOUND_PEERS_FACTOR).ceil() as usize
+    }
+
+    /// Whether a new inbound peer fits without taking the slots kept for outbound peers.
+    pub fn accepts_inbound_peer(&self, peers: &[CachedPeer]) -> bool {
+        let inbound_peers = peers
+            .iter()
+            .filter(|peer| peer.direction == Direction::Inbound)
+            .count();
+        inbound_peers < self.max_peers() - self.min_outbound_peers()
+    }
+
+    /// The number of peers to discover to get back to the target.
+    pub fn peers_to_discover(&self, peers: &[CachedPeer]) -> usize {
+        self.target_peers.saturating_sub(peers.len())
+    }
+
+    /// Picks the peers to disconnect to get back to the target, once there are more than
+    /// `max_peers`.
+    ///
+    /// Inbound peers go first, then the peers serving the fewest of the `required_subnets`, then
+    /// the peers with the lowest score. Peers which are among the last on a required subnet, and
+    /// the outbound peers within the outbound share, are kept.
+    pub fn peers_to_prune(
+        &self,
+        peers: &[CachedPeer],
+        required_subnets: &RequiredSubnets,
+    ) -> Vec<PeerId> {
+        // Peers up to the excess are tolerated, so pruning doesn't kick in for every new peer
+        if peers.len() <= self.max_peers() {
+            return vec![];
+        }
+        let mut excess_peers = peers.len() - self.target_peers;
+
+        let peer_subnets = peers
+            .iter()
+            .map(|peer| (peer.peer_id, subnets_of_peer(peer, required_subnets)))
+            .collect::<HashMap<_, _>>();
+        let mut subnet_peer_counts = HashMap::<Subnet, usize>::new();
+        for subnets in peer_subnets.values() {
+            for subnet in subnets {
+                *subnet_peer_counts.entry(*subnet).or_default() += 1;
+            }
+        }
+        let mut outbound_peers = peers
+            .iter()
+            .filter(|peer| peer.direction == Direction::Outbound)
+            .count();
+
+        let mut candidates = peers.iter().collect::<Vec<_>>();
+        candidates.sort_by(|a, b| {
+            (a.direction == Direction::Outbound)
+                .cmp(&(b.direction == Direction::Outbound))
+                .then(
+                    peer_subnets[&a.peer_id]
+                        .len()
+                        .cmp(&peer_subnets[&b.peer_id].len()),
+                )
+                .then(a.score.score().total_cmp(&b.score.score()))
+        });
+
+        let mut peers_to_prune = vec![];
+        for peer in candidates {
+            if excess_peers == 0 {
+                break;
+            }
+            let is_outbound = peer.direction == Direction::Outbound;
+            if is_outbound && outbound_peers <= self.min_outbound_peers() {
+                continue;
+            }
+            let subnets = &peer_subnets[&peer.peer_id];
+            if subnets
+                .iter()
+                .any(|subnet| subnet_peer_counts[subnet] <= MIN_PEERS_PER_SUBNET)
+            {
+                continue;
+            }
+
+            for subnet in subnets {
+                if let Some(count) = subnet_peer_counts.get_mut(subnet) {
+                    *count -= 1;
+                }
+            }
+            if is_outbound {
+                outbound_peers -= 1;
+            }
+            peers_to_prune.push(peer.peer_id);
+            excess_peers -= 1;
+        }
+        peers_to_prune
+    }
+}
+
+/// The required subnets a peer advertises in its metadata.
+fn subnets_of_peer(peer: &CachedPeer, required_subnets: &RequiredSubnets) -> Vec<Subnet> {
+    let Some(meta_data) = &peer.meta_data else {
+        return vec![];
+    };
+    let attestation_subnets = required_subnets
+        .attestation_subnets()
+        .into_iter()
+        .filter(|subnet_id| meta_data.attnets.get(*subnet_id as usize).unwrap_or(false))
+        .map(Subnet::Attestation);
+    let sync_committee_subnets = required_subnets
+        .sync_committee_subnets
+        .iter()
+        .filter(|subnet_id| {
+            meta_data
+                .syncnets
+                .get(**subnet_id as usize)
+                .unwrap_or(false)
+        })
+        .map(|subnet_id| Subnet::SyncCommittee(*subnet_id));
+    attestation_subnets.chain(sync_committee_subnets).collect()
+}
+
+/// Exports the number of connected peers per direction and client.
+pub fn record_peer_metrics(peers: &[CachedPeer]) {
+    let mut peer_counts = HashMap::<(&str, &str), i64>::new();
+    for peer in peers {
+        let direction = match peer.direction {
+            Direction::Inbound => "inbound",
+            Direction::Outbound => "outbound",
+            Direction::Unknown => "unknown",
+        };
+        *peer_counts
+            .entry((direction, peer.client().as_str()))
+            .or_default() += 1;
+    }
+
+    BEACON_NETWORK_PEERS.reset();
+    for ((direction, client), count) in peer_counts {
+        set_int_gauge_vec(&BEACON_NETWORK_PEERS, count, &[direction, client]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use libp2p::PeerId;
+
+    use super::PeerManager;
+    use crate::{
+        peer::{CachedPeer, ConnectionState, Direction},
+        req_resp::messages::meta_data::GetMetaDataV2,
+        scoring::PeerAction,
+        subnet_service::RequiredSubnets,
+    };
+
+    fn connected_peer(direction: Direction) -> CachedPeer {
+        CachedPeer::new(
+            PeerId::random(),
+            None,
+            ConnectionState::Connected,
+            direction,
+            None,
+        )
+    }
+
+    #[test]
+    fn test_inbound_peers_leave_room_for_outbound_peers() {
+        let peer_manager = PeerManager::new(10);
+        let peers = (0..8)
+            .map(|_| connected_peer(Direction::Inbound))
+            .collect::<Vec<_>>();
+        assert!(peer_manager.accepts_inbound_peer(&peers));
+
+        let peers = (0..9)
+            .map(|_| connected_peer(Direction::Inbound))
+            .collect::<Vec<_>>();
+        assert!(!peer_manager.accepts_inbound_peer(&peers));
+        assert_eq!(peer_manager.peers_to_discover(&peers), 1);
+    }
+
+    #[test]
+    fn test_prune_keeps_outbound_and_subnet_peers() {
+        let peer_manager = PeerManager::new(4);
+        let required_subnets = RequiredSubnets {
+            long_lived_attestation_subnets: [7].into(),
+            ..Default::default()
+        };
+
+        let mut subnet_peer = connected_peer(Direction::Inbound);
+        let mut meta_data = GetMetaDataV2::default();
+        meta_data.attnets.set(7, true).unwrap();
+        subnet_peer.meta_data = Some(meta_data);
+        let mut low_score_peer = connected_peer(Direction::Inbound);
+        low_score_peer
+            .score
+            .apply_action(PeerAction::MidToleranceError);
+        let outbound_peers = (0..2)
+            .map(|_| connected_peer(Direction::Outbound))
+            .collect::<Vec<_>>();
+        let inbound_peer = connected_peer(Direction::Inbound);
+
+        let mut peers = vec![
+            subnet_peer.clone(),
+            low_score_peer.clone(),
+            inbound_peer.clone(),
+        ];
+        peers.extend(outbound_peers);
+
+        // Up to the max peer count nothing is pruned
+        assert_eq!(peers.len(), peer_manager.max_peers());
+        assert!(
+            peer_manager
+                .peers_to_prune(&peers, &required_subnets)
+                .is_empty()
+        );
+
+        // Above it, peers are pruned down to the target
+        peers.push(connected_peer(Direction::Inbound));
+        let pruned = peer_manager.peers_to_prune(&peers, &required_subnets);
+        assert_eq!(pruned, vec![low_score_peer.peer_id, inbound_peer.peer_id]);
+    }
+}
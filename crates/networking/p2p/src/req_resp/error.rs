@@ -2,6 +2,8 @@ use std::io::{self};
 
 use ssz_types::{VariableList, typenum::U256};
 
+use crate::scoring::PeerAction;
+
 #[derive(thiserror::Error, Debug)]
 pub enum ReqRespError {
     #[error("IO error: {0}")]
@@ -27,6 +29,32 @@ pub enum ReqRespError {
 
     #[error("Raw error message {0}")]
     RawError(String),
+
+    #[error("Rate limited")]
+    RateLimited,
+
+    #[error("Resource unavailable: {0}")]
+    ResourceUnavailable(String),
+}
+
+impl ReqRespError {
+    /// The penalty for a peer whose request or response failed with this error, if it is the
+    /// peer's fault.
+    pub fn peer_action(&self) -> Option<PeerAction> {
+        match self {
+            ReqRespError::InvalidData(_) => Some(PeerAction::LowToleranceError),
+            ReqRespError::IncompleteStream => Some(PeerAction::MidToleranceError),
+            ReqRespError::RateLimited => Some(PeerAction::MidToleranceError),
+            ReqRespError::StreamTimedOut | ReqRespError::TokioTimedOut(_) => {
+                Some(PeerAction::HighToleranceError)
+            }
+            ReqRespError::IoError(_)
+            | ReqRespError::Anyhow(_)
+            | ReqRespError::Disconnected
+            | ReqRespError::RawError(_)
+            | ReqRespError::ResourceUnavailable(_) => None,
+        }
+    }
 }
 
 impl From<ssz::DecodeError> for ReqRespError {
@@ -63,7 +63,9 @@ impl RespMessage {
                 ReqRespError::InvalidData(_) => Some(ResponseCode::InvalidRequest),
                 ReqRespError::Disconnected
                 | ReqRespError::StreamTimedOut
-                | ReqRespError::TokioTimedOut(_) => Some(ResponseCode::ResourceUnavailable),
+                | ReqRespError::TokioTimedOut(_)
+                | ReqRespError::RateLimited
+                | ReqRespError::ResourceUnavailable(_) => Some(ResponseCode::ResourceUnavailable),
             },
             RespMessage::EndOfStream => None,
         }
@@ -22,6 +22,12 @@ pub struct BlobSidecarsByRangeV1Request {
     pub count: u64,
 }
 
+impl BlobSidecarsByRangeV1Request {
+    pub fn new(start_slot: u64, count: u64) -> Self {
+        Self { start_slot, count }
+    }
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode)]
 #[ssz(struct_behaviour = "transparent")]
 pub struct BlobSidecarsByRootV1Request {
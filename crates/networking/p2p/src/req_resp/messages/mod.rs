@@ -32,6 +32,19 @@ pub enum RequestMessage {
 }
 
 impl RequestMessage {
+    pub fn protocol(&self) -> SupportedProtocol {
+        match self {
+            RequestMessage::MetaData(_) => SupportedProtocol::GetMetaDataV2,
+            RequestMessage::Goodbye(_) => SupportedProtocol::GoodbyeV1,
+            RequestMessage::Status(_) => SupportedProtocol::StatusV1,
+            RequestMessage::Ping(_) => SupportedProtocol::PingV1,
+            RequestMessage::BeaconBlocksByRange(_) => SupportedProtocol::BeaconBlocksByRangeV2,
+            RequestMessage::BeaconBlocksByRoot(_) => SupportedProtocol::BeaconBlocksByRootV2,
+            RequestMessage::BlobSidecarsByRange(_) => SupportedProtocol::BlobSidecarsByRangeV1,
+            RequestMessage::BlobSidecarsByRoot(_) => SupportedProtocol::BlobSidecarsByRootV1,
+        }
+    }
+
     pub fn supported_protocols(&self) -> Vec<ProtocolId> {
         match self {
             RequestMessage::MetaData(_) => vec![ProtocolId::new(SupportedProtocol::GetMetaDataV2)],
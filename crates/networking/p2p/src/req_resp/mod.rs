@@ -5,9 +5,14 @@ pub mod inbound_protocol;
 pub mod messages;
 pub mod outbound_protocol;
 pub mod protocol_id;
+pub mod rate_limiter;
 
 use std::task::{Context, Poll};
 
+use configurations::REQUEST_TIMEOUT;
+use delay_map::HashMapDelay;
+use error::ReqRespError;
+use futures::StreamExt;
 use handler::{
     HandlerEvent, ReqRespConnectionHandler, ReqRespMessageError, ReqRespMessageReceived,
     RespMessage,
@@ -23,7 +28,8 @@ use libp2p::{
     },
 };
 use messages::RequestMessage;
-use tracing::{debug, trace};
+use rate_limiter::{RateLimitedError, RateLimiter, check_request_size};
+use tracing::{debug, trace, warn};
 
 /// Maximum number of concurrent requests per protocol ID that a client may issue.
 pub const MAX_CONCURRENT_REQUESTS: usize = 2;
@@ -48,16 +54,67 @@ pub enum ConnectionRequest {
     Shutdown,
 }
 
+/// Why an inbound request is answered with an error instead of being handled.
+enum InboundRejection {
+    RateLimited,
+    InvalidRequest(String),
+}
+
+impl InboundRejection {
+    fn to_error(&self) -> ReqRespError {
+        match self {
+            InboundRejection::RateLimited => ReqRespError::RateLimited,
+            InboundRejection::InvalidRequest(reason) => ReqRespError::InvalidData(reason.clone()),
+        }
+    }
+}
+
 pub struct ReqResp {
     pub events: Vec<ToSwarm<ReqRespMessage, ConnectionRequest>>,
+    /// Limits the requests peers send us
+    inbound_rate_limiter: RateLimiter,
+    /// Keeps our requests within the limits peers apply, unless disabled
+    outbound_rate_limiter: Option<RateLimiter>,
+    /// Requests waiting for the peer's rate limit, by request ID
+    delayed_requests: HashMapDelay<u64, (PeerId, RequestMessage)>,
 }
 
 impl ReqResp {
     pub fn new() -> Self {
-        ReqResp { events: vec![] }
+        ReqResp {
+            events: vec![],
+            inbound_rate_limiter: RateLimiter::default(),
+            outbound_rate_limiter: Some(RateLimiter::default()),
+            delayed_requests: HashMapDelay::new(REQUEST_TIMEOUT),
+        }
+    }
+
+    /// Sends requests over the quota of peers instead of delaying them, for simulated peers
+    /// testing the limiter of the remote peer.
+    pub fn without_outbound_rate_limit(mut self) -> Self {
+        self.outbound_rate_limiter = None;
+        self
     }
 
     pub fn send_request(&mut self, peer_id: PeerId, request_id: u64, message: RequestMessage) {
+        if let Some(outbound_rate_limiter) = &mut self.outbound_rate_limiter {
+            match outbound_rate_limiter.allows(peer_id, &message) {
+                Ok(()) => {}
+                Err(RateLimitedError::WaitFor(delay)) => {
+                    trace!("REQRESP: Delaying request {request_id} to {peer_id} by {delay:?}");
+                    self.delayed_requests
+                        .insert_at(request_id, (peer_id, message), delay);
+                    return;
+                }
+                // The peer rejects it whatever we do, so it is sent as is
+                Err(RateLimitedError::TooLarge) => {
+                    warn!(
+                        "REQRESP: Request {request_id} to {peer_id} exceeds the rate limit quota"
+                    );
+                }
+            }
+        }
+
         self.events.push(ToSwarm::NotifyHandler {
             peer_id,
             handler: NotifyHandler::Any,
@@ -86,6 +143,22 @@ impl ReqResp {
     }
 }
 
+impl ReqResp {
+    fn reject_inbound_request(
+        &mut self,
+        peer_id: PeerId,
+        request: &RequestMessage,
+    ) -> Option<InboundRejection> {
+        if let Err(reason) = check_request_size(request) {
+            return Some(InboundRejection::InvalidRequest(reason));
+        }
+        if self.inbound_rate_limiter.allows(peer_id, request).is_err() {
+            return Some(InboundRejection::RateLimited);
+        }
+        None
+    }
+}
+
 impl Default for ReqResp {
     fn default() -> Self {
         ReqResp::new()
@@ -132,13 +205,19 @@ impl NetworkBehaviour for ReqResp {
             peer_id,
             connection_id,
             cause,
-            remaining_established: _,
+            remaining_established,
             ..
         }) = event
         {
             trace!(
                 "REQRESP: Connection closed for peer {peer_id} with connection ID {connection_id} due to {cause:?}"
             );
+            if remaining_established == 0 {
+                self.inbound_rate_limiter.remove_peer(&peer_id);
+                if let Some(outbound_rate_limiter) = &mut self.outbound_rate_limiter {
+                    outbound_rate_limiter.remove_peer(&peer_id);
+                }
+            }
         }
     }
 
@@ -149,11 +228,42 @@ impl NetworkBehaviour for ReqResp {
         event: <Self::ConnectionHandler as ConnectionHandler>::ToBehaviour,
     ) {
         match event {
-            HandlerEvent::Ok(message) => self.events.push(ToSwarm::GenerateEvent(ReqRespMessage {
-                peer_id,
-                connection_id,
-                message: Ok(*message),
-            })),
+            HandlerEvent::Ok(message) => {
+                if let ReqRespMessageReceived::Request {
+                    stream_id,
+                    message: request,
+                } = message.as_ref()
+                    && let Some(rejection) = self.reject_inbound_request(peer_id, request)
+                {
+                    let stream_id = *stream_id;
+                    debug!(
+                        "REQRESP: Rejecting {:?} request from {peer_id}: {}",
+                        request.protocol(),
+                        rejection.to_error()
+                    );
+                    self.send_response(
+                        peer_id,
+                        connection_id,
+                        stream_id,
+                        RespMessage::Error(rejection.to_error()),
+                    );
+                    self.events.push(ToSwarm::GenerateEvent(ReqRespMessage {
+                        peer_id,
+                        connection_id,
+                        message: Err(ReqRespMessageError::Inbound {
+                            stream_id,
+                            err: rejection.to_error(),
+                        }),
+                    }));
+                    return;
+                }
+
+                self.events.push(ToSwarm::GenerateEvent(ReqRespMessage {
+                    peer_id,
+                    connection_id,
+                    message: Ok(*message),
+                }))
+            }
             HandlerEvent::Err(err) => self.events.push(ToSwarm::GenerateEvent(ReqRespMessage {
                 peer_id,
                 connection_id,
@@ -168,8 +278,14 @@ impl NetworkBehaviour for ReqResp {
 
     fn poll(
         &mut self,
-        _cx: &mut Context<'_>,
+        cx: &mut Context<'_>,
     ) -> Poll<ToSwarm<Self::ToSwarm, THandlerInEvent<Self>>> {
+        while let Poll::Ready(Some(Ok((request_id, (peer_id, message))))) =
+            self.delayed_requests.poll_next_unpin(cx)
+        {
+            self.send_request(peer_id, request_id, message);
+        }
+
         debug!("REQRESP: Polling events {:?}", self.events);
         if !self.events.is_empty() {
             return Poll::Ready(self.events.remove(0));
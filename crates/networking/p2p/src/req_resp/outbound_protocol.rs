@@ -225,9 +225,16 @@ impl Decoder for OutboundSSZSnappyCodec {
                         ))),
                     }
                 } else {
-                    Ok(Some(RespMessage::Error(
-                        VariableList::<u8, U256>::from_ssz_bytes(&buf).map(ReqRespError::from).map_err(|err| anyhow!("OutboundSSZSnappyCodec::decode: protocol: {:?}, response_code: {response_code:?}, err: {err:?}", self.protocol.protocol))?,
-                    )))
+                    let error_message = VariableList::<u8, U256>::from_ssz_bytes(&buf).map_err(|err| anyhow!("OutboundSSZSnappyCodec::decode: protocol: {:?}, response_code: {response_code:?}, err: {err:?}", self.protocol.protocol))?;
+                    if response_code == ResponseCode::ResourceUnavailable {
+                        // The peer doesn't have the data or is rate limiting us, neither being a
+                        // fault
+                        Ok(Some(RespMessage::Error(ReqRespError::ResourceUnavailable(
+                            String::from_utf8_lossy(&error_message).to_string(),
+                        ))))
+                    } else {
+                        Ok(Some(RespMessage::Error(ReqRespError::from(error_message))))
+                    }
                 }
             }
             Err(err) => match err.kind() {
@@ -29,7 +29,7 @@ impl AsRef<str> for ProtocolId {
 }
 
 /// All valid protocol name and version combinations.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SupportedProtocol {
     BeaconBlocksByRangeV2,
     BeaconBlocksByRootV2,
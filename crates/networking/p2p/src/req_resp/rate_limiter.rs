@@ -0,0 +1,248 @@
+//! Token bucket rate limiting of req/resp requests, per peer and protocol, and the request size
+//! limits of the spec.
+//!
+//! https://ethereum.github.io/consensus-specs/specs/phase0/p2p-interface/#the-reqresp-domain
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use libp2p::PeerId;
+use ream_network_spec::networks::beacon_network_spec;
+
+use super::{messages::RequestMessage, protocol_id::SupportedProtocol};
+
+/// How many tokens a peer may spend on a protocol, all of them being replenished over
+/// `replenish_all_every`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quota {
+    pub max_tokens: u64,
+    pub replenish_all_every: Duration,
+}
+
+impl Quota {
+    const fn n_every(max_tokens: u64, seconds: u64) -> Self {
+        Self {
+            max_tokens,
+            replenish_all_every: Duration::from_secs(seconds),
+        }
+    }
+
+    /// The quota of a protocol, large enough for the biggest request the spec allows.
+    pub fn for_protocol(protocol: SupportedProtocol) -> Self {
+        let network_spec = beacon_network_spec();
+        match protocol {
+            SupportedProtocol::GetMetaDataV2 => Quota::n_every(2, 5),
+            SupportedProtocol::GoodbyeV1 => Quota::n_every(1, 10),
+            SupportedProtocol::PingV1 => Quota::n_every(2, 10),
+            SupportedProtocol::StatusV1 => Quota::n_every(5, 15),
+            SupportedProtocol::BeaconBlocksByRangeV2 | SupportedProtocol::BeaconBlocksByRootV2 => {
+                Quota::n_every(network_spec.max_request_blocks_deneb, 10)
+            }
+            SupportedProtocol::BlobSidecarsByRangeV1 | SupportedProtocol::BlobSidecarsByRootV1 => {
+                Quota::n_every(network_spec.max_request_blob_sidecars_electra, 10)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitedError {
+    /// The request costs more than the whole quota, so it is never allowed
+    TooLarge,
+    /// The request is allowed once enough tokens were replenished
+    WaitFor(Duration),
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_update: Instant,
+}
+
+/// Limits the requests sent to or received from each peer, by the number of items requested.
+#[derive(Default)]
+pub struct RateLimiter {
+    buckets: HashMap<(PeerId, SupportedProtocol), TokenBucket>,
+}
+
+impl RateLimiter {
+    /// Takes the tokens `message` costs from the peer's bucket, if it has enough of them.
+    pub fn allows(
+        &mut self,
+        peer_id: PeerId,
+        message: &RequestMessage,
+    ) -> Result<(), RateLimitedError> {
+        self.allows_at(peer_id, message, Instant::now())
+    }
+
+    fn allows_at(
+        &mut self,
+        peer_id: PeerId,
+        message: &RequestMessage,
+        now: Instant,
+    ) -> Result<(), RateLimitedError> {
+        let protocol = message.protocol();
+        let quota = Quota::for_protocol(protocol);
+        let cost = request_cost(message) as f64;
+        let max_tokens = quota.max_tokens as f64;
+        if cost > max_tokens {
+            return Err(RateLimitedError::TooLarge);
+        }
+
+        let tokens_per_second = max_tokens / quota.replenish_all_every.as_secs_f64();
+        let bucket = self
+            .buckets
+            .entry((peer_id, protocol))
+            .or_insert(TokenBucket {
+                tokens: max_tokens,
+                last_update: now,
+            });
+        let elapsed = now.saturating_duration_since(bucket.last_update);
+        bucket.tokens = (bucket.tokens + elapsed.as_secs_f64() * tokens_per_second).min(max_tokens);
+        bucket.last_update = now;
+
+        if bucket.tokens < cost {
+            return Err(RateLimitedError::WaitFor(Duration::from_secs_f64(
+                (cost - bucket.tokens) / tokens_per_second,
+            )));
+        }
+        bucket.tokens -= cost;
+        Ok(())
+    }
+
+    /// Drops the buckets of a disconnected peer.
+    pub fn remove_peer(&mut self, peer_id: &PeerId) {
+        self.buckets
+            .retain(|(bucket_peer_id, _), _| bucket_peer_id != peer_id);
+    }
+}
+
+/// The tokens a request costs, i.e. the number of items it asks for.
+fn request_cost(message: &RequestMessage) -> u64 {
+    let cost = match message {
+        RequestMessage::BeaconBlocksByRange(request) => request.count,
+        RequestMessage::BeaconBlocksByRoot(request) => request.inner.len() as u64,
+        RequestMessage::BlobSidecarsByRange(request) => request
+            .count
+            .saturating_mul(beacon_network_spec().max_blobs_per_block_electra),
+        RequestMessage::BlobSidecarsByRoot(request) => request.inner.len() as u64,
+        RequestMessage::MetaData(_)
+        | RequestMessage::Goodbye(_)
+        | RequestMessage::Status(_)
+        | RequestMessage::Ping(_) => 1,
+    };
+    cost.max(1)
+}
+
+/// Checks a request against the request size limits of the spec, returning why it exceeds them.
+pub fn check_request_size(message: &RequestMessage) -> Result<(), String> {
+    let network_spec = beacon_network_spec();
+    let (requested, limit) = match message {
+        RequestMessage::BeaconBlocksByRange(request) => {
+            (request.count, network_spec.max_request_blocks_deneb)
+        }
+        RequestMessage::BeaconBlocksByRoot(request) => (
+            request.inner.len() as u64,
+            network_spec.max_request_blocks_deneb,
+        ),
+        RequestMessage::BlobSidecarsByRange(request) => (
+            request
+                .count
+                .saturating_mul(network_spec.max_blobs_per_block_electra),
+            network_spec.max_request_blob_sidecars_electra,
+        ),
+        RequestMessage::BlobSidecarsByRoot(request) => (
+            request.inner.len() as u64,
+            network_spec.max_request_blob_sidecars_electra,
+        ),
+        RequestMessage::MetaData(_)
+        | RequestMessage::Goodbye(_)
+        | RequestMessage::Status(_)
+        | RequestMessage::Ping(_) => return Ok(()),
+    };
+
+    if requested > limit {
+        return Err(format!(
+            "Requested {requested} items over {:?}, more than the limit of {limit}",
+            message.protocol()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use libp2p::PeerId;
+    use ream_network_spec::networks::initialize_test_network_spec;
+
+    use super::{RateLimitedError, RateLimiter, check_request_size};
+    use crate::req_resp::messages::{
+        RequestMessage, beacon_blocks::BeaconBlocksByRangeV2Request,
+        blob_sidecars::BlobSidecarsByRangeV1Request, ping::Ping,
+    };
+
+    #[test]
+    fn test_rate_limiter_replenishes_tokens() {
+        initialize_test_network_spec();
+        let mut rate_limiter = RateLimiter::default();
+        let peer_id = PeerId::random();
+        let ping = RequestMessage::Ping(Ping::new(0));
+        let now = Instant::now();
+
+        assert!(rate_limiter.allows_at(peer_id, &ping, now).is_ok());
+        assert!(rate_limiter.allows_at(peer_id, &ping, now).is_ok());
+        assert_eq!(
+            rate_limiter.allows_at(peer_id, &ping, now),
+            Err(RateLimitedError::WaitFor(Duration::from_secs(5)))
+        );
+        // Other peers have their own buckets
+        assert!(rate_limiter.allows_at(PeerId::random(), &ping, now).is_ok());
+
+        assert!(
+            rate_limiter
+                .allows_at(peer_id, &ping, now + Duration::from_secs(5))
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_blocks_by_range_quota_matches_the_request_limit() {
+        initialize_test_network_spec();
+        let mut rate_limiter = RateLimiter::default();
+        let peer_id = PeerId::random();
+        let blocks = RequestMessage::BeaconBlocksByRange(BeaconBlocksByRangeV2Request::new(0, 128));
+        let now = Instant::now();
+
+        assert!(rate_limiter.allows_at(peer_id, &blocks, now).is_ok());
+        assert!(matches!(
+            rate_limiter.allows_at(peer_id, &blocks, now),
+            Err(RateLimitedError::WaitFor(_))
+        ));
+    }
+
+    #[test]
+    fn test_oversized_requests_are_rejected() {
+        initialize_test_network_spec();
+        let blocks = RequestMessage::BeaconBlocksByRange(BeaconBlocksByRangeV2Request::new(0, 128));
+        assert!(check_request_size(&blocks).is_ok());
+        let blocks = RequestMessage::BeaconBlocksByRange(BeaconBlocksByRangeV2Request::new(0, 129));
+        assert!(check_request_size(&blocks).is_err());
+
+        // 9 blobs per block, up to 1152 blobs
+        let blobs = RequestMessage::BlobSidecarsByRange(BlobSidecarsByRangeV1Request::new(0, 128));
+        assert!(check_request_size(&blobs).is_ok());
+        let blobs = RequestMessage::BlobSidecarsByRange(BlobSidecarsByRangeV1Request::new(0, 129));
+        assert!(check_request_size(&blobs).is_err());
+
+        let mut rate_limiter = RateLimiter::default();
+        let blobs =
+            RequestMessage::BlobSidecarsByRange(BlobSidecarsByRangeV1Request::new(0, u64::MAX));
+        assert_eq!(
+            rate_limiter.allows(PeerId::random(), &blobs),
+            Err(RateLimitedError::TooLarge)
+        );
+    }
+}
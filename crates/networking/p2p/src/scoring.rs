@@ -0,0 +1,276 @@
+//! Peer reputation, combining penalties applied by the application with the gossipsub peer score.
+
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::anyhow;
+use libp2p::PeerId;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+use crate::gossipsub::beacon::scoring::GRAYLIST_THRESHOLD;
+
+pub const BANNED_PEERS_FILE_NAME: &str = "banned_peers.yaml";
+
+/// The highest and lowest application score a peer can have
+pub const MAX_SCORE: f64 = 100.0;
+pub const MIN_SCORE: f64 = -100.0;
+
+/// Peers are disconnected below this score, they may reconnect once it has decayed
+pub const MIN_SCORE_BEFORE_DISCONNECT: f64 = -20.0;
+
+/// Peers are banned below this score
+pub const MIN_SCORE_BEFORE_BAN: f64 = -50.0;
+
+/// How long a banned peer is refused
+pub const BAN_DURATION: Duration = Duration::from_secs(30 * 60);
+
+/// The application score halves over this duration
+const SCORE_HALFLIFE: Duration = Duration::from_secs(10 * 60);
+
+/// Scales the gossipsub score, so a graylisted peer is disconnected
+const GOSSIPSUB_SCORE_WEIGHT: f64 = MIN_SCORE_BEFORE_DISCONNECT / GRAYLIST_THRESHOLD;
+
+/// A penalty for misbehaviour, sized by how many times it is tolerated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PeerAction {
+    /// The peer is banned right away, e.g. for being on another network
+    Fatal,
+    /// Tolerated about 5 times, e.g. invalid data
+    LowToleranceError,
+    /// Tolerated about 10 times, e.g. a broken response stream
+    MidToleranceError,
+    /// Tolerated about 50 times, e.g. a timed out request
+    HighToleranceError,
+}
+
+impl PeerAction {
+    fn score_change(&self) -> f64 {
+        match self {
+            PeerAction::Fatal => MIN_SCORE,
+            PeerAction::LowToleranceError => -10.0,
+            PeerAction::MidToleranceError => -5.0,
+            PeerAction::HighToleranceError => -1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScoreState {
+    Healthy,
+    Disconnected,
+    Banned,
+}
+
+#[derive(Debug, Clone)]
+pub struct PeerScore {
+    application_score: f64,
+    gossipsub_score: f64,
+    last_updated: Instant,
+}
+
+impl Default for PeerScore {
+    fn default() -> Self {
+        Self {
+            application_score: 0.0,
+            gossipsub_score: 0.0,
+            last_updated: Instant::now(),
+        }
+    }
+}
+
+impl PeerScore {
+    /// The combined score, only a negative gossipsub score counts against the peer.
+    pub fn score(&self) -> f64 {
+        match self.application_score {
+            // A fatal action isn't outweighed by good gossip
+            score if score <= MIN_SCORE => MIN_SCORE,
+            score => score + self.gossipsub_score.min(0.0) * GOSSIPSUB_SCORE_WEIGHT,
+        }
+    }
+
+    pub fn state(&self) -> ScoreState {
+        match self.score() {
+            score if score <= MIN_SCORE_BEFORE_BAN => ScoreState::Banned,
+            score if score <= MIN_SCORE_BEFORE_DISCONNECT => ScoreState::Disconnected,
+            _ => ScoreState::Healthy,
+        }
+    }
+
+    pub fn apply_action(&mut self, action: PeerAction) {
+        self.decay();
+        self.application_score =
+            (self.application_score + action.score_change()).clamp(MIN_SCORE, MAX_SCORE);
+    }
+
+    pub fn update_gossipsub_score(&mut self, gossipsub_score: f64) {
+        self.gossipsub_score = gossipsub_score;
+    }
+
+    /// Decays the application score towards zero. Fatal scores don't decay.
+    pub fn decay(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_updated);
+        self.last_updated = now;
+        if self.application_score > MIN_SCORE {
+            self.application_score *=
+                0.5_f64.powf(elapsed.as_secs_f64() / SCORE_HALFLIFE.as_secs_f64());
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BannedPeer {
+    peer_id: PeerId,
+    /// Unix timestamp in seconds
+    banned_until: u64,
+}
+
+/// Peers banned until a point in time, stored in the data directory so bans survive restarts.
+#[derive(Debug, Default)]
+pub struct BannedPeers {
+    peers: HashMap<PeerId, u64>,
+    path: Option<PathBuf>,
+}
+
+impl BannedPeers {
+    /// Reads the bans which haven't expired from `data_dir`.
+    pub fn load(data_dir: PathBuf) -> anyhow::Result<Self> {
+        let path = data_dir.join(BANNED_PEERS_FILE_NAME);
+        let mut banned_peers = Self {
+            peers: HashMap::new(),
+            path: Some(path.clone()),
+        };
+        if !path.exists() {
+            return Ok(banned_peers);
+        }
+
+        let entries: Vec<BannedPeer> = serde_yaml::from_str(&fs::read_to_string(&path)?)
+            .map_err(|err| anyhow!("Failed to decode banned peers: {err:?}"))?;
+        let now = unix_timestamp();
+        banned_peers.peers = entries
+            .into_iter()
+            .filter(|entry| entry.banned_until > now)
+            .map(|entry| (entry.peer_id, entry.banned_until))
+            .collect();
+        Ok(banned_peers)
+    }
+
+    pub fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.peers
+            .get(peer_id)
+            .is_some_and(|banned_until| *banned_until > unix_timestamp())
+    }
+
+    pub fn ban(&mut self, peer_id: PeerId, duration: Duration) {
+        self.peers
+            .insert(peer_id, unix_timestamp() + duration.as_secs());
+        self.save();
+    }
+
+    /// Lifts the bans which have expired, returning the unbanned peers.
+    pub fn remove_expired(&mut self) -> Vec<PeerId> {
+        let now = unix_timestamp();
+        let expired = self
+            .peers
+            .iter()
+            .filter(|(_, banned_until)| **banned_until <= now)
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<Vec<_>>();
+        if !expired.is_empty() {
+            for peer_id in &expired {
+                self.peers.remove(peer_id);
+            }
+            self.save();
+        }
+        expired
+    }
+
+    pub fn len(&self) -> usize {
+        self.peers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.peers.is_empty()
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let entries = self
+            .peers
+            .iter()
+            .map(|(peer_id, banned_until)| BannedPeer {
+                peer_id: *peer_id,
+                banned_until: *banned_until,
+            })
+            .collect::<Vec<_>>();
+        let result = serde_yaml::to_string(&entries)
+            .map_err(|err| anyhow!("Failed to encode banned peers: {err:?}"))
+            .and_then(|yaml| Ok(fs::write(path, yaml)?));
+        if let Err(err) = result {
+            warn!("Failed to write banned peers to disk: {err:?}");
+        }
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("correct time")
+        .as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use libp2p::PeerId;
+    use tempdir::TempDir;
+
+    use super::{BannedPeers, PeerAction, PeerScore, ScoreState};
+
+    #[test]
+    fn test_score_thresholds() {
+        let mut score = PeerScore::default();
+        assert_eq!(score.state(), ScoreState::Healthy);
+
+        score.apply_action(PeerAction::LowToleranceError);
+        assert_eq!(score.state(), ScoreState::Healthy);
+        score.apply_action(PeerAction::LowToleranceError);
+        assert_eq!(score.state(), ScoreState::Disconnected);
+        for _ in 0..3 {
+            score.apply_action(PeerAction::LowToleranceError);
+        }
+        assert_eq!(score.state(), ScoreState::Banned);
+
+        let mut score = PeerScore::default();
+        score.update_gossipsub_score(1000.0);
+        score.apply_action(PeerAction::Fatal);
+        assert_eq!(score.state(), ScoreState::Banned);
+    }
+
+    #[test]
+    fn test_bans_are_persisted() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new("test_bans_are_persisted")?;
+        let banned_peer = PeerId::random();
+        let expired_peer = PeerId::random();
+
+        let mut banned_peers = BannedPeers::load(tmp_dir.path().to_path_buf())?;
+        banned_peers.ban(banned_peer, Duration::from_secs(60));
+        banned_peers.ban(expired_peer, Duration::ZERO);
+        assert!(banned_peers.is_banned(&banned_peer));
+        assert!(!banned_peers.is_banned(&expired_peer));
+
+        let banned_peers = BannedPeers::load(tmp_dir.path().to_path_buf())?;
+        assert!(banned_peers.is_banned(&banned_peer));
+        assert_eq!(banned_peers.len(), 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,181 @@
+//! Tracks the attestation and sync committee subnets the node has to be subscribed to.
+//!
+//! https://ethereum.github.io/consensus-specs/specs/phase0/p2p-interface/#attestation-subnet-subscription
+
+use std::collections::{BTreeSet, HashMap};
+
+use alloy_primitives::{B256, U256};
+use ream_consensus_misc::{
+    constants::beacon::SYNC_COMMITTEE_SIZE,
+    misc::{compute_epoch_at_slot, compute_shuffled_index},
+};
+use ream_network_spec::networks::beacon_network_spec;
+use ream_validator_beacon::{
+    attestation::compute_subnet_for_attestation, constants::SYNC_COMMITTEE_SUBNET_COUNT,
+};
+use sha2::{Digest, Sha256};
+
+const NODE_ID_BITS: u64 = 256;
+
+/// The subnets the node needs at a slot.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RequiredSubnets {
+    /// Subnets derived from the node ID, advertised in the ENR and metadata
+    pub long_lived_attestation_subnets: BTreeSet<u64>,
+    /// Subnets of the committees our validators aggregate for
+    pub short_lived_attestation_subnets: BTreeSet<u64>,
+    pub sync_committee_subnets: BTreeSet<u64>,
+}
+
+impl RequiredSubnets {
+    pub fn attestation_subnets(&self) -> BTreeSet<u64> {
+        self.long_lived_attestation_subnets
+            .union(&self.short_lived_attestation_subnets)
+            .copied()
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct SubnetService {
+    node_id: B256,
+    /// Short-lived attestation subnets, with the last slot they are needed for
+    attestation_subscriptions: HashMap<u64, u64>,
+    /// Sync committee subnets, with the epoch they are needed until
+    sync_committee_subscriptions: HashMap<u64, u64>,
+}
+
+impl SubnetService {
+    pub fn new(node_id: B256) -> Self {
+        Self {
+            node_id,
+            attestation_subscriptions: HashMap::new(),
+            sync_committee_subscriptions: HashMap::new(),
+        }
+    }
+
+    /// Subscribes to the subnet of a beacon committee until the end of `slot`, for a validator
+    /// aggregating the committee's attestations.
+    pub fn subscribe_to_committee_subnet(
+        &mut self,
+        committees_per_slot: u64,
+        slot: u64,
+        committee_index: u64,
+    ) {
+        let subnet_id = compute_subnet_for_attestation(committees_per_slot, slot, committee_index);
+        let until_slot = self
+            .attestation_subscriptions
+            .entry(subnet_id)
+            .or_insert(slot);
+        *until_slot = (*until_slot).max(slot);
+    }
+
+    /// Subscribes to the subnets of `sync_committee_indices` until the start of `until_epoch`,
+    /// ignoring indices outside of the sync committee.
+    pub fn subscribe_to_sync_committee_subnets(
+        &mut self,
+        sync_committee_indices: &[u64],
+        until_epoch: u64,
+    ) {
+        for sync_committee_index in sync_committee_indices
+            .iter()
+            .filter(|index| **index < SYNC_COMMITTEE_SIZE)
+        {
+            let subnet_id =
+                sync_committee_index / (SYNC_COMMITTEE_SIZE / SYNC_COMMITTEE_SUBNET_COUNT);
+            let subscribed_until = self
+                .sync_committee_subscriptions
+                .entry(subnet_id)
+                .or_insert(until_epoch);
+            *subscribed_until = (*subscribed_until).max(until_epoch);
+        }
+    }
+
+    /// Returns the subnets needed at `slot`, dropping the subscriptions which expired.
+    pub fn required_subnets(&mut self, slot: u64) -> RequiredSubnets {
+        let epoch = compute_epoch_at_slot(slot);
+        self.attestation_subscriptions
+            .retain(|_, until_slot| *until_slot >= slot);
+        self.sync_committee_subscriptions
+            .retain(|_, until_epoch| *until_epoch > epoch);
+
+        RequiredSubnets {
+            long_lived_attestation_subnets: compute_subscribed_subnets(self.node_id, epoch)
+                .into_iter()
+                .collect(),
+            short_lived_attestation_subnets: self
+                .attestation_subscriptions
+                .keys()
+                .copied()
+                .collect(),
+            sync_committee_subnets: self.sync_committee_subscriptions.keys().copied().collect(),
+        }
+    }
+}
+
+/// The long-lived attestation subnets of `node_id` at `epoch`.
+pub fn compute_subscribed_subnets(node_id: B256, epoch: u64) -> Vec<u64> {
+    (0..beacon_network_spec().subnets_per_node)
+        .map(|index| compute_subscribed_subnet(node_id, epoch, index))
+        .collect()
+}
+
+fn compute_subscribed_subnet(node_id: B256, epoch: u64, index: u64) -> u64 {
+    let network_spec = beacon_network_spec();
+    let node_id = U256::from_be_bytes(node_id.0);
+    let node_id_prefix: u64 =
+        (node_id >> (NODE_ID_BITS - network_spec.attestation_subnet_prefix_bits) as usize).to();
+    let node_offset: u64 = (node_id % U256::from(network_spec.epochs_per_subnet_subscription)).to();
+    let permutation_seed = B256::from_slice(&Sha256::digest(
+        ((epoch + node_offset) / network_spec.epochs_per_subnet_subscription).to_le_bytes(),
+    ));
+    let permutated_prefix = compute_shuffled_index(
+        node_id_prefix as usize,
+        1 << network_spec.attestation_subnet_prefix_bits,
+        permutation_seed,
+    )
+    .expect("node ID prefix is less than the prefix count") as u64;
+    (permutated_prefix + index) % network_spec.attestation_subnet_count
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_consensus_misc::constants::beacon::{SLOTS_PER_EPOCH, SYNC_COMMITTEE_SIZE};
+    use ream_network_spec::networks::initialize_test_network_spec;
+
+    use super::{SubnetService, compute_subscribed_subnets};
+
+    #[test]
+    fn test_long_lived_subnets_are_stable_within_subscription_period() {
+        initialize_test_network_spec();
+        let node_id = B256::repeat_byte(0xab);
+
+        let subnets = compute_subscribed_subnets(node_id, 100);
+        assert_eq!(subnets.len(), 2);
+        assert_eq!(subnets[1], (subnets[0] + 1) % 64);
+
+        // A node ID ending in 0xab starts its subscription period 0xab epochs early, so epochs 85
+        // to 340 share a period
+        assert_eq!(compute_subscribed_subnets(node_id, 85), subnets);
+        assert_eq!(compute_subscribed_subnets(node_id, 340), subnets);
+    }
+
+    #[test]
+    fn test_short_lived_subscriptions_expire() {
+        initialize_test_network_spec();
+        let mut subnet_service = SubnetService::new(B256::ZERO);
+        // Committee 1 of the slot after the first one, with 4 committees per slot, and two sync
+        // committee subnets besides an index outside of the sync committee
+        subnet_service.subscribe_to_committee_subnet(4, 1, 1);
+        subnet_service.subscribe_to_sync_committee_subnets(&[0, 300, SYNC_COMMITTEE_SIZE], 4);
+
+        let subnets = subnet_service.required_subnets(1);
+        assert!(subnets.short_lived_attestation_subnets.contains(&5));
+        assert_eq!(subnets.sync_committee_subnets.len(), 2);
+
+        let subnets = subnet_service.required_subnets(4 * SLOTS_PER_EPOCH);
+        assert!(subnets.short_lived_attestation_subnets.is_empty());
+        assert!(subnets.sync_committee_subnets.is_empty());
+    }
+}
@@ -1,9 +1,17 @@
-use std::{cmp::max, fs, path::PathBuf};
+use std::{
+    cmp::max,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 use anyhow::anyhow;
 use discv5::{Enr, multiaddr::Protocol};
 use libp2p::Multiaddr;
+use libp2p_identity::secp256k1;
 use ssz::Decode;
+use tracing::info;
 
 use crate::{
     constants::MAX_PAYLOAD_SIZE, network::misc::peer_id_from_enr,
@@ -11,6 +19,8 @@ use crate::{
 };
 
 pub const META_DATA_FILE_NAME: &str = "meta_data.ssz";
+pub const NODE_KEY_FILE_NAME: &str = "node_key";
+pub const ENR_FILE_NAME: &str = "enr";
 
 /// Worst-case compressed length for a given payload of size n when using snappy:
 /// https://github.com/google/snappy/blob/32ded457c0b1fe78ceb8397632c416568d6714a0/snappy.cc#L218C1-L218C47
@@ -34,6 +44,57 @@ pub fn read_meta_data_from_disk(path: PathBuf) -> anyhow::Result<GetMetaDataV2>
         .map_err(|err| anyhow!("Failed to decode meta data: {err:?}"))
 }
 
+/// Reads the node key from `data_dir`, so the node keeps its peer ID and ENR across restarts. A new
+/// key is generated and stored if there is none yet or `rotate` is set.
+pub fn load_node_key(data_dir: &Path, rotate: bool) -> anyhow::Result<secp256k1::Keypair> {
+    fs::create_dir_all(data_dir)?;
+    let node_key_path = data_dir.join(NODE_KEY_FILE_NAME);
+    if !rotate && node_key_path.exists() {
+        let mut secret_key = fs::read(&node_key_path)?;
+        let secret_key = secp256k1::SecretKey::try_from_bytes(&mut secret_key)
+            .map_err(|err| anyhow!("Failed to decode node key: {err:?}"))?;
+        return Ok(secret_key.into());
+    }
+
+    let keypair = secp256k1::Keypair::generate();
+    // A rotated key replaces the old file rather than being written into it, so the permissions
+    // below always apply
+    if node_key_path.exists() {
+        fs::remove_file(&node_key_path)?;
+    }
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    // Created readable by the owner only, so the key is never exposed to other users
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(&node_key_path)
+        .and_then(|mut file| file.write_all(&keypair.secret().to_bytes()))
+        .map_err(|err| anyhow!("Failed to write node key to disk: {err:?}"))?;
+    info!("Generated a new node key at {node_key_path:?}");
+    Ok(keypair)
+}
+
+/// Reads the ENR the node advertised when it last ran, if any.
+pub fn read_enr_from_disk(data_dir: &Path) -> anyhow::Result<Option<Enr>> {
+    let enr_path = data_dir.join(ENR_FILE_NAME);
+    if !enr_path.exists() {
+        return Ok(None);
+    }
+
+    Enr::from_str(fs::read_to_string(enr_path)?.trim())
+        .map(Some)
+        .map_err(|err| anyhow!("Failed to decode ENR: {err}"))
+}
+
+pub fn write_enr_to_disk(data_dir: &Path, enr: &Enr) -> anyhow::Result<()> {
+    fs::write(data_dir.join(ENR_FILE_NAME), enr.to_base64())
+        .map_err(|err| anyhow!("Failed to write ENR to disk: {err:?}"))
+}
+
 pub fn to_multiaddrs(enrs: &[Enr]) -> Vec<Multiaddr> {
     let mut multiaddrs: Vec<Multiaddr> = Vec::new();
     for enr in enrs {
@@ -58,3 +119,62 @@ pub fn to_multiaddrs(enrs: &[Enr]) -> Vec<Multiaddr> {
     }
     multiaddrs
 }
+
+#[cfg(test)]
+mod tests {
+    use discv5::{Enr, enr::CombinedKey};
+    use tempdir::TempDir;
+
+    use super::{NODE_KEY_FILE_NAME, load_node_key, read_enr_from_disk, write_enr_to_disk};
+
+    #[test]
+    fn test_node_key_is_reused_until_rotated() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new("test_node_key_is_reused_until_rotated")?;
+        // The data directory is created along with the key
+        let data_dir = tmp_dir.path().join("data");
+        let node_key = load_node_key(&data_dir, false)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let metadata = std::fs::metadata(data_dir.join(NODE_KEY_FILE_NAME))?;
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        }
+        assert_eq!(load_node_key(&data_dir, false)?.public(), node_key.public());
+
+        // A rotated key doesn't keep the permissions of the file it replaces
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(
+                data_dir.join(NODE_KEY_FILE_NAME),
+                std::fs::Permissions::from_mode(0o644),
+            )?;
+        }
+        let rotated_node_key = load_node_key(&data_dir, true)?;
+        assert_ne!(rotated_node_key.public(), node_key.public());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let metadata = std::fs::metadata(data_dir.join(NODE_KEY_FILE_NAME))?;
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        }
+        assert_eq!(
+            load_node_key(&data_dir, false)?.public(),
+            rotated_node_key.public()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_enr_is_read_back() -> anyhow::Result<()> {
+        let tmp_dir = TempDir::new("test_enr_is_read_back")?;
+        assert_eq!(read_enr_from_disk(tmp_dir.path())?, None);
+
+        let enr = Enr::builder()
+            .seq(5)
+            .build(&CombinedKey::generate_secp256k1())?;
+        write_enr_to_disk(tmp_dir.path(), &enr)?;
+        assert_eq!(read_enr_from_disk(tmp_dir.path())?, Some(enr));
+        Ok(())
+    }
+}
@@ -0,0 +1,357 @@
+use std::{sync::Arc, time::Duration};
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, bail, ensure};
+use libp2p::PeerId;
+use ream_chain_beacon::beacon_chain::BeaconChain;
+use ream_consensus_beacon::{blob_sidecar::BlobSidecar, electra::beacon_block::SignedBeaconBlock};
+use ream_consensus_misc::misc::compute_start_slot_at_epoch;
+use ream_executor::ReamExecutor;
+use ream_network_spec::networks::beacon_network_spec;
+use ream_p2p::{
+    channel::P2PSender,
+    network_state::{BackfillProgress, NetworkState},
+};
+use ream_storage::{
+    db::ReamDB,
+    tables::{Field, Table},
+};
+use tokio::{task::JoinHandle, time::sleep};
+use tracing::{error, info, warn};
+use tree_hash::TreeHash;
+
+use crate::{
+    block_range::{
+        peer_manager::PeerManager,
+        peer_range_downloader::{PeerBlobRangeDownloader, PeerRangeDownloader, Range},
+    },
+    verification::{
+        ProposerKeys, blob_retention_slot, verify_blobs, verify_chain_segment,
+        verify_proposer_signatures,
+    },
+};
+
+/// Number of slots requested per backfill batch
+const BACKFILL_BATCH_SLOTS: u64 = 64;
+
+/// Pause between batches, so backfill doesn't compete with forward sync and gossip for peers
+const BACKFILL_BATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+const SLEEP_DURATION: Duration = Duration::from_secs(5);
+
+/// Number of times the slots skipped as empty are downloaded again before the peer serving a
+/// batch which doesn't link up is blamed for it
+const MAX_RELINK_ATTEMPTS: usize = 3;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BackfillConfig {
+    /// Backfill down to the Electra fork instead of stopping at the blob retention boundary.
+    /// Blocks of earlier forks can't be decoded, so backfill never reaches genesis itself.
+    pub to_genesis: bool,
+    /// Verify the proposer signature of every backfilled block
+    pub verify_signatures: bool,
+}
+
+/// Downloads the blocks, and blobs within the retention window, behind the oldest block in the
+/// database.
+///
+/// Backfill walks backwards one batch at a time, checking that every block is the parent of the
+/// block after it, so only blocks on the chain of the checkpoint the node started from are stored.
+/// Blocks are stored without states, they are only served to peers and the APIs.
+pub struct BackfillSyncer {
+    pub beacon_chain: Arc<BeaconChain>,
+    pub peer_manager: PeerManager,
+    pub p2p_sender: P2PSender,
+    pub network_state: Arc<NetworkState>,
+    pub executor: ReamExecutor,
+    pub config: BackfillConfig,
+}
+
+impl BackfillSyncer {
+    pub fn new(
+        beacon_chain: Arc<BeaconChain>,
+        p2p_sender: P2PSender,
+        network_state: Arc<NetworkState>,
+        executor: ReamExecutor,
+        config: BackfillConfig,
+    ) -> Self {
+        Self {
+            beacon_chain,
+            peer_manager: PeerManager::new(network_state.clone(), p2p_sender.clone()),
+            p2p_sender,
+            network_state,
+            executor,
+            config,
+        }
+    }
+
+    pub fn start(mut self) -> JoinHandle<anyhow::Result<()>> {
+        let executor = self.executor.clone();
+        executor.spawn(async move {
+            if let Err(err) = self.run().await {
+                error!("Backfill sync failed: {err:?}");
+            }
+        })
+    }
+
+    async fn run(&mut self) -> anyhow::Result<()> {
+        let (db, current_slot) = {
+            let store = self.beacon_chain.store.lock().await;
+            (store.db.clone(), store.get_current_slot()?)
+        };
+
+        let slot_index_provider = db.slot_index_provider();
+        let (Some(mut oldest_slot), Some(oldest_root)) = (
+            slot_index_provider.get_oldest_slot()?,
+            slot_index_provider.get_oldest_root()?,
+        ) else {
+            bail!("No blocks in the database to backfill from");
+        };
+        let mut expected_root = db
+            .beacon_block_provider()
+            .get(oldest_root)?
+            .ok_or_else(|| anyhow!("Oldest block {oldest_root} is missing from the database"))?
+            .message
+            .parent_root;
+
+        let target_slot = backfill_target_slot(current_slot, self.config.to_genesis);
+        let blob_retention_slot = blob_retention_slot(current_slot);
+        // The finalized state knows every proposer before it
+        let proposer_keys = match self.config.verify_signatures {
+            true => {
+                let finalized_root = db.finalized_checkpoint_provider().get()?.root;
+                let state = db
+                    .beacon_state_provider()
+                    .get(finalized_root)?
+                    .ok_or_else(|| anyhow!("No state for the finalized block {finalized_root}"))?;
+                Some(ProposerKeys::from_state(&state))
+            }
+            false => None,
+        };
+
+        // Pin the earliest blob slot, otherwise it falls back to the oldest block, which backfill
+        // moves past blocks that have no blobs stored
+        let mut earliest_blob_slot = db.earliest_available_blob_slot()?;
+        db.earliest_blob_slot_provider()
+            .insert(earliest_blob_slot)?;
+
+        // `end_slot` only moves past `oldest_slot` over slots which peers reported as empty. If
+        // the next block doesn't link up, those slots are requested again.
+        let mut end_slot = oldest_slot;
+        let mut relink_attempts = 0;
+        loop {
+            let is_complete = end_slot <= target_slot || expected_root == B256::ZERO;
+            *self.network_state.backfill_progress.write() = Some(BackfillProgress {
+                oldest_slot,
+                target_slot,
+                is_complete,
+            });
+            if is_complete {
+                break;
+            }
+
+            let Some(peer) = self.peer_manager.fetch_idle_peer() else {
+                self.peer_manager.update_peer_set();
+                info!("No idle peers available for backfill sync.");
+                sleep(SLEEP_DURATION).await;
+                continue;
+            };
+
+            let start_slot = end_slot
+                .saturating_sub(BACKFILL_BATCH_SLOTS)
+                .max(target_slot);
+            let range = Range::new(start_slot, end_slot - start_slot);
+            let blob_range = match start_slot.max(blob_retention_slot) {
+                blobs_start_slot if blobs_start_slot < end_slot => {
+                    Some(Range::new(blobs_start_slot, end_slot - blobs_start_slot))
+                }
+                _ => None,
+            };
+
+            let result = self.download_batch(peer.peer_id, range, blob_range).await;
+            self.peer_manager.mark_peer_as_idle(&peer.peer_id);
+            let (mut blocks, blobs) = match result {
+                Ok(batch) => batch,
+                Err(err) => {
+                    // The request failed, which isn't necessarily the fault of the peer
+                    warn!(
+                        "Failed to download backfill batch {range:?} from peer {}: {err}",
+                        peer.peer_id
+                    );
+                    sleep(SLEEP_DURATION).await;
+                    continue;
+                }
+            };
+
+            match verify_batch(&blocks, &blobs, range, blob_range, expected_root) {
+                Ok(()) => {}
+                Err(err) if end_slot < oldest_slot && relink_attempts < MAX_RELINK_ATTEMPTS => {
+                    warn!(
+                        "Backfill batch {range:?} doesn't link up, retrying skipped slots: {err}"
+                    );
+                    relink_attempts += 1;
+                    end_slot = oldest_slot;
+                    continue;
+                }
+                Err(err) => {
+                    warn!(
+                        "Invalid backfill batch {range:?} from peer {}: {err}",
+                        peer.peer_id
+                    );
+                    self.peer_manager
+                        .ban_peer(&peer.peer_id, format!("Invalid backfill batch: {err}"));
+                    relink_attempts = 0;
+                    continue;
+                }
+            }
+
+            if let Some(proposer_keys) = &proposer_keys {
+                let proposer_keys = proposer_keys.clone();
+                let (returned_blocks, verification) = self
+                    .executor
+                    .spawn_blocking(move || {
+                        let verification = verify_proposer_signatures(&blocks, &proposer_keys)
+                            .and_then(|verified| {
+                                ensure!(
+                                    verified.iter().all(|verified| *verified),
+                                    "Block proposed by an unknown validator"
+                                );
+                                Ok(())
+                            });
+                        (blocks, verification)
+                    })
+                    .await?;
+                blocks = returned_blocks;
+                if let Err(err) = verification {
+                    warn!(
+                        "Invalid signature in backfill batch from peer {}: {err}",
+                        peer.peer_id
+                    );
+                    self.peer_manager.ban_peer(
+                        &peer.peer_id,
+                        format!("Invalid signature in backfill batch: {err}"),
+                    );
+                    continue;
+                }
+            }
+
+            store_batch(&db, &blocks, blobs)?;
+            if let Some(oldest_block) = blocks.first() {
+                oldest_slot = oldest_block.message.slot;
+                expected_root = oldest_block.message.parent_root;
+                relink_attempts = 0;
+            }
+            end_slot = start_slot;
+
+            if let Some(blob_range) = blob_range
+                && blob_range.start_slot < earliest_blob_slot
+            {
+                earliest_blob_slot = blob_range.start_slot;
+                db.earliest_blob_slot_provider()
+                    .insert(earliest_blob_slot)?;
+            }
+
+            info!(
+                "Backfill sync status: Oldest slot {oldest_slot}, Target slot {target_slot}, Remaining slots {}",
+                end_slot.saturating_sub(target_slot)
+            );
+            sleep(BACKFILL_BATCH_INTERVAL).await;
+        }
+
+        info!("Backfill sync completed at slot {oldest_slot}");
+        Ok(())
+    }
+
+    /// Downloads the blocks in `range` and the blobs in `blob_range` from a single peer.
+    ///
+    /// The blocks are returned sorted by slot. Blobs are only requested if a block in
+    /// `blob_range` commits to any.
+    async fn download_batch(
+        &self,
+        peer_id: PeerId,
+        range: Range,
+        blob_range: Option<Range>,
+    ) -> anyhow::Result<(Vec<SignedBeaconBlock>, Vec<BlobSidecar>)> {
+        let mut blocks = PeerRangeDownloader::start(
+            peer_id,
+            self.p2p_sender.clone(),
+            self.executor.clone(),
+            range,
+        )
+        .await???;
+        blocks.sort_by_key(|block| block.message.slot);
+
+        let expects_blobs = blob_range.is_some_and(|blob_range| {
+            blocks.iter().any(|block| {
+                block.message.slot >= blob_range.start_slot
+                    && !block.message.body.blob_kzg_commitments.is_empty()
+            })
+        });
+        let blobs = match (blob_range, expects_blobs) {
+            (Some(blob_range), true) => {
+                PeerBlobRangeDownloader::start(
+                    peer_id,
+                    self.p2p_sender.clone(),
+                    self.executor.clone(),
+                    blob_range,
+                )
+                .await???
+            }
+            _ => vec![],
+        };
+
+        Ok((blocks, blobs))
+    }
+}
+
+/// Checks that the downloaded `blocks`, sorted by slot, form a chain ending in `expected_root`,
+/// and that `blobs` are the blobs they commit to within `blob_range`.
+fn verify_batch(
+    blocks: &[SignedBeaconBlock],
+    blobs: &[BlobSidecar],
+    range: Range,
+    blob_range: Option<Range>,
+    expected_root: B256,
+) -> anyhow::Result<()> {
+    verify_chain_segment(blocks, range)?;
+    if let Some(newest_block) = blocks.last() {
+        ensure!(
+            newest_block.message.tree_hash_root() == expected_root,
+            "Block at slot {} is not the parent of the oldest stored block",
+            newest_block.message.slot
+        );
+    }
+    if let Some(blob_range) = blob_range {
+        verify_blobs(blocks, blobs, blob_range)?;
+    }
+
+    Ok(())
+}
+
+/// The slot backfill sync stops at.
+///
+/// Only Electra blocks can be decoded, so backfill never goes past the Electra fork.
+fn backfill_target_slot(current_slot: u64, to_genesis: bool) -> u64 {
+    let electra_fork_slot = compute_start_slot_at_epoch(beacon_network_spec().electra_fork_epoch);
+    match to_genesis {
+        true => electra_fork_slot,
+        false => blob_retention_slot(current_slot).max(electra_fork_slot),
+    }
+}
+
+/// Stores the blobs before the blocks, so a stored block never misses its blobs.
+fn store_batch(
+    db: &ReamDB,
+    blocks: &[SignedBeaconBlock],
+    blobs: Vec<BlobSidecar>,
+) -> anyhow::Result<()> {
+    db.blobs_and_proofs_provider().insert_blob_sidecars(blobs)?;
+
+    let batch = db.begin_write_batch()?;
+    for block in blocks {
+        batch.insert_beacon_block(block.message.tree_hash_root(), block)?;
+    }
+    batch.commit()?;
+
+    Ok(())
+}
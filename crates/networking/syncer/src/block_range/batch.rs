@@ -0,0 +1,90 @@
+use std::collections::HashSet;
+
+use libp2p::PeerId;
+use ream_consensus_beacon::{blob_sidecar::BlobSidecar, electra::beacon_block::SignedBeaconBlock};
+use ream_consensus_misc::misc::{compute_epoch_at_slot, compute_start_slot_at_epoch};
+
+use super::peer_range_downloader::Range;
+
+/// Number of times a batch is downloaded or imported before it starts over with a clean slate
+pub const MAX_BATCH_ATTEMPTS: usize = 5;
+
+/// A batch of blocks, downloaded from a single peer and verified as one chain segment.
+///
+/// Batches end on an epoch boundary, so every batch but the first covers exactly one epoch.
+pub struct Batch {
+    pub range: Range,
+    pub state: BatchState,
+    pub attempts: usize,
+    /// Peers which failed to serve this batch, they are only retried if no other peer is left
+    pub failed_peers: HashSet<PeerId>,
+}
+
+pub enum BatchState {
+    AwaitingDownload,
+    Downloading(PeerId),
+    AwaitingImport(DownloadedBatch),
+}
+
+pub struct DownloadedBatch {
+    pub peer_id: PeerId,
+    /// Blocks sorted by slot, already checked to form a chain
+    pub blocks: Vec<SignedBeaconBlock>,
+    pub blobs: Vec<BlobSidecar>,
+    /// Whether the proposer signature of the block at the same index was verified
+    pub signature_verified: Vec<bool>,
+}
+
+impl Batch {
+    /// Creates the batch starting at `start_slot`, ending at the next epoch boundary or after
+    /// `end_slot`, whichever comes first.
+    pub fn new(start_slot: u64, end_slot: u64) -> Self {
+        let epoch_end_slot = compute_start_slot_at_epoch(compute_epoch_at_slot(start_slot) + 1);
+        let count = epoch_end_slot.min(end_slot + 1) - start_slot;
+        Self {
+            range: Range::new(start_slot, count),
+            state: BatchState::AwaitingDownload,
+            attempts: 0,
+            failed_peers: HashSet::new(),
+        }
+    }
+
+    pub fn end_slot(&self) -> u64 {
+        self.range.start_slot + self.range.count
+    }
+
+    /// Puts the batch back in the download queue, remembering the peer which failed it.
+    pub fn retry(&mut self, peer_id: PeerId) {
+        self.failed_peers.insert(peer_id);
+        self.state = BatchState::AwaitingDownload;
+    }
+
+    /// Puts the batch back in the download queue, forgetting its attempts and failed peers.
+    pub fn reset(&mut self) {
+        self.attempts = 0;
+        self.failed_peers.clear();
+        self.state = BatchState::AwaitingDownload;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_consensus_misc::constants::beacon::SLOTS_PER_EPOCH;
+
+    use super::Batch;
+
+    #[test]
+    fn test_batches_are_epoch_aligned() {
+        // The first batch only covers the rest of the epoch
+        let batch = Batch::new(SLOTS_PER_EPOCH + 5, 10 * SLOTS_PER_EPOCH);
+        assert_eq!(batch.range.start_slot, SLOTS_PER_EPOCH + 5);
+        assert_eq!(batch.end_slot(), 2 * SLOTS_PER_EPOCH);
+
+        let batch = Batch::new(batch.end_slot(), 10 * SLOTS_PER_EPOCH);
+        assert_eq!(batch.range.count, SLOTS_PER_EPOCH);
+
+        // The last batch stops at the target slot
+        let batch = Batch::new(2 * SLOTS_PER_EPOCH, 2 * SLOTS_PER_EPOCH + 3);
+        assert_eq!(batch.range.count, 4);
+    }
+}
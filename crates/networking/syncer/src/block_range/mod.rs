@@ -1,58 +1,70 @@
-mod block_cache;
-mod peer_manager;
-mod peer_range_downloader;
+pub mod batch;
+pub mod peer_manager;
+pub mod peer_range_downloader;
 
 use std::{
-    pin::Pin,
+    collections::{BTreeMap, HashSet},
     sync::Arc,
-    task::{Context, Poll},
     time::Duration,
 };
 
 use alloy_primitives::B256;
 use anyhow::{anyhow, bail};
-use block_cache::{BlockAndBlobBundle, BlockCache, DataToFetch};
-use futures::task::noop_waker;
+use batch::{Batch, BatchState, DownloadedBatch, MAX_BATCH_ATTEMPTS};
 use libp2p::PeerId;
 use peer_manager::PeerManager;
-use peer_range_downloader::{PeerBlobIdentifierDownloader, PeerRootsDownloader};
+use peer_range_downloader::{DownloadError, PeerBlobRangeDownloader, PeerRangeDownloader, Range};
 use ream_chain_beacon::beacon_chain::BeaconChain;
-use ream_consensus_beacon::{
-    blob_sidecar::{BlobIdentifier, BlobSidecar},
-    electra::beacon_block::SignedBeaconBlock,
-};
 use ream_executor::ReamExecutor;
-use ream_p2p::{
-    channel::P2PMessage, network_state::NetworkState, req_resp::MAX_CONCURRENT_REQUESTS,
+use ream_fork_choice::handlers::InvalidBlockError;
+use ream_p2p::{channel::P2PSender, network_state::NetworkState};
+use ream_storage::{db::ReamDB, tables::Table};
+use tokio::{
+    sync::mpsc::{self, UnboundedSender},
+    task::JoinHandle,
+    time::sleep,
 };
-use ream_storage::tables::Table;
-use tokio::{sync::mpsc::UnboundedSender, task::JoinHandle, time::sleep};
 use tracing::{info, warn};
+use tree_hash::TreeHash;
 
-use crate::block_range::peer_range_downloader::{PeerRangeDownloader, Range};
+use crate::verification::{
+    ProposerKeys, blob_retention_slot, verify_blobs, verify_chain_segment,
+    verify_proposer_signatures,
+};
 
-const MAX_BLOBS_PER_REQUEST: usize = 6;
-const MAX_BLOCKS_PER_REQUEST: u64 = 10;
+/// Number of batches downloaded ahead of the oldest batch which isn't imported yet
+const MAX_BATCHES_IN_FLIGHT: usize = 8;
 const SLEEP_DURATION: Duration = Duration::from_secs(5);
 
+struct BatchResult {
+    start_slot: u64,
+    peer_id: PeerId,
+    result: Result<DownloadedBatch, DownloadError>,
+}
+
+/// Syncs forward from the newest block in the database up to the finalized slot of our peers.
+///
+/// The range is split into epoch-sized batches, which are downloaded concurrently from different
+/// peers and verified as a whole, including their proposer signatures, before being imported in
+/// order.
 pub struct BlockRangeSyncer {
     pub beacon_chain: Arc<BeaconChain>,
     pub peer_manager: PeerManager,
-    pub p2p_sender: UnboundedSender<P2PMessage>,
+    pub p2p_sender: P2PSender,
     pub executor: ReamExecutor,
 }
 
 impl BlockRangeSyncer {
     pub fn new(
         beacon_chain: Arc<BeaconChain>,
-        p2p_sender: UnboundedSender<P2PMessage>,
+        p2p_sender: P2PSender,
         network_state: Arc<NetworkState>,
         executor: ReamExecutor,
     ) -> Self {
         Self {
             beacon_chain,
+            peer_manager: PeerManager::new(network_state, p2p_sender.clone()),
             p2p_sender,
-            peer_manager: PeerManager::new(network_state),
             executor,
         }
     }
@@ -76,391 +88,371 @@ impl BlockRangeSyncer {
     pub fn start(mut self) -> JoinHandle<anyhow::Result<anyhow::Result<BlockRangeSyncer>>> {
         let executor = self.executor.clone();
         executor.spawn(async move {
-            let Some(latest_synced_root) = self
-                .beacon_chain
-                .store
-                .lock()
-                .await
-                .db
-                .slot_index_provider()
-                .get_highest_root()
-                .map_err(|err| anyhow!("Failed to get highest root: {err}"))?
-            else {
-                bail!("No synced root found in the database");
-            };
+            self.run().await?;
+            Ok(self)
+        })
+    }
 
-            let Some(latest_synced_slot) = self
-                .beacon_chain
-                .store
-                .lock()
-                .await
-                .db
-                .slot_index_provider()
+    async fn run(&mut self) -> anyhow::Result<()> {
+        let (db, current_slot) = {
+            let store = self.beacon_chain.store.lock().await;
+            (store.db.clone(), store.get_current_slot()?)
+        };
+
+        let slot_index_provider = db.slot_index_provider();
+        let (Some(mut imported_root), Some(mut imported_slot)) = (
+            slot_index_provider
+                .get_highest_root()
+                .map_err(|err| anyhow!("Failed to get highest root: {err}"))?,
+            slot_index_provider
                 .get_highest_slot()
-                .map_err(|err| anyhow!("Failed to get highest slot: {err}"))?
-            else {
-                bail!("No synced slot found in the database");
+                .map_err(|err| anyhow!("Failed to get highest slot: {err}"))?,
+        ) else {
+            bail!("No synced block found in the database");
+        };
+
+        // The state we sync from knows the proposers of most blocks, the rest are verified by
+        // the state transition
+        let proposer_keys = db
+            .beacon_state_provider()
+            .get(imported_root)?
+            .map(|state| ProposerKeys::from_state(&state));
+        let blob_retention_slot = blob_retention_slot(current_slot);
+
+        let (result_sender, mut result_receiver) = mpsc::unbounded_channel();
+        let mut batches: BTreeMap<u64, Batch> = BTreeMap::new();
+        let mut next_batch_slot = imported_slot + 1;
+        loop {
+            self.peer_manager.update_peer_set();
+            let Some(target_slot) = self.peer_manager.finalized_slot() else {
+                warn!("No peers available to determine finalized slot, retrying...");
+                sleep(SLEEP_DURATION).await;
+                continue;
             };
 
-            // phase 1: download majority of blocks from ranges
-            let mut block_cache =
-                BlockCache::new(latest_synced_root, latest_synced_slot);
-            let mut task_handles = vec![];
-            loop {
-                poll_ready_tasks(&mut task_handles, &mut block_cache, &mut self.peer_manager)?;
-
-                let finalized_slot = match self.peer_manager.finalized_slot() {
-                    Some(finalized_slot) => finalized_slot,
-                    None => {
-                        warn!("No peers available to determine finalized slot, retrying...");
-                        sleep(SLEEP_DURATION).await;
-                        self.peer_manager.update_peer_set();
-                        continue;
-                    }
+            while batches.len() < MAX_BATCHES_IN_FLIGHT && next_batch_slot <= target_slot {
+                let batch = Batch::new(next_batch_slot, target_slot);
+                next_batch_slot = batch.end_slot();
+                batches.insert(batch.range.start_slot, batch);
+            }
+
+            self.dispatch_batches(
+                &mut batches,
+                &result_sender,
+                proposer_keys.as_ref(),
+                blob_retention_slot,
+            );
+
+            // Import every downloaded batch at the front of the queue
+            while batches
+                .first_key_value()
+                .is_some_and(|(_, batch)| matches!(batch.state, BatchState::AwaitingImport(_)))
+            {
+                let Some((start_slot, mut batch)) = batches.pop_first() else {
+                    break;
+                };
+                let BatchState::AwaitingImport(downloaded) =
+                    std::mem::replace(&mut batch.state, BatchState::AwaitingDownload)
+                else {
+                    unreachable!("Only downloaded batches are imported");
                 };
+                let peer_id = downloaded.peer_id;
 
-                let data_to_fetch = block_cache.data_to_fetch(finalized_slot);
-                info!(
-                    "Forward sync status: Downloaded Blocks {}, Downloaded Blobs {}/{}, Stage {data_to_fetch}",
-                    block_cache.block_count(),
-                    block_cache.downloaded_blob_count(),
-                    block_cache.blob_count(),
-                );
-
-                match data_to_fetch {
-                    DataToFetch::BlockRange(range) => {
-                        let Some(peer) = self.peer_manager.fetch_idle_peer() else {
-                            self.peer_manager.update_peer_set();
-                            info!("No idle peers available for block range sync.");
-                            sleep(SLEEP_DURATION).await;
-                            continue;
-                        };
-
-                        task_handles.push(DownloadTask::new_block_range(
-                            PeerRangeDownloader::start(
-                                peer.peer_id,
-                                self.p2p_sender.clone(),
-                                self.executor.clone(),
-                                range,
-                            ),
-                            range,
-                            peer.peer_id,
-                        ));
+                match self.import_batch(&db, &downloaded, imported_root).await {
+                    Ok(Some((root, slot))) => {
+                        imported_root = root;
+                        imported_slot = slot;
                     }
-                    DataToFetch::MissingBlockRoots(block_roots) => {
-                        for block_roots_chunk in block_roots.chunks(MAX_CONCURRENT_REQUESTS) {
-                            let Some(peer) = self.peer_manager.fetch_idle_peer() else {
-                                self.peer_manager.update_peer_set();
-                                info!("No idle peers available for block roots sync.");
-                                sleep(SLEEP_DURATION).await;
-                                break;
-                            };
-                            block_cache.extend_block_roots_in_progress(block_roots_chunk);
-
-                            task_handles.push(DownloadTask::new_block_roots(
-                                PeerRootsDownloader::start(
-                                    peer.peer_id,
-                                    self.p2p_sender.clone(),
-                                    self.executor.clone(),
-                                    block_roots_chunk.to_vec(),
-                                ),
-                                block_roots_chunk.to_vec(),
-                                peer.peer_id,
-                            ));
-                        }
+                    Ok(None) => {}
+                    Err(DownloadError::Invalid(err)) if start_slot > imported_slot + 1 => {
+                        // The batches in between were empty, their peers may have withheld
+                        // blocks, so everything after the last imported block is downloaded again
+                        warn!(
+                            "Batch {:?} doesn't link to the block at slot {imported_slot}, restarting sync from there: {err}",
+                            batch.range
+                        );
+                        batches.clear();
+                        next_batch_slot = imported_slot + 1;
                     }
-                    DataToFetch::MissingBlobIdentifiers(blob_identifiers) => {
-                        for blob_identifiers_chunk in blob_identifiers.chunks(MAX_BLOBS_PER_REQUEST) {
-                            let Some(peer) = self.peer_manager.fetch_idle_peer() else {
-                                self.peer_manager.update_peer_set();
-                                info!("No idle peers available for blob sync. {}", self.peer_manager.peer_counts());
-                                sleep(SLEEP_DURATION).await;
-                                break;
-                            };
-
-                            block_cache.extend_blob_identifiers_in_progress(blob_identifiers_chunk);
-
-                            task_handles.push(DownloadTask::new_blob_identifiers(
-                                PeerBlobIdentifierDownloader::start(
-                                    peer.peer_id,
-                                    self.p2p_sender.clone(),
-                                    self.executor.clone(),
-                                    blob_identifiers_chunk.to_vec(),
-                                ),
-                                blob_identifiers_chunk.to_vec(),
-                                peer.peer_id,
-                            ));
-                        }
+                    Err(DownloadError::Import(err)) if batch.attempts < MAX_BATCH_ATTEMPTS => {
+                        // Nothing is known to be wrong with the batch, so it is kept and imported
+                        // again after a while. Each import counts as an attempt, so a batch that
+                        // never imports is eventually downloaded again.
+                        warn!("Failed to import batch {:?}, retrying: {err}", batch.range);
+                        batch.attempts += 1;
+                        batch.state = BatchState::AwaitingImport(downloaded);
+                        batches.insert(start_slot, batch);
+                        break;
                     }
-                    DataToFetch::DownloadsInProgress => {
-                        info!("Waiting for ongoing downloads to complete... {}", self.peer_manager.peer_counts());
-                        sleep(Duration::from_secs(10)).await;
+                    Err(err) => {
+                        self.handle_batch_failure(&mut batch, peer_id, err);
+                        batches.insert(start_slot, batch);
                     }
-                    DataToFetch::Finished => break,
                 }
             }
 
-            info!("Block range sync completed a segment successfully with {} blocks and {} blobs.",
-                block_cache.block_count(),
-                block_cache.downloaded_blob_count(),
+            info!(
+                "Forward sync status: Imported slot {imported_slot}, Target slot {target_slot}, Batches in flight {}, {}",
+                batches.len(),
+                self.peer_manager.peer_counts()
             );
 
-            // execute all the blocks downloaded
-            for BlockAndBlobBundle { block, blobs } in block_cache.get_blocks_and_blobs()?  {
-                info!("Processing block with slot {}",
-                    block.message.slot,
-                );
-                for (blob_identifier, blob_sidecar) in blobs {
-                    if let Err(err) = self
-                        .beacon_chain
-                        .store
-                        .lock()
-                        .await
-                        .db
-                        .blobs_and_proofs_provider()
-                        .insert(blob_identifier, blob_sidecar.into())
-                    {
-                        warn!("Failed to insert blob into database: {err}");
+            if batches.is_empty() && next_batch_slot > target_slot {
+                break;
+            }
+
+            tokio::select! {
+                Some(batch_result) = result_receiver.recv() => {
+                    self.handle_batch_result(&mut batches, batch_result);
+                    while let Ok(batch_result) = result_receiver.try_recv() {
+                        self.handle_batch_result(&mut batches, batch_result);
                     }
                 }
-
-                self.beacon_chain.process_block(block).await?;
+                _ = sleep(SLEEP_DURATION) => {}
             }
+        }
 
-            info!("All blocks processed successfully.");
-
-            Ok(self)
-        })
+        info!("Forward sync reached slot {imported_slot}");
+        Ok(())
     }
-}
 
-pub enum DownloadTask {
-    BlockRange {
-        handle: JoinHandle<anyhow::Result<anyhow::Result<Vec<SignedBeaconBlock>>>>,
-        range: Range,
-        peer_id: PeerId,
-    },
-    BlockRoots {
-        handle: JoinHandle<anyhow::Result<anyhow::Result<Vec<SignedBeaconBlock>>>>,
-        roots: Vec<B256>,
-        peer_id: PeerId,
-    },
-    BlobIdentifiers {
-        handle: JoinHandle<anyhow::Result<anyhow::Result<Vec<BlobSidecar>>>>,
-        blob_identifiers: Vec<BlobIdentifier>,
-        peer_id: PeerId,
-    },
-}
+    /// Starts a download for every batch waiting for one, as long as there are idle peers.
+    fn dispatch_batches(
+        &mut self,
+        batches: &mut BTreeMap<u64, Batch>,
+        result_sender: &UnboundedSender<BatchResult>,
+        proposer_keys: Option<&ProposerKeys>,
+        blob_retention_slot: u64,
+    ) {
+        for batch in batches.values_mut() {
+            if !matches!(batch.state, BatchState::AwaitingDownload) {
+                continue;
+            }
 
-impl DownloadTask {
-    pub fn new_block_range(
-        handle: JoinHandle<anyhow::Result<anyhow::Result<Vec<SignedBeaconBlock>>>>,
-        range: Range,
-        peer_id: PeerId,
-    ) -> Self {
-        DownloadTask::BlockRange {
-            handle,
-            range,
-            peer_id,
+            // Retry a peer which failed the batch before, if it is the only one left
+            let peer = match self
+                .peer_manager
+                .fetch_idle_peer_for_slot(batch.range.start_slot, &batch.failed_peers)
+            {
+                Some(peer) => Some(peer),
+                None => self
+                    .peer_manager
+                    .fetch_idle_peer_for_slot(batch.range.start_slot, &HashSet::new()),
+            };
+            let Some(peer) = peer else {
+                continue;
+            };
+
+            batch.attempts += 1;
+            batch.state = BatchState::Downloading(peer.peer_id);
+            let range = batch.range;
+            let blob_range = match range.start_slot.max(blob_retention_slot) {
+                blobs_start_slot if blobs_start_slot < batch.end_slot() => Some(Range::new(
+                    blobs_start_slot,
+                    batch.end_slot() - blobs_start_slot,
+                )),
+                _ => None,
+            };
+
+            let p2p_sender = self.p2p_sender.clone();
+            let executor = self.executor.clone();
+            let proposer_keys = proposer_keys.cloned();
+            let result_sender = result_sender.clone();
+            self.executor.spawn(async move {
+                let result = download_batch(
+                    peer.peer_id,
+                    p2p_sender,
+                    executor,
+                    range,
+                    blob_range,
+                    proposer_keys,
+                )
+                .await;
+                let _ = result_sender.send(BatchResult {
+                    start_slot: range.start_slot,
+                    peer_id: peer.peer_id,
+                    result,
+                });
+            });
         }
     }
 
-    pub fn new_block_roots(
-        handle: JoinHandle<anyhow::Result<anyhow::Result<Vec<SignedBeaconBlock>>>>,
-        roots: Vec<B256>,
-        peer_id: PeerId,
-    ) -> Self {
-        DownloadTask::BlockRoots {
-            handle,
-            roots,
+    /// Records the result of a batch download, unless sync moved on from the batch meanwhile.
+    fn handle_batch_result(
+        &mut self,
+        batches: &mut BTreeMap<u64, Batch>,
+        batch_result: BatchResult,
+    ) {
+        let BatchResult {
+            start_slot,
             peer_id,
+            result,
+        } = batch_result;
+        self.peer_manager.mark_peer_as_idle(&peer_id);
+
+        let Some(batch) = batches.get_mut(&start_slot) else {
+            return;
+        };
+        if !matches!(batch.state, BatchState::Downloading(downloading_peer) if downloading_peer == peer_id)
+        {
+            return;
+        }
+        match result {
+            Ok(downloaded) => batch.state = BatchState::AwaitingImport(downloaded),
+            Err(err) => self.handle_batch_failure(batch, peer_id, err),
         }
     }
 
-    pub fn new_blob_identifiers(
-        handle: JoinHandle<anyhow::Result<anyhow::Result<Vec<BlobSidecar>>>>,
-        blob_identifiers: Vec<BlobIdentifier>,
-        peer_id: PeerId,
-    ) -> Self {
-        DownloadTask::BlobIdentifiers {
-            handle,
-            blob_identifiers,
-            peer_id,
+    /// Penalizes the peer that served a failed batch and queues the batch for another peer.
+    ///
+    /// A batch which failed too often starts over, so any peer can serve it again rather than
+    /// stopping sync.
+    fn handle_batch_failure(&mut self, batch: &mut Batch, peer_id: PeerId, err: DownloadError) {
+        warn!(
+            "Batch {:?} from peer {peer_id} failed on attempt {}: {err}",
+            batch.range, batch.attempts
+        );
+        if let DownloadError::Invalid(err) = &err {
+            self.peer_manager
+                .ban_peer(&peer_id, format!("Served an invalid batch: {err}"));
+        }
+        if batch.attempts >= MAX_BATCH_ATTEMPTS {
+            warn!(
+                "Batch {:?} failed {} times, downloading it from scratch",
+                batch.range, batch.attempts
+            );
+            batch.reset();
+            return;
         }
+        batch.retry(peer_id);
     }
-}
 
-fn poll_ready_tasks(
-    tasks: &mut Vec<DownloadTask>,
-    block_cache: &mut BlockCache,
-    peer_manager: &mut PeerManager,
-) -> anyhow::Result<()> {
-    let waker = noop_waker();
-    let mut context = Context::from_waker(&waker);
-    let mut indexes_to_remove = vec![];
-
-    for index in (0..tasks.len()).rev() {
-        let Some(task) = tasks.get_mut(index) else {
-            bail!("Task handle not found at index {index}");
+    /// Imports a downloaded batch on top of `parent_root`.
+    ///
+    /// Returns the root and slot of the last block of the batch, or `None` if the batch was empty.
+    async fn import_batch(
+        &self,
+        db: &ReamDB,
+        downloaded: &DownloadedBatch,
+        parent_root: B256,
+    ) -> Result<Option<(B256, u64)>, DownloadError> {
+        let DownloadedBatch {
+            blocks,
+            blobs,
+            signature_verified,
+            ..
+        } = downloaded;
+        let Some(first_block) = blocks.first() else {
+            return Ok(None);
         };
+        if first_block.message.parent_root != parent_root {
+            return Err(DownloadError::Invalid(anyhow!(
+                "Block at slot {} is not a child of the last imported block",
+                first_block.message.slot
+            )));
+        }
 
-        match task {
-            DownloadTask::BlockRange {
-                handle,
-                range,
-                peer_id,
-            } => {
-                let pinned = Pin::new(handle);
-
-                match pinned.poll(&mut context) {
-                    Poll::Ready(Ok(blocks_result)) => {
-                        indexes_to_remove.push(index);
-                        peer_manager.mark_peer_as_idle(peer_id);
-                        let blocks = match blocks_result {
-                            Ok(blocks) => blocks,
-                            Err(err) => {
-                                warn!("Failed to fetch blocks from peer: {err:?}");
-                                block_cache.push_retry_range(*range);
-                                continue;
-                            }
-                        };
-
-                        let blocks = match blocks {
-                            Ok(blocks) => blocks,
-                            Err(err) => {
-                                block_cache.push_retry_range(*range);
-                                peer_manager
-                                    .ban_peer(peer_id, format!("Failed to fetch blocks: {err:?}"));
-                                continue;
-                            }
-                        };
-
-                        if blocks.is_empty() {
-                            warn!("Received empty block range from peer: {peer_id}");
-                            block_cache.push_retry_range(*range);
-                            peer_manager
-                                .ban_peer(peer_id, "Received empty block range".to_string());
-                            continue;
-                        }
-
-                        if let Err(err) = block_cache.add_blocks(blocks, true) {
-                            warn!("Failed to add downloaded blocks to cache: {err:?}");
-                            block_cache.push_retry_range(*range);
-                        }
+        // Blobs are stored first, so the blocks pass the data availability check
+        db.blobs_and_proofs_provider()
+            .insert_blob_sidecars(blobs.iter().cloned())
+            .map_err(|err| DownloadError::Import(anyhow!("Failed to store blobs: {err}")))?;
+
+        let mut last_imported = None;
+        for (block, signature_verified) in blocks.iter().zip(signature_verified.iter().copied()) {
+            let block_root = block.message.tree_hash_root();
+            let block_slot = block.message.slot;
+            // A previous attempt, or gossip, may have imported part of the batch already
+            let is_known = db
+                .beacon_block_provider()
+                .get(block_root)
+                .map_err(|err| DownloadError::Import(anyhow!("Failed to read block: {err}")))?
+                .is_some();
+            if !is_known {
+                let result = match signature_verified {
+                    true => {
+                        self.beacon_chain
+                            .process_signature_verified_block(block.clone())
+                            .await
                     }
-                    Poll::Ready(Err(err)) => {
-                        warn!("Forward fill task failed: {err}");
-                        indexes_to_remove.push(index);
-                    }
-                    Poll::Pending => {}
-                }
-            }
-            DownloadTask::BlockRoots {
-                handle,
-                roots,
-                peer_id,
-            } => {
-                let pinned = Pin::new(handle);
-
-                match pinned.poll(&mut context) {
-                    Poll::Ready(Ok(blocks_result)) => {
-                        indexes_to_remove.push(index);
-                        block_cache.remove_block_roots_in_progress(roots);
-                        peer_manager.mark_peer_as_idle(peer_id);
-                        let blocks = match blocks_result {
-                            Ok(blocks) => blocks,
-                            Err(err) => {
-                                warn!("Failed to fetch blocks from peer: {err:?}");
-                                continue;
-                            }
-                        };
-
-                        let blocks = match blocks {
-                            Ok(blocks) => blocks,
-                            Err(err) => {
-                                warn!("Failed to fetch blocks from roots: {err:?}");
-                                peer_manager.ban_peer(
-                                    peer_id,
-                                    format!("Failed to fetch blocks from receipts: {err:?}"),
-                                );
-                                continue;
-                            }
-                        };
-
-                        if blocks.is_empty() {
-                            warn!("Received empty block roots from peer: {peer_id}");
-                            peer_manager
-                                .ban_peer(peer_id, "Received empty block roots".to_string());
-                            continue;
-                        }
-
-                        if let Err(err) = block_cache.add_blocks(blocks, false) {
-                            warn!("Failed to add downloaded blocks to cache: {err:?}");
-                        }
-                    }
-                    Poll::Ready(Err(err)) => {
-                        warn!("Forward fill task failed: {err}");
-                        indexes_to_remove.push(index);
-                    }
-                    Poll::Pending => {}
-                }
-            }
-            DownloadTask::BlobIdentifiers {
-                handle,
-                blob_identifiers,
-                peer_id,
-            } => {
-                let pinned = Pin::new(handle);
-
-                match pinned.poll(&mut context) {
-                    Poll::Ready(Ok(blob_sidecars_result)) => {
-                        indexes_to_remove.push(index);
-                        block_cache.remove_blob_identifiers_in_progress(blob_identifiers);
-                        peer_manager.mark_peer_as_idle(peer_id);
-                        let blob_sidecars = match blob_sidecars_result {
-                            Ok(blob_sidecars) => blob_sidecars,
-                            Err(err) => {
-                                warn!("Failed to fetch blobs from peer: {err:?}");
-                                continue;
-                            }
-                        };
-
-                        let blob_sidecars = match blob_sidecars {
-                            Ok(blob_sidecars) => blob_sidecars,
-                            Err(err) => {
-                                warn!("Failed to fetch blobs from identifiers: {err:?}");
-                                peer_manager.ban_peer(
-                                    peer_id,
-                                    format!("Failed to fetch blobs from identifiers: {err:?}"),
-                                );
-                                continue;
-                            }
-                        };
-
-                        if blob_sidecars.is_empty() {
-                            warn!("Received empty blob identifiers from peer: {peer_id}");
-                            peer_manager
-                                .ban_peer(peer_id, "Received empty blob identifiers".to_string());
-                            continue;
-                        }
-
-                        if let Err(err) = block_cache.add_blobs(blob_sidecars) {
-                            warn!("Failed to add downloaded blobs to cache: {err:?}");
-                        }
-                    }
-                    Poll::Ready(Err(err)) => {
-                        warn!("Forward fill task failed: {err}");
-                        indexes_to_remove.push(index);
+                    false => self.beacon_chain.process_block(block.clone()).await,
+                };
+                // Only a block failing consensus validation is the fault of the peer
+                result.map_err(|err| {
+                    let is_invalid = err.is::<InvalidBlockError>();
+                    let err = anyhow!("Failed to import block at slot {block_slot}: {err}");
+                    match is_invalid {
+                        true => DownloadError::Invalid(err),
+                        false => DownloadError::Import(err),
                     }
-                    Poll::Pending => {}
-                }
+                })?;
             }
+            last_imported = Some((block_root, block_slot));
         }
-    }
 
-    for index in indexes_to_remove {
-        tasks.remove(index);
+        Ok(last_imported)
     }
+}
 
-    Ok(())
+/// Downloads and verifies the blocks in `range`, and the blobs in `blob_range`, from one peer.
+async fn download_batch(
+    peer_id: PeerId,
+    p2p_sender: P2PSender,
+    executor: ReamExecutor,
+    range: Range,
+    blob_range: Option<Range>,
+    proposer_keys: Option<ProposerKeys>,
+) -> Result<DownloadedBatch, DownloadError> {
+    let mut blocks =
+        PeerRangeDownloader::start(peer_id, p2p_sender.clone(), executor.clone(), range)
+            .await
+            .map_err(|err| DownloadError::Download(err.into()))?
+            .and_then(|result| result)
+            .map_err(DownloadError::Download)?;
+    blocks.sort_by_key(|block| block.message.slot);
+    verify_chain_segment(&blocks, range).map_err(DownloadError::Invalid)?;
+
+    let expects_blobs = blob_range.is_some_and(|blob_range| {
+        blocks.iter().any(|block| {
+            block.message.slot >= blob_range.start_slot
+                && !block.message.body.blob_kzg_commitments.is_empty()
+        })
+    });
+    let blobs = match (blob_range, expects_blobs) {
+        (Some(blob_range), true) => {
+            let blobs =
+                PeerBlobRangeDownloader::start(peer_id, p2p_sender, executor.clone(), blob_range)
+                    .await
+                    .map_err(|err| DownloadError::Download(err.into()))?
+                    .and_then(|result| result)
+                    .map_err(DownloadError::Download)?;
+            verify_blobs(&blocks, &blobs, blob_range).map_err(DownloadError::Invalid)?;
+            blobs
+        }
+        _ => vec![],
+    };
+
+    // Verifying the signatures of a whole batch is too slow for the async runtime
+    let (blocks, signature_verified) = match proposer_keys {
+        Some(proposer_keys) => {
+            let (blocks, signature_verified) = executor
+                .spawn_blocking(move || {
+                    let signature_verified = verify_proposer_signatures(&blocks, &proposer_keys);
+                    (blocks, signature_verified)
+                })
+                .await
+                .map_err(|err| DownloadError::Download(err.into()))?;
+            (blocks, signature_verified.map_err(DownloadError::Invalid)?)
+        }
+        None => {
+            let signature_verified = vec![false; blocks.len()];
+            (blocks, signature_verified)
+        }
+    };
+
+    Ok(DownloadedBatch {
+        peer_id,
+        blocks,
+        blobs,
+        signature_verified,
+    })
 }
@@ -1,8 +1,17 @@
-use std::{collections::HashMap, sync::Arc, time::Instant};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Instant,
+};
 
 use libp2p::PeerId;
 use ream_consensus_misc::constants::beacon::SLOTS_PER_EPOCH;
-use ream_p2p::{network_state::NetworkState, peer::CachedPeer};
+use ream_p2p::{
+    channel::P2PSender,
+    network_state::NetworkState,
+    peer::CachedPeer,
+    scoring::{BAN_DURATION, PeerAction},
+};
 use tracing::warn;
 
 #[derive(Debug, Clone)]
@@ -19,32 +28,40 @@ pub struct PeerInfo {
 
 pub struct PeerManager {
     network_state: Arc<NetworkState>,
+    p2p_sender: P2PSender,
     peers: HashMap<PeerId, PeerInfo>,
+    /// Peers which sent invalid data, skipped for syncing until `BAN_DURATION` has passed
     banned_peers: HashMap<PeerId, Instant>,
-    ban_reasons: HashMap<PeerId, String>,
 }
 
 impl PeerManager {
-    pub fn new(network_state: Arc<NetworkState>) -> Self {
+    pub fn new(network_state: Arc<NetworkState>, p2p_sender: P2PSender) -> Self {
         Self {
             network_state,
+            p2p_sender,
             peers: HashMap::new(),
             banned_peers: HashMap::new(),
-            ban_reasons: HashMap::new(),
         }
     }
 
     pub fn update_peer_set(&mut self) {
+        self.banned_peers
+            .retain(|_, banned_at| banned_at.elapsed() < BAN_DURATION);
+
         let connected_peers = self.network_state.connected_peers();
         for peer in &connected_peers {
             if self.banned_peers.contains_key(&peer.peer_id) {
                 continue;
             }
 
-            self.peers.entry(peer.peer_id).or_insert_with(|| PeerInfo {
-                peer: peer.clone(),
-                peer_status: PeerStatus::Idle,
-            });
+            // Keep the download status, but refresh the status the peer last reported
+            self.peers
+                .entry(peer.peer_id)
+                .and_modify(|peer_info| peer_info.peer = peer.clone())
+                .or_insert_with(|| PeerInfo {
+                    peer: peer.clone(),
+                    peer_status: PeerStatus::Idle,
+                });
         }
 
         // Remove disconnected peers
@@ -52,15 +69,14 @@ impl PeerManager {
             .retain(|peer_id, _| connected_peers.iter().any(|peer| peer.peer_id == *peer_id));
     }
 
-    /// Bans a peer
+    /// Bans a peer from syncing and penalizes its score
     pub fn ban_peer(&mut self, peer_id: &PeerId, reason: String) {
-        self.ban_reasons.insert(*peer_id, reason);
-        if let Some(peer_info) = self.peers.remove(peer_id) {
-            self.banned_peers
-                .insert(peer_info.peer.peer_id, Instant::now());
-        } else {
+        self.p2p_sender
+            .report_peer(*peer_id, PeerAction::LowToleranceError, &reason);
+        if self.peers.remove(peer_id).is_none() {
             warn!("Attempted to ban a peer that is not in the peer set: {peer_id}");
         }
+        self.banned_peers.insert(*peer_id, Instant::now());
     }
 
     /// Fetches an idle peer from the peer set.
@@ -76,6 +92,28 @@ impl PeerManager {
         None
     }
 
+    /// Fetches an idle peer which claims to have blocks up to `min_head_slot`, skipping the
+    /// peers in `excluded`.
+    ///
+    /// Will set the peer status to `Downloading` if a peer is found.
+    pub fn fetch_idle_peer_for_slot(
+        &mut self,
+        min_head_slot: u64,
+        excluded: &HashSet<PeerId>,
+    ) -> Option<CachedPeer> {
+        let peer_info = self.peers.values_mut().find(|peer_info| {
+            matches!(peer_info.peer_status, PeerStatus::Idle)
+                && !excluded.contains(&peer_info.peer.peer_id)
+                && peer_info
+                    .peer
+                    .status
+                    .as_ref()
+                    .is_some_and(|status| status.head_slot >= min_head_slot)
+        })?;
+        peer_info.peer_status = PeerStatus::Downloading;
+        Some(peer_info.peer.clone())
+    }
+
     pub fn peer_counts(&self) -> String {
         let total_peers = self.peers.len();
         let idle_peers = self
@@ -1,3 +1,5 @@
+use std::fmt::Display;
+
 use alloy_primitives::B256;
 use anyhow::bail;
 use libp2p::PeerId;
@@ -7,14 +9,11 @@ use ream_consensus_beacon::{
 };
 use ream_executor::ReamExecutor;
 use ream_p2p::{
-    channel::{P2PCallbackResponse, P2PMessage, P2PRequest},
+    channel::{P2PCallbackResponse, P2PRequest, P2PSender},
     req_resp::messages::ResponseMessage,
 };
 use ssz::Encode;
-use tokio::{
-    sync::mpsc::{self, UnboundedSender},
-    task::JoinHandle,
-};
+use tokio::{sync::mpsc, task::JoinHandle};
 use tracing::info;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -29,12 +28,34 @@ impl Range {
     }
 }
 
+/// Why a download failed, which decides what happens to the peer that served it.
+#[derive(Debug)]
+pub enum DownloadError {
+    /// The request failed or timed out, the request is retried on another peer
+    Download(anyhow::Error),
+    /// The peer served data which failed verification or import, the peer is banned
+    Invalid(anyhow::Error),
+    /// The batch couldn't be imported for a local reason, like the execution engine being
+    /// offline, so the import is retried later without blaming the peer
+    Import(anyhow::Error),
+}
+
+impl Display for DownloadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DownloadError::Download(err) => write!(f, "Download failed: {err}"),
+            DownloadError::Invalid(err) => write!(f, "Invalid data: {err}"),
+            DownloadError::Import(err) => write!(f, "Import failed: {err}"),
+        }
+    }
+}
+
 pub struct PeerRangeDownloader;
 
 impl PeerRangeDownloader {
     pub fn start(
         peer_id: PeerId,
-        p2p_sender: UnboundedSender<P2PMessage>,
+        p2p_sender: P2PSender,
         executor: ReamExecutor,
         range: Range,
     ) -> JoinHandle<anyhow::Result<anyhow::Result<Vec<SignedBeaconBlock>>>> {
@@ -42,12 +63,12 @@ impl PeerRangeDownloader {
             let mut beacon_blocks = vec![];
             let (callback, mut rx) = mpsc::channel(100);
             p2p_sender
-                .send(P2PMessage::Request(P2PRequest::BlockRange {
+                .send_request(P2PRequest::BlockRange {
                     peer_id,
                     start: range.start_slot,
                     count: range.count,
                     callback,
-                }))
+                })
                 .expect("Failed to send block range request");
 
             while let Some(response) = rx.recv().await {
@@ -83,12 +104,61 @@ impl PeerRangeDownloader {
     }
 }
 
+pub struct PeerBlobRangeDownloader;
+
+impl PeerBlobRangeDownloader {
+    pub fn start(
+        peer_id: PeerId,
+        p2p_sender: P2PSender,
+        executor: ReamExecutor,
+        range: Range,
+    ) -> JoinHandle<anyhow::Result<anyhow::Result<Vec<BlobSidecar>>>> {
+        executor.spawn(async move {
+            let mut blob_sidecars = vec![];
+            let (callback, mut rx) = mpsc::channel(100);
+            p2p_sender
+                .send_request(P2PRequest::BlobRange {
+                    peer_id,
+                    start: range.start_slot,
+                    count: range.count,
+                    callback,
+                })
+                .expect("Failed to send blob range request");
+
+            while let Some(response) = rx.recv().await {
+                match response {
+                    Ok(P2PCallbackResponse::ResponseMessage(message)) => {
+                        if let ResponseMessage::BlobSidecarsByRange(blob_sidecar) = *message {
+                            blob_sidecars.push(blob_sidecar);
+                        }
+                    }
+                    Ok(P2PCallbackResponse::EndOfStream) => {
+                        info!("End of blob range request stream received.");
+                        break;
+                    }
+                    Ok(P2PCallbackResponse::Disconnected) => {
+                        bail!("Peer disconnected while receiving blob range.");
+                    }
+                    Ok(P2PCallbackResponse::Timeout) => {
+                        bail!("Blob range request timed out.");
+                    }
+                    Err(err) => {
+                        info!("Error receiving blobs from blob range request: {err:?}");
+                    }
+                }
+            }
+
+            Ok(blob_sidecars)
+        })
+    }
+}
+
 pub struct PeerRootsDownloader;
 
 impl PeerRootsDownloader {
     pub fn start(
         peer_id: PeerId,
-        p2p_sender: UnboundedSender<P2PMessage>,
+        p2p_sender: P2PSender,
         executor: ReamExecutor,
         roots: Vec<B256>,
     ) -> JoinHandle<anyhow::Result<anyhow::Result<Vec<SignedBeaconBlock>>>> {
@@ -96,11 +166,11 @@ impl PeerRootsDownloader {
             let mut beacon_blocks = vec![];
             let (callback, mut rx) = mpsc::channel(100);
             p2p_sender
-                .send(P2PMessage::Request(P2PRequest::BlockRoots {
+                .send_request(P2PRequest::BlockRoots {
                     peer_id,
                     roots: roots.to_vec(),
                     callback,
-                }))
+                })
                 .expect("Failed to send block roots request");
 
             while let Some(response) = rx.recv().await {
@@ -141,7 +211,7 @@ pub struct PeerBlobIdentifierDownloader;
 impl PeerBlobIdentifierDownloader {
     pub fn start(
         peer_id: PeerId,
-        p2p_sender: UnboundedSender<P2PMessage>,
+        p2p_sender: P2PSender,
         executor: ReamExecutor,
         blob_identifiers: Vec<BlobIdentifier>,
     ) -> JoinHandle<anyhow::Result<anyhow::Result<Vec<BlobSidecar>>>> {
@@ -149,11 +219,11 @@ impl PeerBlobIdentifierDownloader {
             let mut blob_sidecars = vec![];
             let (callback, mut rx) = mpsc::channel(100);
             p2p_sender
-                .send(P2PMessage::Request(P2PRequest::BlobIdentifiers {
+                .send_request(P2PRequest::BlobIdentifiers {
                     peer_id,
                     blob_identifiers: blob_identifiers.to_vec(),
                     callback,
-                }))
+                })
                 .expect("Failed to send blob identifiers request");
 
             while let Some(response) = rx.recv().await {
@@ -1 +1,4 @@
+pub mod backfill;
 pub mod block_range;
+pub mod lookup;
+pub mod verification;
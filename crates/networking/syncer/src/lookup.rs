@@ -0,0 +1,460 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+use alloy_primitives::B256;
+use anyhow::anyhow;
+use libp2p::{PeerId, gossipsub::Message};
+use ream_chain_beacon::beacon_chain::BeaconChain;
+use ream_consensus_beacon::{
+    blob_sidecar::{BlobIdentifier, BlobSidecar},
+    electra::beacon_block::SignedBeaconBlock,
+};
+use ream_consensus_misc::constants::beacon::SLOTS_PER_EPOCH;
+use ream_executor::ReamExecutor;
+use ream_fork_choice::handlers::InvalidBlockError;
+use ream_p2p::{channel::P2PSender, network_state::NetworkState};
+use ream_storage::db::ReamDB;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tracing::{info, warn};
+use tree_hash::TreeHash;
+
+use crate::{
+    block_range::{
+        peer_manager::PeerManager,
+        peer_range_downloader::{
+            DownloadError, PeerBlobIdentifierDownloader, PeerRootsDownloader, Range,
+        },
+    },
+    verification::{blob_retention_slot, verify_blobs},
+};
+
+/// Number of unknown ancestors fetched for a single gossip object before giving up, deeper gaps
+/// are left to range sync
+pub const MAX_LOOKUP_DEPTH: usize = 2 * SLOTS_PER_EPOCH as usize;
+
+/// Number of block roots looked up at the same time
+pub const MAX_LOOKUPS: usize = 64;
+
+/// Number of gossip messages held back until the block they depend on is imported
+pub const MAX_PENDING_MESSAGES: usize = 1024;
+
+/// Number of peers a block is requested from before the lookup is dropped
+const MAX_LOOKUP_ATTEMPTS: usize = 3;
+
+/// The result of a block lookup, returned by [`LookupSync::recv`].
+pub struct LookupResult {
+    block_root: B256,
+    peer_id: PeerId,
+    result: Result<(SignedBeaconBlock, Vec<BlobSidecar>), DownloadError>,
+}
+
+enum LookupState {
+    Downloading(PeerId),
+    /// The block is downloaded, but its parent is unknown too
+    AwaitingParent {
+        block: SignedBeaconBlock,
+        blobs: Vec<BlobSidecar>,
+        peer_id: PeerId,
+    },
+}
+
+struct Lookup {
+    state: LookupState,
+    /// Number of blocks between this block and the gossip object which triggered the lookup
+    depth: usize,
+    attempts: usize,
+    failed_peers: HashSet<PeerId>,
+    /// Gossip messages which are replayed once the block is imported
+    pending_messages: Vec<Message>,
+}
+
+/// Fetches blocks which gossip objects refer to but the node hasn't seen, like the parent of a
+/// gossip block or the head block of an attestation.
+///
+/// Missing ancestors are requested one at a time with `BeaconBlocksByRoot`, together with their
+/// blobs using `BlobSidecarsByRoot`, until a block with a known parent is found. The chain is then
+/// imported oldest first, and the gossip messages which waited for a block are handed back to be
+/// processed again.
+pub struct LookupSync {
+    beacon_chain: Arc<BeaconChain>,
+    db: ReamDB,
+    peer_manager: PeerManager,
+    p2p_sender: P2PSender,
+    executor: ReamExecutor,
+    lookups: HashMap<B256, Lookup>,
+    pending_message_count: usize,
+    result_sender: UnboundedSender<LookupResult>,
+    result_receiver: UnboundedReceiver<LookupResult>,
+}
+
+impl LookupSync {
+    pub fn new(
+        beacon_chain: Arc<BeaconChain>,
+        db: ReamDB,
+        p2p_sender: P2PSender,
+        network_state: Arc<NetworkState>,
+        executor: ReamExecutor,
+    ) -> Self {
+        let (result_sender, result_receiver) = mpsc::unbounded_channel();
+        Self {
+            beacon_chain,
+            db,
+            peer_manager: PeerManager::new(network_state, p2p_sender.clone()),
+            p2p_sender,
+            executor,
+            lookups: HashMap::new(),
+            pending_message_count: 0,
+            result_sender,
+            result_receiver,
+        }
+    }
+
+    /// Holds `message` back until the block `block_root` is imported, looking the block up if it
+    /// isn't already.
+    ///
+    /// `slot` is the slot of the gossip object, if it is too far ahead of the newest block the
+    /// gap is left to range sync. The message is dropped if the queue is full.
+    pub async fn search_block(&mut self, block_root: B256, slot: u64, message: Message) {
+        if self.pending_message_count >= MAX_PENDING_MESSAGES {
+            warn!("Lookup queue is full, dropping gossip message waiting for block {block_root}");
+            return;
+        }
+
+        if !self.lookups.contains_key(&block_root) {
+            let highest_slot = self
+                .beacon_chain
+                .store
+                .lock()
+                .await
+                .db
+                .slot_index_provider()
+                .get_highest_slot()
+                .unwrap_or_default()
+                .unwrap_or(0);
+            if slot > highest_slot + MAX_LOOKUP_DEPTH as u64 {
+                return;
+            }
+            if !self.start_lookup(block_root, 0) {
+                return;
+            }
+        }
+        if let Some(lookup) = self.lookups.get_mut(&block_root) {
+            lookup.pending_messages.push(message);
+            self.pending_message_count += 1;
+        }
+    }
+
+    /// Receives the result of the next finished lookup.
+    pub async fn recv(&mut self) -> Option<LookupResult> {
+        self.result_receiver.recv().await
+    }
+
+    /// Processes the result of a lookup, importing every block it completes.
+    ///
+    /// Returns the gossip messages which waited for the imported blocks, to be processed again.
+    pub async fn on_lookup_result(&mut self, lookup_result: LookupResult) -> Vec<Message> {
+        let LookupResult {
+            block_root,
+            peer_id,
+            result,
+        } = lookup_result;
+        self.peer_manager.mark_peer_as_idle(&peer_id);
+
+        let Some(lookup) = self.lookups.get_mut(&block_root) else {
+            return vec![];
+        };
+        if !matches!(lookup.state, LookupState::Downloading(downloading_peer) if downloading_peer == peer_id)
+        {
+            return vec![];
+        }
+
+        let (block, blobs) = match result {
+            Ok(downloaded) => downloaded,
+            Err(err) => {
+                self.retry_lookup(block_root, peer_id, err);
+                return vec![];
+            }
+        };
+
+        let parent_root = block.message.parent_root;
+        let depth = lookup.depth;
+        lookup.state = LookupState::AwaitingParent {
+            block,
+            blobs,
+            peer_id,
+        };
+
+        if self.is_block_imported(parent_root) {
+            return self.import_chain(block_root).await;
+        }
+        if self.lookups.contains_key(&parent_root) {
+            return vec![];
+        }
+        if depth + 1 >= MAX_LOOKUP_DEPTH {
+            info!(
+                "Dropping lookup of block {block_root}, its ancestors are more than {MAX_LOOKUP_DEPTH} blocks away"
+            );
+            self.drop_lookup_chain(block_root);
+            return vec![];
+        }
+        if !self.start_lookup(parent_root, depth + 1) {
+            self.drop_lookup_chain(block_root);
+        }
+
+        vec![]
+    }
+
+    /// Starts downloading `block_root`, returns whether the lookup was started.
+    fn start_lookup(&mut self, block_root: B256, depth: usize) -> bool {
+        if self.lookups.len() >= MAX_LOOKUPS {
+            warn!("Too many block lookups in progress, not looking up block {block_root}");
+            return false;
+        }
+
+        self.peer_manager.update_peer_set();
+        let Some(peer) = self
+            .peer_manager
+            .fetch_idle_peer_for_slot(0, &HashSet::new())
+        else {
+            warn!("No idle peers available to look up block {block_root}");
+            return false;
+        };
+
+        self.lookups.insert(
+            block_root,
+            Lookup {
+                state: LookupState::Downloading(peer.peer_id),
+                depth,
+                attempts: 1,
+                failed_peers: HashSet::new(),
+                pending_messages: vec![],
+            },
+        );
+        self.spawn_download(block_root, peer.peer_id);
+
+        true
+    }
+
+    fn spawn_download(&self, block_root: B256, peer_id: PeerId) {
+        let p2p_sender = self.p2p_sender.clone();
+        let executor = self.executor.clone();
+        let result_sender = self.result_sender.clone();
+        let beacon_chain = self.beacon_chain.clone();
+        self.executor.spawn(async move {
+            let current_slot = beacon_chain
+                .store
+                .lock()
+                .await
+                .get_current_slot()
+                .unwrap_or_default();
+            let result = download_block_and_blobs(
+                peer_id,
+                p2p_sender,
+                executor,
+                block_root,
+                blob_retention_slot(current_slot),
+            )
+            .await;
+            let _ = result_sender.send(LookupResult {
+                block_root,
+                peer_id,
+                result,
+            });
+        });
+    }
+
+    /// Requests the block from another peer, or drops the lookup once it ran out of attempts.
+    fn retry_lookup(&mut self, block_root: B256, peer_id: PeerId, err: DownloadError) {
+        let Some(lookup) = self.lookups.get_mut(&block_root) else {
+            return;
+        };
+        warn!("Lookup of block {block_root} from peer {peer_id} failed: {err}");
+        if let DownloadError::Invalid(err) = &err {
+            self.peer_manager
+                .ban_peer(&peer_id, format!("Served an invalid block by root: {err}"));
+        }
+
+        lookup.failed_peers.insert(peer_id);
+        let peer = match lookup.attempts < MAX_LOOKUP_ATTEMPTS {
+            true => {
+                self.peer_manager.update_peer_set();
+                self.peer_manager
+                    .fetch_idle_peer_for_slot(0, &lookup.failed_peers)
+            }
+            false => None,
+        };
+        let Some(peer) = peer else {
+            warn!("Giving up on the lookup of block {block_root}");
+            self.drop_lookup_chain(block_root);
+            return;
+        };
+
+        lookup.attempts += 1;
+        lookup.state = LookupState::Downloading(peer.peer_id);
+        self.spawn_download(block_root, peer.peer_id);
+    }
+
+    /// Imports the downloaded block `block_root`, whose parent is imported, and every downloaded
+    /// descendant waiting for it.
+    async fn import_chain(&mut self, block_root: B256) -> Vec<Message> {
+        let mut replay = vec![];
+        let mut roots_to_import = vec![block_root];
+        while let Some(block_root) = roots_to_import.pop() {
+            let Some(lookup) = self.lookups.remove(&block_root) else {
+                continue;
+            };
+            self.pending_message_count -= lookup.pending_messages.len();
+            let LookupState::AwaitingParent {
+                block,
+                blobs,
+                peer_id,
+            } = lookup.state
+            else {
+                continue;
+            };
+
+            // Gossip may have imported the block while it was looked up
+            if !self.is_block_imported(block_root)
+                && let Err(err) = self.import_block(block_root, block, blobs).await
+            {
+                warn!("Failed to import looked up block {block_root}: {err}");
+                // Only a block failing consensus validation is the fault of the peer
+                if err.is::<InvalidBlockError>() {
+                    self.peer_manager
+                        .ban_peer(&peer_id, format!("Served an invalid block by root: {err}"));
+                }
+                self.drop_descendants(block_root);
+                continue;
+            }
+            replay.extend(lookup.pending_messages);
+            roots_to_import.extend(self.children_of(block_root));
+        }
+
+        replay
+    }
+
+    /// The downloaded lookups waiting for `parent_root` to be imported.
+    fn children_of(&self, parent_root: B256) -> Vec<B256> {
+        self.lookups
+            .iter()
+            .filter_map(|(root, lookup)| match &lookup.state {
+                LookupState::AwaitingParent { block, .. }
+                    if block.message.parent_root == parent_root =>
+                {
+                    Some(*root)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    async fn import_block(
+        &self,
+        block_root: B256,
+        block: SignedBeaconBlock,
+        blobs: Vec<BlobSidecar>,
+    ) -> anyhow::Result<()> {
+        // Blobs are stored first, so the block passes the data availability check
+        let has_blobs = !blobs.is_empty();
+        self.db
+            .blobs_and_proofs_provider()
+            .insert_blob_sidecars(blobs)?;
+
+        let result = self.beacon_chain.process_block(block).await;
+        // The blobs of a block which wasn't imported would never be pruned
+        if result.is_err()
+            && has_blobs
+            && let Err(err) = self.db.blobs_and_proofs_provider().remove_block(block_root)
+        {
+            warn!("Failed to remove the blobs of block {block_root}: {err}");
+        }
+        result
+    }
+
+    /// Whether fork choice can build on `block_root`.
+    ///
+    /// Called for gossip messages, so it neither waits for the store nor reads the state.
+    pub fn is_block_imported(&self, block_root: B256) -> bool {
+        // Fork choice needs the state of the parent, blocks stored by backfill don't count
+        matches!(
+            self.db.beacon_state_provider().contains(block_root),
+            Ok(true)
+        )
+    }
+
+    /// Drops the lookup of `block_root` and every downloaded descendant waiting for it.
+    fn drop_lookup_chain(&mut self, block_root: B256) {
+        if let Some(lookup) = self.lookups.remove(&block_root) {
+            self.pending_message_count -= lookup.pending_messages.len();
+        }
+        self.drop_descendants(block_root);
+    }
+
+    fn drop_descendants(&mut self, block_root: B256) {
+        let mut dropped_roots = vec![block_root];
+        while let Some(parent_root) = dropped_roots.pop() {
+            for child_root in self.children_of(parent_root) {
+                if let Some(lookup) = self.lookups.remove(&child_root) {
+                    self.pending_message_count -= lookup.pending_messages.len();
+                }
+                dropped_roots.push(child_root);
+            }
+        }
+    }
+}
+
+/// Downloads the block `block_root`, and its blobs if they are still retained, from one peer.
+async fn download_block_and_blobs(
+    peer_id: PeerId,
+    p2p_sender: P2PSender,
+    executor: ReamExecutor,
+    block_root: B256,
+    blob_retention_slot: u64,
+) -> Result<(SignedBeaconBlock, Vec<BlobSidecar>), DownloadError> {
+    let mut blocks = PeerRootsDownloader::start(
+        peer_id,
+        p2p_sender.clone(),
+        executor.clone(),
+        vec![block_root],
+    )
+    .await
+    .map_err(|err| DownloadError::Download(err.into()))?
+    .and_then(|result| result)
+    .map_err(DownloadError::Download)?;
+    let Some(block) = blocks.pop() else {
+        return Err(DownloadError::Download(anyhow!(
+            "Peer doesn't have block {block_root}"
+        )));
+    };
+    if !blocks.is_empty() || block.message.tree_hash_root() != block_root {
+        return Err(DownloadError::Invalid(anyhow!(
+            "Peer served other blocks than {block_root}"
+        )));
+    }
+
+    if block.message.slot < blob_retention_slot
+        || block.message.body.blob_kzg_commitments.is_empty()
+    {
+        return Ok((block, vec![]));
+    }
+
+    let blob_identifiers = (0..block.message.body.blob_kzg_commitments.len() as u64)
+        .map(|index| BlobIdentifier::new(block_root, index))
+        .collect();
+    let blobs =
+        PeerBlobIdentifierDownloader::start(peer_id, p2p_sender, executor, blob_identifiers)
+            .await
+            .map_err(|err| DownloadError::Download(err.into()))?
+            .and_then(|result| result)
+            .map_err(DownloadError::Download)?;
+    verify_blobs(
+        std::slice::from_ref(&block),
+        &blobs,
+        Range::new(block.message.slot, 1),
+    )
+    .map_err(DownloadError::Invalid)?;
+
+    Ok((block, blobs))
+}
@@ -0,0 +1,232 @@
+use std::{collections::HashMap, sync::Arc};
+
+use alloy_primitives::{B256, aliases::B32};
+use anyhow::{bail, ensure};
+use ream_bls::{BLSSignature, PublicKey, traits::BatchVerifiable};
+use ream_consensus_beacon::{
+    blob_sidecar::{BlobIdentifier, BlobSidecar},
+    electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState},
+};
+use ream_consensus_misc::{
+    constants::beacon::DOMAIN_BEACON_PROPOSER,
+    misc::{
+        compute_domain, compute_epoch_at_slot, compute_signing_root, compute_start_slot_at_epoch,
+    },
+};
+use ream_network_spec::networks::beacon_network_spec;
+use tree_hash::TreeHash;
+
+use crate::block_range::peer_range_downloader::Range;
+
+/// Public keys and domain inputs needed to verify proposer signatures without a state per block.
+///
+/// Validators are never removed from the registry, so the keys of a state cover every proposer
+/// before it. Proposers activated after it are unknown, their blocks are left to the state
+/// transition to verify.
+#[derive(Clone)]
+pub struct ProposerKeys {
+    public_keys: Arc<Vec<PublicKey>>,
+    genesis_validators_root: B256,
+}
+
+impl ProposerKeys {
+    pub fn from_state(state: &BeaconState) -> Self {
+        Self {
+            public_keys: Arc::new(
+                state
+                    .validators
+                    .iter()
+                    .map(|validator| validator.public_key.clone())
+                    .collect(),
+            ),
+            genesis_validators_root: state.genesis_validators_root,
+        }
+    }
+}
+
+/// The oldest slot peers are required to serve blobs for.
+pub fn blob_retention_slot(current_slot: u64) -> u64 {
+    compute_start_slot_at_epoch(
+        compute_epoch_at_slot(current_slot)
+            .saturating_sub(beacon_network_spec().min_epochs_for_blob_sidecars_requests),
+    )
+}
+
+/// Checks that `blocks`, sorted by slot, are within `range` and each is the parent of the block
+/// after it.
+pub fn verify_chain_segment(blocks: &[SignedBeaconBlock], range: Range) -> anyhow::Result<()> {
+    let end_slot = range.start_slot + range.count;
+    for block in blocks {
+        ensure!(
+            block.message.slot >= range.start_slot && block.message.slot < end_slot,
+            "Block at slot {} is outside of the requested range {range:?}",
+            block.message.slot
+        );
+    }
+
+    for window in blocks.windows(2) {
+        let (parent, child) = (&window[0], &window[1]);
+        ensure!(
+            parent.message.slot < child.message.slot,
+            "Blocks at slots {} and {} are out of order",
+            parent.message.slot,
+            child.message.slot
+        );
+        let parent_root = parent.message.tree_hash_root();
+        ensure!(
+            child.message.parent_root == parent_root,
+            "Block at slot {} is not a child of the block at slot {}",
+            child.message.slot,
+            parent.message.slot
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks that `blobs` are exactly the blobs committed to by the blocks in `blob_range`.
+pub fn verify_blobs(
+    blocks: &[SignedBeaconBlock],
+    blobs: &[BlobSidecar],
+    blob_range: Range,
+) -> anyhow::Result<()> {
+    let mut commitments = HashMap::new();
+    for block in blocks {
+        if block.message.slot < blob_range.start_slot {
+            continue;
+        }
+        let block_root = block.message.tree_hash_root();
+        for (index, commitment) in block.message.body.blob_kzg_commitments.iter().enumerate() {
+            commitments.insert(
+                BlobIdentifier {
+                    block_root,
+                    index: index as u64,
+                },
+                commitment,
+            );
+        }
+    }
+
+    ensure!(
+        blobs.len() == commitments.len(),
+        "Expected {} blobs, received {}",
+        commitments.len(),
+        blobs.len()
+    );
+    for blob_sidecar in blobs {
+        let blob_identifier = BlobIdentifier {
+            block_root: blob_sidecar.signed_block_header.message.tree_hash_root(),
+            index: blob_sidecar.index,
+        };
+        let Some(commitment) = commitments.remove(&blob_identifier) else {
+            bail!("Unexpected or duplicate blob {blob_identifier:?}");
+        };
+        ensure!(
+            *commitment == blob_sidecar.kzg_commitment,
+            "Blob {blob_identifier:?} doesn't match the commitment in its block"
+        );
+    }
+
+    Ok(())
+}
+
+/// Verifies the proposer signatures of a batch of blocks with a single batch verification.
+///
+/// Returns whether each block was verified, blocks whose proposer isn't in `proposer_keys` are
+/// skipped. Fails if any signature is invalid.
+pub fn verify_proposer_signatures(
+    blocks: &[SignedBeaconBlock],
+    proposer_keys: &ProposerKeys,
+) -> anyhow::Result<Vec<bool>> {
+    let mut signature_verified = vec![false; blocks.len()];
+    let mut signed_blocks = vec![];
+    for (index, block) in blocks.iter().enumerate() {
+        let Some(public_key) = proposer_keys
+            .public_keys
+            .get(block.message.proposer_index as usize)
+        else {
+            continue;
+        };
+        let domain = compute_domain(
+            DOMAIN_BEACON_PROPOSER,
+            Some(fork_version_at_epoch(compute_epoch_at_slot(
+                block.message.slot,
+            ))),
+            Some(proposer_keys.genesis_validators_root),
+        );
+        // The signing root of a block root is the signing root of the block
+        let signing_root = compute_signing_root(block.message.tree_hash_root(), domain);
+        signature_verified[index] = true;
+        signed_blocks.push((&block.signature, public_key, signing_root));
+    }
+
+    let signature_sets = signed_blocks
+        .iter()
+        .map(|(signature, public_key, signing_root)| {
+            (*signature, *public_key, signing_root.as_slice())
+        })
+        .collect::<Vec<_>>();
+    ensure!(
+        BLSSignature::verify_batch(&signature_sets)?,
+        "Invalid proposer signature in the batch of blocks from slot {}",
+        blocks.first().map_or(0, |block| block.message.slot)
+    );
+
+    Ok(signature_verified)
+}
+
+fn fork_version_at_epoch(epoch: u64) -> B32 {
+    beacon_network_spec()
+        .fork_schedule()
+        .iter()
+        .rev()
+        .find(|fork| fork.epoch <= epoch)
+        .map(|fork| fork.current_version)
+        .unwrap_or(beacon_network_spec().genesis_fork_version)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_consensus_beacon::electra::beacon_block::{BeaconBlock, SignedBeaconBlock};
+    use tree_hash::TreeHash;
+
+    use super::verify_chain_segment;
+    use crate::block_range::peer_range_downloader::Range;
+
+    fn block(slot: u64, parent_root: B256) -> SignedBeaconBlock {
+        SignedBeaconBlock {
+            message: BeaconBlock {
+                slot,
+                parent_root,
+                ..Default::default()
+            },
+            signature: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_verify_chain_segment() {
+        let oldest = block(10, B256::repeat_byte(1));
+        let middle = block(12, oldest.message.tree_hash_root());
+        let newest = block(15, middle.message.tree_hash_root());
+        let range = Range::new(10, 6);
+
+        let blocks = vec![oldest.clone(), middle.clone(), newest.clone()];
+        assert!(verify_chain_segment(&blocks, range).is_ok());
+
+        // A skipped block breaks the chain
+        let blocks = vec![oldest.clone(), newest.clone()];
+        assert!(verify_chain_segment(&blocks, range).is_err());
+
+        // Blocks must be sorted by slot
+        let blocks = vec![middle, oldest, newest.clone()];
+        assert!(verify_chain_segment(&blocks, range).is_err());
+
+        // Blocks outside of the requested range are rejected
+        assert!(verify_chain_segment(&[newest], Range::new(10, 5)).is_err());
+
+        // An empty batch is valid, the next batch has to link up instead
+        assert!(verify_chain_segment(&[], range).is_ok());
+    }
+}
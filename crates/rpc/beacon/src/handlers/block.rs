@@ -4,6 +4,7 @@ use actix_web::{
 };
 use alloy_primitives::B256;
 use ream_api_types_beacon::{
+    block::BlockRewards,
     error::ApiError,
     id::{ID, ValidatorID},
     responses::{
@@ -28,22 +29,6 @@ use tracing::error;
 
 use crate::handlers::state::get_state_from_id;
 
-#[derive(Debug, Serialize, Deserialize, Default)]
-pub struct BlockRewards {
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub proposer_index: u64,
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub total: u64,
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub attestations: u64,
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub sync_aggregate: u64,
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub proposer_slashings: u64,
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub attester_slashings: u64,
-}
-
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ValidatorSyncCommitteeReward {
     #[serde(with = "serde_utils::quoted_u64")]
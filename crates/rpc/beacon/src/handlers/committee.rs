@@ -5,6 +5,7 @@ use actix_web::{
     web::{Data, Path, Query},
 };
 use ream_api_types_beacon::{
+    committee::CommitteeData,
     error::ApiError,
     id::ID,
     query::{EpochQuery, IndexQuery, SlotQuery},
@@ -12,30 +13,9 @@ use ream_api_types_beacon::{
 };
 use ream_consensus_misc::{constants::beacon::SLOTS_PER_EPOCH, misc::compute_start_slot_at_epoch};
 use ream_storage::db::ReamDB;
-use serde::Serialize;
 
 use super::state::get_state_from_id;
 
-#[derive(Debug, Serialize, Clone)]
-pub struct CommitteeData {
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub index: u64,
-    #[serde(with = "serde_utils::quoted_u64")]
-    pub slot: u64,
-    #[serde(with = "serde_utils::quoted_u64_vec")]
-    pub validators: Vec<u64>,
-}
-
-impl CommitteeData {
-    pub fn new(index: u64, slot: u64, validators: Vec<u64>) -> Self {
-        Self {
-            index,
-            slot,
-            validators,
-        }
-    }
-}
-
 /// Called by `/states/<state_id>/committees` to get the Committee Data of state.
 /// Optional `epoch`, `index` or `slot` can be provided.
 #[get("/beacon/states/{state_id}/committees")]
@@ -60,18 +40,21 @@ pub async fn get_committees(
 
     let indices: Vec<u64> = match index.index {
         Some(index) => vec![index],
-        None => (0..(committees_per_slot * SLOTS_PER_EPOCH)).collect(),
+        None => (0..committees_per_slot).collect(),
     };
 
     let mut result: Vec<CommitteeData> = Vec::with_capacity(slots.len() * indices.len());
 
     for slot in &slots {
         for index in &indices {
-            let committee = state.get_beacon_committee(*slot, *index).map_err(|err| {
-                ApiError::NotFound(format!(
-                    "Committee with slot: {slot} and index: {index} not found {err:?}"
-                ))
-            })?;
+            let committee = db
+                .read_cache
+                .get_beacon_committee(&state, *slot, *index)
+                .map_err(|err| {
+                    ApiError::NotFound(format!(
+                        "Committee with slot: {slot} and index: {index} not found {err:?}"
+                    ))
+                })?;
             result.push(CommitteeData {
                 index: *index,
                 slot: *slot,
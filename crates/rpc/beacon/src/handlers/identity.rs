@@ -3,6 +3,7 @@ use std::sync::Arc;
 use actix_web::{HttpResponse, Responder, get, web::Data};
 use discv5::Enr;
 use ream_api_types_beacon::{error::ApiError, responses::DataResponse};
+use ream_discv5::enr_ext::EnrExt;
 use ream_p2p::{
     network::misc::peer_id_from_enr, network_state::NetworkState,
     req_resp::messages::meta_data::GetMetaDataV2,
@@ -24,22 +25,12 @@ impl Identity {
         Self {
             peer_id: peer_id.to_string(),
             enr: enr.to_base64(),
-            p2p_address: {
-                let mut addresses = Vec::new();
-
-                if let Some(ip4) = enr.ip4()
-                    && let Some(tcp4) = enr.tcp4()
-                {
-                    addresses.push(format!("/ip4/{ip4}/tcp/{tcp4}/p2p/{peer_id}"));
-                }
-                if let Some(ip6) = enr.ip6()
-                    && let Some(tcp6) = enr.tcp6()
-                {
-                    addresses.push(format!("/ip6/{ip6}/tcp/{tcp6}/p2p/{peer_id}"));
-                }
-
-                addresses
-            },
+            // The ENR follows the addresses learned from peers and mapped by UPnP
+            p2p_address: enr
+                .multiaddrs(true)
+                .into_iter()
+                .map(|address| format!("{address}/p2p/{peer_id}"))
+                .collect(),
             discovery_address: {
                 let mut addresses = Vec::new();
 
@@ -11,6 +11,7 @@ pub mod peers;
 pub mod pool;
 pub mod prepare_beacon_proposer;
 pub mod state;
+pub mod subnet_subscriptions;
 pub mod syncing;
 pub mod validator;
 pub mod version;
@@ -9,7 +9,7 @@ use libp2p::{Multiaddr, PeerId};
 use ream_api_types_beacon::{error::ApiError, responses::DataResponse};
 use ream_p2p::{
     network_state::NetworkState,
-    peer::{ConnectionState, Direction},
+    peer::{Client, ConnectionState, Direction},
 };
 use serde::Serialize;
 
@@ -36,6 +36,9 @@ pub async fn get_peer(
         last_seen_p2p_address: cached_peer.last_seen_p2p_address,
         state: cached_peer.state,
         direction: cached_peer.direction,
+        client: cached_peer.client(),
+        agent_version: cached_peer.agent_version,
+        score: cached_peer.score.score(),
         enr: cached_peer.enr,
     })))
 }
@@ -48,10 +51,19 @@ pub async fn get_peer_count(
     let mut connecting = 0;
     let mut disconnected = 0;
     let mut disconnecting = 0;
+    let mut inbound = 0;
+    let mut outbound = 0;
 
     for peer in network_state.peer_table.read().values() {
         match peer.state {
-            ConnectionState::Connected => connected += 1,
+            ConnectionState::Connected => {
+                connected += 1;
+                match peer.direction {
+                    Direction::Inbound => inbound += 1,
+                    Direction::Outbound => outbound += 1,
+                    Direction::Unknown => {}
+                }
+            }
             ConnectionState::Connecting => connecting += 1,
             ConnectionState::Disconnected => disconnected += 1,
             ConnectionState::Disconnecting => disconnecting += 1,
@@ -63,6 +75,8 @@ pub async fn get_peer_count(
         connecting,
         disconnected,
         disconnecting,
+        inbound,
+        outbound,
     })))
 }
 
@@ -76,6 +90,12 @@ pub struct PeerCount {
     connected: u64,
     #[serde(with = "serde_utils::quoted_u64")]
     disconnecting: u64,
+    /// Connected peers which dialed us
+    #[serde(with = "serde_utils::quoted_u64")]
+    inbound: u64,
+    /// Connected peers we dialed
+    #[serde(with = "serde_utils::quoted_u64")]
+    outbound: u64,
 }
 
 #[derive(Clone, Debug, Serialize)]
@@ -93,6 +113,16 @@ pub struct Peer {
     /// Direction of the most recent connection (inbound/outbound)
     pub direction: Direction,
 
+    /// Client the peer runs, parsed from its agent version
+    pub client: Client,
+
+    /// Agent version the peer sent in identify, if received
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub agent_version: Option<String>,
+
+    /// Reputation of the peer, peers are disconnected below -20 and banned below -50
+    pub score: f64,
+
     /// Ethereum Node Record (ENR), if known
     #[serde(skip_serializing_if = "Option::is_none")]
     pub enr: Option<Enr>,
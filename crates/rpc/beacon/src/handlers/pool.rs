@@ -8,8 +8,15 @@ use ream_api_types_beacon::{error::ApiError, id::ID, responses::DataResponse};
 use ream_consensus_beacon::{
     bls_to_execution_change::SignedBLSToExecutionChange, voluntary_exit::SignedVoluntaryExit,
 };
+use ream_consensus_misc::constants::beacon::genesis_validators_root;
+use ream_network_spec::networks::beacon_network_spec;
 use ream_operation_pool::OperationPool;
+use ream_p2p::{
+    channel::{GossipMessage, P2PSender},
+    gossipsub::beacon::topics::{GossipTopic, GossipTopicKind},
+};
 use ream_storage::db::ReamDB;
+use ssz::Encode;
 
 use crate::handlers::state::get_state_from_id;
 
@@ -27,6 +34,7 @@ pub async fn get_bls_to_execution_changes(
 pub async fn post_bls_to_execution_changes(
     db: Data<ReamDB>,
     operation_pool: Data<Arc<OperationPool>>,
+    p2p_sender: Data<P2PSender>,
     signed_bls_to_execution_change: Json<SignedBLSToExecutionChange>,
 ) -> Result<impl Responder, ApiError> {
     let highest_slot = db
@@ -50,8 +58,12 @@ pub async fn post_bls_to_execution_changes(
         ))
     })?;
 
+    publish(
+        &p2p_sender,
+        GossipTopicKind::BlsToExecutionChange,
+        signed_bls_to_execution_change.as_ssz_bytes(),
+    );
     operation_pool.insert_signed_bls_to_execution_change(signed_bls_to_execution_change);
-    // TODO: publish bls_to_execution_change to peers (gossipsub) - https://github.com/ReamLabs/ream/issues/556
 
     Ok(HttpResponse::Ok())
 }
@@ -70,6 +82,7 @@ pub async fn get_voluntary_exits(
 pub async fn post_voluntary_exits(
     db: Data<ReamDB>,
     operation_pool: Data<Arc<OperationPool>>,
+    p2p_sender: Data<P2PSender>,
     signed_voluntary_exit: Json<SignedVoluntaryExit>,
 ) -> Result<impl Responder, ApiError> {
     let highest_slot = db
@@ -93,8 +106,23 @@ pub async fn post_voluntary_exits(
             ))
         })?;
 
+    publish(
+        &p2p_sender,
+        GossipTopicKind::VoluntaryExit,
+        signed_voluntary_exit.as_ssz_bytes(),
+    );
     operation_pool.insert_signed_voluntary_exit(signed_voluntary_exit);
-    // TODO: publish voluntary exit to peers (gossipsub) - https://github.com/ReamLabs/ream/issues/556
 
     Ok(HttpResponse::Ok())
 }
+
+/// Publishes an operation to peers on the topic of the current fork.
+fn publish(p2p_sender: &P2PSender, kind: GossipTopicKind, data: Vec<u8>) {
+    p2p_sender.publish(GossipMessage {
+        topic: GossipTopic {
+            fork: beacon_network_spec().fork_digest(genesis_validators_root()),
+            kind,
+        },
+        data,
+    });
+}
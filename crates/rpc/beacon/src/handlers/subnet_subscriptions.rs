@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use actix_web::{
+    HttpResponse, Responder, post,
+    web::{Data, Json},
+};
+use ream_api_types_beacon::{
+    committee::{BeaconCommitteeSubscription, SyncCommitteeSubscription},
+    error::ApiError,
+};
+use ream_consensus_misc::{
+    constants::beacon::{
+        EPOCHS_PER_SYNC_COMMITTEE_PERIOD, MAX_COMMITTEES_PER_SLOT, SLOTS_PER_EPOCH,
+        SYNC_COMMITTEE_SIZE,
+    },
+    misc::{compute_epoch_at_slot, compute_sync_committee_period},
+};
+use ream_fork_choice::store::Store;
+use ream_operation_pool::OperationPool;
+use ream_p2p::network_state::NetworkState;
+use ream_storage::db::ReamDB;
+
+/// Subscribes to the attestation subnets of the committees validators aggregate for.
+///
+/// Validators know their duties up to the next epoch, so subscriptions for slots further ahead
+/// or in the past are rejected.
+#[post("/validator/beacon_committee_subscriptions")]
+pub async fn prepare_beacon_committee_subnets(
+    db: Data<ReamDB>,
+    operation_pool: Data<Arc<OperationPool>>,
+    network_state: Data<Arc<NetworkState>>,
+    subscriptions: Json<Vec<BeaconCommitteeSubscription>>,
+) -> Result<impl Responder, ApiError> {
+    let subscriptions = subscriptions.into_inner();
+    if subscriptions.is_empty() {
+        return Err(ApiError::BadRequest("Empty request body".to_string()));
+    }
+
+    let store = Store {
+        db: db.get_ref().clone(),
+        operation_pool: operation_pool.get_ref().clone(),
+    };
+    let current_slot = store.get_current_slot().map_err(|err| {
+        ApiError::InternalError(format!("Failed to get current slot, error: {err:?}"))
+    })?;
+    let max_slot = current_slot + SLOTS_PER_EPOCH;
+    for subscription in &subscriptions {
+        if subscription.slot < current_slot || subscription.slot > max_slot {
+            return Err(ApiError::BadRequest(format!(
+                "Slot {} is not between the current slot {current_slot} and slot {max_slot}",
+                subscription.slot
+            )));
+        }
+        if subscription.committees_at_slot == 0
+            || subscription.committees_at_slot > MAX_COMMITTEES_PER_SLOT
+        {
+            return Err(ApiError::BadRequest(format!(
+                "Committees at slot {} is not between 1 and {MAX_COMMITTEES_PER_SLOT}",
+                subscription.committees_at_slot
+            )));
+        }
+        if subscription.committee_index >= subscription.committees_at_slot {
+            return Err(ApiError::BadRequest(format!(
+                "Committee index {} is not less than committees at slot {}",
+                subscription.committee_index, subscription.committees_at_slot
+            )));
+        }
+    }
+
+    let mut subnet_service = network_state.subnet_service.write();
+    for subscription in subscriptions
+        .iter()
+        .filter(|subscription| subscription.is_aggregator)
+    {
+        subnet_service.subscribe_to_committee_subnet(
+            subscription.committees_at_slot,
+            subscription.slot,
+            subscription.committee_index,
+        );
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+/// Subscribes to the sync committee subnets of validators in the sync committee.
+///
+/// Subscriptions can't outlast the next sync committee period, the furthest one validators know
+/// their sync committee indices for.
+#[post("/validator/sync_committee_subscriptions")]
+pub async fn prepare_sync_committee_subnets(
+    db: Data<ReamDB>,
+    operation_pool: Data<Arc<OperationPool>>,
+    network_state: Data<Arc<NetworkState>>,
+    subscriptions: Json<Vec<SyncCommitteeSubscription>>,
+) -> Result<impl Responder, ApiError> {
+    let subscriptions = subscriptions.into_inner();
+    if subscriptions.is_empty() {
+        return Err(ApiError::BadRequest("Empty request body".to_string()));
+    }
+
+    let store = Store {
+        db: db.get_ref().clone(),
+        operation_pool: operation_pool.get_ref().clone(),
+    };
+    let current_slot = store.get_current_slot().map_err(|err| {
+        ApiError::InternalError(format!("Failed to get current slot, error: {err:?}"))
+    })?;
+    let max_until_epoch = (compute_sync_committee_period(compute_epoch_at_slot(current_slot)) + 2)
+        * EPOCHS_PER_SYNC_COMMITTEE_PERIOD;
+    for subscription in &subscriptions {
+        if let Some(index) = subscription
+            .sync_committee_indices
+            .iter()
+            .find(|index| **index >= SYNC_COMMITTEE_SIZE)
+        {
+            return Err(ApiError::BadRequest(format!(
+                "Sync committee index {index} is not less than {SYNC_COMMITTEE_SIZE}"
+            )));
+        }
+        if subscription.until_epoch > max_until_epoch {
+            return Err(ApiError::BadRequest(format!(
+                "Until epoch {} is after the end of the next sync committee period at epoch {max_until_epoch}",
+                subscription.until_epoch
+            )));
+        }
+    }
+
+    let mut subnet_service = network_state.subnet_service.write();
+    for subscription in subscriptions {
+        subnet_service.subscribe_to_sync_committee_subnets(
+            &subscription.sync_committee_indices,
+            subscription.until_epoch,
+        );
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -9,6 +9,7 @@ use ream_api_types_beacon::{
 use ream_execution_engine::ExecutionEngine;
 use ream_fork_choice::store::Store;
 use ream_operation_pool::OperationPool;
+use ream_p2p::network_state::{BackfillProgress, NetworkState};
 use ream_storage::{db::ReamDB, tables::Table};
 use serde::{Deserialize, Serialize};
 use tracing::error;
@@ -16,6 +17,18 @@ use tracing::error;
 #[derive(Serialize, Deserialize, Default)]
 pub struct Syncing {
     sync_status: SyncStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    backfill: Option<BackfillStatus>,
+}
+
+/// Progress of downloading the blocks behind the checkpoint the node started from.
+#[derive(Serialize, Deserialize, Default)]
+pub struct BackfillStatus {
+    #[serde(with = "serde_utils::quoted_u64")]
+    oldest_slot: u64,
+    #[serde(with = "serde_utils::quoted_u64")]
+    target_slot: u64,
+    is_backfilling: bool,
 }
 
 impl Syncing {
@@ -28,14 +41,25 @@ impl Syncing {
                 el_offline,
                 is_optimistic: EXECUTION_OPTIMISTIC,
             },
+            backfill: None,
         }
     }
+
+    pub fn with_backfill(mut self, backfill_progress: Option<BackfillProgress>) -> Self {
+        self.backfill = backfill_progress.map(|progress| BackfillStatus {
+            oldest_slot: progress.oldest_slot,
+            target_slot: progress.target_slot,
+            is_backfilling: !progress.is_complete,
+        });
+        self
+    }
 }
 
 /// Called by `eth/v1/node/syncing` to get the Node Version.
 #[get("/node/syncing")]
 pub async fn get_syncing_status(
     db: Data<ReamDB>,
+    network_state: Data<Arc<NetworkState>>,
     operation_pool: Data<Arc<OperationPool>>,
     execution_engine: Data<Option<ExecutionEngine>>,
 ) -> Result<impl Responder, ApiError> {
@@ -77,11 +101,14 @@ pub async fn get_syncing_status(
         None => true,
     };
 
-    Ok(HttpResponse::Ok().json(DataResponse::new(Syncing::new(
-        head_slot,
-        sync_distance,
-        el_offline,
-        // get is_syncing
-        sync_distance > 1,
-    ))))
+    Ok(HttpResponse::Ok().json(DataResponse::new(
+        Syncing::new(
+            head_slot,
+            sync_distance,
+            el_offline,
+            // get is_syncing
+            sync_distance > 1,
+        )
+        .with_backfill(*network_state.backfill_progress.read()),
+    )))
 }
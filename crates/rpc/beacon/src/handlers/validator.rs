@@ -71,12 +71,15 @@ pub async fn get_validator_from_state(
                 }
             },
             ValidatorID::Address(public_key) => {
-                match state
-                    .validators
-                    .iter()
-                    .enumerate()
-                    .find(|(_, validator)| validator.public_key == *public_key)
-                {
+                match db
+                    .read_cache
+                    .get_validator_index(&state.validators, public_key)
+                    .and_then(|i| {
+                        state
+                            .validators
+                            .get(i as usize)
+                            .map(|validator| (i as usize, validator))
+                    }) {
                     Some((i, validator)) => (i, validator.to_owned()),
                     None => {
                         return Err(ApiError::NotFound(format!(
@@ -157,12 +160,15 @@ pub async fn get_validators_from_state(
                         }
                     },
                     ValidatorID::Address(public_key) => {
-                        match state
-                            .validators
-                            .iter()
-                            .enumerate()
-                            .find(|(_, validator)| validator.public_key == *public_key)
-                        {
+                        match db
+                            .read_cache
+                            .get_validator_index(&state.validators, public_key)
+                            .and_then(|i| {
+                                state
+                                    .validators
+                                    .get(i as usize)
+                                    .map(|validator| (i as usize, validator))
+                            }) {
                             Some((i, validator)) => (i, validator.to_owned()),
                             None => {
                                 return Err(ApiError::NotFound(format!(
@@ -231,12 +237,15 @@ pub async fn post_validators_from_state(
                         }
                     },
                     ValidatorID::Address(public_key) => {
-                        match state
-                            .validators
-                            .iter()
-                            .enumerate()
-                            .find(|(_, validator)| validator.public_key == *public_key)
-                        {
+                        match db
+                            .read_cache
+                            .get_validator_index(&state.validators, public_key)
+                            .and_then(|i| {
+                                state
+                                    .validators
+                                    .get(i as usize)
+                                    .map(|validator| (i as usize, validator))
+                            }) {
                             Some((i, validator)) => (i, validator.to_owned()),
                             None => {
                                 return Err(ApiError::NotFound(format!(
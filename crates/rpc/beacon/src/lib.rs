@@ -4,7 +4,7 @@ use actix_web::{App, HttpServer, middleware, web::Data};
 use config::RpcServerConfig;
 use ream_execution_engine::ExecutionEngine;
 use ream_operation_pool::OperationPool;
-use ream_p2p::network_state::NetworkState;
+use ream_p2p::{channel::P2PSender, network_state::NetworkState};
 use ream_storage::db::ReamDB;
 use tracing::info;
 
@@ -19,6 +19,7 @@ pub async fn start_server(
     server_config: RpcServerConfig,
     db: ReamDB,
     network_state: Arc<NetworkState>,
+    p2p_sender: P2PSender,
     operation_pool: Arc<OperationPool>,
     execution_engine: Option<ExecutionEngine>,
 ) -> std::io::Result<()> {
@@ -32,6 +33,7 @@ pub async fn start_server(
             .wrap(middleware::Logger::default())
             .app_data(Data::new(db.clone()))
             .app_data(Data::new(network_state.clone()))
+            .app_data(Data::new(p2p_sender.clone()))
             .app_data(Data::new(operation_pool.clone()))
             .app_data(Data::new(execution_engine.clone()))
             .configure(register_routers)
@@ -3,6 +3,7 @@ use actix_web::web::ServiceConfig;
 use crate::handlers::{
     duties::{get_attester_duties, get_proposer_duties},
     prepare_beacon_proposer::prepare_beacon_proposer,
+    subnet_subscriptions::{prepare_beacon_committee_subnets, prepare_sync_committee_subnets},
     validator::get_attestation_data,
 };
 
@@ -11,4 +12,6 @@ pub fn register_validator_routes(config: &mut ServiceConfig) {
     config.service(get_attester_duties);
     config.service(prepare_beacon_proposer);
     config.service(get_attestation_data);
+    config.service(prepare_beacon_committee_subnets);
+    config.service(prepare_sync_committee_subnets);
 }
@@ -0,0 +1,278 @@
+use std::fmt::Debug;
+
+use alloy_primitives::B256;
+use ream_consensus_beacon::electra::{beacon_block::SignedBeaconBlock, beacon_state::BeaconState};
+use ream_consensus_misc::checkpoint::Checkpoint;
+use redb::{Database, Durability, TableDefinition, WriteTransaction};
+use ssz::{Decode, Encode};
+use tree_hash::TreeHash;
+
+use crate::{
+    errors::StoreError,
+    tables::{
+        SSZEncoding,
+        beacon_block::BEACON_BLOCK_TABLE,
+        beacon_state::BEACON_STATE_TABLE,
+        block_timeliness::BLOCK_TIMELINESS_TABLE,
+        finalized_checkpoint::{FINALIZED_CHECKPOINT_FIELD, FINALIZED_CHECKPOINT_FIELD_KEY},
+        justified_checkpoint::{JUSTIFIED_CHECKPOINT_FIELD, JUSTIFIED_CHECKPOINT_KEY},
+        parent_root_index::PARENT_ROOT_INDEX_MULTIMAP_TABLE,
+        proposer_boost_root::{PROPOSER_BOOST_ROOT_FIELD, PROPOSER_BOOST_ROOT_KEY},
+        slot_index::SLOT_INDEX_TABLE,
+        state_root_index::STATE_ROOT_INDEX_TABLE,
+        unrealized_finalized_checkpoint::{
+            UNREALIZED_FINALIZED_CHECKPOINT_FIELD, UNREALIZED_FINALIZED_CHECKPOINT_FIELD_KEY,
+        },
+        unrealized_justifications::UNREALIZED_JUSTIFICATIONS_TABLE,
+        unrealized_justified_checkpoint::{
+            UNREALIZED_JUSTIFED_CHECKPOINT_FIELD, UNREALIZED_JUSTIFED_CHECKPOINT_KEY,
+        },
+    },
+};
+
+/// A set of writes across multiple tables which is committed atomically.
+///
+/// Reads through the batch see its own uncommitted writes, reads through the table providers only
+/// see them after [`WriteBatch::commit`]. redb allows a single write transaction at a time, so
+/// calling `insert` on a table provider while a batch is open blocks until the batch is committed
+/// or dropped. Dropping a batch without committing discards all of its writes.
+pub struct WriteBatch {
+    write_txn: WriteTransaction,
+}
+
+impl WriteBatch {
+    pub fn new(db: &Database) -> Result<Self, StoreError> {
+        let mut write_txn = db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate);
+        Ok(Self { write_txn })
+    }
+
+    pub fn commit(self) -> Result<(), StoreError> {
+        self.write_txn.commit()?;
+        Ok(())
+    }
+
+    fn get_field<T: Debug + Encode + Decode + 'static>(
+        &self,
+        definition: TableDefinition<&'static str, SSZEncoding<T>>,
+        key: &str,
+    ) -> Result<T, StoreError> {
+        let table = self.write_txn.open_table(definition)?;
+        let result = table.get(key)?.ok_or(StoreError::FieldNotInitilized)?;
+        Ok(result.value())
+    }
+
+    fn insert_field<T: Debug + Encode + Decode + 'static>(
+        &self,
+        definition: TableDefinition<&'static str, SSZEncoding<T>>,
+        key: &str,
+        value: T,
+    ) -> Result<(), StoreError> {
+        let mut table = self.write_txn.open_table(definition)?;
+        table.insert(key, value)?;
+        Ok(())
+    }
+
+    fn get_by_root<T: Debug + Encode + Decode + 'static>(
+        &self,
+        definition: TableDefinition<SSZEncoding<B256>, SSZEncoding<T>>,
+        block_root: B256,
+    ) -> Result<Option<T>, StoreError> {
+        let table = self.write_txn.open_table(definition)?;
+        let result = table.get(block_root)?;
+        Ok(result.map(|res| res.value()))
+    }
+
+    fn insert_by_root<T: Debug + Encode + Decode + 'static>(
+        &self,
+        definition: TableDefinition<SSZEncoding<B256>, SSZEncoding<T>>,
+        block_root: B256,
+        value: &T,
+    ) -> Result<(), StoreError> {
+        let mut table = self.write_txn.open_table(definition)?;
+        table.insert(block_root, value)?;
+        Ok(())
+    }
+
+    pub fn get_beacon_block(
+        &self,
+        block_root: B256,
+    ) -> Result<Option<SignedBeaconBlock>, StoreError> {
+        self.get_by_root(BEACON_BLOCK_TABLE, block_root)
+    }
+
+    /// Inserts a block along with its slot, state root and parent root index entries.
+    pub fn insert_beacon_block(
+        &self,
+        block_root: B256,
+        block: &SignedBeaconBlock,
+    ) -> Result<(), StoreError> {
+        let message_root = block.message.tree_hash_root();
+
+        let mut slot_index = self.write_txn.open_table(SLOT_INDEX_TABLE)?;
+        slot_index.insert(block.message.slot, message_root)?;
+        drop(slot_index);
+
+        self.insert_by_root(
+            STATE_ROOT_INDEX_TABLE,
+            block.message.state_root,
+            &message_root,
+        )?;
+
+        let mut parent_root_index = self
+            .write_txn
+            .open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)?;
+        parent_root_index.insert(block.message.parent_root, message_root)?;
+        drop(parent_root_index);
+
+        self.insert_by_root(BEACON_BLOCK_TABLE, block_root, block)
+    }
+
+    pub fn get_beacon_state(&self, block_root: B256) -> Result<Option<BeaconState>, StoreError> {
+        self.get_by_root(BEACON_STATE_TABLE, block_root)
+    }
+
+    pub fn insert_beacon_state(
+        &self,
+        block_root: B256,
+        state: &BeaconState,
+    ) -> Result<(), StoreError> {
+        self.insert_by_root(BEACON_STATE_TABLE, block_root, state)
+    }
+
+    pub fn insert_block_timeliness(
+        &self,
+        block_root: B256,
+        is_timely: bool,
+    ) -> Result<(), StoreError> {
+        self.insert_by_root(BLOCK_TIMELINESS_TABLE, block_root, &is_timely)
+    }
+
+    pub fn insert_unrealized_justification(
+        &self,
+        block_root: B256,
+        checkpoint: Checkpoint,
+    ) -> Result<(), StoreError> {
+        self.insert_by_root(UNREALIZED_JUSTIFICATIONS_TABLE, block_root, &checkpoint)
+    }
+
+    pub fn get_proposer_boost_root(&self) -> Result<B256, StoreError> {
+        self.get_field(PROPOSER_BOOST_ROOT_FIELD, PROPOSER_BOOST_ROOT_KEY)
+    }
+
+    pub fn insert_proposer_boost_root(&self, block_root: B256) -> Result<(), StoreError> {
+        self.insert_field(
+            PROPOSER_BOOST_ROOT_FIELD,
+            PROPOSER_BOOST_ROOT_KEY,
+            block_root,
+        )
+    }
+
+    pub fn get_justified_checkpoint(&self) -> Result<Checkpoint, StoreError> {
+        self.get_field(JUSTIFIED_CHECKPOINT_FIELD, JUSTIFIED_CHECKPOINT_KEY)
+    }
+
+    pub fn insert_justified_checkpoint(&self, checkpoint: Checkpoint) -> Result<(), StoreError> {
+        self.insert_field(
+            JUSTIFIED_CHECKPOINT_FIELD,
+            JUSTIFIED_CHECKPOINT_KEY,
+            checkpoint,
+        )
+    }
+
+    pub fn get_finalized_checkpoint(&self) -> Result<Checkpoint, StoreError> {
+        self.get_field(FINALIZED_CHECKPOINT_FIELD, FINALIZED_CHECKPOINT_FIELD_KEY)
+    }
+
+    pub fn insert_finalized_checkpoint(&self, checkpoint: Checkpoint) -> Result<(), StoreError> {
+        self.insert_field(
+            FINALIZED_CHECKPOINT_FIELD,
+            FINALIZED_CHECKPOINT_FIELD_KEY,
+            checkpoint,
+        )
+    }
+
+    pub fn get_unrealized_justified_checkpoint(&self) -> Result<Checkpoint, StoreError> {
+        self.get_field(
+            UNREALIZED_JUSTIFED_CHECKPOINT_FIELD,
+            UNREALIZED_JUSTIFED_CHECKPOINT_KEY,
+        )
+    }
+
+    pub fn insert_unrealized_justified_checkpoint(
+        &self,
+        checkpoint: Checkpoint,
+    ) -> Result<(), StoreError> {
+        self.insert_field(
+            UNREALIZED_JUSTIFED_CHECKPOINT_FIELD,
+            UNREALIZED_JUSTIFED_CHECKPOINT_KEY,
+            checkpoint,
+        )
+    }
+
+    pub fn get_unrealized_finalized_checkpoint(&self) -> Result<Checkpoint, StoreError> {
+        self.get_field(
+            UNREALIZED_FINALIZED_CHECKPOINT_FIELD,
+            UNREALIZED_FINALIZED_CHECKPOINT_FIELD_KEY,
+        )
+    }
+
+    pub fn insert_unrealized_finalized_checkpoint(
+        &self,
+        checkpoint: Checkpoint,
+    ) -> Result<(), StoreError> {
+        self.insert_field(
+            UNREALIZED_FINALIZED_CHECKPOINT_FIELD,
+            UNREALIZED_FINALIZED_CHECKPOINT_FIELD_KEY,
+            checkpoint,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ream_consensus_beacon::electra::beacon_block::{BeaconBlock, SignedBeaconBlock};
+    use tempdir::TempDir;
+    use tree_hash::TreeHash;
+
+    use crate::{
+        db::ReamDB,
+        errors::StoreError,
+        tables::{Field, Table},
+    };
+
+    #[test]
+    fn test_write_batch_is_atomic() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_write_batch_is_atomic")?;
+        let db = ReamDB::new(tmp_dir.path().to_path_buf())?;
+
+        let block = SignedBeaconBlock {
+            message: BeaconBlock {
+                slot: 1,
+                ..Default::default()
+            },
+            signature: Default::default(),
+        };
+        let block_root = block.message.tree_hash_root();
+
+        // Writes are visible inside the batch, but discarded if it is dropped
+        let batch = db.begin_write_batch()?;
+        batch.insert_beacon_block(block_root, &block)?;
+        batch.insert_proposer_boost_root(block_root)?;
+        assert!(batch.get_beacon_block(block_root)?.is_some());
+        assert_eq!(batch.get_proposer_boost_root()?, block_root);
+        drop(batch);
+        assert!(db.beacon_block_provider().get(block_root)?.is_none());
+        assert!(db.slot_index_provider().get(1)?.is_none());
+
+        let batch = db.begin_write_batch()?;
+        batch.insert_beacon_block(block_root, &block)?;
+        batch.insert_proposer_boost_root(block_root)?;
+        batch.commit()?;
+        assert!(db.beacon_block_provider().get(block_root)?.is_some());
+        assert_eq!(db.slot_index_provider().get(1)?, Some(block_root));
+        assert_eq!(db.proposer_boost_root_provider().get()?, block_root);
+
+        Ok(())
+    }
+}
@@ -1,11 +1,19 @@
 use std::num::NonZeroUsize;
 
+use alloy_primitives::B256;
 use lru::LruCache;
-use ream_bls::{BLSSignature, PublicKey};
+use ream_bls::PublicKey;
 use ream_consensus_beacon::bls_to_execution_change::BLSToExecutionChange;
+use ssz_types::{BitList, typenum::U131072};
 use tokio::sync::RwLock;
 
 const LRU_CACHE_SIZE: usize = 64;
+/// Roughly the number of aggregators in an epoch on mainnet
+const SEEN_AGGREGATORS_CACHE_SIZE: usize = 32768;
+/// The attestation data of two epochs with 64 committees per slot
+const SEEN_AGGREGATES_CACHE_SIZE: usize = 4096;
+/// The contributions of a few slots, each having 16 aggregators per sync subnet
+const SEEN_SYNC_CONTRIBUTIONS_CACHE_SIZE: usize = 512;
 
 #[derive(Debug, Hash, PartialEq, Eq, Default, Clone)]
 pub struct AddressSlotIdentifier {
@@ -33,16 +41,29 @@ pub struct SyncCommitteeKey {
     pub validator_index: u64,
 }
 
+#[derive(Debug, Hash, Eq, PartialEq, Default, Clone)]
+pub struct SyncContributionKey {
+    pub aggregator_index: u64,
+    pub slot: u64,
+    pub subcommittee_index: u64,
+}
+
 /// In-memory LRU cache.
 #[derive(Debug)]
 pub struct CachedDB {
-    pub seen_proposer_signature: RwLock<LruCache<AddressSlotIdentifier, BLSSignature>>,
+    /// Blocks seen per proposer index and slot
+    pub seen_block_proposers: RwLock<LruCache<(u64, u64), ()>>,
     pub seen_bls_to_execution_signature:
         RwLock<LruCache<AddressSlotIdentifier, BLSToExecutionChange>>,
     pub seen_blob_sidecars: RwLock<LruCache<(u64, u64, u64), ()>>,
     pub seen_attestations: RwLock<LruCache<AtestationKey, ()>>,
     pub seen_bls_to_execution_change: RwLock<LruCache<AddressValidaterIndexIdentifier, ()>>,
     pub seen_sync_messages: RwLock<LruCache<SyncCommitteeKey, ()>>,
+    /// Aggregates seen per aggregator index and target epoch
+    pub seen_aggregators: RwLock<LruCache<(u64, u64), ()>>,
+    /// Aggregation bits of the aggregates seen per attestation data root and committee index
+    pub seen_aggregates: RwLock<LruCache<(B256, u64), Vec<BitList<U131072>>>>,
+    pub seen_sync_contributions: RwLock<LruCache<SyncContributionKey, ()>>,
     pub seen_voluntary_exit: RwLock<LruCache<u64, ()>>,
     pub seen_proposer_slashings: RwLock<LruCache<u64, ()>>,
     pub prior_seen_attester_slashing_indices: RwLock<LruCache<u64, ()>>,
@@ -51,7 +72,7 @@ pub struct CachedDB {
 impl CachedDB {
     pub fn new() -> Self {
         Self {
-            seen_proposer_signature: LruCache::new(
+            seen_block_proposers: LruCache::new(
                 NonZeroUsize::new(LRU_CACHE_SIZE).expect("Invalid cache size"),
             )
             .into(),
@@ -75,6 +96,18 @@ impl CachedDB {
                 NonZeroUsize::new(LRU_CACHE_SIZE).expect("Invalid cache size"),
             )
             .into(),
+            seen_aggregators: LruCache::new(
+                NonZeroUsize::new(SEEN_AGGREGATORS_CACHE_SIZE).expect("Invalid cache size"),
+            )
+            .into(),
+            seen_aggregates: LruCache::new(
+                NonZeroUsize::new(SEEN_AGGREGATES_CACHE_SIZE).expect("Invalid cache size"),
+            )
+            .into(),
+            seen_sync_contributions: LruCache::new(
+                NonZeroUsize::new(SEEN_SYNC_CONTRIBUTIONS_CACHE_SIZE).expect("Invalid cache size"),
+            )
+            .into(),
             seen_voluntary_exit: LruCache::new(
                 NonZeroUsize::new(LRU_CACHE_SIZE).expect("Invalid cache size"),
             )
@@ -1,19 +1,25 @@
 use std::{fs, io, path::PathBuf, sync::Arc};
 
+use alloy_primitives::{B256, map::HashSet};
 use anyhow::{Result, anyhow};
 use ream_consensus_beacon::electra::beacon_state::BeaconState;
 use redb::{Builder, Database};
 use tracing::info;
 
 use crate::{
+    batch::WriteBatch,
     errors::StoreError,
+    migrations::run_migrations,
+    read_cache::{ReadCache, ReadCacheSizes},
     tables::{
-        Table,
+        Field, Table,
         beacon_block::{BEACON_BLOCK_TABLE, BeaconBlockTable},
         beacon_state::{BEACON_STATE_TABLE, BeaconStateTable},
         blobs_and_proofs::{BLOB_FOLDER_NAME, BlobsAndProofsTable},
         block_timeliness::{BLOCK_TIMELINESS_TABLE, BlockTimelinessTable},
         checkpoint_states::{CHECKPOINT_STATES_TABLE, CheckpointStatesTable},
+        cold_state::{COLD_STATE_DIFF_TABLE, COLD_STATE_SNAPSHOT_TABLE, ColdStateTable},
+        earliest_blob_slot::{EARLIEST_BLOB_SLOT_FIELD, EarliestBlobSlotField},
         equivocating_indices::{EQUIVOCATING_INDICES_FIELD, EquivocatingIndicesField},
         finalized_checkpoint::{FINALIZED_CHECKPOINT_FIELD, FinalizedCheckpointField},
         genesis_time::{GENESIS_TIME_FIELD, GenesisTimeField},
@@ -21,6 +27,7 @@ use crate::{
         latest_messages::{LATEST_MESSAGES_TABLE, LatestMessagesTable},
         parent_root_index::{PARENT_ROOT_INDEX_MULTIMAP_TABLE, ParentRootIndexMultimapTable},
         proposer_boost_root::{PROPOSER_BOOST_ROOT_FIELD, ProposerBoostRootField},
+        schema_version::{SCHEMA_VERSION_FIELD, SchemaVersionField},
         slot_index::{SLOT_INDEX_TABLE, SlotIndexTable},
         state_root_index::{STATE_ROOT_INDEX_TABLE, StateRootIndexTable},
         time::{TIME_FIELD, TimeField},
@@ -43,10 +50,17 @@ pub const REDB_FILE: &str = "ream.redb";
 /// 1 GiB
 pub const REDB_CACHE_SIZE: usize = 1_024 * 1_024 * 1_024;
 
+/// The default number of slots between full state snapshots in the freezer
+///
+/// 64 epochs
+pub const DEFAULT_STATE_SNAPSHOT_INTERVAL: u64 = 2_048;
+
 #[derive(Clone, Debug)]
 pub struct ReamDB {
     pub db: Arc<Database>,
     pub data_dir: PathBuf,
+    pub state_snapshot_interval: u64,
+    pub read_cache: Arc<ReadCache>,
 }
 
 impl ReamDB {
@@ -55,11 +69,18 @@ impl ReamDB {
             .set_cache_size(REDB_CACHE_SIZE)
             .create(data_dir.join(REDB_FILE))?;
 
+        // Migrations run before the tables below are opened, as opening a table whose key or value
+        // type changed fails
+        run_migrations(&db)?;
+
         let write_txn = db.begin_write()?;
         write_txn.open_table(BEACON_BLOCK_TABLE)?;
         write_txn.open_table(BEACON_STATE_TABLE)?;
         write_txn.open_table(BLOCK_TIMELINESS_TABLE)?;
         write_txn.open_table(CHECKPOINT_STATES_TABLE)?;
+        write_txn.open_table(COLD_STATE_DIFF_TABLE)?;
+        write_txn.open_table(COLD_STATE_SNAPSHOT_TABLE)?;
+        write_txn.open_table(EARLIEST_BLOB_SLOT_FIELD)?;
         write_txn.open_table(EQUIVOCATING_INDICES_FIELD)?;
         write_txn.open_table(FINALIZED_CHECKPOINT_FIELD)?;
         write_txn.open_table(GENESIS_TIME_FIELD)?;
@@ -67,6 +88,7 @@ impl ReamDB {
         write_txn.open_table(LATEST_MESSAGES_TABLE)?;
         write_txn.open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)?;
         write_txn.open_table(PROPOSER_BOOST_ROOT_FIELD)?;
+        write_txn.open_table(SCHEMA_VERSION_FIELD)?;
         write_txn.open_table(SLOT_INDEX_TABLE)?;
         write_txn.open_table(STATE_ROOT_INDEX_TABLE)?;
         write_txn.open_table(TIME_FIELD)?;
@@ -80,18 +102,32 @@ impl ReamDB {
         Ok(Self {
             db: Arc::new(db),
             data_dir,
+            state_snapshot_interval: DEFAULT_STATE_SNAPSHOT_INTERVAL,
+            read_cache: Arc::new(ReadCache::default()),
         })
     }
 
+    pub fn with_state_snapshot_interval(mut self, state_snapshot_interval: u64) -> Self {
+        self.state_snapshot_interval = state_snapshot_interval.max(1);
+        self
+    }
+
+    pub fn with_read_cache_sizes(mut self, sizes: ReadCacheSizes) -> Self {
+        self.read_cache = Arc::new(ReadCache::new(sizes));
+        self
+    }
+
     pub fn beacon_block_provider(&self) -> BeaconBlockTable {
         BeaconBlockTable {
             db: self.db.clone(),
+            read_cache: self.read_cache.clone(),
         }
     }
 
     pub fn beacon_state_provider(&self) -> BeaconStateTable {
         BeaconStateTable {
             db: self.db.clone(),
+            read_cache: self.read_cache.clone(),
         }
     }
 
@@ -110,6 +146,14 @@ impl ReamDB {
     pub fn checkpoint_states_provider(&self) -> CheckpointStatesTable {
         CheckpointStatesTable {
             db: self.db.clone(),
+            read_cache: self.read_cache.clone(),
+        }
+    }
+
+    pub fn cold_state_provider(&self) -> ColdStateTable {
+        ColdStateTable {
+            db: self.db.clone(),
+            snapshot_interval: self.state_snapshot_interval,
         }
     }
 
@@ -173,12 +217,24 @@ impl ReamDB {
         }
     }
 
+    pub fn earliest_blob_slot_provider(&self) -> EarliestBlobSlotField {
+        EarliestBlobSlotField {
+            db: self.db.clone(),
+        }
+    }
+
     pub fn equivocating_indices_provider(&self) -> EquivocatingIndicesField {
         EquivocatingIndicesField {
             db: self.db.clone(),
         }
     }
 
+    pub fn schema_version_provider(&self) -> SchemaVersionField {
+        SchemaVersionField {
+            db: self.db.clone(),
+        }
+    }
+
     pub fn slot_index_provider(&self) -> SlotIndexTable {
         SlotIndexTable {
             db: self.db.clone(),
@@ -191,6 +247,11 @@ impl ReamDB {
         }
     }
 
+    /// Starts a [`WriteBatch`], which commits writes to multiple tables at once.
+    pub fn begin_write_batch(&self) -> Result<WriteBatch, StoreError> {
+        WriteBatch::new(&self.db)
+    }
+
     pub fn is_initialized(&self) -> bool {
         match self.slot_index_provider().get_highest_slot() {
             Ok(Some(slot)) => slot > 0,
@@ -211,6 +272,56 @@ impl ReamDB {
 
         Ok(state)
     }
+
+    /// The indices of the validators which equivocated, empty until the first equivocation is
+    /// recorded.
+    pub fn equivocating_indices(&self) -> Result<HashSet<u64>, StoreError> {
+        match self.equivocating_indices_provider().get() {
+            Ok(equivocating_indices) => Ok(equivocating_indices),
+            Err(StoreError::FieldNotInitilized) => Ok(HashSet::default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// The first slot for which this node still has blobs, i.e. the slot blobs were last pruned
+    /// up to, or the oldest block if they were never pruned.
+    pub fn earliest_available_blob_slot(&self) -> Result<u64, StoreError> {
+        match self.earliest_blob_slot_provider().get() {
+            Ok(slot) => Ok(slot),
+            Err(StoreError::FieldNotInitilized) => Ok(self
+                .slot_index_provider()
+                .get_oldest_slot()?
+                .unwrap_or_default()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Moves the states of all canonical blocks before the finalized block into the freezer.
+    ///
+    /// The finalized state itself stays in the hot database, as fork choice keeps using it.
+    pub fn freeze_finalized_states(&self, finalized_root: B256) -> Result<usize, StoreError> {
+        let Some(finalized_block) = self.beacon_block_provider().get(finalized_root)? else {
+            return Ok(0);
+        };
+
+        let cold_state_provider = self.cold_state_provider();
+        let start_slot = cold_state_provider
+            .get_highest_slot()?
+            .map_or(0, |slot| slot + 1);
+        let blocks = self
+            .slot_index_provider()
+            .get_range(start_slot..finalized_block.message.slot)?;
+        if blocks.is_empty() {
+            return Ok(0);
+        }
+
+        let frozen = cold_state_provider.freeze(&blocks)?;
+        info!(
+            "Moved {frozen} finalized states up to slot {} into the freezer",
+            finalized_block.message.slot
+        );
+        Ok(frozen)
+    }
 }
 
 pub fn reset_db(db_path: PathBuf) -> anyhow::Result<()> {
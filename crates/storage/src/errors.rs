@@ -16,6 +16,17 @@ pub enum StoreError {
 
     #[error("SnappyError not found {0}")]
     SnappyError(#[from] snap::Error),
+
+    #[error("State diff error: {0}")]
+    StateDiffError(String),
+
+    #[error(
+        "Database schema version {stored} is newer than the supported version {supported}, it was written by a newer release of ream"
+    )]
+    SchemaVersionTooNew { stored: u64, supported: u64 },
+
+    #[error("No migration registered from database schema version {0}")]
+    MissingMigration(u64),
 }
 
 impl From<redb::Error> for StoreError {
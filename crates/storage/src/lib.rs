@@ -1,5 +1,11 @@
+pub mod batch;
 pub mod cache;
 pub mod db;
 pub mod dir;
 pub mod errors;
+pub mod maintenance;
+pub mod migrations;
+pub mod pruning;
+pub mod read_cache;
+pub mod state_diff;
 pub mod tables;
@@ -0,0 +1,581 @@
+use std::{collections::HashSet, fmt, fs, path::Path};
+
+use alloy_primitives::B256;
+use anyhow::{anyhow, ensure};
+use ream_consensus_misc::{checkpoint::Checkpoint, misc::compute_start_slot_at_epoch};
+use redb::{
+    Builder, Durability, Key, MultimapTableDefinition, MultimapTableHandle, ReadTransaction,
+    ReadableMultimapTable, ReadableTable, ReadableTableMetadata, TableDefinition, TableHandle,
+    Value,
+};
+use tracing::info;
+
+use crate::{
+    db::{REDB_FILE, ReamDB},
+    errors::StoreError,
+    tables::{
+        Field, Table,
+        beacon_block::BEACON_BLOCK_TABLE,
+        beacon_state::BEACON_STATE_TABLE,
+        blobs_and_proofs::BLOB_FOLDER_NAME,
+        block_timeliness::BLOCK_TIMELINESS_TABLE,
+        checkpoint_states::CHECKPOINT_STATES_TABLE,
+        cold_state::{COLD_STATE_DIFF_TABLE, COLD_STATE_SNAPSHOT_TABLE},
+        earliest_blob_slot::EARLIEST_BLOB_SLOT_FIELD,
+        equivocating_indices::EQUIVOCATING_INDICES_FIELD,
+        finalized_checkpoint::{FINALIZED_CHECKPOINT_FIELD, FINALIZED_CHECKPOINT_FIELD_KEY},
+        genesis_time::GENESIS_TIME_FIELD,
+        justified_checkpoint::{JUSTIFIED_CHECKPOINT_FIELD, JUSTIFIED_CHECKPOINT_KEY},
+        latest_messages::LATEST_MESSAGES_TABLE,
+        parent_root_index::PARENT_ROOT_INDEX_MULTIMAP_TABLE,
+        proposer_boost_root::{PROPOSER_BOOST_ROOT_FIELD, PROPOSER_BOOST_ROOT_KEY},
+        schema_version::SCHEMA_VERSION_FIELD,
+        slot_index::SLOT_INDEX_TABLE,
+        state_root_index::STATE_ROOT_INDEX_TABLE,
+        time::TIME_FIELD,
+        unrealized_finalized_checkpoint::{
+            UNREALIZED_FINALIZED_CHECKPOINT_FIELD, UNREALIZED_FINALIZED_CHECKPOINT_FIELD_KEY,
+        },
+        unrealized_justifications::UNREALIZED_JUSTIFICATIONS_TABLE,
+        unrealized_justified_checkpoint::{
+            UNREALIZED_JUSTIFED_CHECKPOINT_FIELD, UNREALIZED_JUSTIFED_CHECKPOINT_KEY,
+        },
+    },
+};
+
+/// Size and entry count of a single table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableInfo {
+    pub name: String,
+    pub entries: u64,
+    pub stored_bytes: u64,
+    pub metadata_bytes: u64,
+    pub fragmented_bytes: u64,
+}
+
+/// An inconsistency between the block table and one of its indices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IndexIssue {
+    SlotIndexMissingBlock {
+        slot: u64,
+        block_root: B256,
+    },
+    SlotIndexWrongSlot {
+        slot: u64,
+        block_root: B256,
+        block_slot: u64,
+    },
+    StateRootIndexMissingBlock {
+        state_root: B256,
+        block_root: B256,
+    },
+    StateRootIndexWrongStateRoot {
+        state_root: B256,
+        block_root: B256,
+    },
+    ParentRootIndexMissingBlock {
+        parent_root: B256,
+        block_root: B256,
+    },
+    ParentRootIndexWrongParent {
+        parent_root: B256,
+        block_root: B256,
+    },
+    BlockMissingFromParentRootIndex {
+        block_root: B256,
+        parent_root: B256,
+    },
+    BlockMissingFromStateRootIndex {
+        block_root: B256,
+        state_root: B256,
+    },
+}
+
+impl fmt::Display for IndexIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexIssue::SlotIndexMissingBlock { slot, block_root } => write!(
+                f,
+                "slot index entry {slot} points to unknown block {block_root}"
+            ),
+            IndexIssue::SlotIndexWrongSlot {
+                slot,
+                block_root,
+                block_slot,
+            } => write!(
+                f,
+                "slot index entry {slot} points to block {block_root} at slot {block_slot}"
+            ),
+            IndexIssue::StateRootIndexMissingBlock {
+                state_root,
+                block_root,
+            } => write!(
+                f,
+                "state root index entry {state_root} points to unknown block {block_root}"
+            ),
+            IndexIssue::StateRootIndexWrongStateRoot {
+                state_root,
+                block_root,
+            } => write!(
+                f,
+                "state root index entry {state_root} points to block {block_root} with another state root"
+            ),
+            IndexIssue::ParentRootIndexMissingBlock {
+                parent_root,
+                block_root,
+            } => write!(
+                f,
+                "parent root index entry {parent_root} lists unknown block {block_root}"
+            ),
+            IndexIssue::ParentRootIndexWrongParent {
+                parent_root,
+                block_root,
+            } => write!(
+                f,
+                "parent root index entry {parent_root} lists block {block_root} with another parent"
+            ),
+            IndexIssue::BlockMissingFromParentRootIndex {
+                block_root,
+                parent_root,
+            } => write!(
+                f,
+                "block {block_root} is missing from the parent root index entry {parent_root}"
+            ),
+            IndexIssue::BlockMissingFromStateRootIndex {
+                block_root,
+                state_root,
+            } => write!(
+                f,
+                "block {block_root} is missing from the state root index entry {state_root}"
+            ),
+        }
+    }
+}
+
+/// Number of entries removed by [`ReamDB::rollback_to_finalized_epoch`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RollbackSummary {
+    pub blocks: usize,
+    pub states: usize,
+    pub cold_states: usize,
+    pub latest_messages: usize,
+}
+
+fn table_info<K: Key + 'static, V: Value + 'static>(
+    read_txn: &ReadTransaction,
+    definition: TableDefinition<K, V>,
+) -> Result<TableInfo, StoreError> {
+    let table = read_txn.open_table(definition)?;
+    let stats = table.stats()?;
+    Ok(TableInfo {
+        name: definition.name().to_string(),
+        entries: table.len()?,
+        stored_bytes: stats.stored_bytes(),
+        metadata_bytes: stats.metadata_bytes(),
+        fragmented_bytes: stats.fragmented_bytes(),
+    })
+}
+
+fn multimap_table_info<K: Key + 'static, V: Key + 'static>(
+    read_txn: &ReadTransaction,
+    definition: MultimapTableDefinition<K, V>,
+) -> Result<TableInfo, StoreError> {
+    let table = read_txn.open_multimap_table(definition)?;
+    let stats = table.stats()?;
+    Ok(TableInfo {
+        name: definition.name().to_string(),
+        entries: table.len()?,
+        stored_bytes: stats.stored_bytes(),
+        metadata_bytes: stats.metadata_bytes(),
+        fragmented_bytes: stats.fragmented_bytes(),
+    })
+}
+
+impl ReamDB {
+    /// Returns the size and entry count of every table, followed by the blob files.
+    pub fn table_info(&self) -> Result<Vec<TableInfo>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        let mut tables = vec![
+            table_info(&read_txn, BEACON_BLOCK_TABLE)?,
+            table_info(&read_txn, BEACON_STATE_TABLE)?,
+            table_info(&read_txn, BLOCK_TIMELINESS_TABLE)?,
+            table_info(&read_txn, CHECKPOINT_STATES_TABLE)?,
+            table_info(&read_txn, COLD_STATE_DIFF_TABLE)?,
+            table_info(&read_txn, COLD_STATE_SNAPSHOT_TABLE)?,
+            table_info(&read_txn, EARLIEST_BLOB_SLOT_FIELD)?,
+            table_info(&read_txn, EQUIVOCATING_INDICES_FIELD)?,
+            table_info(&read_txn, FINALIZED_CHECKPOINT_FIELD)?,
+            table_info(&read_txn, GENESIS_TIME_FIELD)?,
+            table_info(&read_txn, JUSTIFIED_CHECKPOINT_FIELD)?,
+            table_info(&read_txn, LATEST_MESSAGES_TABLE)?,
+            multimap_table_info(&read_txn, PARENT_ROOT_INDEX_MULTIMAP_TABLE)?,
+            table_info(&read_txn, PROPOSER_BOOST_ROOT_FIELD)?,
+            table_info(&read_txn, SCHEMA_VERSION_FIELD)?,
+            table_info(&read_txn, SLOT_INDEX_TABLE)?,
+            table_info(&read_txn, STATE_ROOT_INDEX_TABLE)?,
+            table_info(&read_txn, TIME_FIELD)?,
+            table_info(&read_txn, UNREALIZED_FINALIZED_CHECKPOINT_FIELD)?,
+            table_info(&read_txn, UNREALIZED_JUSTIFICATIONS_TABLE)?,
+            table_info(&read_txn, UNREALIZED_JUSTIFED_CHECKPOINT_FIELD)?,
+        ];
+
+        let mut blobs = TableInfo {
+            name: BLOB_FOLDER_NAME.to_string(),
+            entries: 0,
+            stored_bytes: 0,
+            metadata_bytes: 0,
+            fragmented_bytes: 0,
+        };
+        for entry in fs::read_dir(self.data_dir.join(BLOB_FOLDER_NAME))? {
+            let metadata = entry?.metadata()?;
+            if metadata.is_file() {
+                blobs.entries += 1;
+                blobs.stored_bytes += metadata.len();
+            }
+        }
+        tables.push(blobs);
+
+        Ok(tables)
+    }
+
+    /// Cross checks the slot, state root and parent root indices against the block table.
+    pub fn check_indices(&self) -> Result<Vec<IndexIssue>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        let blocks = read_txn.open_table(BEACON_BLOCK_TABLE)?;
+        let slot_index = read_txn.open_table(SLOT_INDEX_TABLE)?;
+        let state_root_index = read_txn.open_table(STATE_ROOT_INDEX_TABLE)?;
+        let parent_root_index = read_txn.open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)?;
+
+        let mut issues = vec![];
+        for entry in slot_index.iter()? {
+            let (slot, block_root) = entry?;
+            let (slot, block_root) = (slot.value(), block_root.value());
+            match blocks
+                .get(block_root)?
+                .map(|block| block.value().message.slot)
+            {
+                None => issues.push(IndexIssue::SlotIndexMissingBlock { slot, block_root }),
+                Some(block_slot) if block_slot != slot => {
+                    issues.push(IndexIssue::SlotIndexWrongSlot {
+                        slot,
+                        block_root,
+                        block_slot,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        for entry in state_root_index.iter()? {
+            let (state_root, block_root) = entry?;
+            let (state_root, block_root) = (state_root.value(), block_root.value());
+            match blocks.get(block_root)? {
+                None => issues.push(IndexIssue::StateRootIndexMissingBlock {
+                    state_root,
+                    block_root,
+                }),
+                Some(block) if block.value().message.state_root != state_root => {
+                    issues.push(IndexIssue::StateRootIndexWrongStateRoot {
+                        state_root,
+                        block_root,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+
+        for entry in parent_root_index.iter()? {
+            let (parent_root, children) = entry?;
+            let parent_root = parent_root.value();
+            for child in children {
+                let block_root = child?.value();
+                match blocks.get(block_root)? {
+                    None => issues.push(IndexIssue::ParentRootIndexMissingBlock {
+                        parent_root,
+                        block_root,
+                    }),
+                    Some(block) if block.value().message.parent_root != parent_root => {
+                        issues.push(IndexIssue::ParentRootIndexWrongParent {
+                            parent_root,
+                            block_root,
+                        })
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+
+        for entry in blocks.iter()? {
+            let (block_root, block) = entry?;
+            let (block_root, block) = (block_root.value(), block.value());
+            let parent_root = block.message.parent_root;
+            let mut children = parent_root_index.get(parent_root)?;
+            if !children.any(|child| child.is_ok_and(|child| child.value() == block_root)) {
+                issues.push(IndexIssue::BlockMissingFromParentRootIndex {
+                    block_root,
+                    parent_root,
+                });
+            }
+            let state_root = block.message.state_root;
+            if state_root_index.get(state_root)?.is_none() {
+                issues.push(IndexIssue::BlockMissingFromStateRootIndex {
+                    block_root,
+                    state_root,
+                });
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// Moves the head back to the canonical checkpoint of `epoch`, which must not be later than
+    /// the current finalized checkpoint.
+    ///
+    /// Every block after the checkpoint block is deleted together with its state, indices and
+    /// blobs. The checkpoint becomes the justified and finalized checkpoint, and all latest
+    /// messages are dropped, so fork choice starts over from the checkpoint on the next start.
+    pub fn rollback_to_finalized_epoch(&self, epoch: u64) -> anyhow::Result<RollbackSummary> {
+        let finalized_checkpoint = self.finalized_checkpoint_provider().get()?;
+        ensure!(
+            epoch <= finalized_checkpoint.epoch,
+            "Epoch {epoch} is after the finalized epoch {}",
+            finalized_checkpoint.epoch
+        );
+
+        let epoch_start_slot = compute_start_slot_at_epoch(epoch);
+        let (checkpoint_slot, checkpoint_root) = {
+            let read_txn = self.db.begin_read()?;
+            let slot_index = read_txn.open_table(SLOT_INDEX_TABLE)?;
+            let entry = slot_index
+                .range(..=epoch_start_slot)?
+                .next_back()
+                .transpose()?
+                .ok_or_else(|| {
+                    anyhow!("No canonical block at or before slot {epoch_start_slot}")
+                })?;
+            (entry.0.value(), entry.1.value())
+        };
+        let checkpoint = Checkpoint {
+            epoch,
+            root: checkpoint_root,
+        };
+
+        // Read the states first, the checkpoint state may have to be reconstructed from the
+        // freezer and advanced to the epoch boundary
+        let state = self
+            .beacon_state_provider()
+            .get(checkpoint_root)?
+            .ok_or_else(|| anyhow!("No state for the checkpoint block {checkpoint_root}"))?;
+        let checkpoint_state = match self.checkpoint_states_provider().get(checkpoint)? {
+            Some(checkpoint_state) => checkpoint_state,
+            None => {
+                let mut checkpoint_state = state.clone();
+                if checkpoint_state.slot < epoch_start_slot {
+                    checkpoint_state.process_slots(epoch_start_slot)?;
+                }
+                checkpoint_state
+            }
+        };
+
+        let mut summary = RollbackSummary::default();
+        let mut write_txn = self.db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate);
+        let mut blocks = write_txn.open_table(BEACON_BLOCK_TABLE)?;
+        let mut states = write_txn.open_table(BEACON_STATE_TABLE)?;
+        let mut block_timeliness = write_txn.open_table(BLOCK_TIMELINESS_TABLE)?;
+        let mut checkpoint_states = write_txn.open_table(CHECKPOINT_STATES_TABLE)?;
+        let mut cold_state_snapshots = write_txn.open_table(COLD_STATE_SNAPSHOT_TABLE)?;
+        let mut cold_state_diffs = write_txn.open_table(COLD_STATE_DIFF_TABLE)?;
+        let mut latest_messages = write_txn.open_table(LATEST_MESSAGES_TABLE)?;
+        let mut parent_root_index =
+            write_txn.open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)?;
+        let mut slot_index = write_txn.open_table(SLOT_INDEX_TABLE)?;
+        let mut state_root_index = write_txn.open_table(STATE_ROOT_INDEX_TABLE)?;
+        let mut unrealized_justifications =
+            write_txn.open_table(UNREALIZED_JUSTIFICATIONS_TABLE)?;
+
+        // Canonical blocks are found through the slot index, and blocks on forks through the
+        // children of every block already found, so the block table is never scanned
+        let mut pending_roots = slot_index
+            .range(checkpoint_slot + 1..)?
+            .map(|entry| Ok(entry?.1.value()))
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        pending_roots.push(checkpoint_root);
+        let mut visited_roots = HashSet::new();
+        let mut removed_blocks = vec![];
+        while let Some(block_root) = pending_roots.pop() {
+            if !visited_roots.insert(block_root) {
+                continue;
+            }
+            for child_root in parent_root_index.get(block_root)? {
+                pending_roots.push(child_root?.value());
+            }
+            if block_root == checkpoint_root {
+                continue;
+            }
+            if let Some(block) = blocks.get(block_root)? {
+                removed_blocks.push((block_root, block.value().message));
+            }
+        }
+        for (block_root, block) in &removed_blocks {
+            blocks.remove(*block_root)?;
+            summary.blocks += 1;
+            if states.remove(*block_root)?.is_some() {
+                summary.states += 1;
+            }
+            block_timeliness.remove(*block_root)?;
+            unrealized_justifications.remove(*block_root)?;
+            parent_root_index.remove(block.parent_root, *block_root)?;
+            parent_root_index.remove_all(*block_root)?;
+            if state_root_index
+                .get(block.state_root)?
+                .map(|root| root.value())
+                == Some(*block_root)
+            {
+                state_root_index.remove(block.state_root)?;
+            }
+        }
+
+        let stale_slots = slot_index
+            .range(checkpoint_slot + 1..)?
+            .map(|entry| Ok(entry?.0.value()))
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        for slot in stale_slots {
+            slot_index.remove(slot)?;
+        }
+
+        // The freezer only holds states before the finalized block, which has to be hot
+        let stale_slots = cold_state_snapshots
+            .range(checkpoint_slot..)?
+            .map(|entry| Ok(entry?.0.value()))
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        for slot in stale_slots {
+            cold_state_snapshots.remove(slot)?;
+            summary.cold_states += 1;
+        }
+        let stale_slots = cold_state_diffs
+            .range(checkpoint_slot..)?
+            .map(|entry| Ok(entry?.0.value()))
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        for slot in stale_slots {
+            cold_state_diffs.remove(slot)?;
+            summary.cold_states += 1;
+        }
+        states.insert(checkpoint_root, &state)?;
+
+        let stored_checkpoints = checkpoint_states
+            .iter()?
+            .map(|entry| Ok(entry?.0.value()))
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        for stored_checkpoint in stored_checkpoints {
+            if stored_checkpoint.epoch >= epoch {
+                checkpoint_states.remove(stored_checkpoint)?;
+            }
+        }
+        checkpoint_states.insert(checkpoint, &checkpoint_state)?;
+
+        let validator_indices = latest_messages
+            .iter()?
+            .map(|entry| Ok(entry?.0.value()))
+            .collect::<Result<Vec<_>, StoreError>>()?;
+        for validator_index in validator_indices {
+            latest_messages.remove(validator_index)?;
+            summary.latest_messages += 1;
+        }
+        unrealized_justifications.insert(checkpoint_root, checkpoint)?;
+
+        drop(blocks);
+        drop(states);
+        drop(block_timeliness);
+        drop(checkpoint_states);
+        drop(cold_state_snapshots);
+        drop(cold_state_diffs);
+        drop(latest_messages);
+        drop(parent_root_index);
+        drop(slot_index);
+        drop(state_root_index);
+        drop(unrealized_justifications);
+
+        for (definition, key) in [
+            (JUSTIFIED_CHECKPOINT_FIELD, JUSTIFIED_CHECKPOINT_KEY),
+            (FINALIZED_CHECKPOINT_FIELD, FINALIZED_CHECKPOINT_FIELD_KEY),
+            (
+                UNREALIZED_JUSTIFED_CHECKPOINT_FIELD,
+                UNREALIZED_JUSTIFED_CHECKPOINT_KEY,
+            ),
+            (
+                UNREALIZED_FINALIZED_CHECKPOINT_FIELD,
+                UNREALIZED_FINALIZED_CHECKPOINT_FIELD_KEY,
+            ),
+        ] {
+            write_txn.open_table(definition)?.insert(key, checkpoint)?;
+        }
+        write_txn
+            .open_table(PROPOSER_BOOST_ROOT_FIELD)?
+            .insert(PROPOSER_BOOST_ROOT_KEY, B256::ZERO)?;
+        write_txn.commit()?;
+
+        let removed_roots = removed_blocks
+            .into_iter()
+            .map(|(block_root, _)| block_root)
+            .collect::<Vec<_>>();
+        self.read_cache.remove_blocks(&removed_roots);
+        let blobs_and_proofs = self.blobs_and_proofs_provider();
+        for block_root in removed_roots {
+            blobs_and_proofs.remove_block(block_root)?;
+        }
+
+        info!("Rolled back to checkpoint {checkpoint:?} at slot {checkpoint_slot}: {summary:?}");
+        Ok(summary)
+    }
+}
+
+/// Rewrites the database file in place to release unused space.
+///
+/// The database must exist and not be opened by anything else. Returns whether any space was
+/// released.
+pub fn compact_db(data_dir: &Path) -> Result<bool, StoreError> {
+    let mut db = Builder::new().open(data_dir.join(REDB_FILE))?;
+    let compacted = db.compact().map_err(redb::Error::from)?;
+    Ok(compacted)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_consensus_beacon::electra::beacon_block::{BeaconBlock, SignedBeaconBlock};
+    use tempdir::TempDir;
+    use tree_hash::TreeHash;
+
+    use super::IndexIssue;
+    use crate::{db::ReamDB, errors::StoreError, tables::Table};
+
+    #[test]
+    fn test_check_indices() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_check_indices")?;
+        let db = ReamDB::new(tmp_dir.path().to_path_buf())?;
+
+        let block = SignedBeaconBlock {
+            message: BeaconBlock {
+                slot: 1,
+                ..Default::default()
+            },
+            signature: Default::default(),
+        };
+        db.beacon_block_provider()
+            .insert(block.message.tree_hash_root(), block)?;
+        assert_eq!(db.check_indices()?, vec![]);
+
+        let unknown_root = B256::with_last_byte(1);
+        db.slot_index_provider().insert(2, unknown_root)?;
+        assert_eq!(
+            db.check_indices()?,
+            vec![IndexIssue::SlotIndexMissingBlock {
+                slot: 2,
+                block_root: unknown_root,
+            }]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,216 @@
+use std::time::Instant;
+
+use redb::{Database, Durability, WriteTransaction};
+use tracing::info;
+
+use crate::{
+    errors::StoreError,
+    tables::schema_version::{SCHEMA_VERSION_FIELD, SCHEMA_VERSION_KEY},
+};
+
+/// The schema version written by this release.
+///
+/// Bump this and register a [`Migration`] in [`MIGRATIONS`] whenever the key or value encoding of
+/// a table changes, or a table is added which existing databases have to backfill.
+pub const CURRENT_SCHEMA_VERSION: u64 = 1;
+
+/// A forward migration which upgrades the database to `version` from the version before it.
+pub struct Migration {
+    pub version: u64,
+    pub description: &'static str,
+    pub migrate: fn(&WriteTransaction) -> Result<(), StoreError>,
+}
+
+/// Every migration in order of version. A migration runs in a single write transaction together
+/// with the update of the stored schema version, so an interrupted migration is retried on the
+/// next start.
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    description: "record the schema version of databases created before versioning",
+    migrate: |_write_txn| Ok(()),
+}];
+
+/// Brings the database up to [`CURRENT_SCHEMA_VERSION`].
+///
+/// A new database is stamped with the current version. A database without a stored version, but
+/// with existing tables, predates versioning and is treated as version 0. A database written by a
+/// newer release is refused.
+pub fn run_migrations(db: &Database) -> Result<(), StoreError> {
+    apply_migrations(db, MIGRATIONS, CURRENT_SCHEMA_VERSION)
+}
+
+fn apply_migrations(
+    db: &Database,
+    migrations: &[Migration],
+    target_version: u64,
+) -> Result<(), StoreError> {
+    let mut write_txn = db.begin_write()?;
+    write_txn.set_durability(Durability::Immediate);
+    let is_new = write_txn.list_tables()?.next().is_none()
+        && write_txn.list_multimap_tables()?.next().is_none();
+    let mut table = write_txn.open_table(SCHEMA_VERSION_FIELD)?;
+    let stored_version = table
+        .get(SCHEMA_VERSION_KEY)?
+        .map(|version| version.value());
+    let stored_version = match stored_version {
+        Some(version) => version,
+        None if is_new => {
+            table.insert(SCHEMA_VERSION_KEY, target_version)?;
+            target_version
+        }
+        None => 0,
+    };
+    drop(table);
+    write_txn.commit()?;
+
+    if stored_version > target_version {
+        return Err(StoreError::SchemaVersionTooNew {
+            stored: stored_version,
+            supported: target_version,
+        });
+    }
+    if stored_version == target_version {
+        return Ok(());
+    }
+
+    info!(
+        "Migrating database schema from version {stored_version} to {target_version}, do not stop the node"
+    );
+    let mut version = stored_version;
+    while version < target_version {
+        let migration = migrations
+            .iter()
+            .find(|migration| migration.version == version + 1)
+            .ok_or(StoreError::MissingMigration(version))?;
+
+        info!(
+            "Running database migration {}/{target_version}: {}",
+            migration.version, migration.description
+        );
+        let start = Instant::now();
+
+        let mut write_txn = db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate);
+        (migration.migrate)(&write_txn)?;
+        let mut table = write_txn.open_table(SCHEMA_VERSION_FIELD)?;
+        table.insert(SCHEMA_VERSION_KEY, migration.version)?;
+        drop(table);
+        write_txn.commit()?;
+
+        info!(
+            "Database migration {}/{target_version} finished in {:.2?}",
+            migration.version,
+            start.elapsed()
+        );
+        version = migration.version;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use redb::{Builder, Database, TableDefinition};
+    use tempdir::TempDir;
+
+    use super::{Migration, apply_migrations};
+    use crate::{
+        db::{REDB_FILE, ReamDB},
+        errors::StoreError,
+        migrations::CURRENT_SCHEMA_VERSION,
+        tables::{
+            Field,
+            schema_version::{SCHEMA_VERSION_FIELD, SCHEMA_VERSION_KEY},
+        },
+    };
+
+    const OLD_TABLE: TableDefinition<u64, u64> = TableDefinition::new("old_table");
+    const NEW_TABLE: TableDefinition<u64, u64> = TableDefinition::new("new_table");
+
+    const TEST_MIGRATIONS: &[Migration] = &[
+        Migration {
+            version: 1,
+            description: "no-op",
+            migrate: |_write_txn| Ok(()),
+        },
+        Migration {
+            version: 2,
+            description: "double the values of the old table into the new table",
+            migrate: |write_txn| {
+                let old_table = write_txn.open_table(OLD_TABLE)?;
+                let mut new_table = write_txn.open_table(NEW_TABLE)?;
+                for entry in old_table.iter()? {
+                    let (key, value) = entry?;
+                    new_table.insert(key.value(), value.value() * 2)?;
+                }
+                Ok(())
+            },
+        },
+    ];
+
+    fn stored_version(db: &Database) -> Result<Option<u64>, StoreError> {
+        let read_txn = db.begin_read()?;
+        let table = read_txn.open_table(SCHEMA_VERSION_FIELD)?;
+        Ok(table
+            .get(SCHEMA_VERSION_KEY)?
+            .map(|version| version.value()))
+    }
+
+    #[test]
+    fn test_new_database_is_stamped_with_current_version() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_new_database_is_stamped_with_current_version")?;
+        let db = ReamDB::new(tmp_dir.path().to_path_buf())?;
+        assert_eq!(db.schema_version_provider().get()?, CURRENT_SCHEMA_VERSION);
+        Ok(())
+    }
+
+    #[test]
+    fn test_unversioned_database_is_migrated() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_unversioned_database_is_migrated")?;
+        let db = Builder::new().create(tmp_dir.path().join(REDB_FILE))?;
+
+        let write_txn = db.begin_write()?;
+        let mut old_table = write_txn.open_table(OLD_TABLE)?;
+        old_table.insert(1, 10)?;
+        old_table.insert(2, 20)?;
+        drop(old_table);
+        write_txn.commit()?;
+
+        apply_migrations(&db, TEST_MIGRATIONS, 2)?;
+        assert_eq!(stored_version(&db)?, Some(2));
+
+        let read_txn = db.begin_read()?;
+        let new_table = read_txn.open_table(NEW_TABLE)?;
+        assert_eq!(new_table.get(1)?.map(|value| value.value()), Some(20));
+        assert_eq!(new_table.get(2)?.map(|value| value.value()), Some(40));
+        drop(new_table);
+        drop(read_txn);
+
+        // Running again at the same version is a no-op
+        apply_migrations(&db, &[], 2)?;
+        assert_eq!(stored_version(&db)?, Some(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_newer_or_unknown_schema_version_is_refused() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_newer_or_unknown_schema_version_is_refused")?;
+        let db = Builder::new().create(tmp_dir.path().join(REDB_FILE))?;
+        apply_migrations(&db, TEST_MIGRATIONS, 2)?;
+
+        assert!(matches!(
+            apply_migrations(&db, TEST_MIGRATIONS, 1),
+            Err(StoreError::SchemaVersionTooNew {
+                stored: 2,
+                supported: 1
+            })
+        ));
+        assert!(matches!(
+            apply_migrations(&db, TEST_MIGRATIONS, 3),
+            Err(StoreError::MissingMigration(2))
+        ));
+
+        Ok(())
+    }
+}
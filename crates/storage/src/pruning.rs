@@ -0,0 +1,321 @@
+use std::{collections::HashSet, time::Duration};
+
+use alloy_primitives::B256;
+use ream_consensus_misc::checkpoint::Checkpoint;
+use redb::{Durability, ReadableMultimapTable, ReadableTable};
+
+use crate::{
+    db::ReamDB,
+    errors::StoreError,
+    tables::{
+        Field, SSZEncoding, beacon_block::BEACON_BLOCK_TABLE, beacon_state::BEACON_STATE_TABLE,
+        blobs_and_proofs::BlobRepairSummary, block_timeliness::BLOCK_TIMELINESS_TABLE,
+        checkpoint_states::CHECKPOINT_STATES_TABLE, latest_messages::LATEST_MESSAGES_TABLE,
+        parent_root_index::PARENT_ROOT_INDEX_MULTIMAP_TABLE, slot_index::SLOT_INDEX_TABLE,
+        state_root_index::STATE_ROOT_INDEX_TABLE,
+        unrealized_justifications::UNREALIZED_JUSTIFICATIONS_TABLE,
+    },
+};
+
+/// Number of entries removed from each table by [`ReamDB::prune_finalized`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PruneSummary {
+    pub blocks: usize,
+    pub states: usize,
+    pub checkpoint_states: usize,
+    pub latest_messages: usize,
+    pub block_timeliness: usize,
+    pub blobs: usize,
+}
+
+fn get_children(
+    parent_root_index: &impl ReadableMultimapTable<SSZEncoding<B256>, SSZEncoding<B256>>,
+    block_root: B256,
+) -> Result<Vec<B256>, StoreError> {
+    parent_root_index
+        .get(block_root)?
+        .map(|child| -> Result<_, StoreError> { Ok(child?.value()) })
+        .collect()
+}
+
+impl ReamDB {
+    /// Deletes everything fork choice can no longer use once `finalized_checkpoint` is finalized:
+    /// - every branch which forks off the canonical chain between `previous_finalized_root` and
+    ///   the finalized block, with their states, indices and the latest messages voting for them
+    /// - checkpoint states from before the finalized epoch
+    /// - block timeliness of canonical blocks before the finalized block
+    ///
+    /// The slot index is rewritten to point at the canonical chain, so it stays correct after
+    /// reorgs.
+    pub fn prune_finalized(
+        &self,
+        previous_finalized_root: B256,
+        finalized_checkpoint: Checkpoint,
+    ) -> Result<PruneSummary, StoreError> {
+        let mut summary = PruneSummary::default();
+
+        let mut write_txn = self.db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate);
+        let mut blocks = write_txn.open_table(BEACON_BLOCK_TABLE)?;
+        let mut states = write_txn.open_table(BEACON_STATE_TABLE)?;
+        let mut block_timeliness = write_txn.open_table(BLOCK_TIMELINESS_TABLE)?;
+        let mut checkpoint_states = write_txn.open_table(CHECKPOINT_STATES_TABLE)?;
+        let mut latest_messages = write_txn.open_table(LATEST_MESSAGES_TABLE)?;
+        let mut parent_root_index =
+            write_txn.open_multimap_table(PARENT_ROOT_INDEX_MULTIMAP_TABLE)?;
+        let mut slot_index = write_txn.open_table(SLOT_INDEX_TABLE)?;
+        let mut state_root_index = write_txn.open_table(STATE_ROOT_INDEX_TABLE)?;
+        let mut unrealized_justifications =
+            write_txn.open_table(UNREALIZED_JUSTIFICATIONS_TABLE)?;
+
+        // Walk back from the finalized block to the previously finalized one
+        let mut canonical_chain = vec![];
+        let mut block_root = finalized_checkpoint.root;
+        while let Some(block) = blocks.get(block_root)?.map(|block| block.value()) {
+            canonical_chain.push((block.message.slot, block_root));
+            if block_root == previous_finalized_root {
+                break;
+            }
+            block_root = block.message.parent_root;
+        }
+        canonical_chain.reverse();
+
+        // Every child of a canonical block, other than the next canonical block, starts a branch
+        // which conflicts with finalization
+        let mut pruned_roots = vec![];
+        for window in canonical_chain.windows(2) {
+            let (_, parent_root) = window[0];
+            let (_, canonical_root) = window[1];
+            let mut stack = get_children(&parent_root_index, parent_root)?;
+            stack.retain(|child| *child != canonical_root);
+            while let Some(block_root) = stack.pop() {
+                stack.extend(get_children(&parent_root_index, block_root)?);
+                pruned_roots.push(block_root);
+            }
+        }
+
+        for block_root in &pruned_roots {
+            if let Some(block) = blocks.remove(*block_root)?.map(|block| block.value()) {
+                summary.blocks += 1;
+
+                let slot = block.message.slot;
+                if slot_index.get(slot)?.map(|root| root.value()) == Some(*block_root) {
+                    slot_index.remove(slot)?;
+                }
+                let state_root = block.message.state_root;
+                if state_root_index.get(state_root)?.map(|root| root.value()) == Some(*block_root) {
+                    state_root_index.remove(state_root)?;
+                }
+                parent_root_index.remove(block.message.parent_root, *block_root)?;
+            }
+            parent_root_index.remove_all(*block_root)?;
+
+            if states.remove(*block_root)?.is_some() {
+                summary.states += 1;
+            }
+            if block_timeliness.remove(*block_root)?.is_some() {
+                summary.block_timeliness += 1;
+            }
+            unrealized_justifications.remove(*block_root)?;
+        }
+
+        // A block from a pruned branch may have overwritten the canonical slot index entry
+        for (slot, block_root) in &canonical_chain {
+            slot_index.insert(*slot, *block_root)?;
+        }
+
+        // Timeliness is only used to decide on reorging the head
+        for (_, block_root) in &canonical_chain {
+            if *block_root != finalized_checkpoint.root
+                && block_timeliness.remove(*block_root)?.is_some()
+            {
+                summary.block_timeliness += 1;
+            }
+        }
+
+        let pruned_root_set = pruned_roots.iter().copied().collect::<HashSet<_>>();
+
+        let mut stale_checkpoints = vec![];
+        for entry in checkpoint_states.iter()? {
+            let checkpoint = entry?.0.value();
+            if checkpoint.epoch < finalized_checkpoint.epoch
+                || pruned_root_set.contains(&checkpoint.root)
+            {
+                stale_checkpoints.push(checkpoint);
+            }
+        }
+        for checkpoint in &stale_checkpoints {
+            checkpoint_states.remove(*checkpoint)?;
+            summary.checkpoint_states += 1;
+        }
+
+        if !pruned_root_set.is_empty() {
+            let mut stale_validators = vec![];
+            for entry in latest_messages.iter()? {
+                let (validator_index, latest_message) = entry?;
+                if pruned_root_set.contains(&latest_message.value().root) {
+                    stale_validators.push(validator_index.value());
+                }
+            }
+            for validator_index in stale_validators {
+                latest_messages.remove(validator_index)?;
+                summary.latest_messages += 1;
+            }
+        }
+
+        drop(blocks);
+        drop(states);
+        drop(block_timeliness);
+        drop(checkpoint_states);
+        drop(latest_messages);
+        drop(parent_root_index);
+        drop(slot_index);
+        drop(state_root_index);
+        drop(unrealized_justifications);
+        write_txn.commit()?;
+
+        self.read_cache.remove_blocks(&pruned_roots);
+        self.read_cache.remove_checkpoint_states(&stale_checkpoints);
+
+        let blobs_and_proofs = self.blobs_and_proofs_provider();
+        for block_root in pruned_roots {
+            if blobs_and_proofs.remove_block(block_root)? {
+                summary.blobs += 1;
+            }
+        }
+
+        Ok(summary)
+    }
+
+    /// Deletes the blobs of all canonical blocks before `before_slot` and records `before_slot`
+    /// as the earliest available blob slot.
+    ///
+    /// Returns the number of blocks whose blobs were removed.
+    pub fn prune_blobs(&self, before_slot: u64) -> Result<usize, StoreError> {
+        let earliest_blob_slot = self.earliest_available_blob_slot()?;
+        if before_slot <= earliest_blob_slot {
+            return Ok(0);
+        }
+
+        let blobs_and_proofs = self.blobs_and_proofs_provider();
+        let mut pruned = 0;
+        for (_, block_root) in self
+            .slot_index_provider()
+            .get_range(earliest_blob_slot..before_slot)?
+        {
+            if blobs_and_proofs.remove_block(block_root)? {
+                pruned += 1;
+            }
+        }
+        self.earliest_blob_slot_provider().insert(before_slot)?;
+
+        Ok(pruned)
+    }
+
+    /// Removes blob files which don't belong to any block in the database and are older than
+    /// `retention`, and migrates files written in the old one-file-per-blob layout, see
+    /// [`BlobsAndProofsTable::repair`].
+    ///
+    /// [`BlobsAndProofsTable::repair`]: crate::tables::blobs_and_proofs::BlobsAndProofsTable::repair
+    pub fn repair_blob_storage(
+        &self,
+        retention: Duration,
+    ) -> Result<BlobRepairSummary, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        let blocks = read_txn.open_table(BEACON_BLOCK_TABLE)?;
+
+        self.blobs_and_proofs_provider().repair(
+            |block_root| Ok(blocks.get(block_root)?.is_some()),
+            retention,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_consensus_beacon::{
+        electra::beacon_block::{BeaconBlock, SignedBeaconBlock},
+        fork_choice::latest_message::LatestMessage,
+    };
+    use ream_consensus_misc::checkpoint::Checkpoint;
+    use tempdir::TempDir;
+    use tree_hash::TreeHash;
+
+    use crate::{
+        db::ReamDB,
+        errors::StoreError,
+        tables::{MultimapTable, Table},
+    };
+
+    fn insert_block(
+        db: &ReamDB,
+        slot: u64,
+        proposer_index: u64,
+        parent_root: B256,
+    ) -> Result<B256, StoreError> {
+        let block = SignedBeaconBlock {
+            message: BeaconBlock {
+                slot,
+                proposer_index,
+                parent_root,
+                ..Default::default()
+            },
+            signature: Default::default(),
+        };
+        let block_root = block.message.tree_hash_root();
+        db.beacon_block_provider().insert(block_root, block)?;
+        Ok(block_root)
+    }
+
+    #[test]
+    fn test_prune_conflicting_branches() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_prune_conflicting_branches")?;
+        let db = ReamDB::new(tmp_dir.path().to_path_buf())?;
+
+        let genesis_root = insert_block(&db, 0, 0, B256::ZERO)?;
+        let canonical_root = insert_block(&db, 1, 0, genesis_root)?;
+        let fork_root = insert_block(&db, 1, 1, genesis_root)?;
+        let finalized_root = insert_block(&db, 2, 0, canonical_root)?;
+        let fork_child_root = insert_block(&db, 3, 1, fork_root)?;
+        let head_root = insert_block(&db, 3, 0, finalized_root)?;
+
+        db.latest_messages_provider().insert(
+            0,
+            LatestMessage {
+                epoch: 0,
+                root: fork_child_root,
+            },
+        )?;
+        db.latest_messages_provider().insert(
+            1,
+            LatestMessage {
+                epoch: 0,
+                root: head_root,
+            },
+        )?;
+
+        let summary = db.prune_finalized(
+            genesis_root,
+            Checkpoint {
+                epoch: 1,
+                root: finalized_root,
+            },
+        )?;
+        assert_eq!(summary.blocks, 2);
+        assert_eq!(summary.latest_messages, 1);
+
+        assert!(db.beacon_block_provider().get(fork_root)?.is_none());
+        assert!(db.beacon_block_provider().get(fork_child_root)?.is_none());
+        assert!(db.beacon_block_provider().get(head_root)?.is_some());
+        assert!(db.latest_messages_provider().get(1)?.is_some());
+        assert_eq!(db.slot_index_provider().get(1)?, Some(canonical_root));
+        assert_eq!(db.slot_index_provider().get(3)?, Some(head_root));
+        assert_eq!(
+            db.parent_root_index_multimap_provider().get(genesis_root)?,
+            Some(vec![canonical_root])
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,338 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+    num::NonZeroUsize,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use alloy_primitives::B256;
+use anyhow::ensure;
+use lru::LruCache;
+use ream_bls::PublicKey;
+use ream_consensus_beacon::electra::{beacon_block::BeaconBlock, beacon_state::BeaconState};
+use ream_consensus_misc::{
+    checkpoint::Checkpoint,
+    constants::beacon::{DOMAIN_BEACON_ATTESTER, SLOTS_PER_EPOCH},
+    misc::{compute_committee, compute_epoch_at_slot},
+    validator::Validator,
+};
+use ream_metrics::{BEACON_DB_CACHE_HITS, BEACON_DB_CACHE_MISSES, inc_int_counter_vec};
+
+/// The default number of beacon states kept in memory, for both block and checkpoint states
+pub const DEFAULT_STATE_CACHE_SIZE: usize = 32;
+
+/// The default number of block summaries kept in memory
+pub const DEFAULT_BLOCK_CACHE_SIZE: usize = 8_192;
+
+/// The default number of epochs of committees kept in memory
+pub const DEFAULT_COMMITTEE_CACHE_SIZE: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadCacheSizes {
+    pub states: usize,
+    pub blocks: usize,
+    pub committees: usize,
+}
+
+impl Default for ReadCacheSizes {
+    fn default() -> Self {
+        Self {
+            states: DEFAULT_STATE_CACHE_SIZE,
+            blocks: DEFAULT_BLOCK_CACHE_SIZE,
+            committees: DEFAULT_COMMITTEE_CACHE_SIZE,
+        }
+    }
+}
+
+/// The header fields of a block, which is all fork choice needs to walk the block tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockSummary {
+    pub slot: u64,
+    pub proposer_index: u64,
+    pub parent_root: B256,
+    pub state_root: B256,
+}
+
+impl From<&BeaconBlock> for BlockSummary {
+    fn from(block: &BeaconBlock) -> Self {
+        Self {
+            slot: block.slot,
+            proposer_index: block.proposer_index,
+            parent_root: block.parent_root,
+            state_root: block.state_root,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct PubkeyIndices {
+    indices: HashMap<PublicKey, u64>,
+    validator_count: usize,
+}
+
+/// In-memory caches in front of the block and state tables, so hot paths don't decode SSZ from
+/// redb on every read.
+///
+/// States and blocks are keyed by root and never change once written, so entries only have to be
+/// evicted when they are deleted from the database.
+#[derive(Debug)]
+pub struct ReadCache {
+    states: Mutex<LruCache<B256, Arc<BeaconState>>>,
+    checkpoint_states: Mutex<LruCache<Checkpoint, Arc<BeaconState>>>,
+    blocks: Mutex<LruCache<B256, BlockSummary>>,
+    committees: Mutex<LruCache<(u64, B256), Arc<Vec<Vec<u64>>>>>,
+    pubkey_indices: Mutex<PubkeyIndices>,
+}
+
+/// The cached values are always complete, so a panic while holding a lock can't leave them in an
+/// inconsistent state.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn new_lru<K: Hash + Eq, V>(size: usize) -> Mutex<LruCache<K, V>> {
+    Mutex::new(LruCache::new(
+        NonZeroUsize::new(size.max(1)).expect("Invalid cache size"),
+    ))
+}
+
+fn get_cached<K: Hash + Eq, V: Clone>(
+    cache: &Mutex<LruCache<K, V>>,
+    key: &K,
+    label: &str,
+) -> Option<V> {
+    let value = lock(cache).get(key).cloned();
+    match value {
+        Some(_) => inc_int_counter_vec(&BEACON_DB_CACHE_HITS, &[label]),
+        None => inc_int_counter_vec(&BEACON_DB_CACHE_MISSES, &[label]),
+    }
+    value
+}
+
+impl ReadCache {
+    pub fn new(sizes: ReadCacheSizes) -> Self {
+        Self {
+            states: new_lru(sizes.states),
+            checkpoint_states: new_lru(sizes.states),
+            blocks: new_lru(sizes.blocks),
+            committees: new_lru(sizes.committees),
+            pubkey_indices: Mutex::new(PubkeyIndices::default()),
+        }
+    }
+
+    pub fn get_state(&self, block_root: B256) -> Option<Arc<BeaconState>> {
+        get_cached(&self.states, &block_root, "state")
+    }
+
+    pub fn put_state(&self, block_root: B256, state: Arc<BeaconState>) {
+        lock(&self.states).put(block_root, state);
+    }
+
+    /// Whether the state of `block_root` is cached, without marking it as recently used.
+    pub fn contains_state(&self, block_root: B256) -> bool {
+        lock(&self.states).contains(&block_root)
+    }
+
+    pub fn get_checkpoint_state(&self, checkpoint: Checkpoint) -> Option<Arc<BeaconState>> {
+        get_cached(&self.checkpoint_states, &checkpoint, "checkpoint_state")
+    }
+
+    pub fn put_checkpoint_state(&self, checkpoint: Checkpoint, state: Arc<BeaconState>) {
+        lock(&self.checkpoint_states).put(checkpoint, state);
+    }
+
+    pub fn get_block_summary(&self, block_root: B256) -> Option<BlockSummary> {
+        get_cached(&self.blocks, &block_root, "block_summary")
+    }
+
+    pub fn put_block_summary(&self, block_root: B256, summary: BlockSummary) {
+        lock(&self.blocks).put(block_root, summary);
+    }
+
+    /// Evicts everything cached for blocks which were deleted from the database.
+    pub fn remove_blocks(&self, block_roots: &[B256]) {
+        let mut states = lock(&self.states);
+        let mut blocks = lock(&self.blocks);
+        for block_root in block_roots {
+            states.pop(block_root);
+            blocks.pop(block_root);
+        }
+        drop(states);
+        drop(blocks);
+
+        let mut checkpoint_states = lock(&self.checkpoint_states);
+        let stale_checkpoints = checkpoint_states
+            .iter()
+            .map(|(checkpoint, _)| *checkpoint)
+            .filter(|checkpoint| block_roots.contains(&checkpoint.root))
+            .collect::<Vec<_>>();
+        for checkpoint in stale_checkpoints {
+            checkpoint_states.pop(&checkpoint);
+        }
+    }
+
+    /// Evicts checkpoint states which were deleted from the database.
+    pub fn remove_checkpoint_states(&self, checkpoints: &[Checkpoint]) {
+        let mut checkpoint_states = lock(&self.checkpoint_states);
+        for checkpoint in checkpoints {
+            checkpoint_states.pop(checkpoint);
+        }
+    }
+
+    /// Returns the beacon committee at `slot` and `index`, computing all committees of the epoch
+    /// on a miss.
+    ///
+    /// Committees are keyed by epoch and attester seed. The seed is derived from the RANDAO mix
+    /// two epochs earlier, while the active validator set of an epoch is fixed
+    /// `MAX_SEED_LOOKAHEAD` epochs in advance, so states with the same seed share the shuffling.
+    pub fn get_beacon_committee(
+        &self,
+        state: &BeaconState,
+        slot: u64,
+        index: u64,
+    ) -> anyhow::Result<Vec<u64>> {
+        let epoch = compute_epoch_at_slot(slot);
+        let key = (epoch, state.get_seed(epoch, DOMAIN_BEACON_ATTESTER));
+
+        let committees = match get_cached(&self.committees, &key, "committee") {
+            Some(committees) => committees,
+            None => {
+                let indices = state.get_active_validator_indices(epoch);
+                let count = state.get_committee_count_per_slot(epoch) * SLOTS_PER_EPOCH;
+                let committees = Arc::new(
+                    (0..count)
+                        .map(|index| compute_committee(&indices, key.1, index, count))
+                        .collect::<anyhow::Result<Vec<_>>>()?,
+                );
+                lock(&self.committees).put(key, committees.clone());
+                committees
+            }
+        };
+
+        let committees_per_slot = committees.len() as u64 / SLOTS_PER_EPOCH;
+        ensure!(
+            index < committees_per_slot,
+            "Committee index {index} is out of range, there are {committees_per_slot} committees per slot"
+        );
+        Ok(committees[((slot % SLOTS_PER_EPOCH) * committees_per_slot + index) as usize].clone())
+    }
+
+    /// Returns the index of the validator with `public_key` in the validator list of a state.
+    ///
+    /// Validators are only ever appended, but states on different forks may have processed
+    /// different deposits, so a cached index is only used after checking it against `validators`.
+    pub fn get_validator_index(
+        &self,
+        validators: &[Validator],
+        public_key: &PublicKey,
+    ) -> Option<u64> {
+        let mut pubkey_indices = lock(&self.pubkey_indices);
+        if validators.len() > pubkey_indices.validator_count {
+            for (index, validator) in validators
+                .iter()
+                .enumerate()
+                .skip(pubkey_indices.validator_count)
+            {
+                pubkey_indices
+                    .indices
+                    .entry(validator.public_key.clone())
+                    .or_insert(index as u64);
+            }
+            pubkey_indices.validator_count = validators.len();
+        }
+
+        if let Some(index) = pubkey_indices.indices.get(public_key).copied()
+            && validators
+                .get(index as usize)
+                .is_some_and(|validator| validator.public_key == *public_key)
+        {
+            inc_int_counter_vec(&BEACON_DB_CACHE_HITS, &["pubkey"]);
+            return Some(index);
+        }
+        drop(pubkey_indices);
+
+        inc_int_counter_vec(&BEACON_DB_CACHE_MISSES, &["pubkey"]);
+        validators
+            .iter()
+            .position(|validator| validator.public_key == *public_key)
+            .map(|index| index as u64)
+    }
+}
+
+impl Default for ReadCache {
+    fn default() -> Self {
+        Self::new(ReadCacheSizes::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy_primitives::B256;
+    use ream_bls::PublicKey;
+    use ream_consensus_misc::validator::Validator;
+    use ssz_types::FixedVector;
+
+    use super::{BlockSummary, ReadCache, ReadCacheSizes};
+
+    fn validator(byte: u8) -> Validator {
+        Validator {
+            public_key: PublicKey {
+                inner: FixedVector::from(vec![byte; 48]),
+            },
+            withdrawal_credentials: B256::ZERO,
+            effective_balance: 0,
+            slashed: false,
+            activation_eligibility_epoch: 0,
+            activation_epoch: 0,
+            exit_epoch: 0,
+            withdrawable_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_block_summary_eviction() {
+        let cache = ReadCache::new(ReadCacheSizes {
+            blocks: 1,
+            ..Default::default()
+        });
+        let summary = BlockSummary {
+            slot: 1,
+            proposer_index: 0,
+            parent_root: B256::ZERO,
+            state_root: B256::ZERO,
+        };
+
+        cache.put_block_summary(B256::with_last_byte(1), summary);
+        cache.put_block_summary(B256::with_last_byte(2), summary);
+        assert_eq!(cache.get_block_summary(B256::with_last_byte(1)), None);
+        assert_eq!(
+            cache.get_block_summary(B256::with_last_byte(2)),
+            Some(summary)
+        );
+
+        cache.remove_blocks(&[B256::with_last_byte(2)]);
+        assert_eq!(cache.get_block_summary(B256::with_last_byte(2)), None);
+    }
+
+    #[test]
+    fn test_validator_index_is_checked_against_validators() {
+        let cache = ReadCache::default();
+
+        let validators = vec![validator(1), validator(2)];
+        assert_eq!(
+            cache.get_validator_index(&validators, &validator(2).public_key),
+            Some(1)
+        );
+
+        // A state on another fork with a different validator at the same index
+        let fork_validators = vec![validator(1), validator(3)];
+        assert_eq!(
+            cache.get_validator_index(&fork_validators, &validator(2).public_key),
+            None
+        );
+        assert_eq!(
+            cache.get_validator_index(&fork_validators, &validator(3).public_key),
+            Some(1)
+        );
+    }
+}
@@ -0,0 +1,366 @@
+use std::sync::Arc;
+
+use alloy_primitives::B256;
+use ream_consensus_beacon::{
+    electra::{beacon_state::BeaconState, execution_payload_header::ExecutionPayloadHeader},
+    historical_summary::HistoricalSummary,
+    pending_consolidation::PendingConsolidation,
+    pending_deposit::PendingDeposit,
+    pending_partial_withdrawal::PendingPartialWithdrawal,
+    sync_committee::SyncCommittee,
+};
+use ream_consensus_misc::{
+    beacon_block_header::BeaconBlockHeader, checkpoint::Checkpoint, eth_1_data::Eth1Data,
+    fork::Fork, validator::Validator,
+};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use ssz_types::{
+    BitVector, FixedVector, VariableList,
+    typenum::{U4, Unsigned},
+};
+
+use crate::errors::StoreError;
+
+/// The entries of a list or vector which differ from a base list.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct VectorDiff<T: Encode + Decode> {
+    /// Length of the target list
+    pub length: u64,
+    /// Indices of the entries which differ from the base, in ascending order
+    pub indices: Vec<u64>,
+    /// Values of the entries at `indices`
+    pub values: Vec<T>,
+}
+
+impl<T: Encode + Decode + Clone + PartialEq> VectorDiff<T> {
+    pub fn compute(base: &[T], target: &[T]) -> Self {
+        let (indices, values) = target
+            .iter()
+            .enumerate()
+            .filter(|(index, value)| base.get(*index) != Some(*value))
+            .map(|(index, value)| (index as u64, value.clone()))
+            .unzip();
+
+        Self {
+            length: target.len() as u64,
+            indices,
+            values,
+        }
+    }
+
+    pub fn apply(&self, base: &[T]) -> Result<Vec<T>, StoreError> {
+        if self.indices.len() != self.values.len() {
+            return Err(StoreError::StateDiffError(format!(
+                "Diff has {} indices but {} values",
+                self.indices.len(),
+                self.values.len()
+            )));
+        }
+
+        let length = self.length as usize;
+        let mut result = base[..base.len().min(length)].to_vec();
+        for (index, value) in self.indices.iter().zip(self.values.iter()) {
+            let index = *index as usize;
+            if index < result.len() {
+                result[index] = value.clone();
+            } else if index == result.len() {
+                result.push(value.clone());
+            } else {
+                return Err(StoreError::StateDiffError(format!(
+                    "Diff index {index} is past the end of a list of length {}",
+                    result.len()
+                )));
+            }
+        }
+
+        if result.len() != length {
+            return Err(StoreError::StateDiffError(format!(
+                "Expected a list of length {length}, got {}",
+                result.len()
+            )));
+        }
+
+        Ok(result)
+    }
+
+    pub fn apply_to_list<N: Unsigned>(
+        &self,
+        base: &VariableList<T, N>,
+    ) -> Result<VariableList<T, N>, StoreError> {
+        VariableList::new(self.apply(base)?)
+            .map_err(|err| StoreError::StateDiffError(format!("{err:?}")))
+    }
+
+    pub fn apply_to_vector<N: Unsigned>(
+        &self,
+        base: &FixedVector<T, N>,
+    ) -> Result<FixedVector<T, N>, StoreError> {
+        FixedVector::new(self.apply(base)?)
+            .map_err(|err| StoreError::StateDiffError(format!("{err:?}")))
+    }
+}
+
+/// A `BeaconState` stored relative to an earlier snapshot in the freezer.
+///
+/// Small fields are stored verbatim, while every list and vector only keeps the entries which
+/// changed since the snapshot at `base_slot`.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct BeaconStateDiff {
+    pub base_slot: u64,
+
+    // Versioning
+    pub genesis_time: u64,
+    pub genesis_validators_root: B256,
+    pub slot: u64,
+    pub fork: Fork,
+
+    // History
+    pub latest_block_header: BeaconBlockHeader,
+    pub block_roots: VectorDiff<B256>,
+    pub state_roots: VectorDiff<B256>,
+    pub historical_roots: VectorDiff<B256>,
+
+    // Eth1
+    pub eth1_data: Eth1Data,
+    pub eth1_data_votes: VectorDiff<Eth1Data>,
+    pub eth1_deposit_index: u64,
+
+    // Registry
+    pub validators: VectorDiff<Validator>,
+    pub balances: VectorDiff<u64>,
+
+    // Randomness
+    pub randao_mixes: VectorDiff<B256>,
+
+    // Slashings
+    pub slashings: VectorDiff<u64>,
+
+    // Participation
+    pub previous_epoch_participation: VectorDiff<u8>,
+    pub current_epoch_participation: VectorDiff<u8>,
+
+    // Finality
+    pub justification_bits: BitVector<U4>,
+    pub previous_justified_checkpoint: Checkpoint,
+    pub current_justified_checkpoint: Checkpoint,
+    pub finalized_checkpoint: Checkpoint,
+
+    // Inactivity
+    pub inactivity_scores: VectorDiff<u64>,
+
+    // Sync, empty if unchanged since the snapshot
+    pub current_sync_committee: Vec<SyncCommittee>,
+    pub next_sync_committee: Vec<SyncCommittee>,
+
+    // Execution
+    pub latest_execution_payload_header: ExecutionPayloadHeader,
+
+    // Withdrawals
+    pub next_withdrawal_index: u64,
+    pub next_withdrawal_validator_index: u64,
+
+    // Deep history valid from Capella onwards.
+    pub historical_summaries: VectorDiff<HistoricalSummary>,
+
+    // Electra
+    pub deposit_requests_start_index: u64,
+    pub deposit_balance_to_consume: u64,
+    pub exit_balance_to_consume: u64,
+    pub earliest_exit_epoch: u64,
+    pub consolidation_balance_to_consume: u64,
+    pub earliest_consolidation_epoch: u64,
+    pub pending_deposits: VectorDiff<PendingDeposit>,
+    pub pending_partial_withdrawals: VectorDiff<PendingPartialWithdrawal>,
+    pub pending_consolidations: VectorDiff<PendingConsolidation>,
+}
+
+fn sync_committee_diff(base: &SyncCommittee, target: &SyncCommittee) -> Vec<SyncCommittee> {
+    if base == target {
+        vec![]
+    } else {
+        vec![target.clone()]
+    }
+}
+
+fn apply_sync_committee_diff(
+    base: &Arc<SyncCommittee>,
+    diff: &[SyncCommittee],
+) -> Arc<SyncCommittee> {
+    match diff.first() {
+        Some(sync_committee) => Arc::new(sync_committee.clone()),
+        None => base.clone(),
+    }
+}
+
+impl BeaconStateDiff {
+    pub fn compute(base_slot: u64, base: &BeaconState, target: &BeaconState) -> Self {
+        Self {
+            base_slot,
+            genesis_time: target.genesis_time,
+            genesis_validators_root: target.genesis_validators_root,
+            slot: target.slot,
+            fork: target.fork,
+            latest_block_header: target.latest_block_header.clone(),
+            block_roots: VectorDiff::compute(&base.block_roots, &target.block_roots),
+            state_roots: VectorDiff::compute(&base.state_roots, &target.state_roots),
+            historical_roots: VectorDiff::compute(&base.historical_roots, &target.historical_roots),
+            eth1_data: target.eth1_data.clone(),
+            eth1_data_votes: VectorDiff::compute(&base.eth1_data_votes, &target.eth1_data_votes),
+            eth1_deposit_index: target.eth1_deposit_index,
+            validators: VectorDiff::compute(&base.validators, &target.validators),
+            balances: VectorDiff::compute(&base.balances, &target.balances),
+            randao_mixes: VectorDiff::compute(&base.randao_mixes, &target.randao_mixes),
+            slashings: VectorDiff::compute(&base.slashings, &target.slashings),
+            previous_epoch_participation: VectorDiff::compute(
+                &base.previous_epoch_participation,
+                &target.previous_epoch_participation,
+            ),
+            current_epoch_participation: VectorDiff::compute(
+                &base.current_epoch_participation,
+                &target.current_epoch_participation,
+            ),
+            justification_bits: target.justification_bits.clone(),
+            previous_justified_checkpoint: target.previous_justified_checkpoint,
+            current_justified_checkpoint: target.current_justified_checkpoint,
+            finalized_checkpoint: target.finalized_checkpoint,
+            inactivity_scores: VectorDiff::compute(
+                &base.inactivity_scores,
+                &target.inactivity_scores,
+            ),
+            current_sync_committee: sync_committee_diff(
+                &base.current_sync_committee,
+                &target.current_sync_committee,
+            ),
+            next_sync_committee: sync_committee_diff(
+                &base.next_sync_committee,
+                &target.next_sync_committee,
+            ),
+            latest_execution_payload_header: target.latest_execution_payload_header.clone(),
+            next_withdrawal_index: target.next_withdrawal_index,
+            next_withdrawal_validator_index: target.next_withdrawal_validator_index,
+            historical_summaries: VectorDiff::compute(
+                &base.historical_summaries,
+                &target.historical_summaries,
+            ),
+            deposit_requests_start_index: target.deposit_requests_start_index,
+            deposit_balance_to_consume: target.deposit_balance_to_consume,
+            exit_balance_to_consume: target.exit_balance_to_consume,
+            earliest_exit_epoch: target.earliest_exit_epoch,
+            consolidation_balance_to_consume: target.consolidation_balance_to_consume,
+            earliest_consolidation_epoch: target.earliest_consolidation_epoch,
+            pending_deposits: VectorDiff::compute(&base.pending_deposits, &target.pending_deposits),
+            pending_partial_withdrawals: VectorDiff::compute(
+                &base.pending_partial_withdrawals,
+                &target.pending_partial_withdrawals,
+            ),
+            pending_consolidations: VectorDiff::compute(
+                &base.pending_consolidations,
+                &target.pending_consolidations,
+            ),
+        }
+    }
+
+    /// Reconstruct the full state from the snapshot this diff was computed against.
+    pub fn apply(&self, base: &BeaconState) -> Result<BeaconState, StoreError> {
+        Ok(BeaconState {
+            genesis_time: self.genesis_time,
+            genesis_validators_root: self.genesis_validators_root,
+            slot: self.slot,
+            fork: self.fork,
+            latest_block_header: self.latest_block_header.clone(),
+            block_roots: self.block_roots.apply_to_vector(&base.block_roots)?,
+            state_roots: self.state_roots.apply_to_vector(&base.state_roots)?,
+            historical_roots: self
+                .historical_roots
+                .apply_to_list(&base.historical_roots)?,
+            eth1_data: self.eth1_data.clone(),
+            eth1_data_votes: self.eth1_data_votes.apply_to_list(&base.eth1_data_votes)?,
+            eth1_deposit_index: self.eth1_deposit_index,
+            validators: self.validators.apply_to_list(&base.validators)?,
+            balances: self.balances.apply_to_list(&base.balances)?,
+            randao_mixes: self.randao_mixes.apply_to_vector(&base.randao_mixes)?,
+            slashings: self.slashings.apply_to_vector(&base.slashings)?,
+            previous_epoch_participation: self
+                .previous_epoch_participation
+                .apply_to_list(&base.previous_epoch_participation)?,
+            current_epoch_participation: self
+                .current_epoch_participation
+                .apply_to_list(&base.current_epoch_participation)?,
+            justification_bits: self.justification_bits.clone(),
+            previous_justified_checkpoint: self.previous_justified_checkpoint,
+            current_justified_checkpoint: self.current_justified_checkpoint,
+            finalized_checkpoint: self.finalized_checkpoint,
+            inactivity_scores: self
+                .inactivity_scores
+                .apply_to_list(&base.inactivity_scores)?,
+            current_sync_committee: apply_sync_committee_diff(
+                &base.current_sync_committee,
+                &self.current_sync_committee,
+            ),
+            next_sync_committee: apply_sync_committee_diff(
+                &base.next_sync_committee,
+                &self.next_sync_committee,
+            ),
+            latest_execution_payload_header: self.latest_execution_payload_header.clone(),
+            next_withdrawal_index: self.next_withdrawal_index,
+            next_withdrawal_validator_index: self.next_withdrawal_validator_index,
+            historical_summaries: self
+                .historical_summaries
+                .apply_to_list(&base.historical_summaries)?,
+            deposit_requests_start_index: self.deposit_requests_start_index,
+            deposit_balance_to_consume: self.deposit_balance_to_consume,
+            exit_balance_to_consume: self.exit_balance_to_consume,
+            earliest_exit_epoch: self.earliest_exit_epoch,
+            consolidation_balance_to_consume: self.consolidation_balance_to_consume,
+            earliest_consolidation_epoch: self.earliest_consolidation_epoch,
+            pending_deposits: self
+                .pending_deposits
+                .apply_to_list(&base.pending_deposits)?,
+            pending_partial_withdrawals: self
+                .pending_partial_withdrawals
+                .apply_to_list(&base.pending_partial_withdrawals)?,
+            pending_consolidations: self
+                .pending_consolidations
+                .apply_to_list(&base.pending_consolidations)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ssz::{Decode, Encode};
+
+    use super::VectorDiff;
+
+    #[test]
+    fn test_vector_diff_roundtrip() {
+        let base = vec![1u64, 2, 3, 4, 5];
+        let target = vec![1u64, 7, 3, 4, 9, 10, 11];
+
+        let diff = VectorDiff::compute(&base, &target);
+        assert_eq!(diff.indices, vec![1, 4, 5, 6]);
+
+        let decoded = VectorDiff::<u64>::from_ssz_bytes(&diff.as_ssz_bytes()).unwrap();
+        assert_eq!(decoded.apply(&base).unwrap(), target);
+    }
+
+    #[test]
+    fn test_vector_diff_shrinking_list() {
+        let base = vec![1u8, 2, 3, 4];
+        let target = vec![1u8, 5];
+
+        let diff = VectorDiff::compute(&base, &target);
+        assert_eq!(diff.apply(&base).unwrap(), target);
+    }
+
+    #[test]
+    fn test_vector_diff_rejects_gaps() {
+        let diff = VectorDiff {
+            length: 4,
+            indices: vec![3],
+            values: vec![1u64],
+        };
+        assert!(diff.apply(&[0, 0]).is_err());
+    }
+}
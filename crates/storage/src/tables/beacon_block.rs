@@ -2,14 +2,14 @@ use std::sync::Arc;
 
 use alloy_primitives::B256;
 use ream_consensus_beacon::electra::beacon_block::SignedBeaconBlock;
-use redb::{Database, Durability, TableDefinition};
-use tree_hash::TreeHash;
+use redb::{Database, TableDefinition};
 
-use super::{
-    MultimapTable, SSZEncoding, Table, parent_root_index::ParentRootIndexMultimapTable,
-    slot_index::SlotIndexTable, state_root_index::StateRootIndexTable,
+use super::{SSZEncoding, Table};
+use crate::{
+    batch::WriteBatch,
+    errors::StoreError,
+    read_cache::{BlockSummary, ReadCache},
 };
-use crate::errors::StoreError;
 
 /// Table definition for the Beacon Block table
 ///
@@ -20,6 +20,7 @@ pub const BEACON_BLOCK_TABLE: TableDefinition<SSZEncoding<B256>, SSZEncoding<Sig
 
 pub struct BeaconBlockTable {
     pub db: Arc<Database>,
+    pub read_cache: Arc<ReadCache>,
 }
 
 impl Table for BeaconBlockTable {
@@ -31,34 +32,33 @@ impl Table for BeaconBlockTable {
         let read_txn = self.db.begin_read()?;
 
         let table = read_txn.open_table(BEACON_BLOCK_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        let result = table.get(key)?.map(|res| res.value());
+        if let Some(block) = &result {
+            self.read_cache
+                .put_block_summary(key, BlockSummary::from(&block.message));
+        }
+        Ok(result)
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
-        // insert entry to slot_index table
-        let block_root = value.message.tree_hash_root();
-        let slot_index_table = SlotIndexTable {
-            db: self.db.clone(),
-        };
-        slot_index_table.insert(value.message.slot, block_root)?;
-
-        // insert entry to state root index table
-        let state_root_index_table = StateRootIndexTable {
-            db: self.db.clone(),
-        };
-        state_root_index_table.insert(value.message.state_root, block_root)?;
-
-        let parent_root_index_table = ParentRootIndexMultimapTable {
-            db: self.db.clone(),
-        };
-        parent_root_index_table.insert(value.message.parent_root, block_root)?;
-        let mut write_txn = self.db.begin_write()?;
-        write_txn.set_durability(Durability::Immediate);
-        let mut table = write_txn.open_table(BEACON_BLOCK_TABLE)?;
-        table.insert(key, value)?;
-        drop(table);
-        write_txn.commit()?;
+        // The block and its slot, state root and parent root index entries are written together
+        let batch = WriteBatch::new(&self.db)?;
+        batch.insert_beacon_block(key, &value)?;
+        batch.commit()?;
+        self.read_cache
+            .put_block_summary(key, BlockSummary::from(&value.message));
         Ok(())
     }
 }
+
+impl BeaconBlockTable {
+    /// Returns the header fields of a block, without decoding its body if it is cached.
+    pub fn get_summary(&self, block_root: B256) -> Result<Option<BlockSummary>, StoreError> {
+        if let Some(summary) = self.read_cache.get_block_summary(block_root) {
+            return Ok(Some(summary));
+        }
+        Ok(self
+            .get(block_root)?
+            .map(|block| BlockSummary::from(&block.message)))
+    }
+}
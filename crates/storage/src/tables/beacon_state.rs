@@ -2,13 +2,21 @@ use std::sync::Arc;
 
 use alloy_primitives::B256;
 use ream_consensus_beacon::electra::beacon_state::BeaconState;
-use redb::{Database, Durability, TableDefinition};
+use redb::{Database, Durability, ReadTransaction, TableDefinition};
 
-use super::{SSZEncoding, Table};
-use crate::errors::StoreError;
+use super::{
+    SSZEncoding, Table,
+    beacon_block::BEACON_BLOCK_TABLE,
+    cold_state::{COLD_STATE_DIFF_TABLE, COLD_STATE_SNAPSHOT_TABLE, read_cold_state},
+    slot_index::SLOT_INDEX_TABLE,
+};
+use crate::{errors::StoreError, read_cache::ReadCache};
 
 /// Table definition for the Beacon State table
 ///
+/// Only holds the hot, unfinalized states. Finalized states are moved into the freezer (see
+/// [`ColdStateTable`](super::cold_state::ColdStateTable)), `get` transparently falls back to it.
+///
 /// Key: block_root
 /// Value: BeaconState
 pub const BEACON_STATE_TABLE: TableDefinition<SSZEncoding<B256>, SSZEncoding<BeaconState>> =
@@ -16,6 +24,7 @@ pub const BEACON_STATE_TABLE: TableDefinition<SSZEncoding<B256>, SSZEncoding<Bea
 
 pub struct BeaconStateTable {
     pub db: Arc<Database>,
+    pub read_cache: Arc<ReadCache>,
 }
 
 impl Table for BeaconStateTable {
@@ -24,20 +33,95 @@ impl Table for BeaconStateTable {
     type Value = BeaconState;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(BEACON_STATE_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        Ok(self.get_shared(key)?.map(Arc::unwrap_or_clone))
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
         let mut write_txn = self.db.begin_write()?;
         write_txn.set_durability(Durability::Immediate);
         let mut table = write_txn.open_table(BEACON_STATE_TABLE)?;
-        table.insert(key, value)?;
+        table.insert(key, &value)?;
         drop(table);
         write_txn.commit()?;
+        self.read_cache.put_state(key, Arc::new(value));
         Ok(())
     }
 }
+
+impl BeaconStateTable {
+    /// Like `get`, but shares the cached state instead of copying it, for callers which only read
+    /// it.
+    pub fn get_shared(&self, key: B256) -> Result<Option<Arc<BeaconState>>, StoreError> {
+        if let Some(state) = self.read_cache.get_state(key) {
+            return Ok(Some(state));
+        }
+        let state = self.get_uncached(key)?.map(Arc::new);
+        if let Some(state) = &state {
+            self.read_cache.put_state(key, state.clone());
+        }
+        Ok(state)
+    }
+
+    /// Whether the state of `key` is stored, without reading it.
+    pub fn contains(&self, key: B256) -> Result<bool, StoreError> {
+        if self.read_cache.contains_state(key) {
+            return Ok(true);
+        }
+        let read_txn = self.db.begin_read()?;
+
+        let table = read_txn.open_table(BEACON_STATE_TABLE)?;
+        if table.get(key)?.is_some() {
+            return Ok(true);
+        }
+
+        let Some(slot) = self.canonical_slot(&read_txn, key)? else {
+            return Ok(false);
+        };
+        let snapshots = read_txn.open_table(COLD_STATE_SNAPSHOT_TABLE)?;
+        let diffs = read_txn.open_table(COLD_STATE_DIFF_TABLE)?;
+        Ok(snapshots.get(slot)?.is_some() || diffs.get(slot)?.is_some())
+    }
+
+    fn get_uncached(&self, key: B256) -> Result<Option<BeaconState>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+
+        let table = read_txn.open_table(BEACON_STATE_TABLE)?;
+        if let Some(state) = table.get(key)? {
+            return Ok(Some(state.value()));
+        }
+
+        let Some(slot) = self.canonical_slot(&read_txn, key)? else {
+            return Ok(None);
+        };
+        let snapshots = read_txn.open_table(COLD_STATE_SNAPSHOT_TABLE)?;
+        let diffs = read_txn.open_table(COLD_STATE_DIFF_TABLE)?;
+        read_cold_state(&snapshots, &diffs, slot)
+    }
+
+    /// The freezer is keyed by slot, so finds the slot of the block and makes sure it is the
+    /// canonical block at that slot.
+    fn canonical_slot(
+        &self,
+        read_txn: &ReadTransaction,
+        block_root: B256,
+    ) -> Result<Option<u64>, StoreError> {
+        let slot = match self.read_cache.get_block_summary(block_root) {
+            Some(summary) => summary.slot,
+            None => {
+                let block_table = read_txn.open_table(BEACON_BLOCK_TABLE)?;
+                let Some(slot) = block_table
+                    .get(block_root)?
+                    .map(|block| block.value().message.slot)
+                else {
+                    return Ok(None);
+                };
+                slot
+            }
+        };
+        let slot_index_table = read_txn.open_table(SLOT_INDEX_TABLE)?;
+        if slot_index_table.get(slot)?.map(|root| root.value()) != Some(block_root) {
+            return Ok(None);
+        }
+        Ok(Some(slot))
+    }
+}
@@ -1,79 +1,273 @@
 use std::{
-    fs::File,
+    collections::BTreeMap,
+    fs::{self, File},
     io::{Read, Write},
-    path::PathBuf,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Mutex,
+    time::Duration,
 };
 
+use alloy_primitives::B256;
 use ream_consensus_beacon::{
-    blob_sidecar::BlobIdentifier, execution_engine::rpc_types::get_blobs::BlobAndProofV1,
+    blob_sidecar::{BlobIdentifier, BlobSidecar},
+    execution_engine::rpc_types::get_blobs::BlobAndProofV1,
 };
 use snap::raw::{Decoder, Encoder};
 use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use tree_hash::TreeHash;
 
 use super::Table;
 use crate::errors::StoreError;
 
 pub const BLOB_FOLDER_NAME: &str = "blobs";
 
+const BLOB_FILE_EXTENSION: &str = "ssz_snappy";
+
+const TEMPORARY_FILE_EXTENSION: &str = "tmp";
+
+/// Blobs of the same block share one file, so inserting a blob is a read-modify-write which must
+/// not interleave with another insert.
+static BLOB_WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+/// All blobs and proofs stored for a single block
+#[derive(Debug, Default, Clone, PartialEq, Encode, Decode)]
+pub struct BlockBlobs {
+    pub indices: Vec<u64>,
+    pub blobs_and_proofs: Vec<BlobAndProofV1>,
+}
+
+/// Files removed or rewritten by [`BlobsAndProofsTable::repair`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BlobRepairSummary {
+    /// Files in the pre-grouping layout of one file per blob, merged into their block's file
+    pub migrated_files: usize,
+    /// Files of blocks which are not in the database and were written before the retention
+    /// window
+    pub orphaned_files: usize,
+    /// Leftovers of interrupted writes and files which could not be parsed
+    pub invalid_files: usize,
+}
+
 pub struct BlobsAndProofsTable {
     pub data_dir: PathBuf,
 }
 
 impl BlobsAndProofsTable {
-    fn blob_file_path(&self, blob_identifier: &BlobIdentifier) -> PathBuf {
-        self.data_dir.join(BLOB_FOLDER_NAME).join(format!(
-            "{}_{}.ssz_snappy",
-            blob_identifier.block_root, blob_identifier.index
-        ))
+    fn blob_folder(&self) -> PathBuf {
+        self.data_dir.join(BLOB_FOLDER_NAME)
     }
-}
 
-impl Table for BlobsAndProofsTable {
-    type Key = BlobIdentifier;
+    fn block_file_path(&self, block_root: B256) -> PathBuf {
+        self.blob_folder()
+            .join(format!("{block_root}.{BLOB_FILE_EXTENSION}"))
+    }
 
-    type Value = BlobAndProofV1;
+    fn read_file(path: &Path) -> Result<Vec<u8>, StoreError> {
+        let mut bytes = vec![];
+        let mut file = File::open(path)?;
+        file.read_to_end(&mut bytes)?;
+        let mut decoder = Decoder::new();
+        Ok(decoder.decompress_vec(&bytes)?)
+    }
 
-    fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let file_path = self.blob_file_path(&key);
+    pub fn get_block_blobs(&self, block_root: B256) -> Result<Option<BlockBlobs>, StoreError> {
+        let file_path = self.block_file_path(block_root);
 
         if !file_path.exists() {
             return Ok(None);
         }
 
-        let mut bytes = vec![];
-        let mut file = File::open(file_path)?;
-        file.read_to_end(&mut bytes)?;
-        let mut decoder = Decoder::new();
-        let snappy_decoding = decoder.decompress_vec(&bytes)?;
-
-        Ok(Some(BlobAndProofV1::from_ssz_bytes(&snappy_decoding)?))
+        Ok(Some(BlockBlobs::from_ssz_bytes(&Self::read_file(
+            &file_path,
+        )?)?))
     }
 
-    fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
-        let file_path = self.blob_file_path(&key);
+    /// Writes to a temporary file first, so a crash never leaves a partially written block file.
+    fn write_block_blobs(
+        &self,
+        block_root: B256,
+        block_blobs: &BlockBlobs,
+    ) -> Result<(), StoreError> {
+        let file_path = self.block_file_path(block_root);
+        let temporary_path =
+            file_path.with_extension(format!("{BLOB_FILE_EXTENSION}.{TEMPORARY_FILE_EXTENSION}"));
         let mut encoder = Encoder::new();
-        let snappy_encoding = encoder.compress_vec(&value.as_ssz_bytes())?;
-        let mut file = File::create(file_path)?;
+        let snappy_encoding = encoder.compress_vec(&block_blobs.as_ssz_bytes())?;
+        let mut file = File::create(&temporary_path)?;
         file.write_all(&snappy_encoding)?;
+        file.sync_all()?;
+        fs::rename(temporary_path, file_path)?;
 
         Ok(())
     }
+
+    /// Adds `blobs_and_proofs`, keyed by their index, to the blobs stored for a block, rewriting
+    /// the block's file once.
+    pub fn insert_block_blobs(
+        &self,
+        block_root: B256,
+        blobs_and_proofs: impl IntoIterator<Item = (u64, BlobAndProofV1)>,
+    ) -> Result<(), StoreError> {
+        let _guard = BLOB_WRITE_LOCK
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        let mut block_blobs = self.get_block_blobs(block_root)?.unwrap_or_default();
+        for (index, blob_and_proof) in blobs_and_proofs {
+            match block_blobs
+                .indices
+                .iter()
+                .position(|stored| *stored == index)
+            {
+                Some(position) => block_blobs.blobs_and_proofs[position] = blob_and_proof,
+                None => {
+                    block_blobs.indices.push(index);
+                    block_blobs.blobs_and_proofs.push(blob_and_proof);
+                }
+            }
+        }
+        self.write_block_blobs(block_root, &block_blobs)
+    }
+
+    /// Stores blob sidecars of any number of blocks, writing the file of each block once.
+    pub fn insert_blob_sidecars(
+        &self,
+        blob_sidecars: impl IntoIterator<Item = BlobSidecar>,
+    ) -> Result<(), StoreError> {
+        let mut blobs_by_block = BTreeMap::<B256, Vec<_>>::new();
+        for blob_sidecar in blob_sidecars {
+            blobs_by_block
+                .entry(blob_sidecar.signed_block_header.message.tree_hash_root())
+                .or_default()
+                .push((blob_sidecar.index, blob_sidecar.into()));
+        }
+        for (block_root, blobs_and_proofs) in blobs_by_block {
+            self.insert_block_blobs(block_root, blobs_and_proofs)?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes all blobs of a block, returns whether there were any.
+    pub fn remove_block(&self, block_root: B256) -> Result<bool, StoreError> {
+        let file_path = self.block_file_path(block_root);
+        if !file_path.exists() {
+            return Ok(false);
+        }
+
+        fs::remove_file(file_path)?;
+        Ok(true)
+    }
+
+    /// Cleans up the blob folder after an unclean shutdown or an upgrade:
+    /// - merges files from the one-file-per-blob layout into their block's file
+    /// - removes files of blocks for which `is_known_block` returns false, unless they were
+    ///   written within `retention`, as blobs are stored before their block is imported
+    /// - removes temporary and unparsable files
+    pub fn repair(
+        &self,
+        is_known_block: impl Fn(B256) -> Result<bool, StoreError>,
+        retention: Duration,
+    ) -> Result<BlobRepairSummary, StoreError> {
+        let mut summary = BlobRepairSummary::default();
+
+        for entry in fs::read_dir(self.blob_folder())? {
+            let path = entry?.path();
+            if !path.is_file() {
+                continue;
+            }
+            let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+                fs::remove_file(&path)?;
+                summary.invalid_files += 1;
+                continue;
+            };
+            let Some(stem) = file_name.strip_suffix(&format!(".{BLOB_FILE_EXTENSION}")) else {
+                fs::remove_file(&path)?;
+                summary.invalid_files += 1;
+                continue;
+            };
+
+            let (block_root, legacy_index) = match stem.split_once('_') {
+                Some((block_root, index)) => (block_root, Some(index)),
+                None => (stem, None),
+            };
+            let Ok(block_root) = B256::from_str(block_root) else {
+                fs::remove_file(&path)?;
+                summary.invalid_files += 1;
+                continue;
+            };
+
+            // A file modified in the future is within the retention window
+            let is_expired = fs::metadata(&path)?
+                .modified()?
+                .elapsed()
+                .is_ok_and(|age| age >= retention);
+            if is_expired && !is_known_block(block_root)? {
+                fs::remove_file(&path)?;
+                summary.orphaned_files += 1;
+                continue;
+            }
+
+            if let Some(index) = legacy_index {
+                let blob_and_proof = Self::read_file(&path)
+                    .ok()
+                    .and_then(|bytes| BlobAndProofV1::from_ssz_bytes(&bytes).ok());
+                match (index.parse::<u64>(), blob_and_proof) {
+                    (Ok(index), Some(blob_and_proof)) => {
+                        self.insert_block_blobs(block_root, [(index, blob_and_proof)])?;
+                        summary.migrated_files += 1;
+                    }
+                    _ => summary.invalid_files += 1,
+                }
+                fs::remove_file(&path)?;
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+impl Table for BlobsAndProofsTable {
+    type Key = BlobIdentifier;
+
+    type Value = BlobAndProofV1;
+
+    fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
+        let Some(block_blobs) = self.get_block_blobs(key.block_root)? else {
+            return Ok(None);
+        };
+
+        Ok(block_blobs
+            .indices
+            .iter()
+            .position(|index| *index == key.index)
+            .map(|position| block_blobs.blobs_and_proofs[position].clone()))
+    }
+
+    fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
+        self.insert_block_blobs(key.block_root, [(key.index, value)])
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::fs;
+    use std::{fs, time::Duration};
 
+    use alloy_primitives::B256;
     use ream_consensus_beacon::{
         blob_sidecar::BlobIdentifier, execution_engine::rpc_types::get_blobs::BlobAndProofV1,
     };
+    use snap::raw::Encoder;
+    use ssz::Encode;
     use tempdir::TempDir;
 
     use crate::{
         errors::StoreError,
         tables::{
             Table,
-            blobs_and_proofs::{BLOB_FOLDER_NAME, BlobsAndProofsTable},
+            blobs_and_proofs::{BLOB_FOLDER_NAME, BlobRepairSummary, BlobsAndProofsTable},
         },
     };
 
@@ -119,4 +313,121 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_blobs_of_a_block_share_one_file() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_blobs_of_a_block_share_one_file")?;
+
+        let blob_dir = tmp_dir.path().to_path_buf().join(BLOB_FOLDER_NAME);
+        fs::create_dir_all(&blob_dir)?;
+
+        let table = BlobsAndProofsTable {
+            data_dir: tmp_dir.path().to_path_buf(),
+        };
+
+        let block_root = B256::repeat_byte(1);
+        table.insert(
+            BlobIdentifier::new(block_root, 0),
+            BlobAndProofV1::default(),
+        )?;
+        table.insert(
+            BlobIdentifier::new(block_root, 1),
+            BlobAndProofV1::default(),
+        )?;
+
+        assert_eq!(fs::read_dir(&blob_dir)?.count(), 1);
+        assert!(table.get(BlobIdentifier::new(block_root, 1))?.is_some());
+        assert!(table.get(BlobIdentifier::new(block_root, 2))?.is_none());
+
+        assert!(table.remove_block(block_root)?);
+        assert!(table.get(BlobIdentifier::new(block_root, 0))?.is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_repair_blob_folder() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_repair_blob_folder")?;
+
+        let blob_dir = tmp_dir.path().to_path_buf().join(BLOB_FOLDER_NAME);
+        fs::create_dir_all(&blob_dir)?;
+
+        let table = BlobsAndProofsTable {
+            data_dir: tmp_dir.path().to_path_buf(),
+        };
+
+        let known_root = B256::repeat_byte(1);
+        let orphaned_root = B256::repeat_byte(2);
+        let legacy_file = Encoder::new().compress_vec(&BlobAndProofV1::default().as_ssz_bytes())?;
+        fs::write(
+            blob_dir.join(format!("{known_root}_0.ssz_snappy")),
+            &legacy_file,
+        )?;
+        fs::write(
+            blob_dir.join(format!("{orphaned_root}_0.ssz_snappy")),
+            &legacy_file,
+        )?;
+        fs::write(blob_dir.join(format!("{known_root}.ssz_snappy.tmp")), [0])?;
+
+        let summary = table.repair(|block_root| Ok(block_root == known_root), Duration::ZERO)?;
+
+        assert_eq!(
+            summary,
+            BlobRepairSummary {
+                migrated_files: 1,
+                orphaned_files: 1,
+                invalid_files: 1,
+            }
+        );
+        assert_eq!(fs::read_dir(&blob_dir)?.count(), 1);
+        assert!(table.get(BlobIdentifier::new(known_root, 0))?.is_some());
+
+        // Blobs of a block which isn't imported yet are kept within the retention window
+        table.insert(
+            BlobIdentifier::new(orphaned_root, 0),
+            BlobAndProofV1::default(),
+        )?;
+        let summary = table.repair(
+            |block_root| Ok(block_root == known_root),
+            Duration::from_secs(60),
+        )?;
+        assert_eq!(summary, BlobRepairSummary::default());
+        assert!(table.get(BlobIdentifier::new(orphaned_root, 0))?.is_some());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_insert_block_blobs() -> Result<(), StoreError> {
+        let tmp_dir = TempDir::new("test_insert_block_blobs")?;
+
+        let blob_dir = tmp_dir.path().to_path_buf().join(BLOB_FOLDER_NAME);
+        fs::create_dir_all(&blob_dir)?;
+
+        let table = BlobsAndProofsTable {
+            data_dir: tmp_dir.path().to_path_buf(),
+        };
+
+        let block_root = B256::repeat_byte(1);
+        table.insert_block_blobs(
+            block_root,
+            (0..3).map(|index| (index, BlobAndProofV1::default())),
+        )?;
+        // Blobs added later are merged into the same file
+        table.insert_block_blobs(
+            block_root,
+            [
+                (1, BlobAndProofV1::default()),
+                (3, BlobAndProofV1::default()),
+            ],
+        )?;
+
+        assert_eq!(fs::read_dir(&blob_dir)?.count(), 1);
+        let block_blobs = table
+            .get_block_blobs(block_root)?
+            .expect("Blobs of the block are stored");
+        assert_eq!(block_blobs.indices, vec![0, 1, 2, 3]);
+
+        Ok(())
+    }
 }
@@ -5,7 +5,7 @@ use ream_consensus_misc::checkpoint::Checkpoint;
 use redb::{Database, Durability, TableDefinition};
 
 use super::{SSZEncoding, Table};
-use crate::errors::StoreError;
+use crate::{errors::StoreError, read_cache::ReadCache};
 
 /// Table definition for the Checkpoint States table
 ///
@@ -18,6 +18,7 @@ pub const CHECKPOINT_STATES_TABLE: TableDefinition<
 
 pub struct CheckpointStatesTable {
     pub db: Arc<Database>,
+    pub read_cache: Arc<ReadCache>,
 }
 
 impl Table for CheckpointStatesTable {
@@ -26,20 +27,35 @@ impl Table for CheckpointStatesTable {
     type Value = BeaconState;
 
     fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
-        let read_txn = self.db.begin_read()?;
-
-        let table = read_txn.open_table(CHECKPOINT_STATES_TABLE)?;
-        let result = table.get(key)?;
-        Ok(result.map(|res| res.value()))
+        Ok(self.get_shared(key)?.map(Arc::unwrap_or_clone))
     }
 
     fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
         let mut write_txn = self.db.begin_write()?;
         write_txn.set_durability(Durability::Immediate);
         let mut table = write_txn.open_table(CHECKPOINT_STATES_TABLE)?;
-        table.insert(key, value)?;
+        table.insert(key, &value)?;
         drop(table);
         write_txn.commit()?;
+        self.read_cache.put_checkpoint_state(key, Arc::new(value));
         Ok(())
     }
 }
+
+impl CheckpointStatesTable {
+    /// Like `get`, but shares the cached state instead of copying it, for callers which only read
+    /// it.
+    pub fn get_shared(&self, key: Checkpoint) -> Result<Option<Arc<BeaconState>>, StoreError> {
+        if let Some(state) = self.read_cache.get_checkpoint_state(key) {
+            return Ok(Some(state));
+        }
+        let read_txn = self.db.begin_read()?;
+
+        let table = read_txn.open_table(CHECKPOINT_STATES_TABLE)?;
+        let result = table.get(key)?.map(|res| Arc::new(res.value()));
+        if let Some(state) = &result {
+            self.read_cache.put_checkpoint_state(key, state.clone());
+        }
+        Ok(result)
+    }
+}
@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use alloy_primitives::B256;
+use ream_consensus_beacon::electra::beacon_state::BeaconState;
+use redb::{Database, Durability, ReadableTable, TableDefinition};
+
+use super::{SSZEncoding, Table, beacon_state::BEACON_STATE_TABLE};
+use crate::{errors::StoreError, state_diff::BeaconStateDiff};
+
+/// Table definition for the Cold State Snapshot table
+///
+/// Key: slot
+/// Value: BeaconState
+pub const COLD_STATE_SNAPSHOT_TABLE: TableDefinition<u64, SSZEncoding<BeaconState>> =
+    TableDefinition::new("cold_state_snapshot");
+
+/// Table definition for the Cold State Diff table
+///
+/// Key: slot
+/// Value: BeaconStateDiff
+pub const COLD_STATE_DIFF_TABLE: TableDefinition<u64, SSZEncoding<BeaconStateDiff>> =
+    TableDefinition::new("cold_state_diff");
+
+/// The freezer for finalized beacon states.
+///
+/// A full snapshot is kept at most every `snapshot_interval` slots, every other state is stored as
+/// a diff against the latest snapshot before it and reconstructed on read.
+pub struct ColdStateTable {
+    pub db: Arc<Database>,
+    pub snapshot_interval: u64,
+}
+
+impl Table for ColdStateTable {
+    type Key = u64;
+
+    type Value = BeaconState;
+
+    fn get(&self, key: Self::Key) -> Result<Option<Self::Value>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+
+        let snapshots = read_txn.open_table(COLD_STATE_SNAPSHOT_TABLE)?;
+        let diffs = read_txn.open_table(COLD_STATE_DIFF_TABLE)?;
+        read_cold_state(&snapshots, &diffs, key)
+    }
+
+    fn insert(&self, key: Self::Key, value: Self::Value) -> Result<(), StoreError> {
+        let mut write_txn = self.db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate);
+        let mut snapshots = write_txn.open_table(COLD_STATE_SNAPSHOT_TABLE)?;
+        let mut diffs = write_txn.open_table(COLD_STATE_DIFF_TABLE)?;
+        write_cold_state(
+            &mut snapshots,
+            &mut diffs,
+            &mut None,
+            key,
+            value,
+            self.snapshot_interval,
+        )?;
+        drop(snapshots);
+        drop(diffs);
+        write_txn.commit()?;
+        Ok(())
+    }
+}
+
+impl ColdStateTable {
+    pub fn get_highest_slot(&self) -> Result<Option<u64>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        let snapshots = read_txn.open_table(COLD_STATE_SNAPSHOT_TABLE)?;
+        let diffs = read_txn.open_table(COLD_STATE_DIFF_TABLE)?;
+        let highest_snapshot = snapshots.last()?.map(|result| result.0.value());
+        let highest_diff = diffs.last()?.map(|result| result.0.value());
+        Ok(highest_snapshot.max(highest_diff))
+    }
+
+    /// Moves the hot states of the given `(slot, block_root)` pairs into the freezer in a single
+    /// transaction. Pairs must be sorted by slot, blocks without a hot state are skipped.
+    ///
+    /// Returns the number of states which were moved.
+    pub fn freeze(&self, blocks: &[(u64, B256)]) -> Result<usize, StoreError> {
+        let mut write_txn = self.db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate);
+        let mut hot_states = write_txn.open_table(BEACON_STATE_TABLE)?;
+        let mut snapshots = write_txn.open_table(COLD_STATE_SNAPSHOT_TABLE)?;
+        let mut diffs = write_txn.open_table(COLD_STATE_DIFF_TABLE)?;
+
+        // Keep the latest snapshot in memory so consecutive diffs don't decode it again
+        let mut base = None;
+        let mut frozen = 0;
+        for (slot, block_root) in blocks {
+            let Some(state) = hot_states.remove(*block_root)?.map(|state| state.value()) else {
+                continue;
+            };
+            write_cold_state(
+                &mut snapshots,
+                &mut diffs,
+                &mut base,
+                *slot,
+                state,
+                self.snapshot_interval,
+            )?;
+            frozen += 1;
+        }
+
+        drop(hot_states);
+        drop(snapshots);
+        drop(diffs);
+        write_txn.commit()?;
+        Ok(frozen)
+    }
+}
+
+pub(crate) fn read_cold_state(
+    snapshots: &impl ReadableTable<u64, SSZEncoding<BeaconState>>,
+    diffs: &impl ReadableTable<u64, SSZEncoding<BeaconStateDiff>>,
+    slot: u64,
+) -> Result<Option<BeaconState>, StoreError> {
+    if let Some(state) = snapshots.get(slot)? {
+        return Ok(Some(state.value()));
+    }
+
+    let Some(diff) = diffs.get(slot)?.map(|diff| diff.value()) else {
+        return Ok(None);
+    };
+    let base = snapshots
+        .get(diff.base_slot)?
+        .ok_or_else(|| {
+            StoreError::StateDiffError(format!(
+                "Missing snapshot at slot {} for the diff at slot {slot}",
+                diff.base_slot
+            ))
+        })?
+        .value();
+
+    diff.apply(&base).map(Some)
+}
+
+/// Stores `state` as a diff against the latest snapshot at or before `slot`, or as a new snapshot
+/// if there is none within `snapshot_interval` slots.
+fn write_cold_state(
+    snapshots: &mut redb::Table<u64, SSZEncoding<BeaconState>>,
+    diffs: &mut redb::Table<u64, SSZEncoding<BeaconStateDiff>>,
+    base: &mut Option<(u64, BeaconState)>,
+    slot: u64,
+    state: BeaconState,
+    snapshot_interval: u64,
+) -> Result<(), StoreError> {
+    if base.is_none() {
+        *base = snapshots
+            .range(..=slot)?
+            .next_back()
+            .transpose()?
+            .map(|(slot, state)| (slot.value(), state.value()));
+    }
+
+    match base {
+        Some((base_slot, base_state))
+            if *base_slot < slot && slot - *base_slot < snapshot_interval =>
+        {
+            diffs.insert(
+                slot,
+                BeaconStateDiff::compute(*base_slot, base_state, &state),
+            )?;
+        }
+        _ => {
+            diffs.remove(slot)?;
+            snapshots.insert(slot, &state)?;
+            *base = Some((slot, state));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use redb::{Database, Durability, TableDefinition};
+
+use super::Field;
+use crate::errors::StoreError;
+
+/// Table definition for the Earliest_Blob_Slot table
+///
+/// The first slot for which blobs are kept, everything before it has been pruned.
+///
+/// Value: u64
+pub const EARLIEST_BLOB_SLOT_FIELD: TableDefinition<&str, u64> =
+    TableDefinition::new("earliest_blob_slot");
+
+pub const EARLIEST_BLOB_SLOT_KEY: &str = "earliest_blob_slot_key";
+
+pub struct EarliestBlobSlotField {
+    pub db: Arc<Database>,
+}
+
+impl Field for EarliestBlobSlotField {
+    type Value = u64;
+
+    fn get(&self) -> Result<u64, StoreError> {
+        let read_txn = self.db.begin_read()?;
+
+        let table = read_txn.open_table(EARLIEST_BLOB_SLOT_FIELD)?;
+        let result = table
+            .get(EARLIEST_BLOB_SLOT_KEY)?
+            .ok_or(StoreError::FieldNotInitilized)?;
+        Ok(result.value())
+    }
+
+    fn insert(&self, value: Self::Value) -> Result<(), StoreError> {
+        let mut write_txn = self.db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate);
+        let mut table = write_txn.open_table(EARLIEST_BLOB_SLOT_FIELD)?;
+        table.insert(EARLIEST_BLOB_SLOT_KEY, value)?;
+        drop(table);
+        write_txn.commit()?;
+        Ok(())
+    }
+}
@@ -3,6 +3,8 @@ pub mod beacon_state;
 pub mod blobs_and_proofs;
 pub mod block_timeliness;
 pub mod checkpoint_states;
+pub mod cold_state;
+pub mod earliest_blob_slot;
 pub mod equivocating_indices;
 pub mod finalized_checkpoint;
 pub mod genesis_time;
@@ -10,6 +12,7 @@ pub mod justified_checkpoint;
 pub mod latest_messages;
 pub mod parent_root_index;
 pub mod proposer_boost_root;
+pub mod schema_version;
 pub mod slot_index;
 pub mod state_root_index;
 pub mod time;
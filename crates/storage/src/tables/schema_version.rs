@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+use redb::{Database, Durability, TableDefinition};
+
+use super::Field;
+use crate::errors::StoreError;
+
+/// Table definition for the Schema_Version table
+///
+/// Value: u64
+pub const SCHEMA_VERSION_FIELD: TableDefinition<&str, u64> = TableDefinition::new("schema_version");
+
+pub const SCHEMA_VERSION_KEY: &str = "schema_version_key";
+
+pub struct SchemaVersionField {
+    pub db: Arc<Database>,
+}
+
+impl Field for SchemaVersionField {
+    type Value = u64;
+
+    fn get(&self) -> Result<u64, StoreError> {
+        let read_txn = self.db.begin_read()?;
+
+        let table = read_txn.open_table(SCHEMA_VERSION_FIELD)?;
+        let result = table
+            .get(SCHEMA_VERSION_KEY)?
+            .ok_or(StoreError::FieldNotInitilized)?;
+        Ok(result.value())
+    }
+
+    fn insert(&self, value: Self::Value) -> Result<(), StoreError> {
+        let mut write_txn = self.db.begin_write()?;
+        write_txn.set_durability(Durability::Immediate);
+        let mut table = write_txn.open_table(SCHEMA_VERSION_FIELD)?;
+        table.insert(SCHEMA_VERSION_KEY, value)?;
+        drop(table);
+        write_txn.commit()?;
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{ops::Range, sync::Arc};
 
 use alloy_primitives::B256;
 use redb::{Database, Durability, ReadableTable, TableDefinition};
@@ -60,6 +60,19 @@ impl SlotIndexTable {
         Ok(table.last()?.map(|result| result.0.value()))
     }
 
+    /// Returns the `(slot, block_root)` pairs within `range`, sorted by slot.
+    pub fn get_range(&self, range: Range<u64>) -> Result<Vec<(u64, B256)>, StoreError> {
+        let read_txn = self.db.begin_read()?;
+        let table = read_txn.open_table(SLOT_INDEX_TABLE)?;
+        table
+            .range(range)?
+            .map(|result| -> Result<_, StoreError> {
+                let (slot, block_root) = result?;
+                Ok((slot.value(), block_root.value()))
+            })
+            .collect()
+    }
+
     pub fn get_highest_root(&self) -> Result<Option<B256>, StoreError> {
         let read_txn = self.db.begin_read()?;
         let table = read_txn.open_table(SLOT_INDEX_TABLE)?;
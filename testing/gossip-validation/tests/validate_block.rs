@@ -224,7 +224,7 @@ mod tests {
     }
 
     #[tokio::test]
-    pub async fn test_duplicate_proposer_signature_is_ignored() {
+    pub async fn test_duplicate_proposer_block_is_ignored() {
         initialize_test_network_spec();
         let (beacon_chain, cached_db, _block_root) = db_setup().await;
 
@@ -233,25 +233,14 @@ mod tests {
         )
         .unwrap();
 
-        // Inserting the proposer signature into cache ahead of time
-        {
-            let state = beacon_chain
-                .store
-                .lock()
-                .await
-                .db
-                .get_latest_state()
-                .unwrap();
-            let validator =
-                &state.validators[incoming_beacon_block.message.proposer_index as usize];
-            cached_db.seen_proposer_signature.write().await.put(
-                AddressSlotIdentifier {
-                    address: validator.public_key.clone(),
-                    slot: incoming_beacon_block.message.slot,
-                },
-                incoming_beacon_block.signature.clone(),
-            );
-        }
+        // Inserting the proposer and slot into cache ahead of time
+        cached_db.seen_block_proposers.write().await.put(
+            (
+                incoming_beacon_block.message.proposer_index,
+                incoming_beacon_block.message.slot,
+            ),
+            (),
+        );
 
         let result =
             validate_gossip_beacon_block(&beacon_chain, &cached_db, &incoming_beacon_block)
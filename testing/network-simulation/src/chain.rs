@@ -0,0 +1,161 @@
+use alloy_primitives::B256;
+use anyhow::bail;
+use ream_consensus_beacon::electra::beacon_block::{BeaconBlock, SignedBeaconBlock};
+use ream_consensus_misc::{
+    checkpoint::Checkpoint, constants::beacon::genesis_validators_root,
+    misc::compute_start_slot_at_epoch,
+};
+use ream_network_spec::networks::beacon_network_spec;
+use ream_p2p::req_resp::messages::status::Status;
+
+/// A chain of empty blocks on top of a genesis block shared by every node. A block only depends on
+/// its slot, so nodes holding the same slot agree on its root, whether they built or synced it.
+///
+/// A chain can also hold valid blocks built by [`crate::genesis::Genesis`], for nodes which import
+/// them.
+#[derive(Debug, Clone)]
+pub struct SimulatedChain {
+    /// The block of each slot, starting from genesis, as no slot is skipped
+    blocks: Vec<SignedBeaconBlock>,
+    finalized_checkpoint: Checkpoint,
+}
+
+impl SimulatedChain {
+    /// A chain of `length` blocks on top of genesis.
+    pub fn new(length: u64) -> Self {
+        let mut chain = Self::from_genesis(block(0, B256::ZERO));
+        chain.extend(length);
+        chain
+    }
+
+    /// A chain of `blocks`, starting from the genesis block, each building on the one before it.
+    pub fn from_blocks(blocks: Vec<SignedBeaconBlock>) -> anyhow::Result<Self> {
+        let mut blocks = blocks.into_iter();
+        let Some(genesis_block) = blocks.next() else {
+            bail!("A chain starts with a genesis block");
+        };
+        if genesis_block.message.slot != 0 {
+            bail!(
+                "The genesis block is at slot {} instead of 0",
+                genesis_block.message.slot
+            );
+        }
+
+        let mut chain = Self::from_genesis(genesis_block);
+        for block in blocks {
+            chain.import(block)?;
+        }
+        Ok(chain)
+    }
+
+    fn from_genesis(genesis_block: SignedBeaconBlock) -> Self {
+        let finalized_checkpoint = Checkpoint {
+            epoch: 0,
+            root: genesis_block.message.block_root(),
+        };
+        Self {
+            blocks: vec![genesis_block],
+            finalized_checkpoint,
+        }
+    }
+
+    pub fn genesis_root(&self) -> B256 {
+        self.blocks[0].message.block_root()
+    }
+
+    pub fn head_slot(&self) -> u64 {
+        self.head().message.slot
+    }
+
+    pub fn head_root(&self) -> B256 {
+        self.head().message.block_root()
+    }
+
+    fn head(&self) -> &SignedBeaconBlock {
+        self.blocks.last().expect("The chain has a genesis block")
+    }
+
+    /// Builds `count` blocks on top of the head.
+    pub fn extend(&mut self, count: u64) {
+        for _ in 0..count {
+            let next_block = block(self.head_slot() + 1, self.head_root());
+            self.blocks.push(next_block);
+        }
+    }
+
+    /// Finalizes `epoch`, which must start at or before the head. Range sync only downloads blocks
+    /// up to the finalized slot of its peers.
+    pub fn finalize(&mut self, epoch: u64) -> anyhow::Result<()> {
+        let slot = compute_start_slot_at_epoch(epoch);
+        let Some(block) = self.blocks.get(slot as usize) else {
+            bail!(
+                "Epoch {epoch} starts after the head at slot {}",
+                self.head_slot()
+            );
+        };
+        self.finalized_checkpoint = Checkpoint {
+            epoch,
+            root: block.message.block_root(),
+        };
+        Ok(())
+    }
+
+    /// Imports a block, which must build on the head.
+    pub fn import(&mut self, block: SignedBeaconBlock) -> anyhow::Result<()> {
+        if block.message.slot != self.head_slot() + 1 {
+            bail!(
+                "Block at slot {} doesn't follow the head at slot {}",
+                block.message.slot,
+                self.head_slot()
+            );
+        }
+        if block.message.parent_root != self.head_root() {
+            bail!(
+                "Block at slot {} doesn't build on the head {}",
+                block.message.slot,
+                self.head_root()
+            );
+        }
+        self.blocks.push(block);
+        Ok(())
+    }
+
+    pub fn blocks_by_range(&self, start_slot: u64, count: u64) -> Vec<SignedBeaconBlock> {
+        self.blocks
+            .iter()
+            .skip(start_slot as usize)
+            .take(count as usize)
+            .cloned()
+            .collect()
+    }
+
+    pub fn block_by_root(&self, root: B256) -> Option<SignedBeaconBlock> {
+        self.blocks
+            .iter()
+            .find(|block| block.message.block_root() == root)
+            .cloned()
+    }
+
+    /// Unless the chain was finalized, nothing is finalized past genesis, so the status only tells
+    /// peers apart by their head.
+    pub fn status(&self) -> Status {
+        Status {
+            fork_digest: beacon_network_spec().fork_digest(genesis_validators_root()),
+            finalized_root: self.finalized_checkpoint.root,
+            finalized_epoch: self.finalized_checkpoint.epoch,
+            head_root: self.head_root(),
+            head_slot: self.head_slot(),
+        }
+    }
+}
+
+fn block(slot: u64, parent_root: B256) -> SignedBeaconBlock {
+    SignedBeaconBlock {
+        message: BeaconBlock {
+            slot,
+            parent_root,
+            ..Default::default()
+        },
+        signature: Default::default(),
+    }
+}
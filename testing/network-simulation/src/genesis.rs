@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use alloy_primitives::B256;
+use anyhow::ensure;
+use ream_bls::{BLSSignature, PrivateKey, PublicKey, traits::Signable};
+use ream_consensus_beacon::{
+    electra::{
+        beacon_block::{BeaconBlock, SignedBeaconBlock},
+        beacon_block_body::BeaconBlockBody,
+        beacon_state::BeaconState,
+        execution_payload::ExecutionPayload,
+    },
+    sync_aggregate::SyncAggregate,
+    sync_committee::SyncCommittee,
+};
+use ream_consensus_misc::{
+    beacon_block_header::BeaconBlockHeader,
+    checkpoint::Checkpoint,
+    constants::beacon::{
+        DOMAIN_BEACON_PROPOSER, DOMAIN_RANDAO, FAR_FUTURE_EPOCH, GENESIS_EPOCH,
+        MIN_ACTIVATION_BALANCE, UNSET_DEPOSIT_REQUESTS_START_INDEX,
+    },
+    eth_1_data::Eth1Data,
+    fork::Fork,
+    misc::compute_signing_root,
+    validator::Validator,
+};
+use ream_execution_engine::ExecutionEngine;
+use ream_network_spec::networks::beacon_network_spec;
+use ssz_types::{BitVector, FixedVector, VariableList};
+use tree_hash::TreeHash;
+
+use crate::chain::SimulatedChain;
+
+/// The number of validators `validator_key` has a key for
+const MAX_VALIDATOR_COUNT: u64 = 100;
+
+/// A genesis state of validators whose keys are known, so valid blocks can be built on top of it
+/// for nodes which import them rather than just serving them.
+#[derive(Clone)]
+pub struct Genesis {
+    pub state: BeaconState,
+    pub block: SignedBeaconBlock,
+}
+
+impl Genesis {
+    /// A genesis of `validator_count` active validators, which are neither deposited nor
+    /// withdrawn, starting at `genesis_time`.
+    pub fn new(validator_count: u64, genesis_time: u64) -> anyhow::Result<Self> {
+        ensure!(
+            validator_count <= MAX_VALIDATOR_COUNT,
+            "Only {MAX_VALIDATOR_COUNT} validators have a key"
+        );
+        let validators = (0..validator_count)
+            .map(|index| {
+                Ok(Validator {
+                    public_key: validator_key(index).public_key()?,
+                    // BLS credentials, so the chain has no withdrawals
+                    withdrawal_credentials: B256::ZERO,
+                    effective_balance: MIN_ACTIVATION_BALANCE,
+                    slashed: false,
+                    activation_eligibility_epoch: GENESIS_EPOCH,
+                    activation_epoch: GENESIS_EPOCH,
+                    exit_epoch: FAR_FUTURE_EPOCH,
+                    withdrawable_epoch: FAR_FUTURE_EPOCH,
+                })
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let validators = VariableList::from(validators);
+        let genesis_fork_version = beacon_network_spec().genesis_fork_version;
+        let empty_sync_committee = Arc::new(SyncCommittee {
+            public_keys: FixedVector::default(),
+            aggregate_public_key: PublicKey::default(),
+        });
+
+        let mut state = BeaconState {
+            genesis_time,
+            genesis_validators_root: validators.tree_hash_root(),
+            slot: 0,
+            fork: Fork {
+                previous_version: genesis_fork_version,
+                current_version: genesis_fork_version,
+                epoch: GENESIS_EPOCH,
+            },
+            latest_block_header: BeaconBlockHeader {
+                slot: 0,
+                proposer_index: 0,
+                parent_root: B256::ZERO,
+                state_root: B256::ZERO,
+                body_root: BeaconBlockBody::default().tree_hash_root(),
+            },
+            block_roots: FixedVector::default(),
+            state_roots: FixedVector::default(),
+            historical_roots: VariableList::default(),
+            eth1_data: Eth1Data::default(),
+            eth1_data_votes: VariableList::default(),
+            eth1_deposit_index: 0,
+            balances: VariableList::from(vec![MIN_ACTIVATION_BALANCE; validators.len()]),
+            previous_epoch_participation: VariableList::from(vec![0; validators.len()]),
+            current_epoch_participation: VariableList::from(vec![0; validators.len()]),
+            inactivity_scores: VariableList::from(vec![0; validators.len()]),
+            validators,
+            randao_mixes: FixedVector::default(),
+            slashings: FixedVector::default(),
+            justification_bits: BitVector::default(),
+            previous_justified_checkpoint: Checkpoint::default(),
+            current_justified_checkpoint: Checkpoint::default(),
+            finalized_checkpoint: Checkpoint::default(),
+            current_sync_committee: empty_sync_committee.clone(),
+            next_sync_committee: empty_sync_committee,
+            latest_execution_payload_header: Default::default(),
+            next_withdrawal_index: 0,
+            next_withdrawal_validator_index: 0,
+            historical_summaries: VariableList::default(),
+            deposit_requests_start_index: UNSET_DEPOSIT_REQUESTS_START_INDEX,
+            deposit_balance_to_consume: 0,
+            exit_balance_to_consume: 0,
+            earliest_exit_epoch: 0,
+            consolidation_balance_to_consume: 0,
+            earliest_consolidation_epoch: 0,
+            pending_deposits: VariableList::default(),
+            pending_partial_withdrawals: VariableList::default(),
+            pending_consolidations: VariableList::default(),
+        };
+        // The first two sync committees are the same
+        let sync_committee = Arc::new(state.get_next_sync_committee()?);
+        state.current_sync_committee = sync_committee.clone();
+        state.next_sync_committee = sync_committee;
+
+        let block = SignedBeaconBlock {
+            message: BeaconBlock {
+                slot: 0,
+                proposer_index: 0,
+                parent_root: B256::ZERO,
+                state_root: state.tree_hash_root(),
+                body: BeaconBlockBody::default(),
+            },
+            signature: BLSSignature::default(),
+        };
+
+        Ok(Self { state, block })
+    }
+
+    /// Builds a chain of `length` empty blocks on top of genesis, each signed by its proposer and
+    /// committing to its post state, so the chain passes the state transition.
+    pub async fn build_chain(&self, length: u64) -> anyhow::Result<SimulatedChain> {
+        let mut state = self.state.clone();
+        let mut blocks = vec![self.block.clone()];
+        for slot in 1..=length {
+            state.process_slots(slot)?;
+            let proposer_index = state.get_beacon_proposer_index(None)?;
+            let private_key = validator_key(proposer_index);
+            let epoch = state.get_current_epoch();
+            let randao_reveal = private_key.sign(
+                compute_signing_root(epoch, state.get_domain(DOMAIN_RANDAO, Some(epoch))).as_ref(),
+            )?;
+
+            let mut block = BeaconBlock {
+                slot,
+                proposer_index,
+                parent_root: state.latest_block_header.tree_hash_root(),
+                state_root: B256::ZERO,
+                body: BeaconBlockBody {
+                    randao_reveal,
+                    eth1_data: state.eth1_data.clone(),
+                    // Nobody signs for the sync committee
+                    sync_aggregate: SyncAggregate {
+                        sync_committee_bits: BitVector::default(),
+                        sync_committee_signature: BLSSignature::infinity(),
+                    },
+                    execution_payload: ExecutionPayload {
+                        parent_hash: state.latest_execution_payload_header.block_hash,
+                        prev_randao: state.get_randao_mix(epoch),
+                        timestamp: state.compute_timestamp_at_slot(slot),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+            };
+            state
+                .process_block(&block, &None::<ExecutionEngine>)
+                .await?;
+            block.state_root = state.tree_hash_root();
+
+            let signing_root = compute_signing_root(
+                block.block_root(),
+                state.get_domain(DOMAIN_BEACON_PROPOSER, None),
+            );
+            blocks.push(SignedBeaconBlock {
+                signature: private_key.sign(signing_root.as_ref())?,
+                message: block,
+            });
+        }
+
+        SimulatedChain::from_blocks(blocks)
+    }
+}
+
+/// The key of the validator at `index`. A single byte is a valid scalar in both byte orders the BLS
+/// backends read keys in, as long as it is below the top byte of the curve order.
+fn validator_key(index: u64) -> PrivateKey {
+    PrivateKey {
+        inner: B256::with_last_byte(index as u8 + 1),
+    }
+}
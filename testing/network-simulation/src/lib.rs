@@ -0,0 +1,8 @@
+//! Runs several beacon `Network`s on loopback, driven by scripted nodes, to test gossip
+//! propagation, peer scoring, req/resp rate limiting, serving blocks and range sync without a live
+//! testnet.
+
+pub mod chain;
+pub mod genesis;
+pub mod node;
+pub mod simulation;
@@ -0,0 +1,380 @@
+use std::{
+    net::{IpAddr, Ipv4Addr, TcpListener, UdpSocket},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{anyhow, bail};
+use discv5::Enr;
+use libp2p::{PeerId, gossipsub::MessageAcceptance};
+use parking_lot::RwLock;
+use ream_consensus_beacon::electra::beacon_block::SignedBeaconBlock;
+use ream_consensus_misc::constants::beacon::genesis_validators_root;
+use ream_discv5::{
+    config::DiscoveryConfig,
+    subnet::{AttestationSubnets, SyncCommitteeSubnets},
+};
+use ream_executor::ReamExecutor;
+use ream_network_spec::networks::beacon_network_spec;
+use ream_p2p::{
+    channel::{GossipMessage, P2PCallbackResponse, P2PRequest, P2PSender},
+    config::NetworkConfig,
+    gossipsub::beacon::{
+        configurations::GossipsubConfig,
+        message::GossipsubMessage,
+        topics::{GossipTopic, GossipTopicKind},
+    },
+    network::beacon::{Network, ReamNetworkEvent},
+    network_state::NetworkState,
+    req_resp::messages::{RequestMessage, ResponseMessage, status::Status},
+};
+use tempdir::TempDir;
+use tokio::{
+    sync::mpsc,
+    time::{Instant, timeout, timeout_at},
+};
+
+use crate::chain::SimulatedChain;
+
+/// How long to wait for an event or a response before failing the test
+pub const EVENT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// How a node is scripted to behave.
+#[derive(Clone)]
+pub struct NodeConfig {
+    /// The blocks the node has, on top of the anchor it shares with the other nodes
+    pub chain: SimulatedChain,
+    /// The gossip topics the node subscribes to
+    pub topics: Vec<GossipTopicKind>,
+    /// Decides the validation result of the gossip messages which decode, the others are rejected
+    pub validate_gossip: fn(&GossipsubMessage) -> MessageAcceptance,
+}
+
+impl Default for NodeConfig {
+    fn default() -> Self {
+        Self {
+            chain: SimulatedChain::new(0),
+            topics: vec![GossipTopicKind::BeaconBlock, GossipTopicKind::VoluntaryExit],
+            validate_gossip: |_| MessageAcceptance::Accept,
+        }
+    }
+}
+
+/// A beacon `Network` listening on loopback, whose requests and gossip are handled by a scripted
+/// driver instead of the network manager.
+pub struct SimulatedNode {
+    pub peer_id: PeerId,
+    pub enr: Enr,
+    pub network_state: Arc<NetworkState>,
+    pub p2p_sender: P2PSender,
+    pub chain: Arc<RwLock<SimulatedChain>>,
+    /// The events of the network, after the driver handled them
+    events: mpsc::UnboundedReceiver<ReamNetworkEvent>,
+    /// Removed with the node
+    _data_dir: TempDir,
+}
+
+impl SimulatedNode {
+    pub async fn spawn(
+        executor: &ReamExecutor,
+        config: NodeConfig,
+        bootnodes: Vec<Enr>,
+    ) -> anyhow::Result<Self> {
+        let data_dir = TempDir::new("ream_network_simulation")?;
+        let socket_address = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        // The OS picks free ports, which are released for the network to bind them
+        let socket_port = TcpListener::bind((socket_address, 0))?.local_addr()?.port();
+        let discovery_port = UdpSocket::bind((socket_address, 0))?.local_addr()?.port();
+        let fork = beacon_network_spec().fork_digest(genesis_validators_root());
+
+        let network_config = NetworkConfig {
+            discv5_config: DiscoveryConfig {
+                discv5_config: discv5::ConfigBuilder::new(discv5::ListenConfig::from_ip(
+                    socket_address,
+                    discovery_port,
+                ))
+                .build(),
+                bootnodes,
+                socket_address,
+                socket_address_v6: None,
+                enr_address: None,
+                socket_port,
+                quic_port: socket_port,
+                discovery_port,
+                // Nodes only dial their bootnodes, so each test decides the topology
+                disable_discovery: true,
+                disable_quic: true,
+                attestation_subnets: AttestationSubnets::new(),
+                sync_committee_subnets: SyncCommitteeSubnets::new(),
+            },
+            gossipsub_config: GossipsubConfig {
+                topics: config
+                    .topics
+                    .into_iter()
+                    .map(|kind| GossipTopic { fork, kind })
+                    .collect(),
+                ..Default::default()
+            },
+            data_dir: data_dir.path().to_path_buf(),
+            upnp: false,
+            rotate_node_key: false,
+            genesis_time: 0,
+            // Simulated peers go over the quota on purpose, to test the limiter of the remote peer
+            disable_outbound_rate_limit: true,
+        };
+
+        let chain = config.chain;
+        let network = Network::init(executor.clone(), &network_config, chain.status()).await?;
+        let peer_id = network.peer_id();
+        let enr = network.enr();
+        let network_state = network.network_state();
+
+        let (network_event_sender, network_events) = mpsc::unbounded_channel();
+        let (p2p_command_sender, p2p_receiver) = mpsc::unbounded_channel();
+        let p2p_sender = P2PSender(p2p_command_sender);
+        executor.spawn(network.start(network_event_sender, p2p_receiver));
+
+        let chain = Arc::new(RwLock::new(chain));
+        let (event_sender, events) = mpsc::unbounded_channel();
+        executor.spawn(drive_node(
+            network_events,
+            event_sender,
+            p2p_sender.clone(),
+            chain.clone(),
+            config.validate_gossip,
+        ));
+
+        Ok(Self {
+            peer_id,
+            enr,
+            network_state,
+            p2p_sender,
+            chain,
+            events,
+            _data_dir: data_dir,
+        })
+    }
+
+    pub fn publish(&self, kind: GossipTopicKind, data: Vec<u8>) {
+        self.p2p_sender.publish(GossipMessage {
+            topic: GossipTopic {
+                fork: beacon_network_spec().fork_digest(genesis_validators_root()),
+                kind,
+            },
+            data,
+        });
+    }
+
+    /// Waits for an event `matcher` picks something out of, skipping the others.
+    pub async fn wait_for_event<T>(
+        &mut self,
+        mut matcher: impl FnMut(&ReamNetworkEvent) -> Option<T>,
+    ) -> anyhow::Result<T> {
+        let peer_id = self.peer_id;
+        timeout(EVENT_TIMEOUT, async {
+            while let Some(event) = self.events.recv().await {
+                if let Some(result) = matcher(&event) {
+                    return Ok(result);
+                }
+            }
+            bail!("The network of node {peer_id} stopped")
+        })
+        .await
+        .map_err(|_| anyhow!("Timed out waiting for an event on node {peer_id}"))?
+    }
+
+    /// Fails if `matcher` picks out any of the events received during `window`.
+    pub async fn expect_no_event(
+        &mut self,
+        window: Duration,
+        mut matcher: impl FnMut(&ReamNetworkEvent) -> bool,
+    ) -> anyhow::Result<()> {
+        let deadline = Instant::now() + window;
+        loop {
+            match timeout_at(deadline, self.events.recv()).await {
+                Err(_) => return Ok(()),
+                Ok(None) => bail!("The network of node {} stopped", self.peer_id),
+                Ok(Some(event)) => {
+                    if matcher(&event) {
+                        bail!("Unexpected event on node {}: {event:?}", self.peer_id);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The score this node gives `peer_id`, combining its penalties and gossipsub score.
+    pub fn peer_score(&self, peer_id: &PeerId) -> Option<f64> {
+        self.network_state
+            .peer_table
+            .read()
+            .get(peer_id)
+            .map(|peer| peer.score.score())
+    }
+
+    pub async fn request_blocks_by_range(
+        &self,
+        peer_id: PeerId,
+        start: u64,
+        count: u64,
+    ) -> anyhow::Result<Vec<SignedBeaconBlock>> {
+        let (callback, mut responses) = mpsc::channel(16);
+        self.p2p_sender.send_request(P2PRequest::BlockRange {
+            peer_id,
+            start,
+            count,
+            callback,
+        })?;
+
+        let mut blocks = vec![];
+        loop {
+            let response = timeout(EVENT_TIMEOUT, responses.recv())
+                .await
+                .map_err(|_| anyhow!("Timed out waiting for blocks from {peer_id}"))?
+                .ok_or_else(|| anyhow!("The network dropped the request to {peer_id}"))??;
+            match response {
+                P2PCallbackResponse::ResponseMessage(message) => match *message {
+                    ResponseMessage::BeaconBlocksByRange(block) => blocks.push(block),
+                    message => bail!("Unexpected response from {peer_id}: {message:?}"),
+                },
+                P2PCallbackResponse::EndOfStream => return Ok(blocks),
+                P2PCallbackResponse::Disconnected => bail!("Peer {peer_id} is disconnected"),
+                P2PCallbackResponse::Timeout => bail!("The request to {peer_id} timed out"),
+            }
+        }
+    }
+
+    /// Asks `peer_id` for its status, which `sync_from` downloads the chain up to.
+    pub async fn request_status(&mut self, peer_id: PeerId) -> anyhow::Result<Status> {
+        let status = self.chain.read().status();
+        self.p2p_sender
+            .send_request(P2PRequest::Status { peer_id, status })?;
+        self.wait_for_event(|event| match event {
+            ReamNetworkEvent::PeerStatus {
+                peer_id: status_peer_id,
+                status,
+            } if *status_peer_id == peer_id => Some(status.clone()),
+            _ => None,
+        })
+        .await
+    }
+
+    /// Downloads the chain of `peer_id` up to the head in its status, in batches of the largest
+    /// request the spec allows. A scripted download to test the serving side, not the range
+    /// syncer of the node.
+    pub async fn sync_from(&self, peer_id: PeerId) -> anyhow::Result<()> {
+        let target_slot = self
+            .network_state
+            .peer_table
+            .read()
+            .get(&peer_id)
+            .and_then(|peer| peer.status.as_ref().map(|status| status.head_slot))
+            .ok_or_else(|| anyhow!("No status received from {peer_id}"))?;
+        let batch_size = beacon_network_spec().max_request_blocks_deneb;
+
+        loop {
+            let head_slot = self.chain.read().head_slot();
+            if head_slot >= target_slot {
+                return Ok(());
+            }
+
+            let start_slot = head_slot + 1;
+            let blocks = self
+                .request_blocks_by_range(
+                    peer_id,
+                    start_slot,
+                    batch_size.min(target_slot - head_slot),
+                )
+                .await?;
+            if blocks.is_empty() {
+                bail!("Peer {peer_id} has no blocks from slot {start_slot}");
+            }
+
+            let mut chain = self.chain.write();
+            for block in blocks {
+                chain.import(block)?;
+            }
+            *self.network_state.status.write() = chain.status();
+        }
+    }
+}
+
+/// Answers the requests of peers from the chain of the node and validates gossip with the script,
+/// then hands the events to the test.
+async fn drive_node(
+    mut network_events: mpsc::UnboundedReceiver<ReamNetworkEvent>,
+    event_sender: mpsc::UnboundedSender<ReamNetworkEvent>,
+    p2p_sender: P2PSender,
+    chain: Arc<RwLock<SimulatedChain>>,
+    validate_gossip: fn(&GossipsubMessage) -> MessageAcceptance,
+) {
+    while let Some(event) = network_events.recv().await {
+        match &event {
+            ReamNetworkEvent::RequestMessage {
+                peer_id,
+                stream_id,
+                connection_id,
+                message,
+            } => {
+                let responses = match message {
+                    RequestMessage::Status(_) => {
+                        Some(vec![ResponseMessage::Status(chain.read().status())])
+                    }
+                    RequestMessage::BeaconBlocksByRange(request) => Some(
+                        chain
+                            .read()
+                            .blocks_by_range(request.start_slot, request.count)
+                            .into_iter()
+                            .map(ResponseMessage::BeaconBlocksByRange)
+                            .collect(),
+                    ),
+                    RequestMessage::BeaconBlocksByRoot(request) => {
+                        let chain = chain.read();
+                        Some(
+                            request
+                                .inner
+                                .iter()
+                                .filter_map(|root| chain.block_by_root(*root))
+                                .map(ResponseMessage::BeaconBlocksByRoot)
+                                .collect(),
+                        )
+                    }
+                    // Simulated chains have no blobs
+                    RequestMessage::BlobSidecarsByRange(_)
+                    | RequestMessage::BlobSidecarsByRoot(_) => Some(vec![]),
+                    // Answered by the network
+                    RequestMessage::MetaData(_)
+                    | RequestMessage::Goodbye(_)
+                    | RequestMessage::Ping(_) => None,
+                };
+                if let Some(responses) = responses {
+                    for response in responses {
+                        p2p_sender.send_response(*peer_id, *connection_id, *stream_id, response);
+                    }
+                    p2p_sender.send_end_of_stream_response(*peer_id, *connection_id, *stream_id);
+                }
+            }
+            ReamNetworkEvent::GossipsubMessage {
+                message,
+                message_id,
+                propagation_source,
+            } => {
+                let acceptance = match GossipsubMessage::decode(&message.topic, &message.data) {
+                    Ok(message) => validate_gossip(&message),
+                    Err(_) => MessageAcceptance::Reject,
+                };
+                p2p_sender.report_validation_result(
+                    message_id.clone(),
+                    *propagation_source,
+                    acceptance,
+                );
+            }
+            _ => {}
+        }
+
+        // The test dropped the node
+        if event_sender.send(event).is_err() {
+            return;
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use anyhow::bail;
+use ream_executor::ReamExecutor;
+use ream_network_spec::networks::initialize_test_network_spec;
+use tokio::time::{Instant, sleep};
+
+use crate::node::{EVENT_TIMEOUT, NodeConfig, SimulatedNode};
+
+/// Time for gossipsub to exchange subscriptions and graft the mesh once peers are connected
+pub const MESH_FORMATION_DELAY: Duration = Duration::from_secs(3);
+
+/// How the nodes are connected, each node dialing the nodes spawned before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    /// Every node is connected to every other node
+    FullMesh,
+    /// Each node is only connected to the nodes spawned right before and after it, so gossip
+    /// has to be forwarded by every node in between
+    Line,
+}
+
+pub struct Simulation {
+    pub nodes: Vec<SimulatedNode>,
+}
+
+impl Simulation {
+    /// Spawns a node for each config and waits until they are connected by `topology` and the
+    /// gossip mesh is formed.
+    pub async fn spawn(
+        executor: &ReamExecutor,
+        configs: Vec<NodeConfig>,
+        topology: Topology,
+    ) -> anyhow::Result<Self> {
+        let mut nodes: Vec<SimulatedNode> = vec![];
+        for config in configs {
+            let bootnodes = match topology {
+                Topology::FullMesh => nodes.iter().map(|node| node.enr.clone()).collect(),
+                Topology::Line => nodes
+                    .last()
+                    .map(|node| node.enr.clone())
+                    .into_iter()
+                    .collect(),
+            };
+            nodes.push(SimulatedNode::spawn(executor, config, bootnodes).await?);
+        }
+
+        let node_count = nodes.len();
+        for (index, node) in nodes.iter().enumerate() {
+            let expected_peers = match topology {
+                Topology::FullMesh => node_count - 1,
+                Topology::Line => usize::from(index > 0) + usize::from(index + 1 < node_count),
+            };
+            // Peers are connected once they exchanged statuses
+            wait_until(
+                || node.network_state.connected_peers().len() >= expected_peers,
+                &format!("node {index} is connected to {expected_peers} peers"),
+            )
+            .await?;
+        }
+        sleep(MESH_FORMATION_DELAY).await;
+
+        Ok(Self { nodes })
+    }
+}
+
+/// Runs `test` on the runtime of a new executor, which the nodes are spawned on.
+pub fn run_simulation<F, Fut>(test: F) -> anyhow::Result<()>
+where
+    F: FnOnce(ReamExecutor) -> Fut,
+    Fut: Future<Output = anyhow::Result<()>>,
+{
+    initialize_test_network_spec();
+    let executor = ReamExecutor::new()?;
+    executor.runtime().block_on(test(executor.clone()))
+}
+
+/// Polls `condition` until it holds, failing after `EVENT_TIMEOUT`.
+pub async fn wait_until(
+    mut condition: impl FnMut() -> bool,
+    description: &str,
+) -> anyhow::Result<()> {
+    let deadline = Instant::now() + EVENT_TIMEOUT;
+    while !condition() {
+        if Instant::now() >= deadline {
+            bail!("Timed out waiting until {description}");
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    Ok(())
+}
@@ -0,0 +1,49 @@
+mod tests {
+    use network_simulation::{
+        chain::SimulatedChain,
+        node::NodeConfig,
+        simulation::{Simulation, Topology, run_simulation},
+    };
+
+    /// Checks that status and blocks by range serve a chain across several batches, and that a
+    /// node serves the blocks it downloaded to the next one. The download itself is scripted by
+    /// the harness, this doesn't exercise the range syncer.
+    #[test]
+    fn test_blocks_by_range_serves_the_chain_through_a_relay() -> anyhow::Result<()> {
+        run_simulation(|executor| async move {
+            // Longer than a batch, so syncing takes several requests
+            let chain_length = 300;
+            let Simulation { mut nodes } = Simulation::spawn(
+                &executor,
+                vec![
+                    NodeConfig {
+                        chain: SimulatedChain::new(chain_length),
+                        ..Default::default()
+                    },
+                    NodeConfig::default(),
+                    NodeConfig::default(),
+                ],
+                Topology::Line,
+            )
+            .await?;
+            let head_root = nodes[0].chain.read().head_root();
+
+            // The middle node got the head of the first one when they exchanged statuses
+            nodes[1].sync_from(nodes[0].peer_id).await?;
+            assert_eq!(nodes[1].chain.read().head_root(), head_root);
+
+            // The last node only learns about the new head by asking for it
+            let relay = nodes[1].peer_id;
+            let status = nodes[2].request_status(relay).await?;
+            assert_eq!(status.head_slot, chain_length);
+            nodes[2].sync_from(relay).await?;
+
+            for node in &nodes {
+                assert_eq!(node.chain.read().head_slot(), chain_length);
+                assert_eq!(node.chain.read().head_root(), head_root);
+                assert_eq!(node.network_state.status.read().head_root, head_root);
+            }
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,158 @@
+mod tests {
+    use std::time::Duration;
+
+    use libp2p::gossipsub::MessageAcceptance;
+    use network_simulation::{
+        node::NodeConfig,
+        simulation::{Simulation, Topology, run_simulation},
+    };
+    use ream_consensus_beacon::voluntary_exit::{SignedVoluntaryExit, VoluntaryExit};
+    use ream_p2p::{
+        gossipsub::beacon::{message::GossipsubMessage, topics::GossipTopicKind},
+        network::beacon::ReamNetworkEvent,
+    };
+    use ssz::Encode;
+
+    /// How long to watch for a message which must not arrive
+    const PROPAGATION_WINDOW: Duration = Duration::from_secs(5);
+
+    fn voluntary_exit(validator_index: u64) -> SignedVoluntaryExit {
+        SignedVoluntaryExit {
+            message: VoluntaryExit {
+                epoch: 0,
+                validator_index,
+            },
+            signature: Default::default(),
+        }
+    }
+
+    fn is_gossip_message(event: &ReamNetworkEvent) -> bool {
+        matches!(event, ReamNetworkEvent::GossipsubMessage { .. })
+    }
+
+    #[test]
+    fn test_valid_gossip_propagates() -> anyhow::Result<()> {
+        run_simulation(|executor| async move {
+            let Simulation { mut nodes } =
+                Simulation::spawn(&executor, vec![NodeConfig::default(); 3], Topology::Line)
+                    .await?;
+            let relay = nodes[1].peer_id;
+
+            let exit = voluntary_exit(1);
+            nodes[0].publish(GossipTopicKind::VoluntaryExit, exit.as_ssz_bytes());
+
+            // The last node only hears from the publisher through the relay
+            let (message, propagation_source) = nodes[2]
+                .wait_for_event(|event| match event {
+                    ReamNetworkEvent::GossipsubMessage {
+                        message,
+                        propagation_source,
+                        ..
+                    } => Some((message.clone(), *propagation_source)),
+                    _ => None,
+                })
+                .await?;
+            assert_eq!(propagation_source, relay);
+            assert_eq!(
+                GossipsubMessage::decode(&message.topic, &message.data)?,
+                GossipsubMessage::VoluntaryExit(Box::new(exit))
+            );
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_rejected_gossip_is_not_forwarded() -> anyhow::Result<()> {
+        run_simulation(|executor| async move {
+            let rejecting_relay = NodeConfig {
+                validate_gossip: |_| MessageAcceptance::Reject,
+                ..Default::default()
+            };
+            let Simulation { mut nodes } = Simulation::spawn(
+                &executor,
+                vec![
+                    NodeConfig::default(),
+                    rejecting_relay,
+                    NodeConfig::default(),
+                ],
+                Topology::Line,
+            )
+            .await?;
+            let publisher = nodes[0].peer_id;
+
+            nodes[0].publish(
+                GossipTopicKind::VoluntaryExit,
+                voluntary_exit(1).as_ssz_bytes(),
+            );
+
+            nodes[1]
+                .wait_for_event(|event| match event {
+                    ReamNetworkEvent::GossipValidated {
+                        propagation_source,
+                        acceptance: MessageAcceptance::Reject,
+                        ..
+                    } if *propagation_source == publisher => Some(()),
+                    _ => None,
+                })
+                .await?;
+            nodes[2]
+                .expect_no_event(PROPAGATION_WINDOW, is_gossip_message)
+                .await?;
+
+            let score = nodes[1]
+                .peer_score(&publisher)
+                .expect("The publisher is a peer of the relay");
+            assert!(score < 0.0, "The publisher wasn't penalized: {score}");
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_malformed_gossip_is_rejected() -> anyhow::Result<()> {
+        run_simulation(|executor| async move {
+            let Simulation { mut nodes } =
+                Simulation::spawn(&executor, vec![NodeConfig::default(); 3], Topology::Line)
+                    .await?;
+            let publisher = nodes[0].peer_id;
+
+            // Within the size of the topic, but not a voluntary exit
+            nodes[0].publish(GossipTopicKind::VoluntaryExit, vec![0xff; 50]);
+
+            nodes[1]
+                .wait_for_event(|event| match event {
+                    ReamNetworkEvent::GossipValidated {
+                        propagation_source,
+                        acceptance: MessageAcceptance::Reject,
+                        ..
+                    } if *propagation_source == publisher => Some(()),
+                    _ => None,
+                })
+                .await?;
+            nodes[2]
+                .expect_no_event(PROPAGATION_WINDOW, is_gossip_message)
+                .await?;
+
+            let score = nodes[1]
+                .peer_score(&publisher)
+                .expect("The publisher is a peer of the relay");
+            assert!(score < 0.0, "The publisher wasn't penalized: {score}");
+            Ok(())
+        })
+    }
+
+    #[test]
+    fn test_oversized_gossip_is_dropped() -> anyhow::Result<()> {
+        run_simulation(|executor| async move {
+            let Simulation { mut nodes } =
+                Simulation::spawn(&executor, vec![NodeConfig::default(); 2], Topology::Line)
+                    .await?;
+
+            // Voluntary exits have a fixed size, so this is dropped before being decompressed
+            nodes[0].publish(GossipTopicKind::VoluntaryExit, vec![0; 1024]);
+
+            nodes[1]
+                .expect_no_event(PROPAGATION_WINDOW, is_gossip_message)
+                .await
+        })
+    }
+}
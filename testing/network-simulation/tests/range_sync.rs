@@ -0,0 +1,94 @@
+mod tests {
+    use std::{
+        sync::Arc,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
+
+    use anyhow::anyhow;
+    use network_simulation::{
+        chain::SimulatedChain,
+        genesis::Genesis,
+        node::NodeConfig,
+        simulation::{Simulation, Topology, run_simulation},
+    };
+    use ream_chain_beacon::beacon_chain::BeaconChain;
+    use ream_consensus_misc::constants::beacon::SLOTS_PER_EPOCH;
+    use ream_fork_choice::{handlers::on_tick, store::get_forkchoice_store};
+    use ream_network_spec::networks::beacon_network_spec;
+    use ream_operation_pool::OperationPool;
+    use ream_storage::db::ReamDB;
+    use ream_syncer::block_range::BlockRangeSyncer;
+    use tempdir::TempDir;
+    use tokio::time::timeout;
+
+    const VALIDATOR_COUNT: u64 = 16;
+    /// Importing every block runs the state transition, which is slow in debug builds
+    const SYNC_TIMEOUT: Duration = Duration::from_secs(300);
+
+    /// Checks that the range syncer of a fresh node downloads, verifies and imports the chain of a
+    /// peer up to its finalized checkpoint, across several batches.
+    #[test]
+    fn test_range_sync_catches_up_to_the_finalized_head_of_a_peer() -> anyhow::Result<()> {
+        run_simulation(|executor| async move {
+            // The chain ends on the first slot of the finalized epoch, so its head is finalized
+            let finalized_epoch = 1;
+            let chain_length = finalized_epoch * SLOTS_PER_EPOCH;
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            let genesis_time = now - (chain_length + 1) * beacon_network_spec().seconds_per_slot;
+            let genesis = Genesis::new(VALIDATOR_COUNT, genesis_time)?;
+            let mut peer_chain = genesis.build_chain(chain_length).await?;
+            peer_chain.finalize(finalized_epoch)?;
+            let head_root = peer_chain.head_root();
+
+            let Simulation { nodes } = Simulation::spawn(
+                &executor,
+                vec![
+                    NodeConfig {
+                        chain: peer_chain,
+                        ..Default::default()
+                    },
+                    NodeConfig {
+                        chain: SimulatedChain::from_blocks(vec![genesis.block.clone()])?,
+                        ..Default::default()
+                    },
+                ],
+                Topology::Line,
+            )
+            .await?;
+
+            // The fresh node starts from genesis at the current slot, like a node which just
+            // started
+            let data_dir = TempDir::new("ream_network_simulation_range_sync")?;
+            let mut store = get_forkchoice_store(
+                genesis.state.clone(),
+                genesis.block.message.clone(),
+                ReamDB::new(data_dir.path().to_path_buf())?,
+            )?;
+            on_tick(&mut store, now)?;
+            let beacon_chain = Arc::new(BeaconChain::new(
+                store.db,
+                Arc::new(OperationPool::default()),
+                None,
+            ));
+
+            let syncing_node = &nodes[1];
+            let syncer = BlockRangeSyncer::new(
+                beacon_chain.clone(),
+                syncing_node.p2p_sender.clone(),
+                syncing_node.network_state.clone(),
+                executor.clone(),
+            );
+            timeout(SYNC_TIMEOUT, syncer.start())
+                .await
+                .map_err(|_| anyhow!("Timed out waiting for the range syncer"))????;
+
+            let store = beacon_chain.store.lock().await;
+            assert_eq!(
+                store.db.slot_index_provider().get_highest_slot()?,
+                Some(chain_length)
+            );
+            assert_eq!(store.get_head()?, head_root);
+            Ok(())
+        })
+    }
+}
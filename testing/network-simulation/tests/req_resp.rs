@@ -0,0 +1,141 @@
+mod tests {
+    use futures::future::join_all;
+    use network_simulation::{
+        chain::SimulatedChain,
+        node::NodeConfig,
+        simulation::{Simulation, Topology, run_simulation, wait_until},
+    };
+    use ream_network_spec::networks::beacon_network_spec;
+    use ream_p2p::{
+        network::beacon::ReamNetworkEvent,
+        req_resp::{error::ReqRespError, handler::ReqRespMessageError},
+    };
+
+    #[test]
+    fn test_requests_over_the_rate_limit_are_refused() -> anyhow::Result<()> {
+        run_simulation(|executor| async move {
+            let batch_size = beacon_network_spec().max_request_blocks_deneb;
+            let server_config = NodeConfig {
+                chain: SimulatedChain::new(batch_size),
+                ..Default::default()
+            };
+            let Simulation { mut nodes } = Simulation::spawn(
+                &executor,
+                vec![server_config, NodeConfig::default()],
+                Topology::Line,
+            )
+            .await?;
+            let server = nodes[0].peer_id;
+            let client = nodes[1].peer_id;
+
+            // The quota of the server allows all but the last two requests
+            let allowed_requests = beacon_network_spec().max_request_blocks / batch_size;
+            let responses = join_all(
+                (0..allowed_requests + 2)
+                    .map(|_| nodes[1].request_blocks_by_range(server, 1, batch_size)),
+            )
+            .await;
+            let served = responses
+                .iter()
+                .filter(|response| {
+                    response
+                        .as_ref()
+                        .is_ok_and(|blocks| blocks.len() as u64 == batch_size)
+                })
+                .count();
+            let refused = responses
+                .iter()
+                .filter(|response| response.is_err())
+                .count();
+            assert_eq!(served + refused, responses.len());
+            assert!(served as u64 >= allowed_requests, "Only {served} served");
+            assert!(refused >= 1, "No request was refused");
+
+            // The client is told why, while the server penalizes it for going over the quota
+            let reason = nodes[1]
+                .wait_for_event(|event| match event {
+                    ReamNetworkEvent::RequestFailed {
+                        peer_id,
+                        error:
+                            ReqRespMessageError::Outbound {
+                                err: ReqRespError::ResourceUnavailable(reason),
+                                ..
+                            },
+                    } if *peer_id == server => Some(reason.clone()),
+                    _ => None,
+                })
+                .await?;
+            assert_eq!(reason, "Rate limited");
+
+            nodes[0]
+                .wait_for_event(|event| match event {
+                    ReamNetworkEvent::RequestFailed {
+                        peer_id,
+                        error:
+                            ReqRespMessageError::Inbound {
+                                err: ReqRespError::RateLimited,
+                                ..
+                            },
+                    } if *peer_id == client => Some(()),
+                    _ => None,
+                })
+                .await?;
+            wait_until(
+                || {
+                    nodes[0]
+                        .peer_score(&client)
+                        .is_some_and(|score| score < 0.0)
+                },
+                "the server penalized the client",
+            )
+            .await
+        })
+    }
+
+    #[test]
+    fn test_oversized_request_is_refused() -> anyhow::Result<()> {
+        run_simulation(|executor| async move {
+            let max_request_blocks = beacon_network_spec().max_request_blocks_deneb;
+            let server_config = NodeConfig {
+                chain: SimulatedChain::new(max_request_blocks + 1),
+                ..Default::default()
+            };
+            let Simulation { mut nodes } = Simulation::spawn(
+                &executor,
+                vec![server_config, NodeConfig::default()],
+                Topology::Line,
+            )
+            .await?;
+            let server = nodes[0].peer_id;
+            let client = nodes[1].peer_id;
+
+            let response = nodes[1]
+                .request_blocks_by_range(server, 1, max_request_blocks + 1)
+                .await;
+            assert!(response.is_err(), "The oversized request was served");
+
+            nodes[0]
+                .wait_for_event(|event| match event {
+                    ReamNetworkEvent::RequestFailed {
+                        peer_id,
+                        error:
+                            ReqRespMessageError::Inbound {
+                                err: ReqRespError::InvalidData(_),
+                                ..
+                            },
+                    } if *peer_id == client => Some(()),
+                    _ => None,
+                })
+                .await?;
+            wait_until(
+                || {
+                    nodes[0]
+                        .peer_score(&client)
+                        .is_some_and(|score| score < 0.0)
+                },
+                "the server penalized the client",
+            )
+            .await
+        })
+    }
+}